@@ -0,0 +1,60 @@
+//! Loading a 200-question config with and without the config cache.
+//!
+//! Run with `cargo bench --bench config_cache`.
+
+use baker::config::{Config, ConfigCache, LoadOptions};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt::Write;
+use std::path::Path;
+
+const QUESTIONS: usize = 200;
+
+fn write_template(dir: &Path) {
+    let mut config = String::from(
+        "schemaVersion: v1\nhook_answers_schema:\n  type: object\n  properties:\n    region:\n      type: string\nx-types:\n  identifier:\n    type: str\n    validation:\n      condition: \"name_0|regex('^[a-z_0-9]+$')\"\n      error_message: Use lowercase letters\nquestions:\n",
+    );
+    for i in 0..QUESTIONS {
+        let _ = match i % 4 {
+            0 => write!(
+                config,
+                "  name_{i}:\n    uses: identifier\n    help: Name {i}?\n    default: name_{i}\n"
+            ),
+            1 => write!(
+                config,
+                "  enabled_{i}:\n    type: bool\n    help: Enable feature {i}?\n    default: true\n"
+            ),
+            2 => write!(
+                config,
+                "  choice_{i}:\n    type: str\n    help: Pick option {i}\n    choices: [a, b, c]\n    default: a\n    ask_if: enabled_{}\n",
+                i - 1
+            ),
+            _ => write!(
+                config,
+                "  settings_{i}:\n    type: json\n    help: Settings {i}\n    schema: '{{\"type\": \"object\", \"properties\": {{\"level\": {{\"type\": \"integer\"}}}}}}'\n    default: {{\"level\": 1}}\n"
+            ),
+        };
+    }
+    std::fs::write(dir.join("baker.yaml"), config).unwrap();
+}
+
+fn load_config(c: &mut Criterion) {
+    let template = tempfile::tempdir().unwrap();
+    let cache_dir = tempfile::tempdir().unwrap();
+    write_template(template.path());
+    let cache = ConfigCache::new(cache_dir.path());
+
+    let mut group = c.benchmark_group("load config");
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            let Config::V1(config) = Config::load_config(template.path()).unwrap();
+            config.validate().unwrap();
+        })
+    });
+    group.bench_function("cached", |b| {
+        b.iter(|| cache.load(template.path(), &LoadOptions::default()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, load_config);
+criterion_main!(benches);
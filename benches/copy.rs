@@ -0,0 +1,48 @@
+//! Copying and hashing large template files, as `baker generate` does for every
+//! file that is not rendered.
+//!
+//! Run with `cargo bench --bench copy`.
+
+use baker::generated::{content_hash, file_hash};
+use baker::{atomic, config::Durability, constants::DEFAULT_COPY_BUFFER_SIZE};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::path::Path;
+
+const FILE_SIZE: usize = 64 * 1024 * 1024;
+
+fn write_source(path: &Path) {
+    let content: Vec<u8> = (0..FILE_SIZE).map(|i| (i % 251) as u8).collect();
+    std::fs::write(path, content).unwrap();
+}
+
+fn copy_and_hash(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("source.bin");
+    let target = dir.path().join("target.bin");
+    write_source(&source);
+
+    let mut group = c.benchmark_group("large file");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64)).sample_size(10);
+    for (name, durability) in [
+        ("copy direct", Durability::Direct),
+        ("copy atomic", Durability::Atomic),
+        ("copy fsync", Durability::Fsync),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                atomic::copy(&source, &target, durability, DEFAULT_COPY_BUFFER_SIZE)
+                    .unwrap()
+            })
+        });
+    }
+    group.bench_function("hash whole file", |b| {
+        b.iter(|| content_hash(&std::fs::read(&source).unwrap()))
+    });
+    group.bench_function("hash streamed", |b| {
+        b.iter(|| file_hash(&source, DEFAULT_COPY_BUFFER_SIZE).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, copy_and_hash);
+criterion_main!(benches);
@@ -0,0 +1,182 @@
+//! Writes that never leave a truncated file behind.
+//!
+//! The content goes to a temporary file in the target's directory, which is then
+//! renamed over the target. A rename within one directory replaces the target in
+//! one step, so a run interrupted by a crash, a Ctrl-C or a laptop going to sleep
+//! leaves either the old file or the new one. With [`Durability::Fsync`] the file
+//! and its directory are also flushed to disk, so the new file survives a power
+//! loss right after Baker exits.
+
+use crate::config::Durability;
+use std::{
+    fs::{self, File, Permissions},
+    io::{self, BufReader, Write},
+    path::{Path, PathBuf},
+};
+use tempfile::NamedTempFile;
+
+/// Writes `content` to `path`. An existing file keeps its permissions.
+pub fn write(path: &Path, content: &[u8], durability: Durability) -> io::Result<()> {
+    if durability == Durability::Direct {
+        return fs::write(path, content);
+    }
+    let permissions = fs::metadata(path).ok().map(|metadata| metadata.permissions());
+    replace(path, durability, permissions, |file| file.write_all(content))
+}
+
+/// Copies `source` to `dest` with the permissions of `source`, like [`fs::copy`],
+/// which it uses to benefit from the platform's fast paths, such as
+/// `copy_file_range` on Linux and clones on macOS. On Windows, where the
+/// temporary file cannot be copied to while it is open, `source` is read
+/// `buffer_size` bytes at a time.
+pub fn copy(
+    source: &Path,
+    dest: &Path,
+    durability: Durability,
+    buffer_size: usize,
+) -> io::Result<()> {
+    if durability == Durability::Direct {
+        return fs::copy(source, dest).map(|_| ());
+    }
+    let permissions = fs::metadata(source)?.permissions();
+    replace(dest, durability, Some(permissions), |temp| {
+        if cfg!(windows) {
+            let mut reader = BufReader::with_capacity(buffer_size, File::open(source)?);
+            io::copy(&mut reader, temp).map(|_| ())
+        } else {
+            fs::copy(source, temp.path()).map(|_| ())
+        }
+    })
+}
+
+/// Fills a temporary file next to `path` with `fill` and renames it over `path`.
+/// Without `permissions`, the file gets those of a newly created file. When `path`
+/// is a symlink, the file it points to is replaced and the link is kept.
+fn replace(
+    path: &Path,
+    durability: Durability,
+    permissions: Option<Permissions>,
+    fill: impl FnOnce(&mut NamedTempFile) -> io::Result<()>,
+) -> io::Result<()> {
+    let resolved;
+    let path = if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()) {
+        resolved = resolve_link(path)?;
+        resolved.as_path()
+    } else {
+        path
+    };
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let dir = dir.unwrap_or(Path::new("."));
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".baker-").suffix(".tmp");
+    // Temporary files are private by default; a new file is readable like any
+    // other, within the umask.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+    fill(&mut temp)?;
+    if let Some(permissions) = permissions {
+        temp.as_file().set_permissions(permissions)?;
+    }
+    if durability == Durability::Fsync {
+        temp.as_file().sync_all()?;
+    }
+    temp.persist(path).map_err(|e| e.error)?;
+    // Directories cannot be opened as files on Windows, where the rename is
+    // flushed with the file.
+    #[cfg(unix)]
+    if durability == Durability::Fsync {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// The path the symlink `link` finally points to, which may not exist yet.
+fn resolve_link(link: &Path) -> io::Result<PathBuf> {
+    let mut path = link.to_path_buf();
+    // Like the system, give up on a chain of links that is too long or a loop.
+    for _ in 0..40 {
+        if !fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Ok(path);
+        }
+        let target = fs::read_link(&path)?;
+        path = match path.parent() {
+            Some(dir) => dir.join(target),
+            None => target,
+        };
+    }
+    Err(io::Error::other(format!("Too many levels of symlinks at '{}'", link.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_files_without_leaving_temporary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("Cargo.lock");
+        for durability in [Durability::Atomic, Durability::Fsync, Durability::Direct] {
+            write(&target, b"old", durability).unwrap();
+            write(&target, b"new", durability).unwrap();
+            assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        }
+        let source = dir.path().join("source.bin");
+        fs::write(&source, [1u8, 2, 3]).unwrap();
+        copy(&source, &target, Durability::Atomic, 2).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), [1, 2, 3]);
+
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names.len(), 2, "{names:?}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn keeps_the_permissions_of_existing_files() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        fs::write(&script, "").unwrap();
+        fs::set_permissions(&script, Permissions::from_mode(0o750)).unwrap();
+
+        write(&script, b"#!/bin/sh\n", Durability::Atomic).unwrap();
+        assert_eq!(fs::metadata(&script).unwrap().permissions().mode() & 0o777, 0o750);
+
+        let new = dir.path().join("new.txt");
+        let plain = dir.path().join("plain.txt");
+        write(&new, b"", Durability::Atomic).unwrap();
+        fs::write(&plain, "").unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode(&new), mode(&plain));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn writes_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("shared")).unwrap();
+        let target = dir.path().join("shared/config.toml");
+        fs::write(&target, "old").unwrap();
+        let link = dir.path().join("config.toml");
+        std::os::unix::fs::symlink("shared/config.toml", &link).unwrap();
+
+        write(&link, b"new", Durability::Atomic).unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+
+        let dangling = dir.path().join("dangling.txt");
+        std::os::unix::fs::symlink("shared/created.txt", &dangling).unwrap();
+        write(&dangling, b"created", Durability::Fsync).unwrap();
+        assert!(fs::symlink_metadata(&dangling).unwrap().file_type().is_symlink());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("shared/created.txt")).unwrap(),
+            "created"
+        );
+    }
+}
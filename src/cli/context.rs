@@ -1,4 +1,8 @@
-use crate::{cli::SkipConfirm, config::ConfigV1, conflict::ConflictStyle};
+use crate::{
+    cli::{CopyMode, SkipConfirm},
+    config::ConfigV1,
+    conflict::ConflictStyle,
+};
 use std::path::PathBuf;
 
 /// Shared state describing a single generation run.
@@ -7,12 +11,19 @@ pub struct GenerationContext {
     output_root: PathBuf,
     config: ConfigV1,
     answers: Option<serde_json::Value>,
+    /// The answers with the config's `context_alias` added, for rendering files.
+    template_answers: Option<serde_json::Value>,
+    /// The answers as collected, before the answer transformers ran.
+    recorded_answers: Option<serde_json::Value>,
+    /// Original `!secret` references for answers holding resolved secrets.
+    secret_references: serde_json::Map<String, serde_json::Value>,
     skip_confirms: Vec<SkipConfirm>,
     dry_run: bool,
     /// When true the FileProcessor should write conflict markers instead of overwriting.
     conflict_mode: bool,
     /// The conflict marker style to use (only relevant when conflict_mode is true).
     conflict_style: Option<ConflictStyle>,
+    copy_mode: CopyMode,
 }
 
 impl GenerationContext {
@@ -30,10 +41,14 @@ impl GenerationContext {
             output_root,
             config,
             answers: None,
+            template_answers: None,
+            recorded_answers: None,
+            secret_references: serde_json::Map::new(),
             skip_confirms,
             dry_run,
             conflict_mode,
             conflict_style,
+            copy_mode: CopyMode::default(),
         }
     }
 
@@ -45,6 +60,10 @@ impl GenerationContext {
         &self.output_root
     }
 
+    pub fn set_output_root(&mut self, output_root: PathBuf) {
+        self.output_root = output_root;
+    }
+
     pub fn config(&self) -> &ConfigV1 {
         &self.config
     }
@@ -70,14 +89,52 @@ impl GenerationContext {
         self.conflict_style.or(self.config.conflict_marker_style).unwrap_or_default()
     }
 
+    pub fn copy_mode(&self) -> CopyMode {
+        self.copy_mode
+    }
+
+    pub fn set_copy_mode(&mut self, copy_mode: CopyMode) {
+        self.copy_mode = copy_mode;
+    }
+
     pub fn set_answers(&mut self, answers: serde_json::Value) {
+        self.template_answers = Some(self.config.template_context(&answers));
         self.answers = Some(answers);
     }
 
+    /// The answers as templates see them; see [`ConfigV1::template_context`].
+    pub fn template_answers(&self) -> &serde_json::Value {
+        self.template_answers
+            .as_ref()
+            .expect("generation answers requested before initialization")
+    }
+
     pub fn answers(&self) -> &serde_json::Value {
         self.answers.as_ref().expect("generation answers requested before initialization")
     }
 
+    /// The answers to record in the generated metadata: those collected, before
+    /// the answer transformers ran, so values they expand, such as environment
+    /// variables, are not persisted. Falls back to [`Self::answers`].
+    pub fn recorded_answers(&self) -> &serde_json::Value {
+        self.recorded_answers.as_ref().unwrap_or_else(|| self.answers())
+    }
+
+    pub fn set_recorded_answers(&mut self, answers: serde_json::Value) {
+        self.recorded_answers = Some(answers);
+    }
+
+    pub fn set_secret_references(
+        &mut self,
+        references: serde_json::Map<String, serde_json::Value>,
+    ) {
+        self.secret_references = references;
+    }
+
+    pub fn secret_references(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.secret_references
+    }
+
     pub fn answers_opt(&self) -> Option<&serde_json::Value> {
         self.answers.as_ref()
     }
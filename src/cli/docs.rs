@@ -0,0 +1,361 @@
+//! `baker docs` — writes a reference page for a template: its questions and when
+//! they are asked, the files it generates and their conditions, its hooks and its
+//! profiles. The page is Markdown, for the template's README, or standalone HTML.
+
+use crate::{
+    cli::{audit::fetch_template_root, DocsArgs, DocsFormat},
+    config::{question::Question, Config, ConfigV1, LoadOptions},
+    error::Result,
+    ignore::parse_bakerignore_file,
+    renderer::TemplateRenderer,
+    template::get_template_engine,
+};
+use indexmap::IndexMap;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::{path::Path, sync::LazyLock};
+use walkdir::WalkDir;
+
+/// `{% if condition %}` in a file name.
+static PATH_CONDITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{%-?\s*if\s+(.+?)\s*-?%\}").unwrap());
+
+/// Main entry point for `baker docs`.
+pub fn run_docs(args: DocsArgs) -> Result<()> {
+    let (template_root, _tmp) = fetch_template_root(&args.template)?;
+    let config = Config::load_validated(&template_root, &LoadOptions::default())?;
+
+    let title = template_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| args.template.clone());
+    let page = document_template(&template_root, &config, &title, args.format)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, page)?,
+        None => print!("{page}"),
+    }
+    Ok(())
+}
+
+/// A part of the page.
+enum Block {
+    Paragraph(String),
+    Table { header: &'static [&'static str], rows: Vec<Vec<String>> },
+}
+
+struct Section {
+    title: &'static str,
+    blocks: Vec<Block>,
+}
+
+/// Builds the reference page of the template at `template_root`.
+pub fn document_template(
+    template_root: &Path,
+    config: &ConfigV1,
+    title: &str,
+    format: DocsFormat,
+) -> Result<String> {
+    let engine = get_template_engine();
+    let sections = [
+        questions_section(config),
+        files_section(template_root, config)?,
+        hooks_section(template_root, config, &engine)?,
+        profiles_section(template_root)?,
+    ];
+    Ok(match format {
+        DocsFormat::Markdown => to_markdown(title, &sections),
+        DocsFormat::Html => to_html(title, &sections),
+    })
+}
+
+fn questions_section(config: &ConfigV1) -> Section {
+    let mut rows = Vec::new();
+    question_rows(&config.questions, "", &mut rows);
+    let blocks = match rows.is_empty() {
+        true => vec![Block::Paragraph("The template asks no questions.".to_string())],
+        false => vec![Block::Table {
+            header: &["Question", "Type", "Default", "Help", "Asked when"],
+            rows,
+        }],
+    };
+    Section { title: "Questions", blocks }
+}
+
+/// One row per question, with the sub-questions of objects and lists after it.
+fn question_rows(
+    questions: &IndexMap<String, Question>,
+    prefix: &str,
+    rows: &mut Vec<Vec<String>>,
+) {
+    for (key, question) in questions {
+        let key = format!("{prefix}{key}");
+        let mut kind = format!("{:?}", question.r#type).to_lowercase();
+        if !question.choices.is_empty() {
+            let choices =
+                question.choices.iter().map(|c| format!("`{c}`")).collect::<Vec<_>>();
+            let one_of = if question.multiselect { "any of" } else { "one of" };
+            kind = format!("{kind}, {one_of} {}", choices.join(", "));
+        }
+        if question.secret.is_some() {
+            kind.push_str(", secret");
+        }
+        let default = match &question.default {
+            Value::Null => String::new(),
+            Value::String(text) => format!("`{text}`"),
+            value => format!("`{value}`"),
+        };
+        let condition = match question.ask_if.trim() {
+            "" => String::new(),
+            condition => format!("`{condition}`"),
+        };
+        rows.push(vec![
+            format!("`{key}`"),
+            kind,
+            default,
+            question.help.clone(),
+            condition,
+        ]);
+        question_rows(&question.properties, &format!("{key}."), rows);
+        if let Some(item) = &question.item {
+            question_rows(
+                &IndexMap::from([(String::new(), (**item).clone())]),
+                &format!("{key}[]"),
+                rows,
+            );
+        }
+    }
+}
+
+/// Every file the template can generate, with the conditions in its path.
+fn files_section(template_root: &Path, config: &ConfigV1) -> Result<Section> {
+    let ignored = parse_bakerignore_file(template_root)?;
+    let mut rows = Vec::new();
+    let entries = WalkDir::new(template_root).min_depth(1).sort_by_file_name();
+    for entry in entries.into_iter().filter_entry(|entry| !ignored.is_match(entry.path()))
+    {
+        let entry = entry?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(template_root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        let conditions: Vec<String> = PATH_CONDITION
+            .captures_iter(&path)
+            .map(|captures| format!("`{}`", &captures[1]))
+            .collect();
+        let rendered = config.render_all
+            || path.ends_with(&config.template_suffix)
+            || config.suffixes.keys().any(|suffix| path.ends_with(suffix));
+        rows.push(vec![
+            format!("`{path}`"),
+            if rendered { "yes" } else { "no" }.to_string(),
+            conditions.join(" and "),
+        ]);
+    }
+    let blocks = match rows.is_empty() {
+        true => vec![Block::Paragraph("The template has no files.".to_string())],
+        false => {
+            vec![Block::Table { header: &["File", "Rendered", "Generated when"], rows }]
+        }
+    };
+    Ok(Section { title: "Files", blocks })
+}
+
+fn hooks_section(
+    template_root: &Path,
+    config: &ConfigV1,
+    engine: &dyn TemplateRenderer,
+) -> Result<Section> {
+    let hooks = [
+        (
+            "pre",
+            &config.pre_hook_filename,
+            &config.pre_hook_runner,
+            &config.pre_hook_image,
+        ),
+        (
+            "post",
+            &config.post_hook_filename,
+            &config.post_hook_runner,
+            &config.post_hook_image,
+        ),
+    ];
+    let mut rows = Vec::new();
+    for (stage, filename, runner, image) in hooks {
+        let filename = engine.render(filename, &json!({}), Some(filename))?;
+        if !template_root.join("hooks").join(&filename).exists() {
+            continue;
+        }
+        let runs_with = match (image, runner.is_empty()) {
+            (Some(image), _) => format!("container image `{image}`"),
+            (None, false) => format!("`{}`", runner.join(" ")),
+            (None, true) => "its shebang".to_string(),
+        };
+        rows.push(vec![stage.to_string(), format!("`hooks/{filename}`"), runs_with]);
+    }
+    let blocks = match rows.is_empty() {
+        true => vec![Block::Paragraph("The template has no hooks.".to_string())],
+        false => vec![Block::Table { header: &["Stage", "File", "Runs with"], rows }],
+    };
+    Ok(Section { title: "Hooks", blocks })
+}
+
+/// The overlays `baker.<profile>.yaml` selectable with `--profile`.
+fn profiles_section(template_root: &Path) -> Result<Section> {
+    let mut rows = Vec::new();
+    for entry in std::fs::read_dir(template_root)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        let profile = ["yaml", "yml", "json"].iter().find_map(|extension| {
+            name.strip_prefix("baker.")?.strip_suffix(&format!(".{extension}"))
+        });
+        if let Some(profile) = profile.filter(|profile| !profile.contains('.')) {
+            rows.push(vec![format!("`{profile}`"), format!("`{name}`")]);
+        }
+    }
+    rows.sort();
+    let blocks = match rows.is_empty() {
+        true => vec![Block::Paragraph("The template has no profiles.".to_string())],
+        false => vec![
+            Block::Paragraph("Select a profile with `--profile <profile>`.".to_string()),
+            Block::Table { header: &["Profile", "Overlay"], rows },
+        ],
+    };
+    Ok(Section { title: "Profiles", blocks })
+}
+
+fn to_markdown(title: &str, sections: &[Section]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let mut out = format!("# {title}\n");
+    for section in sections {
+        out.push_str(&format!("\n## {}\n", section.title));
+        for block in &section.blocks {
+            match block {
+                Block::Paragraph(text) => out.push_str(&format!("\n{text}\n")),
+                Block::Table { header, rows } => {
+                    out.push_str(&format!("\n| {} |\n", header.join(" | ")));
+                    out.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+                    for row in rows {
+                        let row: Vec<String> =
+                            row.iter().map(|text| cell(text)).collect();
+                        out.push_str(&format!("| {} |\n", row.join(" | ")));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+fn to_html(title: &str, sections: &[Section]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n</head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    );
+    for section in sections {
+        out.push_str(&format!("<h2>{}</h2>\n", section.title));
+        for block in &section.blocks {
+            match block {
+                Block::Paragraph(text) => {
+                    out.push_str(&format!("<p>{}</p>\n", inline_html(text)))
+                }
+                Block::Table { header, rows } => {
+                    out.push_str("<table>\n<tr>");
+                    for column in *header {
+                        out.push_str(&format!("<th>{column}</th>"));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for text in row {
+                            out.push_str(&format!("<td>{}</td>", inline_html(text)));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// `text` escaped for HTML, with Markdown's `code` spans as `<code>`.
+fn inline_html(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(index, part)| match index % 2 {
+            1 => format!("<code>{}</code>", escape_html(part)),
+            _ => escape_html(part),
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn documents_questions_files_hooks_and_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("baker.yaml"),
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    help: Project name
+    default: demo
+  use_ci:
+    type: bool
+    default: false
+  ci:
+    type: str
+    choices: [github, gitlab]
+    ask_if: "use_ci"
+"#,
+        )
+        .unwrap();
+        fs::write(root.join("baker.prod.yaml"), "questions: {}\n").unwrap();
+        fs::create_dir_all(root.join("hooks")).unwrap();
+        fs::write(root.join("hooks/post"), "#!/bin/sh\n").unwrap();
+        fs::create_dir_all(root.join("{% if use_ci %}.ci{% endif %}")).unwrap();
+        fs::write(root.join("{% if use_ci %}.ci{% endif %}/{{ci}}.yml.baker.j2"), "")
+            .unwrap();
+        fs::write(root.join("README.md"), "").unwrap();
+        let config = Config::load_validated(root, &LoadOptions::default()).unwrap();
+
+        let page = document_template(root, &config, "app", DocsFormat::Markdown).unwrap();
+        assert!(page.starts_with("# app\n\n## Questions\n"));
+        assert!(page.contains("| `name` | str | `demo` | Project name |  |\n"));
+        assert!(
+            page.contains("| `ci` | str, one of `github`, `gitlab` |  |  | `use_ci` |\n")
+        );
+        assert!(page.contains(
+            "| `{% if use_ci %}.ci{% endif %}/{{ci}}.yml.baker.j2` | yes | `use_ci` |\n"
+        ));
+        assert!(page.contains("| `README.md` | no |  |\n"));
+        assert!(!page.contains("baker.yaml`"));
+        assert!(page.contains("| post | `hooks/post` | its shebang |\n"));
+        assert!(page.contains("| `prod` | `baker.prod.yaml` |\n"));
+
+        let html = document_template(root, &config, "app", DocsFormat::Html).unwrap();
+        assert!(html.contains(
+            "<td><code>{% if use_ci %}.ci{% endif %}/{{ci}}.yml.baker.j2</code></td>"
+        ));
+        assert!(html.contains("<h2>Profiles</h2>"));
+    }
+}
@@ -0,0 +1,417 @@
+//! Encrypted answers files — SOPS-encrypted YAML/JSON and age-encrypted files are
+//! detected by content. Age files are decrypted with the `age` crate, SOPS files by
+//! shelling out to `sops`. The answers stored in the generated metadata can be
+//! age-encrypted too. The age identity comes from an identity file named in the
+//! environment or, with the `keyring` feature, from the OS keyring.
+
+use crate::{
+    constants::AGE_ARMOR_HEADER,
+    error::{Error, Result},
+    generated::{self, BakerGenerated},
+    keyring::{Keyring, SecretStore},
+    prompt::{get_prompt_provider, theme, SecretConfig, TextPromptConfig},
+};
+use age::{
+    armor::{ArmoredReader, ArmoredWriter, Format},
+    IdentityFile, NoCallbacks,
+};
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Environment variable pointing to the age identity file used for decryption.
+pub const AGE_IDENTITY_ENV: &str = "BAKER_AGE_IDENTITY";
+
+/// Environment variable listing the age recipients, comma-separated, that the
+/// answers in the generated metadata are encrypted to.
+pub const AGE_RECIPIENTS_ENV: &str = "BAKER_AGE_RECIPIENTS";
+
+/// Account of the age identity in the OS keyring, under the service `baker`.
+pub const AGE_IDENTITY_KEYRING_ACCOUNT: &str = "age-identity";
+
+/// The encryption format of an answers file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncryptedFormat {
+    /// A YAML or JSON document with a top-level `sops` metadata block.
+    Sops,
+    /// An age file, binary or ASCII-armored.
+    Age,
+}
+
+/// Detects whether `content` is an encrypted answers file.
+pub fn detect_format(content: &[u8]) -> Option<EncryptedFormat> {
+    if content.starts_with(b"age-encryption.org/v1")
+        || content.starts_with(AGE_ARMOR_HEADER.as_bytes())
+    {
+        return Some(EncryptedFormat::Age);
+    }
+
+    let text = std::str::from_utf8(content).ok()?;
+    let document: serde_yaml::Value = serde_yaml::from_str(text).ok()?;
+    document.get("sops").filter(|meta| meta.is_mapping()).map(|_| EncryptedFormat::Sops)
+}
+
+/// Decrypts `path` and returns the plaintext document.
+///
+/// In non-interactive mode decryption never prompts: an age file requires an
+/// identity file (see [`find_age_identity`]) and sops must find its keys without
+/// prompting.
+pub fn decrypt(
+    path: &Path,
+    format: EncryptedFormat,
+    non_interactive: bool,
+) -> Result<String> {
+    if format == EncryptedFormat::Age {
+        log::debug!("Decrypting answers file '{}' with age", path.display());
+        let ciphertext = std::fs::read(path)?;
+        return decrypt_age_file(&ciphertext, path, load_age_identity, non_interactive);
+    }
+
+    log::debug!("Decrypting answers file '{}' with sops", path.display());
+    let output = Command::new("sops")
+        .arg("--decrypt")
+        .arg(path)
+        .stdin(if non_interactive { Stdio::null() } else { Stdio::inherit() })
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| {
+            Error::Other(anyhow::anyhow!(
+                "Failed to run 'sops' to decrypt '{}': {e}",
+                path.display()
+            ))
+        })?;
+
+    if !output.status.success() {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Failed to decrypt answers file '{}' with sops: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| {
+        Error::Other(anyhow::anyhow!(
+            "Decrypted answers file '{}' is not valid UTF-8: {e}",
+            path.display()
+        ))
+    })
+}
+
+/// Decrypts the age file `ciphertext`, binary or armored, read from `path`. A file
+/// encrypted with a passphrase asks for it, unless `non_interactive`; any other
+/// file needs the identities `identity` loads.
+fn decrypt_age_file(
+    ciphertext: &[u8],
+    path: &Path,
+    identity: impl FnOnce() -> Result<Option<IdentityFile<NoCallbacks>>>,
+    non_interactive: bool,
+) -> Result<String> {
+    let fail = |reason: &dyn std::fmt::Display| {
+        Error::Other(anyhow::anyhow!(
+            "Failed to decrypt '{}' with age: {reason}",
+            path.display()
+        ))
+    };
+    let decryptor = age::Decryptor::new_buffered(ArmoredReader::new(ciphertext))
+        .map_err(|e| fail(&e))?;
+    let identities: Vec<Box<dyn age::Identity>> = if decryptor.is_scrypt() {
+        if non_interactive {
+            return Err(fail(&"it is encrypted with a passphrase, which is not asked for in non-interactive mode"));
+        }
+        let passphrase = ask_passphrase(path)?;
+        vec![Box::new(age::scrypt::Identity::new(passphrase.into()))]
+    } else {
+        identity()?
+            .ok_or_else(|| {
+                Error::Other(anyhow::anyhow!(
+                    "Cannot decrypt '{}': no age identity found. Set {} to an identity file.",
+                    path.display(),
+                    AGE_IDENTITY_ENV
+                ))
+            })?
+            .into_identities()
+            .map_err(|e| fail(&e))?
+    };
+
+    let mut plaintext = String::new();
+    decryptor
+        .decrypt(identities.iter().map(|identity| identity.as_ref()))
+        .map_err(|e| fail(&e))?
+        .read_to_string(&mut plaintext)
+        .map_err(|e| fail(&e))?;
+    Ok(plaintext)
+}
+
+/// Asks for the passphrase of the age file `path`, in the user's theme.
+fn ask_passphrase(path: &Path) -> Result<String> {
+    let config = TextPromptConfig {
+        prompt: format!("Passphrase for '{}'", path.display()),
+        default: None,
+        secret: Some(SecretConfig { confirm: false, mismatch_error: String::new() }),
+    };
+    theme::with_user_theme(|| get_prompt_provider().prompt_text(&config))
+}
+
+/// Encrypts `plaintext` with age, ASCII-armored, to the recipients in
+/// `BAKER_AGE_RECIPIENTS`, or else to the identity found by [`load_age_identity`].
+pub fn encrypt_age(plaintext: &str) -> Result<String> {
+    let fail = |reason: &dyn std::fmt::Display| {
+        Error::Other(anyhow::anyhow!("Cannot encrypt the answers: {reason}"))
+    };
+    let recipients = std::env::var(AGE_RECIPIENTS_ENV).unwrap_or_default();
+    let mut recipients = recipients
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|r| {
+            r.parse::<age::x25519::Recipient>()
+                .map(|r| Box::new(r) as Box<dyn age::Recipient + Send>)
+                .map_err(|e| fail(&format_args!("'{r}' in {AGE_RECIPIENTS_ENV}: {e}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if recipients.is_empty() {
+        let identity = load_age_identity()?.ok_or_else(|| {
+            fail(&format_args!(
+                "set {AGE_RECIPIENTS_ENV} to age recipients or {AGE_IDENTITY_ENV} to an identity file"
+            ))
+        })?;
+        recipients = identity.to_recipients().map_err(|e| fail(&e))?;
+    }
+    encrypt_age_to(plaintext, &recipients).map_err(|e| fail(&e))
+}
+
+/// Encrypts `plaintext` to `recipients`, ASCII-armored.
+fn encrypt_age_to(
+    plaintext: &str,
+    recipients: &[Box<dyn age::Recipient + Send>],
+) -> std::result::Result<String, age::EncryptError> {
+    let encryptor = age::Encryptor::with_recipients(
+        recipients.iter().map(|r| r.as_ref() as &dyn age::Recipient),
+    )?;
+    let mut ciphertext = Vec::new();
+    let armored = ArmoredWriter::wrap_output(&mut ciphertext, Format::AsciiArmor)?;
+    let mut writer = encryptor.wrap_output(armored)?;
+    writer.write_all(plaintext.as_bytes())?;
+    writer.finish()?.finish()?;
+    Ok(String::from_utf8(ciphertext).expect("armored age output is ASCII"))
+}
+
+/// Decrypts the ASCII-armored age `ciphertext` of the answers stored in `path`
+/// with the identity found by [`load_age_identity`].
+pub fn decrypt_age(ciphertext: &str, path: &Path) -> Result<String> {
+    decrypt_age_file(ciphertext.as_bytes(), path, load_age_identity, true)
+}
+
+/// Decrypts the answers of `meta`, read from `path`, if they are still encrypted.
+/// Commands that only look at the template or the files never need to.
+pub fn decrypt_answers(meta: &mut BakerGenerated, path: &Path) -> Result<()> {
+    if let Some(ciphertext) = meta.answers_ciphertext() {
+        let plaintext = decrypt_age(ciphertext, path)?;
+        meta.answers = serde_json::from_str(&plaintext)?;
+    }
+    Ok(())
+}
+
+/// Writes `data` like [`generated::write`], with the answers encrypted to an
+/// armored age string first when `encrypted_answers` is set.
+pub fn write_generated(
+    output_dir: &Path,
+    file_name: &str,
+    data: &BakerGenerated,
+) -> Result<()> {
+    if !data.encrypted_answers || data.answers_ciphertext().is_some() {
+        return generated::write(output_dir, file_name, data);
+    }
+    let ciphertext = encrypt_age(&data.answers.to_string())?;
+    let data =
+        BakerGenerated { answers: serde_json::Value::String(ciphertext), ..data.clone() };
+    generated::write(output_dir, file_name, &data)
+}
+
+/// Loads the age identity: the identity file found by [`find_age_identity`], else,
+/// with the `keyring` feature, the identity stored in the OS keyring under the
+/// service `baker` and the account [`AGE_IDENTITY_KEYRING_ACCOUNT`].
+pub fn load_age_identity() -> Result<Option<IdentityFile<NoCallbacks>>> {
+    if let Some(path) = find_age_identity() {
+        log::debug!("Using age identity '{}'", path.display());
+        let identity = IdentityFile::from_file(path.to_string_lossy().into_owned())
+            .map_err(|e| {
+                Error::Other(anyhow::anyhow!(
+                    "Cannot read age identity file '{}': {e}",
+                    path.display()
+                ))
+            })?;
+        return Ok(Some(identity));
+    }
+    if !cfg!(feature = "keyring") {
+        return Ok(None);
+    }
+
+    let secret =
+        Keyring::baker()?.get(AGE_IDENTITY_KEYRING_ACCOUNT).unwrap_or_else(|e| {
+            log::debug!("No age identity in the keyring: {e}");
+            None
+        });
+    secret
+        .map(|secret| {
+            log::debug!("Using the age identity from the keyring");
+            IdentityFile::from_buffer(secret.as_bytes()).map_err(|e| {
+                Error::Other(anyhow::anyhow!(
+                    "Cannot read the age identity in the keyring: {e}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Locates the age identity file: `BAKER_AGE_IDENTITY`, then `SOPS_AGE_KEY_FILE`,
+/// then the sops default `<config dir>/sops/age/keys.txt`.
+pub fn find_age_identity() -> Option<PathBuf> {
+    for var in [AGE_IDENTITY_ENV, "SOPS_AGE_KEY_FILE"] {
+        if let Some(path) = std::env::var_os(var).filter(|v| !v.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    let default =
+        crate::config::user::config_dir()?.join("sops").join("age").join("keys.txt");
+    default.exists().then_some(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_age_files() {
+        assert_eq!(
+            detect_format(b"age-encryption.org/v1\n-> X25519 abc\n"),
+            Some(EncryptedFormat::Age)
+        );
+        assert_eq!(
+            detect_format(b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n"),
+            Some(EncryptedFormat::Age)
+        );
+    }
+
+    #[test]
+    fn detects_sops_yaml_and_json() {
+        let yaml = "token: ENC[AES256_GCM,data:abc]\nsops:\n  version: 3.8.1\n";
+        assert_eq!(detect_format(yaml.as_bytes()), Some(EncryptedFormat::Sops));
+
+        let json =
+            r#"{"token": "ENC[AES256_GCM,data:abc]", "sops": {"version": "3.8.1"}}"#;
+        assert_eq!(detect_format(json.as_bytes()), Some(EncryptedFormat::Sops));
+    }
+
+    /// An identity file holding a fresh age identity, and its recipient.
+    fn age_identity(dir: &Path) -> (PathBuf, age::x25519::Recipient) {
+        use age::secrecy::ExposeSecret;
+
+        let identity = age::x25519::Identity::generate();
+        let path = dir.join("keys.txt");
+        std::fs::write(&path, identity.to_string().expose_secret()).unwrap();
+        (path, identity.to_public())
+    }
+
+    fn identity_file(path: &Path) -> Result<Option<IdentityFile<NoCallbacks>>> {
+        Ok(Some(IdentityFile::from_file(path.to_string_lossy().into_owned())?))
+    }
+
+    #[test]
+    fn decrypts_age_answers_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (identity, recipient) = age_identity(dir.path());
+        let path = dir.path().join("answers.age");
+        let answers = r#"{"name": "demo"}"#;
+
+        let armored = age::encrypt_and_armor(&recipient, answers.as_bytes()).unwrap();
+        assert_eq!(detect_format(armored.as_bytes()), Some(EncryptedFormat::Age));
+        let plaintext = decrypt_age_file(
+            armored.as_bytes(),
+            &path,
+            || identity_file(&identity),
+            true,
+        )
+        .unwrap();
+        assert_eq!(plaintext, answers);
+
+        let binary = age::encrypt(&recipient, answers.as_bytes()).unwrap();
+        assert_eq!(detect_format(&binary), Some(EncryptedFormat::Age));
+        std::fs::write(&path, &binary).unwrap();
+        let plaintext =
+            decrypt_age_file(&binary, &path, || identity_file(&identity), true).unwrap();
+        assert_eq!(plaintext, answers);
+
+        let err =
+            decrypt_age_file(&binary, &path, || Ok(None), true).unwrap_err().to_string();
+        assert!(err.contains("no age identity found"), "{err}");
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let (other, _) = age_identity(other_dir.path());
+        let err = decrypt_age_file(&binary, &path, || identity_file(&other), true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("with age"), "{err}");
+    }
+
+    #[test]
+    fn passphrase_age_files_fail_without_prompts() {
+        let mut recipient = age::scrypt::Recipient::new("secret".to_string().into());
+        recipient.set_work_factor(1);
+        let ciphertext = age::encrypt(&recipient, b"{}").unwrap();
+
+        let err =
+            decrypt_age_file(&ciphertext, Path::new("answers.age"), || Ok(None), true)
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("passphrase"), "{err}");
+    }
+
+    #[test]
+    fn generated_answers_round_trip_through_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let (identity, _) = age_identity(dir.path());
+        // No recipients are set, so the answers are encrypted to the identity.
+        std::env::set_var(AGE_IDENTITY_ENV, &identity);
+
+        let source = crate::loader::TemplateSourceInfo::Tar { hash: "abc".to_string() };
+        let meta =
+            BakerGenerated::new(source, serde_json::json!({"db_host": "db.internal"}))
+                .with_encrypted_answers(true);
+        write_generated(dir.path(), ".baker-generated.yaml", &meta).unwrap();
+
+        let written =
+            std::fs::read_to_string(dir.path().join(".baker-generated.yaml")).unwrap();
+        assert!(written.contains(AGE_ARMOR_HEADER), "{written}");
+        assert!(!written.contains("db.internal"), "{written}");
+
+        let path = dir.path().join(".baker-generated.yaml");
+        let mut read = generated::read(dir.path(), ".baker-generated.yaml").unwrap();
+        let ciphertext = read.answers_ciphertext().unwrap().to_string();
+        decrypt_answers(&mut read, &path).unwrap();
+        assert_eq!(read.answers, meta.answers);
+
+        let err = decrypt_age_file(ciphertext.as_bytes(), &path, || Ok(None), true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no age identity found"), "{err}");
+    }
+
+    #[test]
+    fn plain_generated_answers_need_no_identity() {
+        let source = crate::loader::TemplateSourceInfo::Tar { hash: "abc".to_string() };
+        let mut meta = BakerGenerated::new(source, serde_json::json!({"name": "demo"}));
+        decrypt_answers(&mut meta, Path::new(".baker-generated.yaml")).unwrap();
+        assert_eq!(meta.answers["name"], "demo");
+    }
+
+    #[test]
+    fn plain_answers_are_not_encrypted() {
+        assert_eq!(detect_format(br#"{"name": "demo"}"#), None);
+        assert_eq!(detect_format(b"sops: not-a-mapping\n"), None);
+        assert_eq!(detect_format(&[0xff, 0xfe, 0x00]), None);
+    }
+}
@@ -0,0 +1,57 @@
+//! `baker filters` — lists the filters, tests and functions templates can use, with
+//! their signatures and a short description.
+
+use crate::{
+    cli::FiltersArgs,
+    error::{Error, Result},
+    renderer::TemplateRenderer,
+    template::get_template_engine,
+};
+use std::path::Path;
+
+const MINIJINJA_BUILTINS: &str = "\
+MiniJinja's built-in filters (e.g. default, join, upper), tests (e.g. defined, in)
+and functions (range, dict, namespace) are available too:
+https://docs.rs/minijinja/latest/minijinja/filters/index.html
+";
+
+/// Main entry point for `baker filters`.
+pub fn run_filters(args: FiltersArgs) -> Result<()> {
+    print!("{}", describe(args.name.as_deref())?);
+    Ok(())
+}
+
+/// The description of every entry whose name contains `name`, or of all of them.
+pub fn describe(name: Option<&str>) -> Result<String> {
+    let mut engine = get_template_engine();
+    // Functions reading the template root are only registered with one.
+    engine.set_template_root(Path::new("."));
+    let described = engine.registry().describe(name);
+    match name {
+        Some(name) if described.is_empty() => Err(Error::Other(anyhow::anyhow!(
+            "No filter, test or function of Baker is named like '{name}'"
+        ))),
+        Some(_) => Ok(described),
+        None => Ok(format!("{described}\n{MINIJINJA_BUILTINS}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_signatures_and_descriptions() {
+        let all = describe(None).unwrap();
+        assert!(all.starts_with("Filters:\n  value | camel_case\n"));
+        assert!(all
+            .contains("  value | regex_search(pattern, group?)\n      The first match"));
+        assert!(all.contains("Functions:\n  now(tz?)\n"));
+        assert!(all.contains("  render_file(path, context?)\n"));
+
+        let regex = describe(Some("regex_")).unwrap();
+        assert_eq!(regex.lines().filter(|l| l.starts_with("  value")).count(), 3);
+        assert!(!regex.contains("Functions:"));
+        assert!(describe(Some("nope")).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! `baker test --golden` — renders a template once per golden case and compares the
+//! output with the case's checked-in `expected/` tree.
+//!
+//! A golden directory holds one subdirectory per case:
+//!
+//! ```text
+//! golden/
+//!   defaults/
+//!     expected/        the files the template must produce
+//!   no-database/
+//!     answers.yaml     answers for this case (answers.json works too)
+//!     expected/
+//! ```
+//!
+//! With `--update-golden` the `expected/` trees are rewritten from the current output
+//! instead.
+
+use crate::{
+    cli::{run, GenerateArgs, SkipConfirm, TestArgs},
+    constants::DEFAULT_GENERATED_FILE_NAME,
+    error::{Error, Result},
+    temp::TempDir,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Directory of a golden case holding the expected output.
+pub const EXPECTED_DIR: &str = "expected";
+
+/// Files that may hold a golden case's answers, in order of preference.
+const ANSWERS_FILES: [&str; 3] = ["answers.json", "answers.yaml", "answers.yml"];
+
+/// How an output tree differs from the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeDifference {
+    /// The file was generated but is not expected.
+    Unexpected(String),
+    /// The file is expected but was not generated.
+    Missing(String),
+    /// The file exists in both trees with different content; holds the diff.
+    Changed { path: String, diff: String },
+}
+
+impl std::fmt::Display for TreeDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeDifference::Unexpected(path) => write!(f, "+++ {path} (not expected)"),
+            TreeDifference::Missing(path) => write!(f, "--- {path} (not generated)"),
+            TreeDifference::Changed { path, diff } => {
+                write!(f, "~~~ {path}\n{diff}")
+            }
+        }
+    }
+}
+
+/// Main entry point for `baker test`.
+pub fn run_golden(args: TestArgs) -> Result<()> {
+    let cases = golden_cases(&args.golden)?;
+    if cases.is_empty() {
+        return Err(Error::Other(anyhow::anyhow!(
+            "No golden cases in '{}': expected subdirectories with an '{EXPECTED_DIR}' \
+             directory or an answers file",
+            args.golden.display()
+        )));
+    }
+
+    let mut failed = 0;
+    for case in &cases {
+        let name = case.file_name().unwrap_or_default().to_string_lossy();
+        let output = TempDir::new("test")?;
+        render_case(&args.template, case, output.path())?;
+        let expected = case.join(EXPECTED_DIR);
+
+        if args.update_golden {
+            if expected.exists() {
+                fs::remove_dir_all(&expected)?;
+            }
+            copy_tree(output.path(), &expected)?;
+            println!("updated {name}");
+            continue;
+        }
+
+        let differences = compare_trees(output.path(), &expected)?;
+        if differences.is_empty() {
+            println!("ok      {name}");
+        } else {
+            failed += 1;
+            println!("FAILED  {name}");
+            for difference in &differences {
+                println!("{difference}");
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::Other(anyhow::anyhow!(
+            "{failed} of {} golden cases failed; rerun with --update-golden to accept \
+             the new output",
+            cases.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Subdirectories of `golden` that are golden cases, sorted by name.
+fn golden_cases(golden: &Path) -> Result<Vec<PathBuf>> {
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(golden)? {
+        let path = entry?.path();
+        let is_case = path.join(EXPECTED_DIR).is_dir()
+            || ANSWERS_FILES.iter().any(|name| path.join(name).is_file());
+        if path.is_dir() && is_case {
+            cases.push(path);
+        }
+    }
+    cases.sort();
+    Ok(cases)
+}
+
+/// Generates `template` into `output` non-interactively with the case's answers.
+fn render_case(template: &str, case: &Path, output: &Path) -> Result<()> {
+    let answers_file =
+        ANSWERS_FILES.iter().map(|name| case.join(name)).find(|path| path.is_file());
+    let answers = match answers_file {
+        Some(path) => {
+            let value: serde_json::Value =
+                serde_yaml::from_str(&fs::read_to_string(path)?)?;
+            Some(serde_json::to_string(&value)?)
+        }
+        None => None,
+    };
+    run(GenerateArgs {
+        template: template.to_string(),
+        output_dir: output.to_path_buf(),
+        force: true,
+        answers,
+        answers_file: None,
+        skip_confirms: vec![SkipConfirm::All],
+        non_interactive: true,
+        dry_run: false,
+        generated_file: None,
+        conflict_style: None,
+        ..Default::default()
+    })?;
+    // The metadata holds a timestamp, so it can never match a checked-in copy.
+    let _ = fs::remove_file(output.join(DEFAULT_GENERATED_FILE_NAME));
+    Ok(())
+}
+
+/// Compares the files under `actual` with those under `expected`. Directories are not
+/// compared, as empty ones cannot be checked in.
+pub fn compare_trees(actual: &Path, expected: &Path) -> Result<Vec<TreeDifference>> {
+    let actual_files = read_tree(actual)?;
+    let expected_files = read_tree(expected)?;
+    let mut differences = Vec::new();
+    for (path, content) in &actual_files {
+        match expected_files.get(path) {
+            None => differences.push(TreeDifference::Unexpected(path.clone())),
+            Some(expected) if expected != content => {
+                differences.push(TreeDifference::Changed {
+                    path: path.clone(),
+                    diff: diff_contents(expected, content),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for path in expected_files.keys().filter(|path| !actual_files.contains_key(*path)) {
+        differences.push(TreeDifference::Missing(path.clone()));
+    }
+    Ok(differences)
+}
+
+/// The files under `root`, keyed by `/`-separated path relative to it.
+fn read_tree(root: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(root) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            let key = relative.to_string_lossy().replace('\\', "/");
+            files.insert(key, fs::read(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// Line diff between the expected and the actual content: the differing region with
+/// the line it starts at, `-` for expected lines and `+` for actual ones.
+fn diff_contents(expected: &[u8], actual: &[u8]) -> String {
+    let (Ok(expected), Ok(actual)) =
+        (std::str::from_utf8(expected), std::str::from_utf8(actual))
+    else {
+        return format!(
+            "  binary content differs ({} bytes expected, {} bytes generated)",
+            expected.len(),
+            actual.len()
+        );
+    };
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut diff = format!("@@ line {} @@\n", prefix + 1);
+    for line in &old[prefix..old.len() - suffix] {
+        diff.push_str(&format!("-{line}\n"));
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        diff.push_str(&format!("+{line}\n"));
+    }
+    if old.len() == new.len() && prefix + suffix == old.len() {
+        diff.push_str("  (line endings or trailing newline differ)\n");
+    }
+    diff
+}
+
+/// Copies the files under `source` to `target`, creating directories as needed.
+fn copy_tree(source: &Path, target: &Path) -> Result<()> {
+    for entry in WalkDir::new(source) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let destination = target.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &destination)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn compare_trees_reports_each_kind_of_difference() {
+        let actual = TempDir::new().unwrap();
+        let expected = TempDir::new().unwrap();
+        write(actual.path(), "same.txt", "a\n");
+        write(expected.path(), "same.txt", "a\n");
+        write(actual.path(), "src/main.rs", "fn main() {\n    run();\n}\n");
+        write(expected.path(), "src/main.rs", "fn main() {\n    start();\n}\n");
+        write(actual.path(), "extra.txt", "");
+        write(expected.path(), "gone.txt", "");
+
+        let differences = compare_trees(actual.path(), expected.path()).unwrap();
+
+        assert_eq!(
+            differences,
+            vec![
+                TreeDifference::Unexpected("extra.txt".to_string()),
+                TreeDifference::Changed {
+                    path: "src/main.rs".to_string(),
+                    diff: "@@ line 2 @@\n-    start();\n+    run();\n".to_string(),
+                },
+                TreeDifference::Missing("gone.txt".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn golden_cases_are_checked_and_updated() {
+        let template = TempDir::new().unwrap();
+        write(
+            template.path(),
+            "baker.yaml",
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    default: World\n",
+        );
+        write(template.path(), "hello.txt.baker.j2", "Hello {{ name }}\n");
+        let golden = TempDir::new().unwrap();
+        write(golden.path(), "default/expected/hello.txt", "Hello World");
+        write(golden.path(), "named/answers.yaml", "name: Baker\n");
+        write(golden.path(), "named/expected/hello.txt", "Hello Someone\n");
+        let args = |update_golden| TestArgs {
+            template: template.path().display().to_string(),
+            golden: golden.path().to_path_buf(),
+            update_golden,
+        };
+
+        let err = run_golden(args(false)).unwrap_err();
+        assert!(err.to_string().contains("1 of 2 golden cases failed"), "{err}");
+
+        run_golden(args(true)).unwrap();
+        assert_eq!(
+            fs::read_to_string(golden.path().join("named/expected/hello.txt")).unwrap(),
+            "Hello Baker"
+        );
+        run_golden(args(false)).unwrap();
+    }
+}
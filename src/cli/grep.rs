@@ -0,0 +1,145 @@
+//! `baker grep` — searches the paths and contents of a template's files, or of every
+//! template aliased in the user config, without generating anything.
+
+use crate::{
+    cli::{audit::fetch_template_root, GrepArgs},
+    config::UserConfig,
+    error::{Error, Result},
+};
+use regex::{Regex, RegexBuilder};
+use std::{fmt::Display, path::Path};
+use walkdir::WalkDir;
+
+/// A match in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Match {
+    /// The path of a file, relative to the template root, matches.
+    Path(String),
+    /// A line of a text file matches.
+    Line { path: String, number: usize, text: String },
+}
+
+impl Display for Match {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Match::Path(path) => write!(f, "{path}"),
+            Match::Line { path, number, text } => write!(f, "{path}:{number}:{text}"),
+        }
+    }
+}
+
+/// Main entry point for `baker grep`.
+pub fn run_grep(args: GrepArgs) -> Result<()> {
+    let pattern = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|e| Error::Other(anyhow::anyhow!("Invalid pattern: {e}")))?;
+
+    let mut found = 0;
+    match &args.template {
+        Some(template) => {
+            let (root, _tmp) = fetch_template_root(template)?;
+            for found_match in search(&root, &pattern)? {
+                println!("{found_match}");
+                found += 1;
+            }
+        }
+        None => {
+            let aliases = UserConfig::load()?.aliases;
+            if aliases.is_empty() {
+                return Err(Error::Other(anyhow::anyhow!(
+                    "No template given, and the user config has no aliases to search"
+                )));
+            }
+            for alias in aliases.keys() {
+                let matches = fetch_template_root(alias)
+                    .and_then(|(root, _tmp)| search(&root, &pattern));
+                match matches {
+                    Ok(matches) => {
+                        for found_match in matches {
+                            println!("{alias}:{found_match}");
+                            found += 1;
+                        }
+                    }
+                    Err(e) => log::warn!("Skipping template '{alias}': {e}"),
+                }
+            }
+        }
+    }
+    if found == 0 {
+        return Err(Error::Other(anyhow::anyhow!("No match for '{}'", args.pattern)));
+    }
+    Ok(())
+}
+
+/// Every path under `template_root` matching `pattern`, and every matching line of
+/// its text files, in path order. The `.git` directory is skipped.
+pub fn search(template_root: &Path, pattern: &Regex) -> Result<Vec<Match>> {
+    let mut matches = Vec::new();
+    let entries = WalkDir::new(template_root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != ".git");
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(template_root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        if pattern.is_match(&path) {
+            matches.push(Match::Path(path.clone()));
+        }
+        // Binary files are only matched by path.
+        let Ok(content) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        for (index, line) in content.lines().enumerate() {
+            if pattern.is_match(line) {
+                matches.push(Match::Line {
+                    path: path.clone(),
+                    number: index + 1,
+                    text: line.to_string(),
+                });
+            }
+        }
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_matching_paths_and_lines() {
+        let template = tempfile::tempdir().unwrap();
+        let root = template.path();
+        std::fs::create_dir_all(root.join("src/{{ name }}")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("baker.yaml"), "questions:\n  database_url:\n").unwrap();
+        std::fs::write(
+            root.join("src/{{ name }}/db.rs.j2"),
+            "// db\nlet url = \"{{ database_url }}\";\n",
+        )
+        .unwrap();
+        std::fs::write(root.join(".git/config"), "database_url").unwrap();
+        std::fs::write(root.join("logo.png"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let pattern = Regex::new("database_url|db\\.rs").unwrap();
+        let found: Vec<String> =
+            search(root, &pattern).unwrap().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            found,
+            [
+                "baker.yaml:2:  database_url:",
+                "src/{{ name }}/db.rs.j2",
+                "src/{{ name }}/db.rs.j2:2:let url = \"{{ database_url }}\";",
+            ]
+        );
+    }
+}
@@ -1,10 +1,40 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::{ChildStdin, ChildStdout, Command, Stdio};
 
+use crate::cli::answer_source::AnswerSource;
+use crate::cli::observer::HookKind;
+use crate::config::{HookWorkingDir, Question};
 use crate::error::{Error, Result};
+use crate::interrupt;
+
+/// Prefix of a stdout line through which a hook asks Baker a question.
+///
+/// The rest of the line is a JSON object with a `key` plus the same fields as a
+/// question in `baker.yaml`; Baker answers with one JSON line on the hook's stdin.
+pub const HOOK_PROMPT_PREFIX: &str = "baker:ask ";
+
+/// Environment variable naming the container engine hooks with an image run in.
+pub const CONTAINER_ENGINE_ENV: &str = "BAKER_CONTAINER_ENGINE";
+
+/// Where the template, the output directory and the answers are mounted in a hook
+/// container.
+const CONTAINER_TEMPLATE_ROOT: &str = "/baker/template";
+const CONTAINER_OUTPUT_ROOT: &str = "/baker/output";
+#[cfg(feature = "container-hooks")]
+const CONTAINER_ANSWERS_FILE: &str = "/baker/answers.json";
+#[cfg(feature = "container-hooks")]
+const CONTAINER_CONTEXT_FILE: &str = "/baker/context.json";
+
+/// A question asked by a hook.
+#[derive(Deserialize)]
+struct HookPrompt {
+    key: String,
+    #[serde(flatten)]
+    question: Question,
+}
 
 /// Structure representing data passed to hook scripts.
 ///
@@ -19,6 +49,26 @@ struct Output<'a> {
     pub answers: Option<&'a serde_json::Value>,
 }
 
+/// How a hook process is started.
+#[derive(Clone, Copy, Default)]
+pub struct HookOptions<'a> {
+    /// Directory to start the hook in; `None` keeps Baker's working directory.
+    pub working_dir: Option<HookWorkingDir>,
+    /// Stream the hook's stdout to the terminal instead of capturing it.
+    pub inherit_stdout: bool,
+    /// Answers `baker:ask` requests from the hook. When set, stdin stays open after
+    /// the context line so answers can be written back.
+    pub prompts: Option<&'a dyn AnswerSource>,
+    /// Container image to run the hook in, with docker or podman.
+    pub image: Option<&'a str>,
+    /// Context dumped by `--dump-context`, exposed to the hook as
+    /// `BAKER_CONTEXT_FILE`.
+    pub context_file: Option<&'a Path>,
+    /// Echo the hook's output to stderr as it runs, each line prefixed with
+    /// `[pre-hook]` or `[post-hook]`. Stdout is still captured.
+    pub stream: Option<HookKind>,
+}
+
 /// Executes a hook script with the provided context.
 ///
 /// # Arguments
@@ -26,13 +76,20 @@ struct Output<'a> {
 /// * `output_dir` - Path to the output directory
 /// * `script_path` - Path to the hook script to execute
 /// * `context` - Template context data
+/// * `options` - Working directory and stdout handling
 ///
 /// # Returns
 /// * `Result<Option<String>>` - Success or error status of hook execution, with stdout content
 ///
 /// # Notes
 /// - Hook scripts receive context data as JSON via stdin
-/// - Hooks must be executable files
+/// - Hook scripts also receive `BAKER_TEMPLATE_ROOT`, `BAKER_OUTPUT_ROOT`,
+///   `BAKER_ANSWERS_FILE` (a temporary JSON file holding the answers), and
+///   `BAKER_CONTEXT_FILE` when the context was dumped
+/// - Hooks must be executable files, unless they run through a runner
+/// - With an image, the hook runs in a container with the template mounted at
+///   `/baker/template` and the output directory at `/baker/output`
+/// - With `stream`, each output line is echoed to stderr behind the hook's name
 /// - Non-zero exit codes from hooks are treated as errors
 pub fn run_hook<P: AsRef<Path>>(
     template_dir: P,
@@ -40,12 +97,18 @@ pub fn run_hook<P: AsRef<Path>>(
     hook_path: P,
     answers: Option<&serde_json::Value>,
     runner: &[String],
-    inherit_stdout: bool,
+    options: HookOptions<'_>,
 ) -> Result<Option<String>> {
     let hook_path = hook_path.as_ref();
 
-    let template_dir = template_dir.as_ref().display().to_string();
-    let output_dir = output_dir.as_ref().display().to_string();
+    let template_root = template_dir.as_ref();
+    let output_root = output_dir.as_ref();
+    let (template_dir, output_dir) = match options.image {
+        Some(_) => {
+            (CONTAINER_TEMPLATE_ROOT.to_string(), CONTAINER_OUTPUT_ROOT.to_string())
+        }
+        None => (template_root.display().to_string(), output_root.display().to_string()),
+    };
 
     let output = Output { template_dir: &template_dir, output_dir: &output_dir, answers };
 
@@ -56,27 +119,91 @@ pub fn run_hook<P: AsRef<Path>>(
         return Ok(None);
     }
 
+    // Absolute paths stay valid when the hook runs in another working directory.
+    let template_root = std::path::absolute(template_root)?;
+    let output_root = std::path::absolute(output_root)?;
+    let hook_path = std::path::absolute(hook_path)?;
+    let hook_path = hook_path.as_path();
+
+    // Kept alive until the hook exits.
+    let mut answers_file =
+        tempfile::Builder::new().prefix("baker-answers-").suffix(".json").tempfile()?;
+    serde_json::to_writer(&mut answers_file, answers.unwrap_or(&serde_json::json!({})))?;
+    answers_file.flush()?;
+
     log::debug!("Running hook {} via runner {:?}", hook_path.display(), runner);
 
-    let mut command = if runner.is_empty() {
-        Command::new(hook_path)
-    } else {
-        let mut cmd = Command::new(&runner[0]);
-        if runner.len() > 1 {
-            cmd.args(&runner[1..]);
+    let mut command = match options.image {
+        #[cfg(not(feature = "container-hooks"))]
+        Some(image) => {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Hook image '{image}' needs Baker built with the `container-hooks` feature"
+            )));
+        }
+        #[cfg(feature = "container-hooks")]
+        Some(image) => {
+            std::fs::create_dir_all(&output_root)?;
+            container_command(
+                &container_engine()?,
+                image,
+                &template_root,
+                &output_root,
+                hook_path,
+                answers_file.path(),
+                runner,
+                options,
+            )?
+        }
+        None => {
+            let mut command = if runner.is_empty() {
+                Command::new(hook_path)
+            } else {
+                let mut cmd = Command::new(&runner[0]);
+                if runner.len() > 1 {
+                    cmd.args(&runner[1..]);
+                }
+                cmd.arg(hook_path);
+                cmd
+            };
+            command
+                .env("BAKER_TEMPLATE_ROOT", &template_root)
+                .env("BAKER_OUTPUT_ROOT", &output_root)
+                .env("BAKER_ANSWERS_FILE", answers_file.path());
+            if let Some(context_file) = options.context_file {
+                command.env("BAKER_CONTEXT_FILE", std::path::absolute(context_file)?);
+            }
+            match options.working_dir {
+                Some(HookWorkingDir::Template) => {
+                    command.current_dir(&template_root);
+                }
+                Some(HookWorkingDir::Output) => {
+                    std::fs::create_dir_all(&output_root)?;
+                    command.current_dir(&output_root);
+                }
+                None => {}
+            }
+            command
         }
-        cmd.arg(hook_path);
-        cmd
     };
 
     let mut child = command
         .stdin(Stdio::piped())
-        .stdout(if inherit_stdout { Stdio::inherit() } else { Stdio::piped() })
-        .stderr(Stdio::inherit())
+        .stdout(if options.inherit_stdout && options.prompts.is_none() {
+            Stdio::inherit()
+        } else {
+            Stdio::piped()
+        })
+        .stderr(if options.stream.is_some() { Stdio::piped() } else { Stdio::inherit() })
         .spawn()?;
 
-    // Write context to stdin and close it
-    if let Some(mut stdin) = child.stdin.take() {
+    let prefix = options.stream.map(|kind| format!("[{kind}] "));
+    let stderr_echo = child.stderr.take().zip(prefix.clone()).map(|(stderr, prefix)| {
+        std::thread::spawn(move || stream_lines(stderr, &prefix, false))
+    });
+
+    // Write context to stdin and close it, unless the hook may ask questions
+    let mut stdin = child.stdin.take();
+    if let Some(stdin) = stdin.as_mut() {
         // Helper closure to handle broken pipe errors consistently
         let handle_write_error = |e: std::io::Error, operation: &str| {
             if e.kind() == std::io::ErrorKind::BrokenPipe {
@@ -92,14 +219,26 @@ pub fn run_hook<P: AsRef<Path>>(
         } else if let Err(e) = stdin.write_all(b"\n") {
             handle_write_error(e, "write newline to hook stdin");
         }
-
+    }
+    if options.prompts.is_none() {
         // Explicitly close stdin to signal end of input
-        drop(stdin);
+        drop(stdin.take());
     }
 
     // Read stdout before waiting for the process to complete
-    let stdout_output = match child.stdout.take() {
-        Some(stdout) => {
+    let stdout_output = match (child.stdout.take(), options.prompts, prefix.as_deref()) {
+        (Some(stdout), Some(source), prefix) => {
+            match converse(stdout, stdin.take(), source, options.inherit_stdout, prefix) {
+                Ok(output) => output,
+                Err(e) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(e);
+                }
+            }
+        }
+        (Some(stdout), None, Some(prefix)) => Some(stream_lines(stdout, prefix, true)?),
+        (Some(stdout), None, None) => {
             let mut reader = BufReader::new(stdout);
             let mut buffer = Vec::new();
             reader.read_to_end(&mut buffer)?;
@@ -112,11 +251,25 @@ pub fn run_hook<P: AsRef<Path>>(
             }
             Some(decoded.into_owned())
         }
-        None => None,
+        (None, ..) => None,
     };
 
-    // Wait for the process to complete
-    let status = child.wait()?;
+    // Wait for the process to complete; a hook still running after Ctrl-C is killed
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if interrupt::interrupted() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(interrupt::aborted());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    };
+    interrupt::check()?;
+    if let Some(echo) = stderr_echo {
+        let _ = echo.join();
+    }
 
     if !status.success() {
         return Err(Error::HookExecutionError {
@@ -128,6 +281,163 @@ pub fn run_hook<P: AsRef<Path>>(
     Ok(stdout_output)
 }
 
+/// `engine run` of the hook in `image`, with the template mounted read-only and the
+/// output directory writable. The hook starts in the output directory unless
+/// `options` says otherwise.
+#[cfg(feature = "container-hooks")]
+#[allow(clippy::too_many_arguments)]
+fn container_command(
+    engine: &str,
+    image: &str,
+    template_root: &Path,
+    output_root: &Path,
+    hook_path: &Path,
+    answers_file: &Path,
+    runner: &[String],
+    options: HookOptions<'_>,
+) -> Result<Command> {
+    let relative = hook_path.strip_prefix(template_root).map_err(|_| {
+        Error::Other(anyhow::anyhow!(
+            "Hook '{}' is outside the template and cannot run in a container",
+            hook_path.display()
+        ))
+    })?;
+    let hook = format!(
+        "{CONTAINER_TEMPLATE_ROOT}/{}",
+        relative.to_string_lossy().replace('\\', "/")
+    );
+    let working_dir = match options.working_dir {
+        Some(HookWorkingDir::Template) => CONTAINER_TEMPLATE_ROOT,
+        Some(HookWorkingDir::Output) | None => CONTAINER_OUTPUT_ROOT,
+    };
+    let mount = |host: &Path, target: &str, mode: &str| {
+        format!("{}:{target}{mode}", host.display())
+    };
+
+    let mut command = Command::new(engine);
+    command.args(["run", "--rm", "-i"]);
+    // Docker runs as root by default; the generated files should belong to the user.
+    #[cfg(unix)]
+    if Path::new(engine).file_stem().is_some_and(|stem| stem == "docker") {
+        // SAFETY: getuid and getgid cannot fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        command.args(["--user", &format!("{uid}:{gid}")]);
+    }
+    command
+        .arg("-v")
+        .arg(mount(template_root, CONTAINER_TEMPLATE_ROOT, ":ro"))
+        .arg("-v")
+        .arg(mount(output_root, CONTAINER_OUTPUT_ROOT, ""))
+        .arg("-v")
+        .arg(mount(answers_file, CONTAINER_ANSWERS_FILE, ":ro"))
+        .args(["-w", working_dir])
+        .args(["-e", &format!("BAKER_TEMPLATE_ROOT={CONTAINER_TEMPLATE_ROOT}")])
+        .args(["-e", &format!("BAKER_OUTPUT_ROOT={CONTAINER_OUTPUT_ROOT}")])
+        .args(["-e", &format!("BAKER_ANSWERS_FILE={CONTAINER_ANSWERS_FILE}")]);
+    if let Some(context_file) = options.context_file {
+        command
+            .arg("-v")
+            .arg(mount(
+                &std::path::absolute(context_file)?,
+                CONTAINER_CONTEXT_FILE,
+                ":ro",
+            ))
+            .args(["-e", &format!("BAKER_CONTEXT_FILE={CONTAINER_CONTEXT_FILE}")]);
+    }
+    command.arg(image).args(runner).arg(hook);
+    Ok(command)
+}
+
+/// The container engine: `$BAKER_CONTAINER_ENGINE`, else docker or podman,
+/// whichever is found first on `PATH`.
+#[cfg(feature = "container-hooks")]
+fn container_engine() -> Result<String> {
+    if let Some(engine) = std::env::var_os(CONTAINER_ENGINE_ENV).filter(|v| !v.is_empty())
+    {
+        return Ok(engine.to_string_lossy().into_owned());
+    }
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    ["docker", "podman"]
+        .into_iter()
+        .find(|engine| {
+            std::env::split_paths(&path).any(|dir| {
+                dir.join(engine).is_file()
+                    || dir.join(format!("{engine}.exe")).is_file()
+            })
+        })
+        .map(str::to_string)
+        .ok_or_else(|| {
+            Error::Other(anyhow::anyhow!(
+                "Hooks with an image need docker or podman on PATH, or {CONTAINER_ENGINE_ENV}"
+            ))
+        })
+}
+
+/// Copies the lines of a hook's `output` to stderr behind `prefix` as they come,
+/// and returns them when `capture` is set.
+fn stream_lines(
+    output: impl Read,
+    prefix: &str,
+    capture: bool,
+) -> std::io::Result<String> {
+    let mut reader = BufReader::new(output);
+    let mut captured = String::new();
+    let mut buffer = Vec::new();
+    while reader.read_until(b'\n', &mut buffer)? > 0 {
+        let line = String::from_utf8_lossy(&buffer);
+        eprintln!("{prefix}{}", line.trim_end_matches(['\r', '\n']));
+        if capture {
+            captured.push_str(&line);
+        }
+        buffer.clear();
+    }
+    Ok(captured)
+}
+
+/// Reads hook stdout line by line, answering `baker:ask` requests on its stdin.
+/// Other lines are captured, or echoed when `echo` is set. With a `prefix`,
+/// captured lines are also copied to stderr behind it.
+fn converse(
+    stdout: ChildStdout,
+    mut stdin: Option<ChildStdin>,
+    source: &dyn AnswerSource,
+    echo: bool,
+    prefix: Option<&str>,
+) -> Result<Option<String>> {
+    let mut reader = BufReader::new(stdout);
+    let mut captured = String::new();
+    let mut buffer = Vec::new();
+    while reader.read_until(b'\n', &mut buffer)? > 0 {
+        let line = String::from_utf8_lossy(&buffer).into_owned();
+        buffer.clear();
+        let Some(request) = line.trim_end().strip_prefix(HOOK_PROMPT_PREFIX) else {
+            if echo {
+                print!("{line}");
+            } else {
+                if let Some(prefix) = prefix {
+                    eprintln!("{prefix}{}", line.trim_end_matches(['\r', '\n']));
+                }
+                captured.push_str(&line);
+            }
+            continue;
+        };
+
+        let HookPrompt { key, question } =
+            serde_json::from_str(request).map_err(|e| {
+                Error::Other(anyhow::anyhow!("Invalid hook question '{request}': {e}"))
+            })?;
+        let help =
+            if question.help.is_empty() { key.clone() } else { question.help.clone() };
+        let answer = source.answer(&key, &question, &question.default, &help)?;
+        if let Some(stdin) = stdin.as_mut() {
+            writeln!(stdin, "{answer}")?;
+            stdin.flush()?;
+        }
+    }
+    drop(stdin);
+    Ok((!echo).then_some(captured))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,7 +462,7 @@ mod tests {
             &script_path,
             None,
             &["sh".to_string()],
-            false,
+            HookOptions::default(),
         )
         .expect("hook execution")
         .expect("stdout");
@@ -160,6 +470,190 @@ mod tests {
         assert!(output.contains("unix_runner"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn streams_and_captures_hook_output_on_unix() {
+        let temp_dir = TempDir::new().unwrap();
+        let script_path = temp_dir.path().join("hook.sh");
+        File::create(&script_path)
+            .unwrap()
+            .write_all(b"echo working >&2\necho '{\"name\": \"x\"}'")
+            .unwrap();
+
+        let options = HookOptions { stream: Some(HookKind::Pre), ..Default::default() };
+        let output = run_hook(
+            temp_dir.path(),
+            temp_dir.path(),
+            &script_path,
+            None,
+            &["sh".to_string()],
+            options,
+        )
+        .expect("hook execution")
+        .expect("stdout");
+
+        assert_eq!(output, "{\"name\": \"x\"}\n");
+        let captured = stream_lines(&b"one\ntwo"[..], "[pre-hook] ", true).unwrap();
+        assert_eq!(captured, "one\ntwo");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exposes_environment_and_working_dir_on_unix() {
+        let template_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let output_root = output_dir.path().join("out");
+        let script_path = template_dir.path().join("hook.sh");
+        File::create(&script_path)
+            .unwrap()
+            .write_all(b"pwd; echo $BAKER_TEMPLATE_ROOT; cat $BAKER_ANSWERS_FILE")
+            .unwrap();
+
+        let answers = serde_json::json!({"name": "demo"});
+        let options = HookOptions {
+            working_dir: Some(HookWorkingDir::Output),
+            ..Default::default()
+        };
+        let output = run_hook(
+            template_dir.path(),
+            output_root.as_path(),
+            &script_path,
+            Some(&answers),
+            &["sh".to_string()],
+            options,
+        )
+        .expect("hook execution")
+        .expect("stdout");
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            fs::canonicalize(lines[0]).unwrap(),
+            fs::canonicalize(&output_root).unwrap()
+        );
+        assert_eq!(lines[1], template_dir.path().display().to_string());
+        assert_eq!(lines[2], r#"{"name":"demo"}"#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn answers_hook_questions_on_stdin() {
+        use crate::cli::answer_source::FixedSource;
+
+        let temp_dir = TempDir::new().unwrap();
+        let script_path = temp_dir.path().join("hook.sh");
+        File::create(&script_path)
+            .unwrap()
+            .write_all(
+                br#"read context
+echo 'baker:ask {"key": "reuse_db", "type": "bool", "help": "Reuse?"}'
+read reuse
+echo 'baker:ask {"key": "db_name", "type": "str", "default": "app"}'
+read name
+echo "reuse=$reuse name=$name""#,
+            )
+            .unwrap();
+
+        let answers = serde_json::json!({"reuse_db": true}).as_object().unwrap().clone();
+        let source = FixedSource::new(answers);
+        let options = HookOptions { prompts: Some(&source), ..Default::default() };
+        let output = run_hook(
+            temp_dir.path(),
+            temp_dir.path(),
+            &script_path,
+            None,
+            &["sh".to_string()],
+            options,
+        )
+        .expect("hook execution")
+        .expect("stdout");
+
+        assert_eq!(output, "reuse=true name=\"app\"\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn fails_when_hook_question_has_no_answer() {
+        use crate::cli::answer_source::FailFastSource;
+
+        let temp_dir = TempDir::new().unwrap();
+        let script_path = temp_dir.path().join("hook.sh");
+        File::create(&script_path)
+            .unwrap()
+            .write_all(br#"echo 'baker:ask {"key": "name", "type": "str"}'; read name"#)
+            .unwrap();
+
+        let options =
+            HookOptions { prompts: Some(&FailFastSource), ..Default::default() };
+        let result = run_hook(
+            temp_dir.path(),
+            temp_dir.path(),
+            &script_path,
+            None,
+            &["sh".to_string()],
+            options,
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(all(unix, feature = "container-hooks"))]
+    #[test]
+    fn container_hooks_mount_the_template_and_output() {
+        let options = HookOptions {
+            working_dir: Some(HookWorkingDir::Template),
+            ..Default::default()
+        };
+        let command = container_command(
+            "podman",
+            "python:3.12",
+            Path::new("/tpl"),
+            Path::new("/out"),
+            Path::new("/tpl/hooks/post"),
+            Path::new("/tmp/answers.json"),
+            &["python3".to_string()],
+            options,
+        )
+        .unwrap();
+        let args: Vec<_> = command.get_args().map(|a| a.to_str().unwrap()).collect();
+        assert_eq!(command.get_program(), "podman");
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "-i",
+                "-v",
+                "/tpl:/baker/template:ro",
+                "-v",
+                "/out:/baker/output",
+                "-v",
+                "/tmp/answers.json:/baker/answers.json:ro",
+                "-w",
+                "/baker/template",
+                "-e",
+                "BAKER_TEMPLATE_ROOT=/baker/template",
+                "-e",
+                "BAKER_OUTPUT_ROOT=/baker/output",
+                "-e",
+                "BAKER_ANSWERS_FILE=/baker/answers.json",
+                "python:3.12",
+                "python3",
+                "/baker/template/hooks/post",
+            ]
+        );
+
+        let outside = container_command(
+            "podman",
+            "python:3.12",
+            Path::new("/tpl"),
+            Path::new("/out"),
+            Path::new("/elsewhere/post"),
+            Path::new("/tmp/answers.json"),
+            &[],
+            options,
+        );
+        assert!(outside.is_err());
+    }
+
     #[cfg(windows)]
     #[test]
     fn executes_script_via_powershell_runner_on_windows() {
@@ -185,7 +679,7 @@ mod tests {
             &script_path,
             None,
             &runner,
-            false,
+            HookOptions::default(),
         )
         .expect("hook execution")
         .expect("stdout");
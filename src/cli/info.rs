@@ -0,0 +1,172 @@
+//! `baker info` — describes a template: its questions and the usage instructions
+//! its author ships in `README.md` or `docs/`.
+
+use crate::{
+    cli::{audit::fetch_template_root, InfoArgs},
+    config::{Config, ConfigV1, LoadOptions},
+    error::Result,
+    renderer::TemplateRenderer,
+    template::get_template_engine,
+};
+use serde_json::{Map, Value};
+use std::{fs, path::Path};
+
+/// Main entry point for `baker info`.
+pub fn run_info(args: InfoArgs) -> Result<()> {
+    let (template_root, _tmp) = fetch_template_root(&args.template)?;
+
+    let config = Config::load_validated(&template_root, &LoadOptions::default())?;
+
+    print!("{}", describe_template(&template_root, &config, args.render_readme)?);
+    Ok(())
+}
+
+/// Builds the description of a template. With `render_readme`, placeholders in the
+/// README are rendered with the questions' default answers.
+pub fn describe_template(
+    template_root: &Path,
+    config: &ConfigV1,
+    render_readme: bool,
+) -> Result<String> {
+    let engine = get_template_engine();
+    let defaults = default_answers(config, &engine)?;
+    let mut out = String::new();
+
+    if config.questions.is_empty() {
+        out.push_str("Questions: none\n");
+    } else {
+        out.push_str("Questions:\n");
+        for (key, question) in &config.questions {
+            let rendered =
+                question.render(key, &Value::Object(defaults.clone()), &engine)?;
+            let kind = format!("{:?}", question.r#type).to_lowercase();
+            out.push_str(&format!("  {key} ({kind})"));
+            if !rendered.help.is_empty() {
+                out.push_str(&format!(" - {}", rendered.help));
+            }
+            if !rendered.default.is_null() {
+                out.push_str(&format!(" [default: {}]", rendered.default));
+            }
+            out.push('\n');
+        }
+    }
+
+    if let Some((name, readme)) = read_readme(template_root)? {
+        let readme = if render_readme {
+            engine.render(&readme, &Value::Object(defaults), Some(&name)).unwrap_or_else(
+                |err| {
+                    log::warn!("Showing '{name}' unrendered: {err}");
+                    readme
+                },
+            )
+        } else {
+            readme
+        };
+        out.push_str(&format!("\n--- {name} ---\n{readme}"));
+        if !readme.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    Ok(out)
+}
+
+/// Answers every question with its default, in declaration order, so later
+/// defaults can refer to earlier ones.
+fn default_answers(
+    config: &ConfigV1,
+    engine: &dyn TemplateRenderer,
+) -> Result<Map<String, Value>> {
+    let mut answers = Map::new();
+    for (key, question) in &config.questions {
+        let rendered = question.render(key, &Value::Object(answers.clone()), engine)?;
+        if !rendered.default.is_null() {
+            answers.insert(key.clone(), rendered.default);
+        }
+    }
+    Ok(answers)
+}
+
+/// Reads `README.md` from the template root, or else the Markdown files in `docs/`
+/// concatenated in name order. Returns a display name alongside the content.
+fn read_readme(template_root: &Path) -> Result<Option<(String, String)>> {
+    for name in ["README.md", "readme.md", "Readme.md"] {
+        let path = template_root.join(name);
+        if path.is_file() {
+            return Ok(Some((name.to_string(), fs::read_to_string(path)?)));
+        }
+    }
+
+    let docs = template_root.join("docs");
+    if !docs.is_dir() {
+        return Ok(None);
+    }
+    let mut pages: Vec<_> = fs::read_dir(&docs)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .collect();
+    if pages.is_empty() {
+        return Ok(None);
+    }
+    pages.sort();
+
+    let mut content = String::new();
+    for page in pages {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&fs::read_to_string(page)?);
+    }
+    Ok(Some(("docs/".to_string(), content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn parse_config(raw: &str) -> ConfigV1 {
+        let Config::V1(config) = serde_yaml::from_str(raw).unwrap();
+        config
+    }
+
+    const CONFIG: &str = r#"schemaVersion: v1
+questions:
+  project_name:
+    type: str
+    help: Project name
+    default: demo
+  crate_name:
+    type: str
+    help: Crate name
+    default: "{{ project_name | snake_case }}"
+"#;
+
+    #[test]
+    fn describes_questions_and_renders_readme_with_defaults() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("README.md"), "Run `cargo add {{ crate_name }}`")
+            .unwrap();
+        let config = parse_config(CONFIG);
+
+        let raw = describe_template(dir.path(), &config, false).unwrap();
+        assert!(raw.contains("  project_name (str) - Project name [default: \"demo\"]"));
+        assert!(raw.contains("  crate_name (str) - Crate name [default: \"demo\"]"));
+        assert!(raw.contains("--- README.md ---\nRun `cargo add {{ crate_name }}`"));
+
+        let rendered = describe_template(dir.path(), &config, true).unwrap();
+        assert!(rendered.contains("Run `cargo add demo`"));
+    }
+
+    #[test]
+    fn falls_back_to_docs_folder() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/b.md"), "second\n").unwrap();
+        fs::write(dir.path().join("docs/a.md"), "first\n").unwrap();
+        fs::write(dir.path().join("docs/notes.txt"), "ignored\n").unwrap();
+
+        let out = describe_template(dir.path(), &parse_config(CONFIG), false).unwrap();
+        assert!(out.ends_with("--- docs/ ---\nfirst\n\nsecond\n"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Introspection of the project a template is applied to: before the questions are
+//! asked, the files named by `introspect` in the template config are read from the
+//! output directory into the question context, so that defaults can follow the
+//! host project, e.g. `default: "{{ host.name }}"`.
+//!
+//! A missing or unreadable file gives an empty object, so templates can fall back
+//! with `{{ host.name | default('app') }}`.
+
+use crate::config::{Introspector, ManifestKind};
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use std::path::{Component, Path, PathBuf};
+
+/// Reads every introspector of `introspect` in `output_root`, keyed as configured.
+pub fn introspect(
+    introspect: &IndexMap<String, Introspector>,
+    output_root: &Path,
+) -> Map<String, Value> {
+    introspect
+        .iter()
+        .map(|(key, introspector)| {
+            let values = read(introspector, output_root).unwrap_or_else(|| {
+                log::debug!(
+                    "Nothing to introspect for '{key}' in '{}'",
+                    output_root.display()
+                );
+                Value::Object(Map::new())
+            });
+            (key.clone(), values)
+        })
+        .collect()
+}
+
+fn read(introspector: &Introspector, output_root: &Path) -> Option<Value> {
+    match introspector {
+        Introspector::Manifest(ManifestKind::Cargo) => {
+            let manifest = read_file(&output_root.join("Cargo.toml"))?;
+            manifest.get("package").cloned()
+        }
+        Introspector::Manifest(ManifestKind::Npm) => {
+            read_file(&output_root.join("package.json"))
+        }
+        Introspector::Manifest(ManifestKind::Pyproject) => {
+            let manifest = read_file(&output_root.join("pyproject.toml"))?;
+            manifest.get("project").or_else(|| manifest.pointer("/tool/poetry")).cloned()
+        }
+        Introspector::File { file } => read_file(&inside(output_root, file)?),
+    }
+}
+
+/// `file` under `output_root`, if it stays inside it: relative, without `..` and
+/// not through a symlink pointing out of it.
+fn inside(output_root: &Path, file: &str) -> Option<PathBuf> {
+    let relative = Path::new(file);
+    let path = output_root.join(relative);
+    let escapes = !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        || match (path.canonicalize(), output_root.canonicalize()) {
+            (Ok(path), Ok(root)) => !path.starts_with(root),
+            _ => false,
+        };
+    if escapes {
+        log::warn!(
+            "Cannot introspect '{file}': it must stay inside the output directory"
+        );
+        return None;
+    }
+    Some(path)
+}
+
+/// Parses the JSON, YAML or TOML file at `path`, by its extension.
+fn read_file(path: &Path) -> Option<Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let parsed = match extension {
+        "json" => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        "toml" => Ok(parse_toml(&content)),
+        _ => Err(format!("unsupported file type '.{extension}'")),
+    };
+    parsed.inspect_err(|e| log::warn!("Cannot introspect '{}': {e}", path.display())).ok()
+}
+
+/// The simple values of a TOML document: strings, numbers, booleans and arrays of
+/// them written on one line, in their tables. Inline tables, arrays of tables and
+/// multi-line values are skipped, which is enough for the fields of a manifest.
+fn parse_toml(content: &str) -> Value {
+    let mut root = Map::new();
+    let mut table: Option<Vec<String>> = Some(Vec::new());
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("[[") {
+            table = None;
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            table = header.split_once(']').map(|(header, _)| split_key(header));
+            continue;
+        }
+        let Some((key, raw)) = line.split_once('=') else {
+            continue;
+        };
+        let raw = raw.trim();
+        let value = parse_value(raw).filter(|(_, rest)| {
+            let rest = rest.trim();
+            rest.is_empty() || rest.starts_with('#')
+        });
+        let Some((value, _)) = value else {
+            // Skip the rest of a multi-line string or array.
+            let end = match raw.get(..3) {
+                Some(quotes @ ("\"\"\"" | "'''")) => Some(quotes),
+                _ if raw.starts_with('[') => Some("]"),
+                _ => None,
+            };
+            if let Some(end) = end.filter(|end| !raw[1..].contains(end)) {
+                lines.by_ref().find(|line| line.contains(end));
+            }
+            continue;
+        };
+        let Some(table) = &table else {
+            continue;
+        };
+        let mut path = table.clone();
+        path.extend(split_key(key));
+        insert(&mut root, &path, value);
+    }
+    Value::Object(root)
+}
+
+/// The parts of a dotted key, without quotes.
+fn split_key(key: &str) -> Vec<String> {
+    key.split('.').map(|part| part.trim().trim_matches(['"', '\'']).to_string()).collect()
+}
+
+fn insert(map: &mut Map<String, Value>, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut map = map;
+    for parent in parents {
+        let entry =
+            map.entry(parent.clone()).or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(child) = entry else {
+            return;
+        };
+        map = child;
+    }
+    map.insert(last.clone(), value);
+}
+
+/// Parses the value at the start of `raw`, returning it and the text after it.
+fn parse_value(raw: &str) -> Option<(Value, &str)> {
+    if raw.starts_with("\"\"\"") || raw.starts_with("'''") {
+        return None;
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        // Basic strings use JSON's escapes, except for rare ones like `\e`.
+        let mut escaped = false;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            let end = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            end.then_some(i)
+        })?;
+        let value = serde_json::from_str(&raw[..end + 2]).ok()?;
+        return Some((value, &rest[end + 1..]));
+    }
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let (literal, rest) = rest.split_once('\'')?;
+        return Some((Value::String(literal.to_string()), rest));
+    }
+    if let Some(mut rest) = raw.strip_prefix('[') {
+        let mut items = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Some((Value::Array(items), after));
+            }
+            let (item, after) = parse_value(rest)?;
+            items.push(item);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+    let end = raw.find([',', ']', '#', ' ']).unwrap_or(raw.len());
+    let (token, rest) = raw.split_at(end);
+    let value = match token {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => serde_json::from_str::<serde_json::Number>(&token.replace('_', ""))
+            .ok()
+            .map(Value::Number)?,
+    };
+    Some((value, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_manifests_of_the_host_project() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(
+            project.path().join("Cargo.toml"),
+            r#"# the host
+[package]
+name = "host-app" # inline comment
+version = '1.2.3'
+authors = ["Ann <ann@example.com>", "Bob"]
+description = """
+Multi-line = skipped
+"""
+publish = false
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+anyhow = "1.0"
+"#,
+        )
+        .unwrap();
+        std::fs::write(project.path().join("package.json"), r#"{"name": "web"}"#)
+            .unwrap();
+
+        let config: IndexMap<String, Introspector> = serde_yaml::from_str(
+            "host: cargo\nweb: npm\npy: pyproject\ndeps: {file: Cargo.toml}\n",
+        )
+        .unwrap();
+        let context = introspect(&config, project.path());
+        assert_eq!(
+            context["host"],
+            json!({
+                "name": "host-app",
+                "version": "1.2.3",
+                "authors": ["Ann <ann@example.com>", "Bob"],
+                "publish": false,
+            })
+        );
+        assert_eq!(context["web"], json!({"name": "web"}));
+        assert_eq!(context["py"], json!({}));
+        assert_eq!(context["deps"]["dependencies"], json!({"anyhow": "1.0"}));
+    }
+
+    #[test]
+    fn files_outside_the_output_directory_are_not_read() {
+        let parent = tempfile::tempdir().unwrap();
+        let project = parent.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::write(parent.path().join("secret.json"), r#"{"token": "x"}"#).unwrap();
+
+        let config: IndexMap<String, Introspector> = serde_yaml::from_str(&format!(
+            "up: {{file: ../secret.json}}\nabsolute: {{file: '{}'}}\n",
+            parent.path().join("secret.json").display()
+        ))
+        .unwrap();
+        let context = introspect(&config, &project);
+        assert_eq!(context["up"], json!({}));
+        assert_eq!(context["absolute"], json!({}));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(
+                parent.path().join("secret.json"),
+                project.join("link.json"),
+            )
+            .unwrap();
+            let config: IndexMap<String, Introspector> =
+                serde_yaml::from_str("link: {file: link.json}\n").unwrap();
+            assert_eq!(introspect(&config, &project)["link"], json!({}));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! `--matrix` support: one template rendered into several output directories,
+//! each with its own answer overrides.
+//!
+//! Named entries are recorded under `steps.<name>` so that questions of later
+//! entries can build on their answers and output.
+
+use crate::{
+    cli::context::GenerationContext,
+    error::{Error, Result},
+    generated,
+    template::processor::unsafe_path_reason,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// A single generation target of a matrix file.
+#[derive(Debug, Deserialize)]
+pub struct MatrixEntry {
+    /// Name under which later entries see this one as `steps.<name>`.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Output directory, relative to the `OUTPUT_DIR` argument.
+    pub output: PathBuf,
+    /// Answers merged on top of `--answers` for this entry.
+    #[serde(default)]
+    pub answers: Map<String, Value>,
+}
+
+/// Reads a matrix file: a YAML or JSON list of [`MatrixEntry`].
+pub fn load_matrix(path: &Path) -> Result<Vec<MatrixEntry>> {
+    let content = fs::read_to_string(path)?;
+    let entries: Vec<MatrixEntry> = serde_yaml::from_str(&content).map_err(|e| {
+        Error::Other(anyhow::anyhow!("Invalid matrix file '{}': {e}", path.display()))
+    })?;
+    if entries.is_empty() {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Matrix file '{}' has no entries",
+            path.display()
+        )));
+    }
+    check_outputs(&entries)?;
+    Ok(entries)
+}
+
+/// Fails unless every entry has its own output directory inside `OUTPUT_DIR`.
+fn check_outputs(entries: &[MatrixEntry]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for entry in entries {
+        let output = entry.output.display();
+        if let Some(reason) = unsafe_path_reason(&entry.output) {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Matrix output '{output}' is outside the output directory: {reason}"
+            )));
+        }
+        let normalized: PathBuf = entry
+            .output
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .collect();
+        if !seen.insert(normalized) {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Matrix output '{output}' is used by more than one entry"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Merges an entry's answers over the `--answers` JSON, returning the JSON to pass on.
+pub fn merge_answers(
+    base: Option<&str>,
+    overrides: &Map<String, Value>,
+) -> Result<Option<String>> {
+    if overrides.is_empty() {
+        return Ok(base.map(str::to_string));
+    }
+    let mut merged = match base {
+        Some(raw) => match serde_json::from_str::<Value>(raw)? {
+            Value::Object(map) => map,
+            _ => return Err(Error::AnswersNotObject),
+        },
+        None => Map::new(),
+    };
+    merged.extend(overrides.clone());
+    Ok(Some(Value::Object(merged).to_string()))
+}
+
+/// What later entries see of a finished one: its answers (without secrets), its
+/// output directory and `files`, the paths it generated relative to the output
+/// directory.
+pub fn step_record(context: &GenerationContext, files: &[String]) -> Value {
+    json!({
+        "answers": generated::strip_secret_answers(context.answers(), context.config()),
+        "output": context.output_root().to_string_lossy(),
+        "files": files,
+    })
+}
+
+/// Outcome of every matrix entry, in file order.
+#[derive(Debug, Default)]
+pub struct MatrixReport {
+    results: Vec<(PathBuf, Option<String>)>,
+}
+
+impl MatrixReport {
+    pub fn record(&mut self, output: PathBuf, result: Result<()>) {
+        self.results.push((output, result.err().map(|e| e.to_string())));
+    }
+
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|(_, err)| err.is_some()).count()
+    }
+
+    /// Fails when any entry failed.
+    pub fn into_result(self) -> Result<()> {
+        match self.failures() {
+            0 => Ok(()),
+            failed => Err(Error::Other(anyhow::anyhow!(
+                "{failed} of {} matrix entries failed",
+                self.results.len()
+            ))),
+        }
+    }
+}
+
+impl Display for MatrixReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (output, err) in &self.results {
+            match err {
+                None => writeln!(f, "ok      {}", output.display())?,
+                Some(err) => writeln!(f, "failed  {}: {err}", output.display())?,
+            }
+        }
+        writeln!(
+            f,
+            "\n{} succeeded, {} failed",
+            self.results.len() - self.failures(),
+            self.failures()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn load_matrix_reads_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matrix.yaml");
+        fs::write(
+            &path,
+            "- output: users\n  answers:\n    name: users\n- output: orders\n",
+        )
+        .unwrap();
+
+        let entries = load_matrix(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].output, PathBuf::from("users"));
+        assert_eq!(entries[0].answers["name"], json!("users"));
+        assert!(entries[1].answers.is_empty());
+    }
+
+    #[test]
+    fn load_matrix_rejects_empty_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matrix.yaml");
+        fs::write(&path, "[]").unwrap();
+        assert!(load_matrix(&path).is_err());
+    }
+
+    #[test]
+    fn load_matrix_rejects_escaping_and_shared_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matrix.yaml");
+        for (matrix, error) in [
+            ("- output: ../users\n", "outside the output directory"),
+            ("- output: /tmp/users\n", "outside the output directory"),
+            ("- output: users\n- output: ./users\n", "more than one entry"),
+        ] {
+            fs::write(&path, matrix).unwrap();
+            let err = load_matrix(&path).unwrap_err().to_string();
+            assert!(err.contains(error), "{matrix}: {err}");
+        }
+    }
+
+    #[test]
+    fn merge_answers_overrides_base() {
+        let overrides = json!({"name": "users"}).as_object().unwrap().clone();
+        let merged =
+            merge_answers(Some(r#"{"name": "base", "port": 80}"#), &overrides).unwrap();
+        let merged: Value = serde_json::from_str(&merged.unwrap()).unwrap();
+        assert_eq!(merged, json!({"name": "users", "port": 80}));
+        assert_eq!(
+            merge_answers(Some("{}"), &Map::new()).unwrap().as_deref(),
+            Some("{}")
+        );
+        assert!(merge_answers(Some("[1]"), &overrides).is_err());
+    }
+
+    #[test]
+    fn report_counts_failures() {
+        let mut report = MatrixReport::default();
+        report.record("a".into(), Ok(()));
+        report.record("b".into(), Err(Error::AnswersNotObject));
+        assert!(report.to_string().contains("1 succeeded, 1 failed"));
+        assert!(report.into_result().is_err());
+    }
+}
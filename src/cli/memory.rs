@@ -0,0 +1,161 @@
+//! Answer memory: the answers given at the prompts of a template are remembered in
+//! the user's data directory and offered as the defaults the next time the same
+//! template is generated interactively. `--no-memory` turns it off for a run.
+//!
+//! Templates are identified by their git URL, local path or plugin URL, so every
+//! version of a template shares its memory. Secret answers are never stored.
+
+use crate::{
+    config::{user::data_dir, ConfigV1},
+    error::Result,
+    generated::strip_secret_answers,
+    loader::TemplateSourceInfo,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// The stored answers of one template.
+#[derive(Debug, Serialize, Deserialize)]
+struct Remembered {
+    template: String,
+    answers: Map<String, Value>,
+}
+
+/// Remembered answers, one file per template.
+#[derive(Debug, Clone)]
+pub struct AnswerMemory {
+    dir: PathBuf,
+}
+
+impl AnswerMemory {
+    /// Memory stored under `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Memory stored under `baker/answers` in the user's data directory.
+    pub fn default_location() -> Option<Self> {
+        data_dir().map(|dir| Self::new(dir.join("baker").join("answers")))
+    }
+
+    /// The answers remembered for `template`; empty when there are none or the
+    /// file cannot be read.
+    pub fn recall(&self, template: &TemplateSourceInfo) -> Map<String, Value> {
+        let path = self.path(template);
+        if !path.exists() {
+            return Map::new();
+        }
+        let remembered = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                serde_json::from_str::<Remembered>(&content).map_err(|e| e.to_string())
+            });
+        match remembered {
+            Ok(remembered) => remembered.answers,
+            Err(e) => {
+                log::warn!("Ignoring remembered answers '{}': {e}", path.display());
+                Map::new()
+            }
+        }
+    }
+
+    /// Remembers the answers to `config`'s questions for `template`, except secrets
+    /// and the answers resolved from the `!secret` `references`.
+    pub fn remember(
+        &self,
+        template: &TemplateSourceInfo,
+        config: &ConfigV1,
+        answers: &Value,
+        references: &Map<String, Value>,
+    ) -> Result<()> {
+        let Value::Object(answers) = strip_secret_answers(answers, config) else {
+            return Ok(());
+        };
+        let answers: Map<String, Value> = answers
+            .into_iter()
+            .filter(|(key, _)| {
+                config.questions.contains_key(key) && !references.contains_key(key)
+            })
+            .collect();
+        let remembered = Remembered { template: identity(template).to_string(), answers };
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.path(template);
+        std::fs::write(&path, serde_json::to_string_pretty(&remembered)?)?;
+        log::debug!("Remembered answers in '{}'", path.display());
+        Ok(())
+    }
+
+    fn path(&self, template: &TemplateSourceInfo) -> PathBuf {
+        let digest = hex::encode(Sha256::digest(identity(template).as_bytes()));
+        self.dir.join(format!("{}.json", &digest[..16]))
+    }
+}
+
+/// What identifies a template across its versions.
+pub(crate) fn identity(template: &TemplateSourceInfo) -> &str {
+    match template {
+        TemplateSourceInfo::Git { url, .. } => url,
+        TemplateSourceInfo::Filesystem { path, .. } => path,
+        TemplateSourceInfo::Plugin { url, .. } => url,
+        TemplateSourceInfo::Tar { .. } => "-",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> ConfigV1 {
+        let crate::config::Config::V1(config) = serde_yaml::from_str(
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    help: Name\n  token:\n    type: str\n    help: Token\n    secret: {}\n  region:\n    type: str\n    help: Region\n",
+        )
+        .unwrap();
+        config
+    }
+
+    #[test]
+    fn remembers_answers_per_template_without_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = AnswerMemory::new(dir.path());
+        let template = |commit: &str| TemplateSourceInfo::Git {
+            url: "https://github.com/acme/api.git".to_string(),
+            commit: commit.to_string(),
+            tag: None,
+        };
+        let other = TemplateSourceInfo::Filesystem {
+            path: "/templates/web".to_string(),
+            hash: "abc".to_string(),
+        };
+
+        memory
+            .remember(
+                &template("v1"),
+                &config(),
+                &json!({"name": "users", "token": "s3cret", "region": "eu", "steps": {}}),
+                &Map::from_iter([("region".to_string(), json!("!secret env:REGION"))]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Value::Object(memory.recall(&template("v2"))),
+            json!({"name": "users"})
+        );
+        assert!(memory.recall(&other).is_empty());
+    }
+
+    #[test]
+    fn ignores_unreadable_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = AnswerMemory::new(dir.path());
+        let template = TemplateSourceInfo::Plugin {
+            url: "s3://bucket/template".to_string(),
+            hash: String::new(),
+        };
+        std::fs::write(memory.path(&template), "not json").unwrap();
+
+        assert!(memory.recall(&template).is_empty());
+    }
+}
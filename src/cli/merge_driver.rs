@@ -0,0 +1,281 @@
+//! `baker merge-driver` — a git merge driver for generated projects.
+//!
+//! The file is merged as git would. Conflicts that remain are resolved with the
+//! template: the file is rendered again, at the template version and with the
+//! answers recorded in the generated metadata, and in every conflict the side that
+//! the rendering has, as whole lines between the lines around the conflict, wins.
+//! Conflicts where neither or both sides match are left with markers, and the
+//! driver fails so git reports them.
+
+use crate::{
+    cli::{
+        encrypted,
+        verify::{fetch_locked_template, with_recorded_answers},
+        MergeDriverArgs,
+    },
+    constants::DEFAULT_GENERATED_FILE_NAME,
+    error::{Error, Result},
+    generated,
+    temp::TempDir,
+};
+use std::path::Path;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// Main entry point for `baker merge-driver`.
+pub fn run_merge_driver(args: MergeDriverArgs) -> Result<()> {
+    let ancestor = std::fs::read(&args.ancestor)?;
+    let ours = std::fs::read(&args.current)?;
+    let theirs = std::fs::read(&args.other)?;
+
+    let (content, clean) = merge_file(&args.path, &ancestor, &ours, &theirs)?;
+    if clean {
+        std::fs::write(&args.current, content)?;
+        return Ok(());
+    }
+
+    let file_name = args.generated_file.as_deref().unwrap_or(DEFAULT_GENERATED_FILE_NAME);
+    let rendered = render_recorded(&args.path, file_name).unwrap_or_else(|e| {
+        log::warn!("Cannot render '{}' from its template: {e}", args.path);
+        None
+    });
+    let (content, remaining) = match (rendered, String::from_utf8(content)) {
+        (Some(rendered), Ok(text)) => {
+            let (resolved, remaining) = resolve_conflicts(&text, &rendered);
+            (resolved.into_bytes(), remaining)
+        }
+        (_, Ok(text)) => {
+            let remaining = text.lines().filter(|l| l.starts_with(OURS_MARKER)).count();
+            (text.into_bytes(), remaining)
+        }
+        (_, Err(binary)) => (binary.into_bytes(), 1),
+    };
+    std::fs::write(&args.current, content)?;
+    if remaining == 0 {
+        println!("Resolved the conflicts in '{}' with its template", args.path);
+        return Ok(());
+    }
+    Err(Error::Other(anyhow::anyhow!("{remaining} conflict(s) left in '{}'", args.path)))
+}
+
+/// Three-way merges the contents of the file at `path` with libgit2, returning the
+/// result, with conflict markers if needed, and whether it merged cleanly.
+fn merge_file(
+    path: &str,
+    ancestor: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<(Vec<u8>, bool)> {
+    // The contents are merged as blobs of a scratch repository, so the project's
+    // repository is left untouched.
+    let scratch = TempDir::new("merge")?;
+    let repo = git2::Repository::init_bare(scratch.path())?;
+    let entry = |content: &[u8]| -> Result<git2::IndexEntry> {
+        Ok(git2::IndexEntry {
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode: 0o100644,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id: repo.blob(content)?,
+            flags: 0,
+            flags_extended: 0,
+            path: path.as_bytes().to_vec(),
+        })
+    };
+    let mut options = git2::MergeFileOptions::new();
+    options.our_label("ours").their_label("theirs").style_standard(true);
+    let merged = repo.merge_file_from_index(
+        &entry(ancestor)?,
+        &entry(ours)?,
+        &entry(theirs)?,
+        Some(&mut options),
+    )?;
+    Ok((merged.content().to_vec(), merged.is_automergeable()))
+}
+
+/// Renders the generated file at `path`, relative to the repository root, from the
+/// template recorded in the nearest generated metadata that lists it.
+fn render_recorded(path: &str, file_name: &str) -> Result<Option<String>> {
+    let path = Path::new(path);
+    for dir in path.ancestors().skip(1) {
+        let Ok(output) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        if !dir.join(file_name).exists() {
+            continue;
+        }
+        let mut meta = generated::read(dir, file_name)?;
+        let key = output.to_string_lossy().replace('\\', "/");
+        let Some(provenance) = meta.files.get(&key).cloned() else {
+            continue;
+        };
+        encrypted::decrypt_answers(&mut meta, &dir.join(file_name))?;
+        let (loaded, _tmp) = fetch_locked_template(&meta.template, file_name)?;
+        let rendered = with_recorded_answers(&loaded, dir, &meta, |files| {
+            files.render_file(&provenance.template, output)
+        })??;
+        return Ok(rendered);
+    }
+    Ok(None)
+}
+
+/// What has to surround a side of a conflict in the rendering for it to match.
+#[derive(Debug, Clone, Copy)]
+enum Context<'a> {
+    /// This line.
+    Line(&'a str),
+    /// The start or the end of the file.
+    Edge,
+    /// Anything, next to another conflict.
+    Any,
+}
+
+impl<'a> Context<'a> {
+    fn of(line: Option<&'a str>) -> Self {
+        match line {
+            None => Context::Edge,
+            Some(line) if is_marker(line) => Context::Any,
+            Some(line) => Context::Line(line.trim_end_matches(['\n', '\r'])),
+        }
+    }
+}
+
+fn is_marker(line: &str) -> bool {
+    [OURS_MARKER, SEPARATOR_MARKER, THEIRS_MARKER]
+        .iter()
+        .any(|marker| line.starts_with(marker))
+}
+
+/// Replaces every conflict of `merged` where exactly one side appears in
+/// `rendered` as whole lines, between the same lines that surround the conflict,
+/// with that side. Returns the result and the number of conflicts left.
+fn resolve_conflicts(merged: &str, rendered: &str) -> (String, usize) {
+    let rendered: Vec<&str> = rendered.lines().collect();
+
+    let mut resolved = String::with_capacity(merged.len());
+    let mut remaining = 0;
+    let mut lines = merged.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        if !line.starts_with(OURS_MARKER) {
+            resolved.push_str(line);
+            continue;
+        }
+        let before = Context::of(resolved.split_inclusive('\n').next_back());
+        let mut conflict = line.to_string();
+        let (mut ours, mut theirs) = (String::new(), String::new());
+        let mut in_theirs = false;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            conflict.push_str(line);
+            if line.starts_with(SEPARATOR_MARKER) && !in_theirs {
+                in_theirs = true;
+            } else if line.starts_with(THEIRS_MARKER) && in_theirs {
+                closed = true;
+                break;
+            } else if in_theirs {
+                theirs.push_str(line);
+            } else {
+                ours.push_str(line);
+            }
+        }
+        let after = Context::of(lines.peek().copied());
+        let matches = |side: &str| side_matches(&rendered, before, side, after);
+        match (closed, matches(&ours), matches(&theirs)) {
+            (true, true, false) => resolved.push_str(&ours),
+            (true, false, true) => resolved.push_str(&theirs),
+            _ => {
+                resolved.push_str(&conflict);
+                remaining += 1;
+            }
+        }
+    }
+    (resolved, remaining)
+}
+
+/// Whether the lines of `side` appear in `rendered` as a whole, between `before`
+/// and `after`. Blank sides never match.
+fn side_matches(rendered: &[&str], before: Context, side: &str, after: Context) -> bool {
+    let side: Vec<&str> = side.lines().collect();
+    if side.iter().all(|line| line.trim().is_empty()) || side.len() > rendered.len() {
+        return false;
+    }
+    (0..=rendered.len() - side.len()).any(|start| {
+        let end = start + side.len();
+        rendered[start..end] == side[..]
+            && match before {
+                Context::Line(line) => start > 0 && rendered[start - 1] == line,
+                Context::Edge => start == 0,
+                Context::Any => true,
+            }
+            && match after {
+                Context::Line(line) => rendered.get(end) == Some(&line),
+                Context::Edge => end == rendered.len(),
+                Context::Any => true,
+            }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_cleanly_when_changes_do_not_overlap() {
+        let (merged, clean) = merge_file(
+            "a.txt",
+            b"one\ntwo\nthree\n",
+            b"ONE\ntwo\nthree\n",
+            b"one\ntwo\nTHREE\n",
+        )
+        .unwrap();
+        assert!(clean);
+        assert_eq!(merged, b"ONE\ntwo\nTHREE\n");
+
+        let (merged, clean) =
+            merge_file("a.txt", b"version: 1\n", b"version: 2\n", b"version: 3\n")
+                .unwrap();
+        assert!(!clean);
+        let merged = String::from_utf8(merged).unwrap();
+        assert!(merged
+            .contains("<<<<<<< ours\nversion: 2\n=======\nversion: 3\n>>>>>>> theirs"));
+    }
+
+    #[test]
+    fn conflicts_are_resolved_with_the_side_matching_the_template() {
+        let merged =
+            "a\n<<<<<<< ours\nversion: 2\n=======\nversion: 3\n>>>>>>> theirs\nb\n\
+                      <<<<<<< ours\nx\n=======\ny\n>>>>>>> theirs\n";
+        let (resolved, remaining) = resolve_conflicts(merged, "a\nversion: 3\nb\nz\n");
+        assert_eq!(remaining, 1);
+        assert_eq!(
+            resolved,
+            "a\nversion: 3\nb\n<<<<<<< ours\nx\n=======\ny\n>>>>>>> theirs\n"
+        );
+
+        let (resolved, remaining) = resolve_conflicts(merged, "a\nversion: 2\nb\nx\n");
+        assert_eq!(remaining, 0);
+        assert_eq!(resolved, "a\nversion: 2\nb\nx\n");
+    }
+
+    #[test]
+    fn sides_only_match_whole_lines_in_the_same_place() {
+        let merged =
+            "a\n<<<<<<< ours\nport: 80\n=======\nport: 8080\n>>>>>>> theirs\nb\n";
+
+        // Part of a line, or the right lines elsewhere in the file, are no match.
+        for rendered in ["a\nport: 8080 # default\nb\n", "a\nb\nport: 8080\n"] {
+            let (resolved, remaining) = resolve_conflicts(merged, rendered);
+            assert_eq!((resolved.as_str(), remaining), (merged, 1), "{rendered}");
+        }
+
+        let (resolved, remaining) = resolve_conflicts(merged, "a\r\nport: 8080\r\nb\r\n");
+        assert_eq!((resolved.as_str(), remaining), ("a\nport: 8080\nb\n", 0));
+    }
+}
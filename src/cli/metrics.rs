@@ -0,0 +1,99 @@
+//! Render metrics of a generation: how long each template entry took to render,
+//! how large its output is and how many other templates it pulls in. `--verbose`
+//! runs list the slowest entries, to find the templates that make a generation
+//! slow.
+
+use regex::Regex;
+use std::{fmt::Display, sync::LazyLock, time::Duration};
+
+/// How many of the slowest entries `--verbose` lists.
+pub const SLOWEST_SHOWN: usize = 10;
+
+static INCLUDE_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{%-?\s*(include|import|from|extends)\s").expect("valid include regex")
+});
+
+/// The metrics of one template entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderMetric {
+    /// Template entry relative to the template root.
+    pub template: String,
+    /// Time spent rendering the entry's path and content.
+    pub duration: Duration,
+    /// Size of the rendered content, or of the copied file.
+    pub bytes: u64,
+    /// `include`, `import`, `from` and `extends` tags in the entry's content.
+    pub includes: usize,
+}
+
+/// Number of tags in `source` that pull in another template.
+pub fn count_includes(source: &str) -> usize {
+    INCLUDE_TAG.find_iter(source).count()
+}
+
+/// The slowest entries of a generation, slowest first.
+pub struct SlowestEntries<'a> {
+    metrics: &'a [RenderMetric],
+    count: usize,
+}
+
+impl<'a> SlowestEntries<'a> {
+    pub fn new(metrics: &'a [RenderMetric], count: usize) -> Self {
+        Self { metrics, count }
+    }
+}
+
+impl Display for SlowestEntries<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut slowest: Vec<&RenderMetric> = self.metrics.iter().collect();
+        slowest.sort_by_key(|metric| std::cmp::Reverse(metric.duration));
+        let total: Duration = self.metrics.iter().map(|metric| metric.duration).sum();
+        write!(
+            f,
+            "Rendered {} template entries in {:.1}ms; the slowest:",
+            self.metrics.len(),
+            total.as_secs_f64() * 1000.0
+        )?;
+        for metric in slowest.into_iter().take(self.count) {
+            write!(
+                f,
+                "\n  {:>9.1}ms {:>10} bytes {:>3} includes  {}",
+                metric.duration.as_secs_f64() * 1000.0,
+                metric.bytes,
+                metric.includes,
+                metric.template
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_the_slowest_entries_first() {
+        assert_eq!(
+            count_includes(
+                "{% include 'a.j2' %}{%- from 'm.j2' import x %}{% extends base %}{{ include }}"
+            ),
+            3
+        );
+        let metric = |template: &str, millis| RenderMetric {
+            template: template.to_string(),
+            duration: Duration::from_millis(millis),
+            bytes: 10,
+            includes: 0,
+        };
+        let metrics =
+            [metric("fast.txt", 1), metric("slow.txt", 30), metric("mid.txt", 5)];
+
+        let report = SlowestEntries::new(&metrics, 2).to_string();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Rendered 3 template entries in 36.0ms; the slowest:");
+        assert!(lines[1].ends_with("slow.txt"));
+        assert!(lines[2].ends_with("mid.txt"));
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -1,14 +1,57 @@
+pub mod answer_source;
 pub mod answers;
 pub mod args;
+pub mod audit;
+pub mod changelog;
 pub mod context;
+pub mod docs;
+pub mod encrypted;
+pub mod filters;
+pub mod golden;
+pub mod grep;
 pub mod hooks;
+pub mod info;
+pub mod introspect;
+pub mod matrix;
+pub mod memory;
+pub mod merge_driver;
+pub mod metrics;
+pub mod observer;
+pub mod operations_log;
+pub mod preview;
 pub mod processor;
+pub mod remote;
+pub mod render;
 pub mod runner;
+pub mod self_update;
+pub mod shell;
+pub mod transformers;
+pub mod trust;
 pub mod update;
+pub mod validate;
+pub mod verify;
+pub mod workspace;
 
 pub use args::{
-    get_args, get_log_level_from_verbose, Args, Commands, GenerateArgs, SkipConfirm,
-    UpdateArgs,
+    get_args, get_log_level_from_verbose, Args, AuditArgs, Commands, CopyMode, DocsArgs,
+    DocsFormat, FiltersArgs, GenerateArgs, GrepArgs, InfoArgs, MergeDriverArgs,
+    RenderArgs, SelfUpdateArgs, ShellArgs, SkipConfirm, TestArgs, TrustArgs,
+    TrustCommand, UpdateArgs, ValidateArgs, VerifyArgs, WorkspaceArgs, WorkspaceCommand,
+    WorkspaceUpdateArgs,
 };
+pub use audit::run_audit;
+pub use docs::run_docs;
+pub use filters::run_filters;
+pub use golden::run_golden;
+pub use grep::run_grep;
+pub use info::run_info;
+pub use merge_driver::run_merge_driver;
+pub use render::run_render;
 pub use runner::run;
+pub use self_update::{notify_if_outdated, run_self_update};
+pub use shell::run_shell;
+pub use trust::run_trust;
 pub use update::{run_update, run_update_in_dir};
+pub use validate::run_validate;
+pub use verify::run_verify;
+pub use workspace::run_workspace;
@@ -0,0 +1,103 @@
+//! Generation observers: callbacks through which embedding applications follow a
+//! generation run, e.g. to drive their own progress UI or gather metrics, instead
+//! of parsing Baker's logs.
+//!
+//! Observers are registered with [`Runner::with_observer`] and notified in the
+//! order they were registered:
+//!
+//! ```ignore
+//! use baker::cli::observer::GenerationObserver;
+//! use baker::cli::runner::Runner;
+//!
+//! struct Progress;
+//!
+//! impl GenerationObserver for Progress {
+//!     fn on_file_written(&self, target: &std::path::Path) {
+//!         println!("wrote {}", target.display());
+//!     }
+//! }
+//!
+//! Runner::new(args).with_observer(Progress).run()?;
+//! ```
+//!
+//! [`Runner::with_observer`]: crate::cli::runner::Runner::with_observer
+
+use crate::error::Error;
+use serde_json::Value;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Which hook of the template runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    Pre,
+    Post,
+}
+
+impl Display for HookKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookKind::Pre => write!(f, "pre-hook"),
+            HookKind::Post => write!(f, "post-hook"),
+        }
+    }
+}
+
+/// What a finished generation produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenerationSummary {
+    /// Directory the project was generated into.
+    pub output_root: PathBuf,
+    /// Number of files the template produced.
+    pub files: usize,
+    pub dry_run: bool,
+    /// Time from the pre-hook to the generated metadata.
+    pub elapsed: Duration,
+}
+
+/// Callbacks for the steps of a generation run. Every method does nothing by
+/// default, so observers only implement the events they need.
+pub trait GenerationObserver {
+    /// A question got its final answer. Answers of secret questions, and answers
+    /// resolved from `!secret` references, are masked.
+    fn on_question_answered(&self, _key: &str, _answer: &Value) {}
+
+    /// A file was written to `target`. Not called in dry runs, nor for files that
+    /// were skipped.
+    fn on_file_written(&self, _target: &Path) {}
+
+    /// The `hook` script at `script` is about to run.
+    fn on_hook_start(&self, _hook: HookKind, _script: &Path) {}
+
+    /// The `hook` script finished, successfully or not, after `elapsed`.
+    fn on_hook_end(&self, _hook: HookKind, _succeeded: bool, _elapsed: Duration) {}
+
+    /// The generation finished, with its summary or the error that stopped it.
+    fn on_finish(&self, _result: Result<&GenerationSummary, &Error>) {}
+}
+
+/// Notifies every observer in turn.
+impl GenerationObserver for Vec<Box<dyn GenerationObserver>> {
+    fn on_question_answered(&self, key: &str, answer: &Value) {
+        self.iter().for_each(|o| o.on_question_answered(key, answer));
+    }
+
+    fn on_file_written(&self, target: &Path) {
+        self.iter().for_each(|o| o.on_file_written(target));
+    }
+
+    fn on_hook_start(&self, hook: HookKind, script: &Path) {
+        self.iter().for_each(|o| o.on_hook_start(hook, script));
+    }
+
+    fn on_hook_end(&self, hook: HookKind, succeeded: bool, elapsed: Duration) {
+        self.iter().for_each(|o| o.on_hook_end(hook, succeeded, elapsed));
+    }
+
+    fn on_finish(&self, result: Result<&GenerationSummary, &Error>) {
+        self.iter().for_each(|o| o.on_finish(result));
+    }
+}
@@ -0,0 +1,173 @@
+//! JSON-lines log of every template operation, written with `--operations-log`.
+//!
+//! Each line describes one output of a template entry: the resolved paths, what
+//! happened to it and why, how long the entry took and how many bytes it carried.
+//! The console reporter keeps logging the human-readable message alongside.
+
+use crate::error::Result;
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// What happened to a single output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    /// The file or directory was written (or would be, in a dry run).
+    Written,
+    /// The existing file was merged with conflict markers.
+    Merged,
+    /// Nothing was written; `reason` says why.
+    Skipped,
+    /// The entry matches `.bakerignore`.
+    Ignored,
+    /// Rendering or writing failed; `reason` holds the error.
+    Failed,
+}
+
+/// The decision for an output together with the reason, when there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub decision: Decision,
+    pub reason: Option<String>,
+}
+
+impl Outcome {
+    pub fn written() -> Self {
+        Self { decision: Decision::Written, reason: None }
+    }
+
+    pub fn merged() -> Self {
+        Self {
+            decision: Decision::Merged,
+            reason: Some("conflict markers written".to_string()),
+        }
+    }
+
+    pub fn skipped(reason: &str) -> Self {
+        Self { decision: Decision::Skipped, reason: Some(reason.to_string()) }
+    }
+
+    /// Whether the output was written, with or without conflict markers.
+    pub fn is_written(&self) -> bool {
+        matches!(self.decision, Decision::Written | Decision::Merged)
+    }
+}
+
+/// One line of the operations log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OperationRecord {
+    /// Template entry relative to the template root.
+    pub template: String,
+    /// `write`, `copy`, `append`, `create_directory` or `ignore`; absent when
+    /// rendering failed.
+    pub operation: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub decision: Decision,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Time spent rendering and handling the template entry, in milliseconds.
+    pub duration_ms: f64,
+    /// Size of the rendered content or of the copied file.
+    pub bytes: u64,
+    pub dry_run: bool,
+    /// Position of the loop iteration that emitted the file, for loop templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loop_index: Option<usize>,
+    /// The loop variable of that iteration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loop_item: Option<serde_json::Value>,
+}
+
+/// Sink writing [`OperationRecord`]s as JSON lines.
+pub struct OperationsLog {
+    writer: RefCell<Box<dyn Write>>,
+}
+
+impl OperationsLog {
+    /// Creates (or truncates) the log file at `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self { writer: RefCell::new(Box::new(writer)) }
+    }
+
+    /// Appends `record` as a single line.
+    pub fn record(&self, record: &OperationRecord) -> Result<()> {
+        let mut writer = self.writer.borrow_mut();
+        serde_json::to_writer(&mut *writer, record)?;
+        writer.write_all(b"\n")?;
+        Ok(writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn records_are_written_as_json_lines() {
+        let buffer = SharedBuffer::default();
+        let log = OperationsLog::new(buffer.clone());
+        let record = OperationRecord {
+            template: "README.md.baker.j2".to_string(),
+            operation: Some("write"),
+            source: None,
+            target: Some("/out/README.md".to_string()),
+            decision: Decision::Skipped,
+            reason: Some("target already exists".to_string()),
+            duration_ms: 1.5,
+            bytes: 42,
+            dry_run: false,
+            loop_index: None,
+            loop_item: None,
+        };
+        log.record(&record).unwrap();
+        log.record(&OperationRecord {
+            decision: Decision::Written,
+            reason: None,
+            loop_index: Some(1),
+            loop_item: Some(serde_json::json!({"name": "web"})),
+            ..record
+        })
+        .unwrap();
+
+        let output = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        let lines: Vec<serde_json::Value> =
+            output.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["decision"], "skipped");
+        assert_eq!(lines[0]["reason"], "target already exists");
+        assert_eq!(lines[0]["bytes"], 42);
+        assert_eq!(lines[1]["decision"], "written");
+        assert!(lines[1].get("reason").is_none());
+        assert!(lines[1].get("source").is_none());
+        assert!(lines[0].get("loop_index").is_none());
+        assert_eq!(lines[1]["loop_index"], 1);
+        assert_eq!(lines[1]["loop_item"]["name"], "web");
+    }
+}
@@ -0,0 +1,78 @@
+//! `--show-content`: prints the content a dry run would write to the files matching a
+//! glob, or saves it under a separate directory.
+
+use crate::error::{Error, Result};
+use globset::{Glob, GlobMatcher};
+use std::path::{Path, PathBuf};
+
+/// Shows the would-be content of files whose output path matches a glob.
+pub struct ContentPreview {
+    matcher: GlobMatcher,
+    /// Directory receiving the previews; stdout when unset.
+    destination: Option<PathBuf>,
+}
+
+impl ContentPreview {
+    /// `glob` is matched against output paths relative to the output root.
+    pub fn new(glob: &str, destination: Option<PathBuf>) -> Result<Self> {
+        let matcher = Glob::new(glob)
+            .map_err(|e| {
+                Error::Other(anyhow::anyhow!("Invalid --show-content glob '{glob}': {e}"))
+            })?
+            .compile_matcher();
+        Ok(Self { matcher, destination })
+    }
+
+    /// Shows `content` if `relative` (the output path) matches.
+    pub fn show(&self, relative: &Path, content: &str) -> Result<()> {
+        if !self.matcher.is_match(relative) {
+            return Ok(());
+        }
+        match &self.destination {
+            Some(dir) => {
+                let path = dir.join(relative);
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, content)?;
+                log::info!(
+                    "[DRY RUN] Content of '{}' saved to '{}'",
+                    relative.display(),
+                    path.display()
+                );
+            }
+            None => {
+                println!("==> {} <==", relative.display());
+                println!("{content}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_only_matching_files_under_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let preview =
+            ContentPreview::new("src/**/*.rs", Some(dir.path().to_path_buf())).unwrap();
+
+        preview.show(Path::new("src/cli/main.rs"), "fn main() {}").unwrap();
+        preview.show(Path::new("README.md"), "# readme").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/cli/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(!dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_globs() {
+        let err = ContentPreview::new("src/[", None).err().unwrap();
+        assert!(err.to_string().contains("Invalid --show-content glob"));
+    }
+}
@@ -1,20 +1,114 @@
 use crate::{
-    cli::{context::GenerationContext, SkipConfirm},
+    atomic,
+    cli::{
+        context::GenerationContext,
+        metrics::{count_includes, RenderMetric},
+        observer::GenerationObserver,
+        operations_log::{Decision, OperationRecord, OperationsLog, Outcome},
+        preview::ContentPreview,
+        CopyMode,
+    },
     conflict::apply_conflict_markers,
+    constants::DEFAULT_COPY_BUFFER_SIZE,
+    editorconfig::{keeps_indentation, EditorConfig, EndOfLine},
     error::{Error, Result},
+    generated::{content_hash, file_hash, FileProvenance},
     prompt::confirm,
     template::{
         operation::{TemplateOperation, WriteOp},
-        processor::TemplateProcessor,
+        processor::{TemplateProcessor, INVALID_RENDERED_PATH},
+    },
+    winpath::long_path,
+};
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
     },
+    time::{Duration, Instant},
 };
-use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Progress of a generation run, reported after each template entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// Entries handled so far, including the current one.
+    pub processed: usize,
+    /// Total number of entries in the template directory.
+    pub total: usize,
+    /// Path of the current entry relative to the template root.
+    pub current: String,
+}
+
+/// Receives a [`Progress`] report after each template entry.
+pub type ProgressCallback<'a> = Box<dyn Fn(&Progress) + 'a>;
+
+/// Decides whether a generated file replaces the existing file at its target.
+///
+/// It is only consulted when the target exists and `--skip-confirms` does not cover
+/// overwrites. The CLI asks on the terminal with [`PromptResolver`]; embedders
+/// supply a policy, e.g. an [`OverwritePolicy`] or a closure.
+pub trait ConflictResolver {
+    /// Whether `target` is overwritten. Declining skips the file.
+    fn overwrite(&self, target: &Path) -> Result<bool>;
+}
+
+impl<F: Fn(&Path) -> Result<bool>> ConflictResolver for F {
+    fn overwrite(&self, target: &Path) -> Result<bool> {
+        self(target)
+    }
+}
+
+/// Asks on the terminal before overwriting each file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PromptResolver;
+
+impl ConflictResolver for PromptResolver {
+    fn overwrite(&self, target: &Path) -> Result<bool> {
+        confirm(false, format!("Overwrite {}?", target.display()))
+    }
+}
+
+/// Overwrites every existing file, or none, without asking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    Always,
+    Never,
+}
+
+impl ConflictResolver for OverwritePolicy {
+    fn overwrite(&self, _target: &Path) -> Result<bool> {
+        Ok(*self == OverwritePolicy::Always)
+    }
+}
+
 /// Handles the processing of template files and directories
 pub struct FileProcessor<'a> {
     processor: TemplateProcessor<'a, PathBuf>,
     context: &'a GenerationContext,
+    progress: Option<ProgressCallback<'a>>,
+    cancel: Option<Arc<AtomicBool>>,
+    operations_log: Option<&'a OperationsLog>,
+    content_preview: Option<&'a ContentPreview>,
+    observer: Option<&'a dyn GenerationObserver>,
+    resolver: &'a dyn ConflictResolver,
+    /// Report the failed entries at the end instead of only logging them.
+    keep_going: bool,
+    /// Entries that failed to render or write, with their error.
+    failures: RefCell<Vec<(String, Error)>>,
+    /// Provenance of every file the template produced, keyed by output path.
+    provenance: RefCell<BTreeMap<String, FileProvenance>>,
+    /// Render metrics of the entries that produced output.
+    metrics: RefCell<Vec<RenderMetric>>,
+    /// Output paths produced so far, keyed by their lowercase form.
+    folded_outputs: RefCell<HashMap<String, String>>,
+    /// The template's `.editorconfig`, when the config enables it.
+    editorconfig: Option<EditorConfig>,
+    /// Size in bytes of the buffer copied files are read with.
+    copy_buffer_size: usize,
 }
 
 impl<'a> FileProcessor<'a> {
@@ -22,14 +116,214 @@ impl<'a> FileProcessor<'a> {
         processor: TemplateProcessor<'a, PathBuf>,
         context: &'a GenerationContext,
     ) -> Self {
-        Self { processor, context }
+        Self {
+            processor,
+            context,
+            progress: None,
+            cancel: None,
+            operations_log: None,
+            content_preview: None,
+            observer: None,
+            resolver: &PromptResolver,
+            keep_going: false,
+            failures: RefCell::new(Vec::new()),
+            provenance: RefCell::new(BTreeMap::new()),
+            folded_outputs: RefCell::new(HashMap::new()),
+            metrics: RefCell::new(Vec::new()),
+            editorconfig: context
+                .config()
+                .editorconfig
+                .then(|| EditorConfig::load(context.template_root()))
+                .flatten(),
+            copy_buffer_size: DEFAULT_COPY_BUFFER_SIZE,
+        }
+    }
+
+    /// Calls `callback` after each template entry has been handled.
+    pub fn with_progress(mut self, callback: impl Fn(&Progress) + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Stops processing before the next entry once `cancel` is set.
+    pub fn with_cancellation(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Records every operation in `log` in addition to the console messages.
+    pub fn with_operations_log(mut self, log: &'a OperationsLog) -> Self {
+        self.operations_log = Some(log);
+        self
     }
 
-    /// Processes all files in the template directory
+    /// In a dry run, shows the content that would be written to the files `preview`
+    /// matches.
+    pub fn with_content_preview(mut self, preview: &'a ContentPreview) -> Self {
+        self.content_preview = Some(preview);
+        self
+    }
+
+    /// Notifies `observer` of every file written.
+    pub fn with_observer(mut self, observer: &'a dyn GenerationObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Lets `resolver` decide whether existing files are overwritten, instead of
+    /// asking on the terminal.
+    pub fn with_conflict_resolver(mut self, resolver: &'a dyn ConflictResolver) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Reads copied files `buffer_size` bytes at a time, when they are hashed and
+    /// where the platform has no faster way to copy them.
+    pub fn with_copy_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.copy_buffer_size = buffer_size;
+        self
+    }
+
+    /// Makes [`Self::process_all_files`] fail with every failed entry once all
+    /// entries are done. Without it, failures are only logged. Either way, the
+    /// remaining entries are processed and the files that rendered are written.
+    pub fn with_keep_going(mut self, keep_going: bool) -> Self {
+        self.keep_going = keep_going;
+        self
+    }
+
+    /// Provenance of the files produced by [`Self::process_all_files`], keyed by
+    /// path relative to the output root.
+    pub fn provenance(&self) -> BTreeMap<String, FileProvenance> {
+        self.provenance.borrow().clone()
+    }
+
+    /// Render metrics of the entries [`Self::process_all_files`] rendered to an
+    /// output, in template order.
+    pub fn render_metrics(&self) -> Vec<RenderMetric> {
+        self.metrics.borrow().clone()
+    }
+
+    /// The files the template would produce, without touching the output directory.
+    pub fn plan(&self) -> BTreeMap<String, FileProvenance> {
+        let walker = WalkDir::new(self.context.template_root())
+            .follow_links(self.context.config().follow_symlinks);
+        let mut planned = BTreeMap::new();
+        for entry in walker.into_iter().filter_map(|e| e.ok()) {
+            let template_name = self.get_template_name(entry.path());
+            match self.processor.process(entry.path().to_path_buf()) {
+                Ok(operation) => {
+                    let operation = self.apply_editorconfig(operation);
+                    planned.extend(self.provenance_of(&operation, &template_name))
+                }
+                Err(e) => log::debug!("Not planning '{template_name}': {e}"),
+            }
+        }
+        planned
+    }
+
+    /// The content the template entry `template_name` renders for `output`, a path
+    /// relative to the output root, or `None` when the entry does not produce it.
+    pub fn render_file(
+        &self,
+        template_name: &str,
+        output: &Path,
+    ) -> Result<Option<String>> {
+        let entry = self.context.template_root().join(template_name);
+        let target = self.context.output_root().join(output);
+        let rendered = match self.apply_editorconfig(self.processor.process(entry)?) {
+            TemplateOperation::Write { target: written, content, .. }
+            | TemplateOperation::Append { target: written, content, .. }
+                if written == target =>
+            {
+                Some(content)
+            }
+            TemplateOperation::MultipleWrite { writes } => {
+                writes.into_iter().find(|w| w.target == target).map(|w| w.content)
+            }
+            _ => None,
+        };
+        Ok(rendered)
+    }
+
+    /// Pairs of an earlier output and one of `outputs` whose paths differ only in
+    /// case. Names are already in NFC, so lowercasing is enough to compare them.
+    fn case_collisions(
+        &self,
+        outputs: &[(String, FileProvenance)],
+    ) -> Vec<(String, String)> {
+        let mut folded = self.folded_outputs.borrow_mut();
+        let mut collisions = Vec::new();
+        for (output, _) in outputs {
+            match folded.entry(output.to_lowercase()) {
+                Entry::Occupied(earlier) if earlier.get() != output => {
+                    collisions.push((earlier.get().clone(), output.clone()))
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(entry) => {
+                    entry.insert(output.clone());
+                }
+            }
+        }
+        collisions
+    }
+
+    /// Output files of `operation` with their provenance.
+    fn provenance_of(
+        &self,
+        operation: &TemplateOperation,
+        template_name: &str,
+    ) -> Vec<(String, FileProvenance)> {
+        let record = |target: &Path, hash: String, binary: bool| {
+            let output = target
+                .strip_prefix(self.context.output_root())
+                .unwrap_or(target)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let template = template_name.to_string();
+            (output, FileProvenance { template, hash, binary })
+        };
+        match operation {
+            TemplateOperation::Write { target, content, .. }
+            | TemplateOperation::Append { target, content, .. } => {
+                vec![record(target, content_hash(&self.encode(target, content)), false)]
+            }
+            TemplateOperation::Copy { source, target, .. } => {
+                let (hash, binary) =
+                    file_hash(source, self.copy_buffer_size).unwrap_or_default();
+                vec![record(target, hash, binary)]
+            }
+            TemplateOperation::MultipleWrite { writes } => writes
+                .iter()
+                .map(|w| {
+                    record(
+                        &w.target,
+                        content_hash(&self.encode(&w.target, &w.content)),
+                        false,
+                    )
+                })
+                .collect(),
+            TemplateOperation::CreateDirectory { .. }
+            | TemplateOperation::Ignore { .. } => Vec::new(),
+        }
+    }
+
+    /// Processes all files in the template directory. Every entry is rendered
+    /// before anything is written, so that entries rendering to the same output are
+    /// found while the output directory is still untouched.
     pub fn process_all_files(&self) -> Result<()> {
         let walker = WalkDir::new(self.context.template_root())
             .follow_links(self.context.config().follow_symlinks);
-        for dir_entry in walker {
+        // Collected up front so progress can report a total.
+        let dir_entries: Vec<_> = walker.into_iter().collect();
+        let total = dir_entries.len();
+        let mut rendered = Vec::with_capacity(total);
+        for dir_entry in dir_entries {
+            if self.is_cancelled() {
+                return Err(Error::Aborted(
+                    "Generation cancelled before anything was written".to_string(),
+                ));
+            }
             let entry = match dir_entry {
                 Ok(e) => e,
                 Err(e) => {
@@ -44,35 +338,349 @@ impl<'a> FileProcessor<'a> {
                     return Err(e.into());
                 }
             };
-            let template_entry = entry.path().to_path_buf();
-            let template_name = self.get_template_name(&template_entry);
-            match self.processor.process(template_entry) {
-                Ok(file_operation) => {
-                    let user_confirmed_overwrite = match &file_operation {
-                        TemplateOperation::Ignore { .. } => continue,
-                        _ => match self.handle_file_operation(&file_operation) {
-                            Ok(confirmed) => confirmed,
-                            Err(e) => {
-                                log::error!(
-                                    "Failed to handle file operation for template '{}' ({}): {e}",
-                                    template_name,
-                                    file_operation.error_context()
-                                );
-                                continue;
+            let template_name = self.get_template_name(entry.path());
+            let started = Instant::now();
+            let operation = self
+                .processor
+                .process(entry.path().to_path_buf())
+                .map(|operation| self.apply_editorconfig(operation));
+            let render_time = started.elapsed();
+            if let Ok(operation) = &operation {
+                self.record_metric(entry.path(), &template_name, operation, render_time);
+            }
+            rendered.push((template_name, operation, render_time));
+        }
+        self.check_output_collisions(&rendered)?;
+
+        let total = rendered.len();
+        for (index, (template_name, operation, render_time)) in
+            rendered.into_iter().enumerate()
+        {
+            if self.is_cancelled() {
+                return Err(Error::Aborted(format!(
+                    "Generation cancelled after {index} of {total} entries"
+                )));
+            }
+            self.process_entry(operation, &template_name, render_time);
+            if let Some(progress) = &self.progress {
+                progress(&Progress {
+                    processed: index + 1,
+                    total,
+                    current: template_name,
+                });
+            }
+        }
+        if self.keep_going {
+            self.failure_report()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fails when two template entries, or two iterations of a loop template,
+    /// render to the same output. Appends are left out, since several entries may
+    /// append to one file.
+    fn check_output_collisions(
+        &self,
+        rendered: &[(String, Result<TemplateOperation>, Duration)],
+    ) -> Result<()> {
+        let mut sources: BTreeMap<&Path, Vec<String>> = BTreeMap::new();
+        for (template_name, operation, _) in rendered {
+            match operation {
+                Ok(TemplateOperation::Write { target, .. })
+                | Ok(TemplateOperation::Copy { target, .. }) => {
+                    sources.entry(target).or_default().push(format!("'{template_name}'"))
+                }
+                Ok(TemplateOperation::MultipleWrite { writes }) => {
+                    for write in writes {
+                        let source = match &write.iteration {
+                            Some(iteration) => {
+                                format!("'{template_name}' for {}", iteration.item)
                             }
-                        },
-                    };
-                    let message = file_operation
-                        .get_message(user_confirmed_overwrite, self.context.dry_run());
-                    log::info!("{message}");
+                            None => format!("'{template_name}'"),
+                        };
+                        sources.entry(&write.target).or_default().push(source);
+                    }
                 }
-                Err(e) => match e {
-                    crate::error::Error::ProcessError { .. } => log::warn!("{e}"),
-                    _ => log::error!("{e}"),
-                },
+                _ => {}
+            }
+        }
+        let collisions: Vec<String> = sources
+            .into_iter()
+            .filter(|(_, sources)| sources.len() > 1)
+            .map(|(target, sources)| {
+                format!(
+                    "  - {}: {}",
+                    self.output_path(target).display(),
+                    sources.join(", ")
+                )
+            })
+            .collect();
+        match collisions.is_empty() {
+            true => Ok(()),
+            false => Err(Error::OutputCollisions(collisions)),
+        }
+    }
+
+    /// Records the render metrics of an entry that produces output.
+    fn record_metric(
+        &self,
+        template_entry: &Path,
+        template_name: &str,
+        operation: &TemplateOperation,
+        duration: Duration,
+    ) {
+        let bytes = match operation {
+            TemplateOperation::Write { content, .. }
+            | TemplateOperation::Append { content, .. } => content.len() as u64,
+            TemplateOperation::MultipleWrite { writes } => {
+                writes.iter().map(|write| write.content.len() as u64).sum()
+            }
+            TemplateOperation::Copy { source, .. } => {
+                std::fs::metadata(source).map(|m| m.len()).unwrap_or_default()
+            }
+            TemplateOperation::CreateDirectory { .. }
+            | TemplateOperation::Ignore { .. } => return,
+        };
+        let includes = match operation {
+            TemplateOperation::Copy { .. } => 0,
+            _ => std::fs::read_to_string(template_entry)
+                .map(|source| count_includes(&source))
+                .unwrap_or_default(),
+        };
+        self.metrics.borrow_mut().push(RenderMetric {
+            template: template_name.to_string(),
+            duration,
+            bytes,
+            includes,
+        });
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    /// Fails with every recorded failure, if there is any.
+    fn failure_report(&self) -> Result<()> {
+        let failures = self.failures.borrow();
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(Error::TemplateEntriesFailed(
+            failures
+                .iter()
+                .map(|(template_name, error)| format!("  - {template_name}: {error}"))
+                .collect(),
+        ))
+    }
+
+    /// Handles the rendered operation of a single template entry, logging failures
+    /// and recording them for [`Self::process_all_files`].
+    fn process_entry(
+        &self,
+        rendered: Result<TemplateOperation>,
+        template_name: &str,
+        render_time: Duration,
+    ) {
+        let started = Instant::now();
+        let elapsed = || render_time + started.elapsed();
+        match rendered {
+            Ok(file_operation) => {
+                let outputs = self.provenance_of(&file_operation, template_name);
+                for (earlier, output) in self.case_collisions(&outputs) {
+                    log::warn!(
+                        "'{output}' differs from '{earlier}' only in case; case-insensitive filesystems, as on macOS and Windows, write both to the same file"
+                    );
+                }
+                self.provenance.borrow_mut().extend(outputs);
+                let outcomes = match &file_operation {
+                    TemplateOperation::Ignore { .. } => {
+                        let ignored =
+                            Outcome { decision: Decision::Ignored, reason: None };
+                        self.log_operation(
+                            template_name,
+                            &file_operation,
+                            &[ignored],
+                            elapsed(),
+                        );
+                        return;
+                    }
+                    _ => match self.handle_file_operation(&file_operation) {
+                        Ok(outcomes) => outcomes,
+                        Err(e) => {
+                            log::error!(
+                                "Failed to handle file operation for template '{}' ({}): {e}",
+                                template_name,
+                                file_operation.error_context()
+                            );
+                            let failed = Outcome {
+                                decision: Decision::Failed,
+                                reason: Some(e.to_string()),
+                            };
+                            self.log_operation(
+                                template_name,
+                                &file_operation,
+                                &[failed],
+                                elapsed(),
+                            );
+                            self.failures
+                                .borrow_mut()
+                                .push((template_name.to_string(), e));
+                            return;
+                        }
+                    },
+                };
+                self.log_operation(template_name, &file_operation, &outcomes, elapsed());
+                self.notify_written(&file_operation, &outcomes);
+                let user_confirmed_overwrite =
+                    matches!(file_operation, TemplateOperation::MultipleWrite { .. })
+                        || outcomes.iter().all(Outcome::is_written);
+                let message = file_operation
+                    .get_message(user_confirmed_overwrite, self.context.dry_run());
+                log::info!("{message}");
+            }
+            Err(e) if is_conditional_skip(&e) => {
+                self.log_failure(template_name, &e, elapsed());
+                log::warn!("{e}");
+            }
+            Err(e) => {
+                self.log_failure(template_name, &e, elapsed());
+                log::error!("{e}");
+                self.failures.borrow_mut().push((template_name.to_string(), e));
             }
         }
-        Ok(())
+    }
+
+    /// Writes one operations-log line per output of `operation`. A single outcome is
+    /// applied to every output.
+    fn log_operation(
+        &self,
+        template_name: &str,
+        operation: &TemplateOperation,
+        outcomes: &[Outcome],
+        duration: Duration,
+    ) {
+        let Some(operations_log) = self.operations_log else {
+            return;
+        };
+        // A loop template over an empty list has no outputs to record.
+        if outcomes.is_empty() {
+            return;
+        }
+        let record =
+            |kind, source: Option<&Path>, target: &Path, bytes, outcome: &Outcome| {
+                OperationRecord {
+                    template: template_name.to_string(),
+                    operation: Some(kind),
+                    source: source.map(|s| s.display().to_string()),
+                    target: Some(target.display().to_string()),
+                    decision: outcome.decision,
+                    reason: outcome.reason.clone(),
+                    duration_ms: duration.as_secs_f64() * 1000.0,
+                    bytes,
+                    dry_run: self.context.dry_run(),
+                    loop_index: None,
+                    loop_item: None,
+                }
+            };
+        let outcome_at = |i: usize| &outcomes[i.min(outcomes.len() - 1)];
+        let records = match operation {
+            TemplateOperation::Write { target, content, .. } => {
+                vec![record("write", None, target, content.len() as u64, outcome_at(0))]
+            }
+            TemplateOperation::Copy { source, target, .. } => {
+                let bytes = std::fs::metadata(source).map(|m| m.len()).unwrap_or(0);
+                vec![record("copy", Some(source), target, bytes, outcome_at(0))]
+            }
+            TemplateOperation::CreateDirectory { target, .. } => {
+                vec![record("create_directory", None, target, 0, outcome_at(0))]
+            }
+            TemplateOperation::Append { target, content, .. } => {
+                vec![record("append", None, target, content.len() as u64, outcome_at(0))]
+            }
+            TemplateOperation::Ignore { source } => {
+                let mut ignored =
+                    record("ignore", Some(source), source, 0, outcome_at(0));
+                ignored.target = None;
+                vec![ignored]
+            }
+            TemplateOperation::MultipleWrite { writes } => writes
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    let mut written = record(
+                        "write",
+                        None,
+                        &w.target,
+                        w.content.len() as u64,
+                        outcome_at(i),
+                    );
+                    if let Some(iteration) = &w.iteration {
+                        written.loop_index = Some(iteration.index);
+                        written.loop_item = Some(iteration.item.clone());
+                    }
+                    written
+                })
+                .collect(),
+        };
+        for record in records {
+            if let Err(e) = operations_log.record(&record) {
+                log::warn!("Failed to write the operations log: {e}");
+            }
+        }
+    }
+
+    /// Notifies the observer of the files `operation` wrote, pairing outputs with
+    /// outcomes as [`Self::log_operation`] does.
+    fn notify_written(&self, operation: &TemplateOperation, outcomes: &[Outcome]) {
+        let Some(observer) = self.observer else {
+            return;
+        };
+        if self.context.dry_run() || outcomes.is_empty() {
+            return;
+        }
+        let outcome_at = |i: usize| &outcomes[i.min(outcomes.len() - 1)];
+        match operation {
+            TemplateOperation::Write { target, .. }
+            | TemplateOperation::Copy { target, .. }
+            | TemplateOperation::Append { target, .. } => {
+                if outcome_at(0).is_written() {
+                    observer.on_file_written(target);
+                }
+            }
+            TemplateOperation::MultipleWrite { writes } => {
+                for (i, write) in writes.iter().enumerate() {
+                    if outcome_at(i).is_written() {
+                        observer.on_file_written(&write.target);
+                    }
+                }
+            }
+            TemplateOperation::CreateDirectory { .. }
+            | TemplateOperation::Ignore { .. } => {}
+        }
+    }
+
+    /// Writes an operations-log line for a template entry that failed to render.
+    fn log_failure(&self, template_name: &str, error: &Error, duration: Duration) {
+        let Some(operations_log) = self.operations_log else {
+            return;
+        };
+        let record = OperationRecord {
+            template: template_name.to_string(),
+            operation: None,
+            source: None,
+            target: None,
+            decision: Decision::Failed,
+            reason: Some(error.to_string()),
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            bytes: 0,
+            dry_run: self.context.dry_run(),
+            loop_index: None,
+            loop_item: None,
+        };
+        if let Err(e) = operations_log.record(&record) {
+            log::warn!("Failed to write the operations log: {e}");
+        }
     }
 
     /// Returns the relative path from template root for use in error messages.
@@ -84,25 +692,32 @@ impl<'a> FileProcessor<'a> {
             .unwrap_or_else(|| path.display().to_string().replace('\\', "/"))
     }
 
-    /// Handles a single file operation (write, copy, create directory, or ignore).
-    /// Symlink loop errors are skipped gracefully.
-    fn handle_file_operation(&self, file_operation: &TemplateOperation) -> Result<bool> {
+    /// Handles a single file operation (write, copy, create directory, or ignore),
+    /// returning the outcome of each output. Symlink loop errors are skipped gracefully.
+    fn handle_file_operation(
+        &self,
+        file_operation: &TemplateOperation,
+    ) -> Result<Vec<Outcome>> {
         log::debug!("Handling file operation: {file_operation:?}");
-        match file_operation {
+        let outcome = match file_operation {
             TemplateOperation::Write { target, target_exists, content, .. } => {
-                self.handle_write(target, *target_exists, content)
+                self.handle_write(target, *target_exists, content)?
             }
             TemplateOperation::Copy { target, target_exists, source, .. } => {
-                self.handle_copy(source, target, *target_exists)
+                self.handle_copy(source, target, *target_exists)?
             }
             TemplateOperation::CreateDirectory { target, target_exists } => {
-                self.handle_create_dir(target, *target_exists)
+                self.handle_create_dir(target, *target_exists)?
+            }
+            TemplateOperation::Append { target, target_exists, content } => {
+                self.handle_append(target, *target_exists, content)?
             }
-            TemplateOperation::Ignore { .. } => Ok(true),
+            TemplateOperation::Ignore { .. } => Outcome::written(),
             TemplateOperation::MultipleWrite { writes, .. } => {
-                self.handle_multiple_write(writes)
+                return self.handle_multiple_write(writes)
             }
-        }
+        };
+        Ok(vec![outcome])
     }
 
     /// Writes content to a target file.
@@ -115,34 +730,33 @@ impl<'a> FileProcessor<'a> {
         target: &Path,
         target_exists: bool,
         content: &str,
-    ) -> Result<bool> {
+    ) -> Result<Outcome> {
         if self.context.conflict_mode() && target_exists {
-            if let Ok(raw) = std::fs::read_to_string(target) {
-                let existing = normalize_line_endings(&raw);
+            if let Ok(existing) = self.read_existing(target) {
                 if has_unresolved_conflict_markers(&existing) {
                     log::warn!(
                         "Skipping '{}': file already contains unresolved conflict markers.",
                         target.display()
                     );
-                    return Ok(false);
+                    return Ok(Outcome::skipped(UNRESOLVED_CONFLICTS));
                 }
                 if existing != content {
                     let style = self.context.conflict_style();
                     let merged = apply_conflict_markers(&existing, content, style);
                     self.write_file(&merged, target)?;
                     log::info!("Conflict markers written to '{}'", target.display());
-                    return Ok(true);
+                    return Ok(Outcome::merged());
                 }
                 log::debug!("Skipping unchanged file '{}'", target.display());
-                return Ok(false);
+                return Ok(Outcome::skipped(UNCHANGED));
             }
         }
 
-        let user_confirmed = self.confirm_overwrite(target, target_exists)?;
-        if user_confirmed {
+        let outcome = self.confirm_overwrite(target, target_exists)?;
+        if outcome.is_written() {
             self.write_file(content, target)?;
         }
-        Ok(user_confirmed)
+        Ok(outcome)
     }
 
     /// Copies a file from source to target.
@@ -153,40 +767,65 @@ impl<'a> FileProcessor<'a> {
         source: &Path,
         target: &Path,
         target_exists: bool,
-    ) -> Result<bool> {
+    ) -> Result<Outcome> {
         if self.context.conflict_mode() && target_exists {
             log::warn!(
                 "Overwriting binary file '{}' during update (cannot add conflict markers).",
                 target.display()
             );
             self.copy_file(source, target)?;
-            return Ok(true);
+            return Ok(Outcome::written());
         }
 
-        let user_confirmed = self.confirm_overwrite(target, target_exists)?;
-        if user_confirmed {
+        let outcome = self.confirm_overwrite(target, target_exists)?;
+        if outcome.is_written() {
             self.copy_file(source, target)?;
         }
-        Ok(user_confirmed)
+        Ok(outcome)
     }
 
-    fn handle_create_dir(&self, target: &Path, target_exists: bool) -> Result<bool> {
-        if !target_exists {
-            self.create_dir_all(target)?;
+    fn handle_create_dir(&self, target: &Path, target_exists: bool) -> Result<Outcome> {
+        if target_exists {
+            return Ok(Outcome::skipped("directory already exists"));
+        }
+        self.create_dir_all(target)?;
+        Ok(Outcome::written())
+    }
+
+    /// Appends content to the target file, creating it when missing. Content the
+    /// file already contains is not appended again, so that regenerating or updating
+    /// does not repeat it.
+    fn handle_append(
+        &self,
+        target: &Path,
+        target_exists: bool,
+        content: &str,
+    ) -> Result<Outcome> {
+        let existing = match target_exists {
+            true => self.read_existing(target)?,
+            false => String::new(),
+        };
+        if !content.is_empty() && existing.contains(content) {
+            log::debug!("Skipping append to '{}': already present", target.display());
+            return Ok(Outcome::skipped("content already present"));
         }
-        Ok(true)
+        let separator =
+            if existing.is_empty() || existing.ends_with('\n') { "" } else { "\n" };
+        self.write_file(&format!("{existing}{separator}{content}"), target)?;
+        Ok(Outcome::written())
     }
 
-    fn handle_multiple_write(&self, writes: &[WriteOp]) -> Result<bool> {
+    fn handle_multiple_write(&self, writes: &[WriteOp]) -> Result<Vec<Outcome>> {
+        let mut outcomes = Vec::with_capacity(writes.len());
         for write in writes {
             if self.context.conflict_mode() && write.target_exists {
-                if let Ok(raw) = std::fs::read_to_string(&write.target) {
-                    let existing = normalize_line_endings(&raw);
+                if let Ok(existing) = self.read_existing(&write.target) {
                     if has_unresolved_conflict_markers(&existing) {
                         log::warn!(
                             "Skipping '{}': file already contains unresolved conflict markers.",
                             write.target.display()
                         );
+                        outcomes.push(Outcome::skipped(UNRESOLVED_CONFLICTS));
                         continue;
                     }
                     if existing != write.content {
@@ -198,31 +837,39 @@ impl<'a> FileProcessor<'a> {
                             "Conflict markers written to '{}'",
                             write.target.display()
                         );
+                        outcomes.push(Outcome::merged());
                     } else {
                         log::debug!(
                             "Skipping unchanged file '{}'",
                             write.target.display()
                         );
+                        outcomes.push(Outcome::skipped(UNCHANGED));
                     }
                     continue;
                 }
             }
-            let user_confirmed =
-                self.confirm_overwrite(&write.target, write.target_exists)?;
-            if user_confirmed {
+            let outcome = self.confirm_overwrite(&write.target, write.target_exists)?;
+            if outcome.is_written() {
                 self.write_file(&write.content, &write.target)?;
             }
+            outcomes.push(outcome);
         }
-        Ok(true)
+        Ok(outcomes)
     }
 
-    fn confirm_overwrite(&self, target: &Path, target_exists: bool) -> Result<bool> {
-        let skip_prompt = self.should_skip_overwrite_prompt(target_exists);
-        confirm(skip_prompt, format!("Overwrite {}?", target.display()))
+    fn confirm_overwrite(&self, target: &Path, target_exists: bool) -> Result<Outcome> {
+        if self.should_skip_overwrite_prompt(target, target_exists)
+            || self.resolver.overwrite(target)?
+        {
+            Ok(Outcome::written())
+        } else {
+            Ok(Outcome::skipped("overwrite declined"))
+        }
     }
 
     /// Copy a file from source to destination, creating parent directories if needed.
     fn copy_file<P: AsRef<Path>>(&self, source_path: P, dest_path: P) -> Result<()> {
+        let dest_path = long_path(dest_path.as_ref());
         let dest_path = dest_path.as_ref();
         let source_path = source_path.as_ref();
 
@@ -243,7 +890,26 @@ impl<'a> FileProcessor<'a> {
             }
         }
 
-        Ok(std::fs::copy(source_path, dest_path).map(|_| ())?)
+        self.copy_regular_file(source_path, dest_path)
+    }
+
+    /// Copies a regular file, or hard-links it with [`CopyMode::Hardlink`], falling
+    /// back to a copy when linking fails, e.g. across filesystems.
+    fn copy_regular_file(&self, source_path: &Path, dest_path: &Path) -> Result<()> {
+        if self.context.copy_mode() == CopyMode::Hardlink {
+            if dest_path.exists() || dest_path.is_symlink() {
+                std::fs::remove_file(dest_path)?;
+            }
+            match std::fs::hard_link(source_path, dest_path) {
+                Ok(()) => return Ok(()),
+                Err(e) => log::debug!(
+                    "Cannot hard-link '{}' ({e}), copying instead",
+                    source_path.display()
+                ),
+            }
+        }
+        let durability = self.context.config().durability;
+        Ok(atomic::copy(source_path, dest_path, durability, self.copy_buffer_size)?)
     }
 
     /// When follow_symlinks is enabled, copy the content the symlink points to.
@@ -258,8 +924,7 @@ impl<'a> FileProcessor<'a> {
         };
         let target_meta = std::fs::metadata(&resolved_target)?;
         if target_meta.is_file() {
-            std::fs::copy(&resolved_target, dest_path)?;
-            return Ok(());
+            return self.copy_regular_file(&resolved_target, dest_path);
         }
         self.copy_symlink(source_link, dest_path)
     }
@@ -290,12 +955,88 @@ impl<'a> FileProcessor<'a> {
         Ok(())
     }
 
+    /// Formats the rendered content of `operation` with the `.editorconfig`
+    /// properties of its target.
+    fn apply_editorconfig(&self, operation: TemplateOperation) -> TemplateOperation {
+        let Some(editorconfig) = &self.editorconfig else {
+            return operation;
+        };
+        let format = |target: &Path, content: String| {
+            let mut properties = editorconfig.properties(self.output_path(target));
+            if keeps_indentation(target, &content) {
+                properties.indent_style = None;
+            }
+            properties.format(&content)
+        };
+        match operation {
+            TemplateOperation::Write { target, content, target_exists } => {
+                let content = format(&target, content);
+                TemplateOperation::Write { target, content, target_exists }
+            }
+            TemplateOperation::Append { target, content, target_exists } => {
+                let content = format(&target, content);
+                TemplateOperation::Append { target, content, target_exists }
+            }
+            TemplateOperation::MultipleWrite { mut writes } => {
+                for write in &mut writes {
+                    write.content =
+                        format(&write.target, std::mem::take(&mut write.content));
+                }
+                TemplateOperation::MultipleWrite { writes }
+            }
+            operation => operation,
+        }
+    }
+
+    /// `target` relative to the output root.
+    fn output_path<'p>(&self, target: &'p Path) -> &'p Path {
+        target.strip_prefix(self.context.output_root()).unwrap_or(target)
+    }
+
+    /// The content of the existing file at `target`, decoded with the charset of
+    /// the `.editorconfig`, with `\n` line endings.
+    fn read_existing(&self, target: &Path) -> Result<String> {
+        let bytes = std::fs::read(long_path(target))?;
+        let content = match &self.editorconfig {
+            Some(editorconfig) => {
+                editorconfig.properties(self.output_path(target)).decode(&bytes)
+            }
+            None => String::from_utf8(bytes).ok(),
+        };
+        match content {
+            Some(content) => Ok(normalize_line_endings(&content)),
+            None => Err(Error::Other(anyhow::anyhow!(
+                "'{}' is not text in the expected charset",
+                target.display()
+            ))),
+        }
+    }
+
+    /// The bytes written for `content` at `target`: with the line endings and
+    /// charset of the `.editorconfig`, or native line endings without one.
+    fn encode(&self, target: &Path, content: &str) -> Vec<u8> {
+        match &self.editorconfig {
+            Some(editorconfig) => {
+                let native = if cfg!(windows) { EndOfLine::Crlf } else { EndOfLine::Lf };
+                editorconfig.properties(self.output_path(target)).encode(content, native)
+            }
+            None => to_native_line_endings(content).into_bytes(),
+        }
+    }
+
     /// Write content to a file, creating parent directories if needed.
-    /// Line endings are converted to the platform default before writing.
+    /// Line endings are converted to the platform default before writing, unless
+    /// the `.editorconfig` sets them.
     fn write_file<P: AsRef<Path>>(&self, content: &str, dest_path: P) -> Result<()> {
         let dest_path = dest_path.as_ref();
 
         if self.context.dry_run() {
+            if let Some(preview) = self.content_preview {
+                let relative = dest_path
+                    .strip_prefix(self.context.output_root())
+                    .unwrap_or(dest_path);
+                preview.show(relative, content)?;
+            }
             return Ok(());
         }
 
@@ -303,8 +1044,9 @@ impl<'a> FileProcessor<'a> {
             self.create_dir_all(parent)?;
         }
 
-        let native = to_native_line_endings(content);
-        std::fs::write(dest_path, native).map_err(Error::from)
+        let durability = self.context.config().durability;
+        let content = self.encode(dest_path, content);
+        atomic::write(&long_path(dest_path), &content, durability).map_err(Error::from)
     }
 
     /// Create directory and all parent directories if they don't exist.
@@ -313,19 +1055,34 @@ impl<'a> FileProcessor<'a> {
             return Ok(());
         }
 
-        std::fs::create_dir_all(dest_path.as_ref()).map_err(Error::from)
+        std::fs::create_dir_all(long_path(dest_path.as_ref())).map_err(Error::from)
     }
 
-    /// Determines if overwrite prompts should be skipped
-    fn should_skip_overwrite_prompt(&self, target_exists: bool) -> bool {
-        self.context.skip_confirms().contains(&SkipConfirm::All)
-            || self.context.skip_confirms().contains(&SkipConfirm::Overwrite)
-            || !target_exists
+    /// Determines if the overwrite prompt of `target` should be skipped
+    fn should_skip_overwrite_prompt(&self, target: &Path, target_exists: bool) -> bool {
+        let relative = target.strip_prefix(self.context.output_root()).unwrap_or(target);
+        !target_exists
+            || self
+                .context
+                .skip_confirms()
+                .iter()
+                .any(|skip| skip.skips_overwrite(relative))
     }
 }
 
+/// Skip reason for files that still contain conflict markers.
+const UNRESOLVED_CONFLICTS: &str = "unresolved conflict markers";
+/// Skip reason for files whose content would not change.
+const UNCHANGED: &str = "unchanged";
+
 /// Returns `true` if `content` contains a baker conflict marker that has not
 /// yet been resolved (i.e. `<<<<<<< current` is still present).
+/// Whether `error` only means that the entry's path rendered empty, which is how
+/// templates leave files out.
+fn is_conditional_skip(error: &Error) -> bool {
+    matches!(error, Error::ProcessError { e, .. } if e == INVALID_RENDERED_PATH)
+}
+
 fn has_unresolved_conflict_markers(content: &str) -> bool {
     content.contains("<<<<<<< current")
 }
@@ -353,6 +1110,7 @@ fn to_native_line_endings(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::SkipConfirm;
     use crate::renderer::MiniJinjaRenderer;
     use globset::GlobSetBuilder;
     use indexmap::IndexMap;
@@ -386,6 +1144,31 @@ mod tests {
                 follow_symlinks,
                 generated_file_name: None,
                 conflict_marker_style: None,
+                answer_precedence: None,
+                pre_hook_working_dir: None,
+                post_hook_working_dir: None,
+                hook_prompts: false,
+                output_subdir: None,
+                hook_answers_schema: None,
+                namespace_hook_answers: false,
+                suffixes: Default::default(),
+                answer_transformers: Vec::new(),
+                context_alias: None,
+                render_all: false,
+                copier_answers: false,
+                validations: Vec::new(),
+                strict: false,
+                editorconfig: false,
+                introspect: IndexMap::new(),
+                prompt_theme: Default::default(),
+                pre_hook_image: None,
+                post_hook_image: None,
+                safe_expressions: false,
+                durability: Default::default(),
+                windows_paths: Default::default(),
+                non_ascii_names: Default::default(),
+                undefined_behavior: Default::default(),
+                questions_files: Vec::new(),
             },
             skip_confirms,
             false,
@@ -399,19 +1182,184 @@ mod tests {
         (template_root, output_root, FileProcessor::new(processor, context))
     }
 
+    #[test]
+    fn operations_log_records_decision_and_bytes_per_output() {
+        let (template_root, output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        std::fs::write(template_root.path().join("hello.txt.baker.j2"), "Hello").unwrap();
+        std::fs::write(template_root.path().join("data.bin"), [0u8; 3]).unwrap();
+        let log_dir = TempDir::new().unwrap();
+        let log_path = log_dir.path().join("operations.jsonl");
+        let log = Box::leak(Box::new(OperationsLog::create(&log_path).unwrap()));
+
+        processor.with_operations_log(log).process_all_files().unwrap();
+
+        let records: Vec<serde_json::Value> = std::fs::read_to_string(&log_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let find = |template: &str| {
+            records.iter().find(|r| r["template"] == template).unwrap().clone()
+        };
+        let hello = find("hello.txt.baker.j2");
+        assert_eq!(hello["operation"], "write");
+        assert_eq!(hello["decision"], "written");
+        assert_eq!(hello["bytes"], 5);
+        assert_eq!(
+            hello["target"],
+            output_root.path().join("hello.txt").display().to_string()
+        );
+        let data = find("data.bin");
+        assert_eq!(data["operation"], "copy");
+        assert_eq!(data["bytes"], 3);
+        assert!(data["source"].is_string());
+        let root = records.iter().find(|r| r["operation"] == "create_directory").unwrap();
+        assert_eq!(root["decision"], "skipped");
+        assert_eq!(root["reason"], "directory already exists");
+    }
+
+    #[test]
+    fn operations_without_outputs_log_nothing() {
+        let (_template_root, _output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        let log_dir = TempDir::new().unwrap();
+        let log_path = log_dir.path().join("operations.jsonl");
+        let log = Box::leak(Box::new(OperationsLog::create(&log_path).unwrap()));
+        let observers: Vec<Box<dyn GenerationObserver>> = Vec::new();
+        let processor = processor.with_operations_log(log).with_observer(&observers);
+        let empty_loop = TemplateOperation::MultipleWrite { writes: Vec::new() };
+
+        processor.log_operation("loop.baker.j2", &empty_loop, &[], Duration::ZERO);
+        processor.notify_written(&empty_loop, &[]);
+
+        assert_eq!(std::fs::read_to_string(&log_path).unwrap(), "");
+    }
+
+    #[test]
+    fn finds_outputs_differing_only_in_case() {
+        let (_template_root, _output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        let output = |path: &str| {
+            let provenance = FileProvenance {
+                template: path.into(),
+                hash: String::new(),
+                binary: false,
+            };
+            (path.to_string(), provenance)
+        };
+
+        assert!(processor.case_collisions(&[output("README.md")]).is_empty());
+        assert!(processor.case_collisions(&[output("README.md")]).is_empty());
+        assert_eq!(
+            processor.case_collisions(&[output("docs/a.md"), output("Readme.md")]),
+            [("README.md".to_string(), "Readme.md".to_string())]
+        );
+    }
+
+    #[test]
+    fn fails_before_writing_when_entries_render_to_the_same_output() {
+        let (template_root, output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        std::fs::write(template_root.path().join("a.txt"), "a").unwrap();
+        std::fs::write(template_root.path().join("config.yml"), "raw").unwrap();
+        std::fs::write(template_root.path().join("config.yml.baker.j2"), "rendered")
+            .unwrap();
+
+        let err = processor.process_all_files().unwrap_err();
+        assert!(matches!(err, Error::OutputCollisions(_)));
+        assert!(err
+            .to_string()
+            .contains("config.yml: 'config.yml', 'config.yml.baker.j2'"));
+        assert!(!output_root.path().join("a.txt").exists());
+    }
+
+    #[test]
+    fn records_render_metrics_of_entries_with_output() {
+        let (template_root, _output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        std::fs::write(template_root.path().join("base.j2"), "base").unwrap();
+        std::fs::write(
+            template_root.path().join("page.txt.baker.j2"),
+            "{% if false %}{% include 'base.j2' %}{% endif %}!",
+        )
+        .unwrap();
+        std::fs::create_dir(template_root.path().join("empty")).unwrap();
+
+        processor.process_all_files().unwrap();
+
+        let metrics = processor.render_metrics();
+        let page = metrics.iter().find(|m| m.template == "page.txt.baker.j2").unwrap();
+        assert_eq!(page.includes, 1);
+        let base = metrics.iter().find(|m| m.template == "base.j2").unwrap();
+        assert_eq!((base.bytes, base.includes), (4, 0));
+        assert!(!metrics.iter().any(|m| m.template == "empty"));
+    }
+
+    #[test]
+    fn append_adds_content_once() {
+        let (_template_root, output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        let target = output_root.path().join(".gitignore");
+
+        let outcome = processor.handle_append(&target, false, "dist/\n").unwrap();
+        assert!(outcome.is_written());
+        std::fs::write(&target, "target/").unwrap();
+        processor.handle_append(&target, true, "dist/\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "target/\ndist/\n");
+
+        let outcome = processor.handle_append(&target, true, "dist/\n").unwrap();
+        assert_eq!(outcome, Outcome::skipped("content already present"));
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "target/\ndist/\n");
+    }
+
+    #[test]
+    fn append_reads_existing_files_in_the_editorconfig_charset() {
+        let (_template_root, output_root, mut processor) =
+            build_file_processor(Vec::new(), false);
+        processor.editorconfig = Some(EditorConfig::parse(
+            "[*.txt]\nend_of_line = lf\ncharset = utf-8-bom\n[*.ini]\nend_of_line = lf\ncharset = latin1\n",
+        ));
+        let notes = output_root.path().join("notes.txt");
+        let settings = output_root.path().join("settings.ini");
+        std::fs::write(&notes, b"\xEF\xBB\xBFcaf\xC3\xA9\n").unwrap();
+        std::fs::write(&settings, b"caf\xE9\n").unwrap();
+
+        processor.handle_append(&notes, true, "th\u{e9}\n").unwrap();
+        processor.handle_append(&settings, true, "th\u{e9}\n").unwrap();
+
+        assert_eq!(
+            std::fs::read(&notes).unwrap(),
+            b"\xEF\xBB\xBFcaf\xC3\xA9\nth\xC3\xA9\n"
+        );
+        assert_eq!(std::fs::read(&settings).unwrap(), b"caf\xE9\nth\xE9\n");
+    }
+
     #[test]
     fn skips_overwrite_prompt_for_new_files() {
         let (_template_root, _output_root, processor) =
             build_file_processor(Vec::new(), false);
-        assert!(processor.should_skip_overwrite_prompt(false));
-        assert!(!processor.should_skip_overwrite_prompt(true));
+        let target = Path::new("README.md");
+        assert!(processor.should_skip_overwrite_prompt(target, false));
+        assert!(!processor.should_skip_overwrite_prompt(target, true));
     }
 
     #[test]
     fn skips_overwrite_prompt_when_flagged() {
         let (_template_root, _output_root, processor) =
             build_file_processor(vec![SkipConfirm::Overwrite], false);
-        assert!(processor.should_skip_overwrite_prompt(true));
+        assert!(processor.should_skip_overwrite_prompt(Path::new("README.md"), true));
+    }
+
+    #[test]
+    fn skips_overwrite_prompt_of_matching_paths() {
+        let skip = "overwrite:docs/**".parse().unwrap();
+        let (_template_root, output_root, processor) =
+            build_file_processor(vec![skip], false);
+        let target = output_root.path().join("docs").join("index.md");
+        assert!(processor.should_skip_overwrite_prompt(&target, true));
+        let target = output_root.path().join("README.md");
+        assert!(!processor.should_skip_overwrite_prompt(&target, true));
     }
 
     #[test]
@@ -420,7 +1368,32 @@ mod tests {
             build_file_processor(Vec::new(), false);
         let target = output_root.path().join("new-file.txt");
         let result = processor.confirm_overwrite(&target, false).unwrap();
-        assert!(result);
+        assert!(result.is_written());
+    }
+
+    #[test]
+    fn conflict_resolver_decides_existing_files() {
+        let (template_root, output_root, processor) =
+            build_file_processor(Vec::new(), false);
+        std::fs::write(template_root.path().join("keep.txt"), "new").unwrap();
+        std::fs::write(template_root.path().join("replace.txt"), "new").unwrap();
+        std::fs::write(output_root.path().join("keep.txt"), "old").unwrap();
+        std::fs::write(output_root.path().join("replace.txt"), "old").unwrap();
+        let asked = RefCell::new(Vec::new());
+        let resolver = |target: &Path| {
+            let name = target.file_name().unwrap().to_string_lossy().to_string();
+            asked.borrow_mut().push(name.clone());
+            Ok(name == "replace.txt")
+        };
+
+        processor.with_conflict_resolver(&resolver).process_all_files().unwrap();
+
+        asked.borrow_mut().sort();
+        assert_eq!(*asked.borrow(), ["keep.txt", "replace.txt"]);
+        let read = |name: &str| std::fs::read_to_string(output_root.path().join(name));
+        assert_eq!(read("keep.txt").unwrap(), "old");
+        assert_eq!(read("replace.txt").unwrap(), "new");
+        assert!(!OverwritePolicy::Never.overwrite(Path::new("x")).unwrap());
     }
 
     #[test]
@@ -508,6 +1481,31 @@ mod tests {
                 follow_symlinks: false,
                 generated_file_name: None,
                 conflict_marker_style: None,
+                answer_precedence: None,
+                pre_hook_working_dir: None,
+                post_hook_working_dir: None,
+                hook_prompts: false,
+                output_subdir: None,
+                hook_answers_schema: None,
+                namespace_hook_answers: false,
+                suffixes: Default::default(),
+                answer_transformers: Vec::new(),
+                context_alias: None,
+                render_all: false,
+                copier_answers: false,
+                validations: Vec::new(),
+                strict: false,
+                editorconfig: false,
+                introspect: IndexMap::new(),
+                prompt_theme: Default::default(),
+                pre_hook_image: None,
+                post_hook_image: None,
+                safe_expressions: false,
+                durability: Default::default(),
+                windows_paths: Default::default(),
+                non_ascii_names: Default::default(),
+                undefined_behavior: Default::default(),
+                questions_files: Vec::new(),
             },
             vec![SkipConfirm::All],
             false,
@@ -539,7 +1537,7 @@ fn new() {}
 
         let result = processor.handle_write(&target, true, "fn new() {}\n").unwrap();
 
-        assert!(!result, "should return false (skipped)");
+        assert_eq!(result, Outcome::skipped(UNRESOLVED_CONFLICTS), "should be skipped");
         assert_eq!(
             std::fs::read_to_string(&target).unwrap(),
             existing_with_markers,
@@ -570,4 +1568,68 @@ fn new() {}
         assert!(!dest_link.is_symlink());
         assert_eq!(std::fs::read_to_string(dest_link).unwrap(), "hello-follow");
     }
+
+    #[test]
+    fn reports_progress_for_every_entry() {
+        let (template_root, _output_root, processor) =
+            build_file_processor(vec![SkipConfirm::All], false);
+        std::fs::write(template_root.path().join("a.txt"), "a").unwrap();
+        std::fs::write(template_root.path().join("b.txt"), "b").unwrap();
+        let reports = std::cell::RefCell::new(Vec::new());
+        let processor = processor.with_progress(|p| reports.borrow_mut().push(p.clone()));
+
+        processor.process_all_files().unwrap();
+        drop(processor);
+
+        let reports = reports.into_inner();
+        // The template root itself plus two files
+        assert_eq!(reports.len(), 3);
+        assert!(reports.iter().all(|p| p.total == 3));
+        assert_eq!(reports.last().unwrap().processed, 3);
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let (template_root, output_root, processor) =
+            build_file_processor(vec![SkipConfirm::All], false);
+        std::fs::write(template_root.path().join("a.txt"), "a").unwrap();
+        let processor = processor.with_cancellation(Arc::new(AtomicBool::new(true)));
+
+        let err = processor.process_all_files().unwrap_err();
+
+        assert!(err.to_string().contains("cancelled"));
+        assert!(!output_root.path().join("a.txt").exists());
+    }
+    #[test]
+    fn keep_going_reports_every_failure_and_writes_the_rest() {
+        let (template_root, output_root, processor) =
+            build_file_processor(vec![SkipConfirm::All], false);
+        for (name, content) in [
+            ("a.txt.baker.j2", "{{ broken"),
+            ("b.txt.baker.j2", "fine"),
+            ("c.txt.baker.j2", "{% if %}"),
+        ] {
+            std::fs::write(template_root.path().join(name), content).unwrap();
+        }
+
+        let err = processor.with_keep_going(true).process_all_files().unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.starts_with("2 template entries failed:"), "{message}");
+        assert!(message.contains("  - a.txt.baker.j2: "), "{message}");
+        assert!(message.contains("  - c.txt.baker.j2: "), "{message}");
+        assert!(output_root.path().join("b.txt").exists());
+    }
+
+    #[test]
+    fn logs_failures_and_continues_by_default() {
+        let (template_root, output_root, processor) =
+            build_file_processor(vec![SkipConfirm::All], false);
+        std::fs::write(template_root.path().join("a.txt.baker.j2"), "{{ broken").unwrap();
+        std::fs::write(template_root.path().join("b.txt.baker.j2"), "fine").unwrap();
+
+        processor.process_all_files().unwrap();
+
+        assert!(output_root.path().join("b.txt").exists());
+    }
 }
@@ -0,0 +1,162 @@
+//! `baker generate --remote` — the template is rendered locally into a staging
+//! directory, which is then uploaded to the remote directory over SFTP.
+//!
+//! The upload runs the system `sftp` client in batch mode, so hosts, users, keys and
+//! jump hosts come from the usual OpenSSH configuration and agent.
+
+use crate::error::{Error, Result};
+use std::{
+    fmt::Display,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+    str::FromStr,
+};
+use walkdir::WalkDir;
+
+/// A directory on a remote host, written `[user@]host:/path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    /// `host` or `user@host`, as passed to `sftp`.
+    pub host: String,
+    pub path: String,
+}
+
+impl FromStr for RemoteTarget {
+    type Err = String;
+
+    fn from_str(target: &str) -> std::result::Result<Self, Self::Err> {
+        match target.split_once(':') {
+            Some((host, path)) if !host.is_empty() && !path.is_empty() => Ok(Self {
+                host: host.to_string(),
+                path: path.trim_end_matches('/').to_string(),
+            }),
+            _ => Err(format!("expected [user@]host:/path, got '{target}'")),
+        }
+    }
+}
+
+impl Display for RemoteTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.host, self.path)
+    }
+}
+
+/// Uploads the contents of `local_root` to `target`. The remote directory must not
+/// exist unless `force` is set; its parent must exist.
+pub fn upload(local_root: &Path, target: &RemoteTarget, force: bool) -> Result<()> {
+    let batch = sftp_batch(local_root, &target.path, force)?;
+    log::debug!("Uploading '{}' to '{target}'", local_root.display());
+    let mut child = Command::new("sftp")
+        .args(["-q", "-b", "-", &target.host])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Other(anyhow::anyhow!("Cannot run sftp: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(batch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::Network(format!(
+            "Upload to '{target}' failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The `sftp` batch recreating `local_root` at `remote_path`: directories before
+/// their contents, files put with their permissions, symlinks as symlinks.
+fn sftp_batch(local_root: &Path, remote_path: &str, force: bool) -> Result<String> {
+    // A failing command stops the batch, except when it is prefixed with `-`.
+    let mut batch =
+        format!("{}mkdir {}\n", if force { "-" } else { "" }, quote(remote_path)?);
+    for entry in WalkDir::new(local_root).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        let relative = entry
+            .path()
+            .strip_prefix(local_root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        let remote = quote(&format!("{remote_path}/{relative}"))?;
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            let link = std::fs::read_link(entry.path())?;
+            let link = link.to_string_lossy().replace('\\', "/");
+            if force {
+                batch.push_str(&format!("-rm {remote}\n"));
+            }
+            batch.push_str(&format!("symlink {} {remote}\n", quote(&link)?));
+        } else if file_type.is_dir() {
+            batch.push_str(&format!("-mkdir {remote}\n"));
+        } else {
+            let local = quote(&entry.path().to_string_lossy())?;
+            batch.push_str(&format!("put -p {local} {remote}\n"));
+        }
+    }
+    Ok(batch)
+}
+
+/// `path` quoted for an `sftp` batch. Paths with control characters are
+/// refused: a newline would end the quoted path and start another command.
+fn quote(path: &str) -> Result<String> {
+    if path.chars().any(char::is_control) {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Cannot upload '{}': the path contains control characters",
+            path.escape_debug()
+        )));
+    }
+    Ok(format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\"")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_targets() {
+        let target: RemoteTarget = "deploy@jump.acme.corp:/srv/app/".parse().unwrap();
+        assert_eq!(target.host, "deploy@jump.acme.corp");
+        assert_eq!(target.path, "/srv/app");
+        assert_eq!(target.to_string(), "deploy@jump.acme.corp:/srv/app");
+        assert!("jump.acme.corp".parse::<RemoteTarget>().is_err());
+        assert!(":/srv/app".parse::<RemoteTarget>().is_err());
+    }
+
+    #[test]
+    fn batch_creates_directories_before_their_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("My \"App\".md"), "").unwrap();
+
+        let batch = sftp_batch(dir.path(), "/srv/app", false).unwrap();
+        let local = |name: &str| quote(&dir.path().join(name).to_string_lossy()).unwrap();
+        assert_eq!(
+            batch,
+            format!(
+                "mkdir \"/srv/app\"\n\
+                 put -p {} \"/srv/app/My \\\"App\\\".md\"\n\
+                 -mkdir \"/srv/app/src\"\n\
+                 put -p {} \"/srv/app/src/main.rs\"\n",
+                local("My \"App\".md"),
+                local("src/main.rs"),
+            )
+        );
+        assert!(sftp_batch(dir.path(), "/srv/app", true).unwrap().starts_with("-mkdir"));
+    }
+
+    #[test]
+    fn paths_with_control_characters_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a\"\nrm \"x"), "").unwrap();
+        let err = sftp_batch(dir.path(), "/srv/app", false).unwrap_err();
+        assert!(err.to_string().contains("control characters"), "{err}");
+        assert!(
+            sftp_batch(tempfile::tempdir().unwrap().path(), "/srv/\napp", false).is_err()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! `baker render` — renders a single Jinja file or string with Baker's engine,
+//! filters and answer sources, without a `baker.yaml`.
+
+use crate::{
+    cli::{answers::AnswerCollector, update::add_templates_in_renderer, RenderArgs},
+    config::ConfigV1,
+    constants::STDIN_INDICATOR,
+    error::Result,
+    renderer::TemplateRenderer,
+    template::get_template_engine,
+};
+use serde_json::json;
+use std::{fs, io::Read, path::Path};
+
+/// Main entry point for `baker render`.
+pub fn run_render(args: RenderArgs) -> Result<()> {
+    print!("{}", render(&args)?);
+    Ok(())
+}
+
+/// Renders the input described by `args` and returns the output.
+pub fn render(args: &RenderArgs) -> Result<String> {
+    let (template, name) = if args.input == STDIN_INDICATOR {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        (buf, "stdin".to_string())
+    } else if Path::new(&args.input).is_file() {
+        (fs::read_to_string(&args.input)?, args.input.clone())
+    } else {
+        (args.input.clone(), "input".to_string())
+    };
+
+    // No questions: only the predefined answer sources contribute. Every file
+    // under the import root can be included.
+    let config: ConfigV1 = serde_json::from_value(json!({"template_globs": ["**"]}))?;
+
+    let mut engine = get_template_engine();
+    if let Some(import_root) = &args.import_root {
+        add_templates_in_renderer(import_root, &config, &mut engine);
+    } else {
+        engine.set_template_root(&std::env::current_dir()?);
+    }
+    let cwd = std::env::current_dir()?;
+    let answers = AnswerCollector::new(&engine, true, &cwd)
+        .with_set_answers(args.set.clone())
+        .collect_answers(
+            &config,
+            None,
+            args.answers.clone(),
+            args.answers_file.clone(),
+        )?;
+
+    engine.render(&template, &answers, Some(&name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: &str) -> RenderArgs {
+        RenderArgs {
+            input: input.to_string(),
+            answers: None,
+            answers_file: None,
+            set: Vec::new(),
+            import_root: None,
+        }
+    }
+
+    #[test]
+    fn renders_string_with_answers_and_filters() {
+        let mut args = args("{{ name | snake_case }}-{{ port }}");
+        args.answers = Some(r#"{"name": "MyService", "port": 80}"#.to_string());
+        args.set = vec!["port=81".to_string()];
+        assert_eq!(render(&args).unwrap(), "my_service-81");
+    }
+
+    #[test]
+    fn renders_file_with_includes_from_import_root() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("partials")).unwrap();
+        fs::write(dir.path().join("partials/greet.j2"), "hi {{ name }}").unwrap();
+        let file = dir.path().join("main.j2");
+        fs::write(&file, "{% include 'partials/greet.j2' %}!").unwrap();
+
+        let mut args = args(file.to_str().unwrap());
+        args.set = vec!["name=baker".to_string()];
+        args.import_root = Some(dir.path().to_path_buf());
+        assert_eq!(render(&args).unwrap(), "hi baker!");
+    }
+}
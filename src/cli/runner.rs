@@ -1,75 +1,355 @@
 use crate::{
     cli::{
-        answers::AnswerCollector, context::GenerationContext, hooks::run_hook,
-        processor::FileProcessor, GenerateArgs, SkipConfirm,
+        answer_source::{answer_source_for, InteractiveSource, StreamSource},
+        answers::AnswerCollector,
+        context::GenerationContext,
+        encrypted,
+        hooks::{run_hook, HookOptions},
+        introspect::introspect,
+        matrix::{self, MatrixReport},
+        memory::AnswerMemory,
+        metrics::{SlowestEntries, SLOWEST_SHOWN},
+        observer::{GenerationObserver, GenerationSummary, HookKind},
+        operations_log::OperationsLog,
+        preview::ContentPreview,
+        processor::{ConflictResolver, FileProcessor},
+        remote::{self, RemoteTarget},
+        transformers::{apply_transformers, AnswerTransformer},
+        trust::{self, HookApproval},
+        GenerateArgs, SkipConfirm,
     },
-    config::{Config, ConfigV1},
+    config::{
+        policy, Config, ConfigCache, ConfigV1, HookWorkingDir, LoadOptions, UserConfig,
+    },
+    constants::{exit_codes, STDIN_INDICATOR},
+    copier,
     error::{Error, Result},
-    generated,
+    generated::{self, FileProvenance},
     ignore::parse_bakerignore_file,
-    loader::get_template,
-    prompt::confirm,
+    interrupt,
+    keyring::Keyring,
+    loader::{
+        get_template, interface::TemplateLoader, tar::TarStreamLoader, TemplateSource,
+        TemplateSourceInfo,
+    },
+    lock,
+    manifest::{self, BakerManifest, ManifestFormat},
+    prompt::theme::set_template_theme,
     renderer::TemplateRenderer,
+    secrets,
+    temp::TempDir,
     template::{get_template_engine, processor::TemplateProcessor},
 };
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use log::debug;
 use serde_json::json;
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 use walkdir::WalkDir;
 
 /// Main CLI runner that orchestrates the entire template generation workflow
 pub struct Runner {
     args: GenerateArgs,
+    operations_log: Option<OperationsLog>,
+    content_preview: Option<ContentPreview>,
+    transformers: Vec<Box<dyn AnswerTransformer>>,
+    answer_memory: Option<AnswerMemory>,
+    observers: Vec<Box<dyn GenerationObserver>>,
+    conflict_resolver: Option<Box<dyn ConflictResolver>>,
+    stdin_template: Option<TarStreamLoader>,
 }
 
 impl Runner {
     pub fn new(args: GenerateArgs) -> Self {
-        Self { args }
+        Self {
+            args,
+            operations_log: None,
+            content_preview: None,
+            transformers: Vec::new(),
+            answer_memory: None,
+            observers: Vec::new(),
+            conflict_resolver: None,
+            stdin_template: None,
+        }
     }
 
-    /// Executes the complete template generation workflow
-    pub fn run(self) -> Result<()> {
-        let mut engine = get_template_engine();
-        let (mut context, source_info) = self.prepare_environment(&mut engine)?;
+    /// Registers a transformer that rewrites the answers before rendering, after
+    /// the template's `answer_transformers`.
+    pub fn with_transformer(
+        mut self,
+        transformer: impl AnswerTransformer + 'static,
+    ) -> Self {
+        self.transformers.push(Box::new(transformer));
+        self
+    }
+
+    /// Registers an observer notified of the answers, written files, hooks and the
+    /// end of every generation.
+    pub fn with_observer(mut self, observer: impl GenerationObserver + 'static) -> Self {
+        self.observers.push(Box::new(observer));
+        self
+    }
 
-        let hook_plan = self.prepare_hooks(&context, &engine)?;
+    /// Lets `resolver` decide whether existing files are overwritten, instead of
+    /// asking on the terminal.
+    pub fn with_conflict_resolver(
+        mut self,
+        resolver: impl ConflictResolver + 'static,
+    ) -> Self {
+        self.conflict_resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Executes the complete template generation workflow
+    pub fn run(mut self) -> Result<()> {
+        if let Some(path) = &self.args.operations_log {
+            self.operations_log = Some(OperationsLog::create(path)?);
+        }
+        if !self.args.no_memory && !self.args.non_interactive {
+            self.answer_memory = AnswerMemory::default_location();
+        }
+        if let Some(output) = self.args.output.take() {
+            self.args.output_dir = output;
+        }
+        if self.args.stdin_tar {
+            self.stdin_template = Some(self.read_stdin_template()?);
+        }
+        if let Some(glob) = &self.args.show_content {
+            self.content_preview =
+                Some(ContentPreview::new(glob, self.args.show_content_dir.clone())?);
+        }
+        if let Some(matrix_file) = &self.args.matrix {
+            return self.run_matrix(matrix_file);
+        }
+        if let Some(target) = self.args.remote.clone() {
+            return self.run_remote(&target);
+        }
+        self.run_once().map(|_| ())
+    }
 
-        let pre_hook_output = self.maybe_run_pre_hook(&hook_plan, &context, &engine)?;
+    /// Generates the project once into the output directory.
+    fn run_once(&self) -> Result<GenerationContext> {
+        let mut engine = get_template_engine();
+        let (context, source_info) = self.prepare_environment(&mut engine)?;
 
-        let answers = self.gather_answers(
+        let hook_plan = self.prepare_hooks(
+            context.template_root(),
+            &source_info,
             context.config(),
             &engine,
+        )?;
+
+        self.generate(
+            context,
+            &source_info,
+            &hook_plan,
+            &engine,
+            self.args.answers.clone(),
+            serde_json::Map::new(),
+        )
+        .map(|(context, _)| context)
+    }
+
+    /// Generates the project into a staging directory and uploads it to `target`.
+    fn run_remote(mut self, target: &RemoteTarget) -> Result<()> {
+        let staging = TempDir::new("remote")?;
+        self.args.output_dir = staging.path().join("output");
+        let context = self.run_once()?;
+        if context.dry_run() {
+            log_dry_run_action("Would upload to", target.to_string());
+            return Ok(());
+        }
+        remote::upload(&self.args.output_dir, target, self.args.force)?;
+        println!("Uploaded the generated project to {target}.");
+        Ok(())
+    }
+
+    /// Renders the template once per matrix entry, sharing the loaded template,
+    /// its configuration and the hook confirmation.
+    fn run_matrix(&self, matrix_file: &Path) -> Result<()> {
+        let entries = matrix::load_matrix(matrix_file)?;
+        let mut engine = get_template_engine();
+        let loaded = self.resolve_template()?;
+        let config = self.load_and_validate_config(&loaded.root)?;
+        set_template_theme(&config.prompt_theme)?;
+        self.add_templates_in_renderer(&loaded.root, &config, &mut engine);
+        let hook_plan =
+            self.prepare_hooks(&loaded.root, &loaded.source, &config, &engine)?;
+
+        // stdin can only be read once, so read it before the first entry.
+        let base_answers = match self.args.answers.as_deref() {
+            Some(STDIN_INDICATOR) => {
+                let mut buf = String::new();
+                std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)?;
+                Some(buf)
+            }
+            other => other.map(str::to_string),
+        };
+
+        let mut report = MatrixReport::default();
+        let mut steps = serde_json::Map::new();
+        for entry in entries {
+            let output_dir = self.args.output_dir.join(&entry.output);
+            engine.set_output_root(&output_dir);
+            let result = self
+                .prepare_output_dir(&output_dir, &config)
+                .and_then(|output_root| {
+                    let context = GenerationContext::new(
+                        loaded.root.clone(),
+                        output_root,
+                        config.clone(),
+                        self.args.skip_confirms.clone(),
+                        self.args.dry_run,
+                        false,
+                        None,
+                    );
+                    let answers =
+                        matrix::merge_answers(base_answers.as_deref(), &entry.answers)?;
+                    let extra = serde_json::Map::from_iter([(
+                        "steps".to_string(),
+                        serde_json::Value::Object(steps.clone()),
+                    )]);
+                    self.generate(
+                        context,
+                        &loaded.source,
+                        &hook_plan,
+                        &engine,
+                        answers,
+                        extra,
+                    )
+                })
+                .map(|(context, files)| {
+                    if let Some(name) = &entry.name {
+                        steps.insert(name.clone(), matrix::step_record(&context, &files));
+                    }
+                });
+            if let Err(err) = &result {
+                log::error!("Matrix entry '{}' failed: {err}", output_dir.display());
+            }
+            report.record(output_dir, result);
+        }
+
+        print!("{report}");
+        report.into_result()
+    }
+
+    /// Runs hooks, collects answers and renders the template into the context's output
+    /// root. `extra` is visible to question templates but not stored as answers.
+    /// Returns the context and the output paths of the files generated.
+    fn generate(
+        &self,
+        context: GenerationContext,
+        source_info: &TemplateSourceInfo,
+        hook_plan: &HookPlan,
+        engine: &dyn TemplateRenderer,
+        cli_answers: Option<String>,
+        extra: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(GenerationContext, Vec<String>)> {
+        let started = Instant::now();
+        let generated = self.generate_files(
+            context,
+            source_info,
+            hook_plan,
+            engine,
+            cli_answers,
+            extra,
+        );
+        match &generated {
+            Ok((context, files)) => self.observers.on_finish(Ok(&GenerationSummary {
+                output_root: context.output_root().to_path_buf(),
+                files: files.len(),
+                dry_run: context.dry_run(),
+                elapsed: started.elapsed(),
+            })),
+            Err(err) => self.observers.on_finish(Err(err)),
+        }
+        generated
+    }
+
+    /// [`Self::generate`] without notifying the observers.
+    fn generate_files(
+        &self,
+        mut context: GenerationContext,
+        source_info: &TemplateSourceInfo,
+        hook_plan: &HookPlan,
+        engine: &dyn TemplateRenderer,
+        cli_answers: Option<String>,
+        mut extra: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(GenerationContext, Vec<String>)> {
+        let pre_hook_output = self.maybe_run_pre_hook(hook_plan, &context, engine)?;
+        extra.extend(introspect(&context.config().introspect, context.output_root()));
+
+        let (mut answers, secret_references) = self.gather_answers(
+            &context,
+            engine,
             pre_hook_output,
-            context.template_root(),
+            source_info,
+            cli_answers,
+            extra,
+        )?;
+        interrupt::check()?;
+        context.set_recorded_answers(answers.clone());
+        apply_transformers(
+            &context.config().answer_transformers,
+            &self.transformers,
+            &mut answers,
         )?;
         context.set_answers(answers);
+        context.set_secret_references(secret_references);
+        if let Some(path) = &self.args.dump_context {
+            dump_context(path, &context)?;
+        }
 
-        self.process_templates(&context, &engine)?;
-
-        self.maybe_run_post_hook(&hook_plan, &context, &engine)?;
+        if let Some(subdir) = self.output_subdir(context.config()) {
+            let subdir = render_output_subdir(engine, subdir, context.answers())?;
+            let output_root = self.get_output_dir(
+                context.output_root().join(subdir),
+                self.args.force,
+                context.dry_run(),
+            )?;
+            context.set_output_root(output_root);
+        }
 
-        self.finish(&context, source_info)?;
+        let created = !context.dry_run() && !context.output_root().exists();
+        let written = self.process_templates(&context, engine).and_then(|files| {
+            self.maybe_run_post_hook(hook_plan, &context, engine).map(|()| files)
+        });
+        let files = match written {
+            Err(err) if err.exit_code() == exit_codes::ABORTED => {
+                clean_aborted_output(
+                    context.output_root(),
+                    created,
+                    self.args.clean_on_abort,
+                );
+                return Err(err);
+            }
+            written => written?,
+        };
 
-        Ok(())
+        let paths = files.keys().cloned().collect();
+        self.finish(&context, source_info.clone(), files)?;
+        Ok((context, paths))
     }
 
     fn prepare_environment(
         &self,
         engine: &mut dyn TemplateRenderer,
     ) -> Result<(GenerationContext, crate::loader::TemplateSourceInfo)> {
-        let output_root = self.prepare_output_dir()?;
         let loaded = self.resolve_template()?;
         let template_root = loaded.root;
         let source_info = loaded.source;
         let config = self.load_and_validate_config(&template_root)?;
+        set_template_theme(&config.prompt_theme)?;
+        let output_root = self.prepare_output_dir(&self.args.output_dir, &config)?;
         debug!("Loaded config: follow_symlinks={}", config.follow_symlinks);
         self.add_templates_in_renderer(&template_root, &config, engine);
+        engine.set_output_root(&output_root);
 
-        let ctx = GenerationContext::new(
+        let mut ctx = GenerationContext::new(
             template_root,
             output_root,
             config,
@@ -78,34 +358,74 @@ impl Runner {
             false,
             None,
         );
+        ctx.set_copy_mode(self.args.copy_mode);
         Ok((ctx, source_info))
     }
 
-    fn prepare_output_dir(&self) -> Result<PathBuf> {
-        self.get_output_dir(&self.args.output_dir, self.args.force, self.args.dry_run)
+    /// Checks the output directory, unless the project goes into a subdirectory of
+    /// it; that one is checked once its name is rendered.
+    fn prepare_output_dir(
+        &self,
+        output_dir: &Path,
+        config: &ConfigV1,
+    ) -> Result<PathBuf> {
+        if self.output_subdir(config).is_some() {
+            return Ok(output_dir.to_path_buf());
+        }
+        self.get_output_dir(output_dir, self.args.force, self.args.dry_run)
+    }
+
+    /// The `--output-subdir` template, or else `output_subdir` from the config.
+    fn output_subdir<'c>(&'c self, config: &'c ConfigV1) -> Option<&'c str> {
+        self.args.output_subdir.as_deref().or(config.output_subdir.as_deref())
     }
 
     fn resolve_template(&self) -> Result<crate::loader::LoadedTemplate> {
-        get_template(self.args.template.as_str(), self.should_skip_overwrite_prompts())
+        match &self.stdin_template {
+            Some(loader) => loader.load(),
+            None => get_template(
+                self.args.template.as_str(),
+                self.should_skip_overwrite_prompts(),
+            ),
+        }
     }
 
-    /// Loads and validates the template configuration
+    /// Unpacks the template streamed on stdin for `--stdin-tar`.
+    fn read_stdin_template(&self) -> Result<TarStreamLoader> {
+        if self.args.answers.as_deref() == Some(STDIN_INDICATOR) {
+            return Err(Error::Other(anyhow::anyhow!(
+                "--answers - cannot be combined with --stdin-tar, both read stdin"
+            )));
+        }
+        policy::check_source(&TemplateSource::TarStream)?;
+        TarStreamLoader::extract(std::io::stdin().lock())
+    }
+
+    /// Loads and validates the template configuration, through the config cache
+    /// unless `--no-cache` is set.
     fn load_and_validate_config(
         &self,
         template_root: &PathBuf,
     ) -> Result<crate::config::ConfigV1> {
-        let config = Config::load_config(template_root)?;
-        let Config::V1(config) = config;
-        config.validate()?;
-        Ok(config)
+        let options = LoadOptions {
+            strict: self.args.strict_config,
+            profile: self.args.profile.clone(),
+        };
+        if !self.args.no_cache {
+            if let Some(cache) = ConfigCache::default_location() {
+                return cache.load(template_root, &options);
+            }
+        }
+        Config::load_validated(template_root, &options)
     }
 
     fn prepare_hooks(
         &self,
-        context: &GenerationContext,
+        template_root: &Path,
+        source: &TemplateSourceInfo,
+        config: &ConfigV1,
         engine: &dyn crate::renderer::TemplateRenderer,
     ) -> Result<HookPlan> {
-        let config = context.config();
         let pre_hook_filename = engine.render(
             &config.pre_hook_filename,
             &json!({}),
@@ -121,20 +441,17 @@ impl Runner {
         let post_hook_print_stdout = config.post_hook_print_stdout;
 
         let execute_hooks = self.confirm_hook_execution(
-            context.template_root(),
-            self.should_skip_hook_prompts(),
+            template_root,
+            source,
             &pre_hook_filename,
             &post_hook_filename,
         )?;
 
-        let (pre_hook_file, post_hook_file) = self.get_hook_files(
-            context.template_root(),
-            &pre_hook_filename,
-            &post_hook_filename,
-        );
+        let (pre_hook_file, post_hook_file) =
+            self.get_hook_files(template_root, &pre_hook_filename, &post_hook_filename);
 
         log::debug!(
-            "Prepared hooks: pre={}, post={}, execute_hooks={}",
+            "Prepared hooks: pre={}, post={}, execute_hooks={:?}",
             pre_hook_file.display(),
             post_hook_file.display(),
             execute_hooks
@@ -147,6 +464,11 @@ impl Runner {
             pre_hook_runner,
             post_hook_runner,
             post_hook_print_stdout,
+            pre_hook_working_dir: config.pre_hook_working_dir,
+            post_hook_working_dir: config.post_hook_working_dir,
+            hook_prompts: config.hook_prompts,
+            pre_hook_image: config.pre_hook_image.clone(),
+            post_hook_image: config.post_hook_image.clone(),
         })
     }
 
@@ -165,21 +487,31 @@ impl Runner {
             return Ok(None);
         }
 
-        if hook_plan.execute_hooks {
+        if hook_plan.execute_hooks.pre {
             let runner = render_hook_runner(
                 engine,
                 &hook_plan.pre_hook_runner,
                 context.answers_opt(),
             )?;
             log::debug!("Executing pre-hook: {}", hook_plan.pre_hook_file.display());
-            run_hook(
-                context.template_root(),
-                context.output_root(),
-                &hook_plan.pre_hook_file,
-                None,
-                &runner,
-                false,
-            )
+            let prompts = answer_source_for(self.args.non_interactive);
+            self.observe_hook(HookKind::Pre, &hook_plan.pre_hook_file, || {
+                run_hook(
+                    context.template_root(),
+                    context.output_root(),
+                    &hook_plan.pre_hook_file,
+                    None,
+                    &runner,
+                    HookOptions {
+                        working_dir: hook_plan.pre_hook_working_dir,
+                        inherit_stdout: false,
+                        prompts: hook_plan.hook_prompts.then_some(prompts.as_ref()),
+                        image: hook_plan.pre_hook_image.as_deref(),
+                        context_file: None,
+                        stream: (!self.args.quiet_hooks).then_some(HookKind::Pre),
+                    },
+                )
+            })
         } else {
             Ok(None)
         }
@@ -188,31 +520,99 @@ impl Runner {
     /// Collects answers from all available sources
     fn gather_answers(
         &self,
-        config: &crate::config::ConfigV1,
+        context: &GenerationContext,
         engine: &dyn crate::renderer::TemplateRenderer,
         pre_hook_output: Option<String>,
-        template_root: &Path,
-    ) -> Result<serde_json::Value> {
-        let collector =
-            AnswerCollector::new(engine, self.args.non_interactive, template_root);
-        collector.collect_answers(
+        source_info: &TemplateSourceInfo,
+        cli_answers: Option<String>,
+        extra: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(serde_json::Value, serde_json::Map<String, serde_json::Value>)> {
+        let remembered = self
+            .answer_memory
+            .as_ref()
+            .map(|memory| memory.recall(source_info))
+            .unwrap_or_default();
+        let mut collector = AnswerCollector::new(
+            engine,
+            self.args.non_interactive,
+            context.template_root(),
+        )
+        .with_set_answers(self.args.set.clone())
+        .with_context(extra)
+        .with_remembered(remembered)
+        .with_ask_only(self.args.ask_only.clone(), self.args.reuse_defaults_for_rest)
+        .with_observer(&self.observers);
+        if self.args.answers_stream {
+            if self.args.answers.as_deref() == Some(STDIN_INDICATOR) {
+                return Err(Error::Other(anyhow::anyhow!(
+                    "--answers - cannot be combined with --answers-stream, both read stdin"
+                )));
+            }
+            collector = collector.with_answer_source(StreamSource::stdio());
+        }
+        if let Some(seconds) =
+            self.args.prompt_timeout.filter(|_| cfg!(feature = "terminal-ui"))
+        {
+            collector = collector.with_answer_source(
+                InteractiveSource::default()
+                    .with_timeout(Some(Duration::from_secs(seconds))),
+            );
+        }
+        if self.args.keyring {
+            collector = collector.with_keyring(Keyring::for_template(source_info)?);
+        }
+        let config = context.config();
+        let answers = collector.collect_answers(
             config,
             pre_hook_output,
-            self.args.answers.clone(),
+            cli_answers,
             self.args.answers_file.clone(),
-        )
+        )?;
+        if self.args.explain_answers {
+            print!("{}", collector.explain());
+        }
+        // Only answers someone typed are worth offering again.
+        if let Some(memory) = &self.answer_memory {
+            if collector.prompted() && !self.args.dry_run {
+                let references = collector.secret_references();
+                if let Err(e) = memory.remember(source_info, config, &answers, references)
+                {
+                    log::warn!("Failed to remember answers: {e}");
+                }
+            }
+        }
+        Ok((answers, collector.secret_references().clone()))
     }
+    /// Renders the template and returns the provenance of the generated files.
     fn process_templates(
         &self,
         context: &GenerationContext,
         engine: &dyn crate::renderer::TemplateRenderer,
-    ) -> Result<()> {
+    ) -> Result<BTreeMap<String, FileProvenance>> {
         let bakerignore = parse_bakerignore_file(context.template_root())?;
 
         let processor = TemplateProcessor::new(engine, context, &bakerignore);
 
-        let file_processor = FileProcessor::new(processor, context);
-        file_processor.process_all_files()
+        let mut file_processor = FileProcessor::new(processor, context)
+            .with_keep_going(self.args.keep_going)
+            .with_copy_buffer_size(UserConfig::load()?.copy.buffer_size)
+            .with_cancellation(interrupt::flag())
+            .with_observer(&self.observers);
+        if let Some(operations_log) = &self.operations_log {
+            file_processor = file_processor.with_operations_log(operations_log);
+        }
+        if let Some(preview) = &self.content_preview {
+            file_processor = file_processor.with_content_preview(preview);
+        }
+        if let Some(resolver) = &self.conflict_resolver {
+            file_processor = file_processor.with_conflict_resolver(resolver.as_ref());
+        }
+        file_processor.process_all_files()?;
+        if log::log_enabled!(log::Level::Info) {
+            let metrics = file_processor.render_metrics();
+            log::info!("{}", SlowestEntries::new(&metrics, SLOWEST_SHOWN));
+        }
+        Ok(file_processor.provenance())
     }
 
     fn maybe_run_post_hook(
@@ -230,21 +630,32 @@ impl Runner {
             return Ok(());
         }
 
-        if hook_plan.execute_hooks {
+        if hook_plan.execute_hooks.post {
             let runner = render_hook_runner(
                 engine,
                 &hook_plan.post_hook_runner,
                 context.answers_opt(),
             )?;
             log::debug!("Executing post-hook: {}", hook_plan.post_hook_file.display());
-            let post_hook_stdout = run_hook(
-                context.template_root(),
-                context.output_root(),
-                &hook_plan.post_hook_file,
-                Some(context.answers()),
-                &runner,
-                hook_plan.post_hook_print_stdout,
-            )?;
+            let prompts = answer_source_for(self.args.non_interactive);
+            let post_hook_stdout =
+                self.observe_hook(HookKind::Post, &hook_plan.post_hook_file, || {
+                    run_hook(
+                        context.template_root(),
+                        context.output_root(),
+                        &hook_plan.post_hook_file,
+                        Some(context.answers()),
+                        &runner,
+                        HookOptions {
+                            working_dir: hook_plan.post_hook_working_dir,
+                            inherit_stdout: hook_plan.post_hook_print_stdout,
+                            prompts: hook_plan.hook_prompts.then_some(prompts.as_ref()),
+                            image: hook_plan.post_hook_image.as_deref(),
+                            context_file: self.args.dump_context.as_deref(),
+                            stream: (!self.args.quiet_hooks).then_some(HookKind::Post),
+                        },
+                    )
+                })?;
 
             if let Some(result) = post_hook_stdout {
                 log::debug!("Post-hook stdout content: {result}");
@@ -253,10 +664,25 @@ impl Runner {
         Ok(())
     }
 
+    /// Runs `hook` between the observers' start and end notifications.
+    fn observe_hook<T>(
+        &self,
+        kind: HookKind,
+        script: &Path,
+        hook: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        self.observers.on_hook_start(kind, script);
+        let started = Instant::now();
+        let result = hook();
+        self.observers.on_hook_end(kind, result.is_ok(), started.elapsed());
+        result
+    }
+
     fn finish(
         &self,
         context: &GenerationContext,
         source_info: crate::loader::TemplateSourceInfo,
+        files: BTreeMap<String, FileProvenance>,
     ) -> Result<()> {
         let file_name = generated::resolve_file_name(
             self.args.generated_file.as_deref(),
@@ -269,26 +695,63 @@ impl Runner {
                 context.output_root(),
             );
         } else {
+            let answers = generated::strip_secret_answers(
+                context.recorded_answers(),
+                context.config(),
+            );
             let answers =
-                generated::strip_secret_answers(context.answers(), context.config());
-            let data = generated::BakerGenerated::new(source_info, answers);
-            generated::write(context.output_root(), file_name, &data)?;
+                secrets::restore_references(&answers, context.secret_references());
+            if self.args.lock {
+                let lock = lock::BakerLock::new(source_info.clone(), &answers, &files);
+                lock::write(context.output_root(), &lock)?;
+            }
+            let data = generated::BakerGenerated::new(source_info, answers)
+                .with_files(files)
+                .with_profile(self.args.profile.clone())
+                .with_encrypted_answers(self.args.encrypt_answers);
+            encrypted::write_generated(context.output_root(), file_name, &data)?;
+            if let Some(format) = self.args.manifest {
+                self.write_manifest(context, &data, format)?;
+            }
+            if context.config().copier_answers {
+                copier::write(context.output_root(), &data)?;
+            }
         }
 
         println!("{}", completion_message(context.dry_run(), context.output_root()));
         Ok(())
     }
 
+    /// Writes the manifest of the generation recorded in `data`.
+    fn write_manifest(
+        &self,
+        context: &GenerationContext,
+        data: &generated::BakerGenerated,
+        format: ManifestFormat,
+    ) -> Result<()> {
+        let answer_keys = context
+            .answers()
+            .as_object()
+            .map(|answers| answers.keys().cloned().collect())
+            .unwrap_or_default();
+        let manifest = BakerManifest::new(
+            context.output_root(),
+            data.template.clone(),
+            answer_keys,
+            self.args.manifest_values.then(|| data.answers.clone()),
+            &data.files,
+        )?;
+        manifest::write(context.output_root(), &manifest, format)
+    }
+
     /// Determines if overwrite prompts should be skipped
     fn should_skip_overwrite_prompts(&self) -> bool {
-        self.args.skip_confirms.contains(&SkipConfirm::All)
-            || self.args.skip_confirms.contains(&SkipConfirm::Overwrite)
+        self.args.skip_confirms.iter().any(SkipConfirm::skips_all_overwrites)
     }
 
-    /// Determines if hook execution prompts should be skipped
-    fn should_skip_hook_prompts(&self) -> bool {
-        self.args.skip_confirms.contains(&SkipConfirm::All)
-            || self.args.skip_confirms.contains(&SkipConfirm::Hooks)
+    /// Determines if the execution prompt of the `kind` hook should be skipped
+    fn should_skip_hook_prompt(&self, kind: HookKind) -> bool {
+        self.args.skip_confirms.iter().any(|skip| skip.skips_hook(kind))
     }
 
     /// Ensures the output directory exists and is safe to write to.
@@ -332,6 +795,8 @@ impl Runner {
         config: &ConfigV1,
         engine: &mut dyn TemplateRenderer,
     ) {
+        engine.set_template_root(template_root);
+        engine.set_undefined_behavior(config.undefined_behavior);
         let import_root = if let Some(ref import_root_str) = config.import_root {
             let import_path = Path::new(import_root_str);
             if import_path.is_absolute() {
@@ -418,25 +883,20 @@ impl Runner {
     fn confirm_hook_execution<P: AsRef<Path>>(
         &self,
         template_dir: P,
-        skip_hooks_check: bool,
+        source: &TemplateSourceInfo,
         pre_hook_filename: &str,
         post_hook_filename: &str,
-    ) -> Result<bool> {
+    ) -> Result<HookApproval> {
+        let template_dir = template_dir.as_ref();
         let (pre_hook_file, post_hook_file) =
             self.get_hook_files(template_dir, pre_hook_filename, post_hook_filename);
-        if pre_hook_file.exists() || post_hook_file.exists() {
-            Ok(confirm(
-            skip_hooks_check,
-                format!(
-                    "WARNING: This template contains the following hooks that will execute commands on your system:\n{}{}{}",
-                    self.get_path_if_exists(&pre_hook_file),
-                    self.get_path_if_exists(&post_hook_file),
-                    "Do you want to run these hooks?",
-                ),
-            )?)
-        } else {
-            Ok(false)
-        }
+        trust::approve_hooks(
+            source,
+            template_dir,
+            &pre_hook_file,
+            &post_hook_file,
+            |kind| self.should_skip_hook_prompt(kind),
+        )
     }
 
     /// Gets paths to pre and post generation hook scripts.
@@ -457,31 +917,20 @@ impl Runner {
 
         (hooks_dir.join(pre_hook_filename), hooks_dir.join(post_hook_filename))
     }
-
-    /// Returns the file path as a string if the file exists; otherwise, returns an empty string.
-    ///
-    /// # Arguments
-    /// * `path` - Path to the file
-    ///
-    /// # Returns
-    /// * `String` - The file path or empty string
-    fn get_path_if_exists<P: AsRef<Path>>(&self, path: P) -> String {
-        let path = path.as_ref();
-        if path.exists() {
-            format!("{}\n", path.to_string_lossy())
-        } else {
-            String::new()
-        }
-    }
 }
 
 struct HookPlan {
     pre_hook_file: PathBuf,
     post_hook_file: PathBuf,
-    execute_hooks: bool,
+    execute_hooks: HookApproval,
     pre_hook_runner: Vec<String>,
     post_hook_runner: Vec<String>,
     post_hook_print_stdout: bool,
+    pre_hook_working_dir: Option<HookWorkingDir>,
+    post_hook_working_dir: Option<HookWorkingDir>,
+    hook_prompts: bool,
+    pre_hook_image: Option<String>,
+    post_hook_image: Option<String>,
 }
 
 fn render_hook_runner(
@@ -502,6 +951,25 @@ fn log_dry_run_action<A: AsRef<Path>>(action: &str, target: A) {
     log::info!("[DRY RUN] {}: {}", action, target.as_ref().display());
 }
 
+/// Renders `output_subdir`, which must be a relative path inside the output directory.
+fn render_output_subdir(
+    engine: &dyn TemplateRenderer,
+    template: &str,
+    answers: &serde_json::Value,
+) -> Result<PathBuf> {
+    let rendered = engine.render(template, answers, Some("output_subdir"))?;
+    let subdir = PathBuf::from(rendered.trim());
+    let inside =
+        subdir.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+    if subdir.as_os_str().is_empty() || !inside {
+        return Err(Error::Other(anyhow::anyhow!(
+            "output_subdir rendered to '{}', which is not a relative path inside the output directory",
+            subdir.display()
+        )));
+    }
+    Ok(subdir)
+}
+
 /// Produces the user-facing completion string for the current run, accounting for dry-run mode.
 fn completion_message(dry_run: bool, output_root: &Path) -> String {
     if dry_run {
@@ -517,6 +985,45 @@ fn completion_message(dry_run: bool, output_root: &Path) -> String {
     }
 }
 
+/// Writes the context templates are rendered with to `path` for `--dump-context`,
+/// with secret answers masked.
+fn dump_context(path: &Path, context: &GenerationContext) -> Result<()> {
+    let answers = generated::redact_secret_answers(
+        context.answers(),
+        context.config(),
+        context.secret_references(),
+    );
+    let dump =
+        serde_json::to_string_pretty(&context.config().template_context(&answers))?;
+    fs::write(path, dump + "\n")?;
+    log::info!("Wrote the rendering context to '{}'", path.display());
+    Ok(())
+}
+
+/// Removes the output of an aborted generation when `clean` is set and this run
+/// `created` the directory; otherwise tells the user where the partial output is.
+fn clean_aborted_output(output_root: &Path, created: bool, clean: bool) {
+    if !output_root.exists() {
+        return;
+    }
+    if clean && created {
+        match fs::remove_dir_all(output_root) {
+            Ok(()) => {
+                println!("Generation aborted; removed '{}'", output_root.display())
+            }
+            Err(e) => println!(
+                "Generation aborted; cannot remove '{}': {e}",
+                output_root.display()
+            ),
+        }
+    } else {
+        println!(
+            "Generation aborted; partial output is left in '{}'",
+            output_root.display()
+        );
+    }
+}
+
 /// Main entry point for `baker generate`
 pub fn run(args: GenerateArgs) -> Result<()> {
     let runner = Runner::new(args);
@@ -526,6 +1033,7 @@ pub fn run(args: GenerateArgs) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::CopyMode;
     use serde_json::json;
     use tempfile::TempDir;
 
@@ -541,7 +1049,333 @@ mod tests {
             dry_run: false,
             generated_file: None,
             conflict_style: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn run_matrix_generates_each_entry() {
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    help: Name\n",
+        )
+        .unwrap();
+        fs::write(template.path().join("service.txt.baker.j2"), "{{ name }}:{{ port }}")
+            .unwrap();
+        let output = TempDir::new().unwrap();
+        let matrix_file = output.path().join("matrix.yaml");
+        fs::write(
+            &matrix_file,
+            "- output: users\n  answers: {name: users}\n- output: orders\n  answers: {name: orders, port: 81}\n",
+        )
+        .unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().join("services");
+        args.answers = Some(r#"{"port": 80}"#.to_string());
+        args.non_interactive = true;
+        args.skip_confirms = vec![SkipConfirm::All];
+        args.matrix = Some(matrix_file);
+        Runner::new(args).run().unwrap();
+
+        let services = output.path().join("services");
+        assert_eq!(
+            fs::read_to_string(services.join("users/service.txt")).unwrap(),
+            "users:80"
+        );
+        assert_eq!(
+            fs::read_to_string(services.join("orders/service.txt")).unwrap(),
+            "orders:81"
+        );
+    }
+
+    #[test]
+    fn run_matrix_exposes_named_steps_to_later_questions() {
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    help: Name
+  upstream:
+    type: str
+    help: Upstream
+    default: "{{ steps.db.answers.name if steps.db else 'none' }}"
+  upstream_files:
+    type: str
+    help: Upstream files
+    default: "{{ steps.db.files | join(',') if steps.db else '' }}"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            template.path().join("service.txt.baker.j2"),
+            "{{ name }}->{{ upstream }}:{{ upstream_files }}",
+        )
+        .unwrap();
+        let output = TempDir::new().unwrap();
+        let matrix_file = output.path().join("matrix.yaml");
+        fs::write(
+            &matrix_file,
+            "- name: db\n  output: db\n  answers: {name: postgres}\n- output: api\n  answers: {name: api}\n",
+        )
+        .unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().join("services");
+        args.non_interactive = true;
+        args.skip_confirms = vec![SkipConfirm::All];
+        args.matrix = Some(matrix_file);
+        Runner::new(args).run().unwrap();
+
+        let services = output.path().join("services");
+        assert_eq!(
+            fs::read_to_string(services.join("db/service.txt")).unwrap(),
+            "postgres->none:"
+        );
+        assert_eq!(
+            fs::read_to_string(services.join("api/service.txt")).unwrap(),
+            "api->postgres:service.txt"
+        );
+    }
+
+    #[test]
+    fn output_subdir_is_rendered_inside_existing_output_dir() {
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\noutput_subdir: \"{{ name | replace('_', '-') }}\"\nquestions:\n  name:\n    type: str\n    help: Name\n",
+        )
+        .unwrap();
+        fs::write(template.path().join("README.md.baker.j2"), "# {{ name }}").unwrap();
+        let output = TempDir::new().unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().to_path_buf();
+        args.answers = Some(r#"{"name": "my_service"}"#.to_string());
+        args.non_interactive = true;
+        Runner::new(args).run().unwrap();
+        assert_eq!(
+            fs::read_to_string(output.path().join("my-service/README.md")).unwrap(),
+            "# my_service"
+        );
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().to_path_buf();
+        args.answers = Some(r#"{"name": "../escape"}"#.to_string());
+        args.non_interactive = true;
+        args.output_subdir = Some("{{ name }}".to_string());
+        let err = Runner::new(args).run().unwrap_err();
+        assert!(err.to_string().contains("output_subdir"), "{err}");
+    }
+
+    #[test]
+    fn dry_run_saves_content_of_matching_files() {
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    help: Name\n",
+        )
+        .unwrap();
+        fs::create_dir(template.path().join("src")).unwrap();
+        fs::write(template.path().join("src/lib.rs.baker.j2"), "// {{ name }}").unwrap();
+        fs::write(template.path().join("README.md.baker.j2"), "# {{ name }}").unwrap();
+        let output = TempDir::new().unwrap();
+        let previews = TempDir::new().unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().join("project");
+        args.answers = Some(r#"{"name": "demo"}"#.to_string());
+        args.non_interactive = true;
+        args.dry_run = true;
+        args.show_content = Some("src/*.rs".to_string());
+        args.show_content_dir = Some(previews.path().to_path_buf());
+        Runner::new(args).run().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(previews.path().join("src/lib.rs")).unwrap(),
+            "// demo"
+        );
+        assert!(!previews.path().join("README.md").exists());
+        assert!(!output.path().join("project").exists());
+    }
+
+    #[test]
+    fn transformers_rewrite_answers_before_rendering() {
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nanswer_transformers: [trim_strings]\nquestions:\n  name:\n    type: str\n    help: Name\n",
+        )
+        .unwrap();
+        fs::write(template.path().join("README.md.baker.j2"), "# {{ name }}").unwrap();
+        let output = TempDir::new().unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().to_path_buf();
+        args.answers = Some(r#"{"name": "  service  "}"#.to_string());
+        args.non_interactive = true;
+        args.force = true;
+        Runner::new(args)
+            .with_transformer(|answers: &mut serde_json::Value| {
+                answers["name"] = json!(answers["name"].as_str().unwrap().to_uppercase());
+                Ok(())
+            })
+            .run()
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(output.path().join("README.md")).unwrap(),
+            "# SERVICE"
+        );
+        let generated =
+            generated::read(output.path(), crate::constants::DEFAULT_GENERATED_FILE_NAME)
+                .unwrap();
+        assert_eq!(generated.answers["name"], "  service  ");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn observers_follow_answers_files_hooks_and_the_end() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::sync::{Arc, Mutex};
+
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        impl GenerationObserver for Recorder {
+            fn on_question_answered(&self, key: &str, answer: &serde_json::Value) {
+                self.0.lock().unwrap().push(format!("answer {key}={answer}"));
+            }
+            fn on_file_written(&self, target: &Path) {
+                let name = target.file_name().unwrap().to_string_lossy();
+                self.0.lock().unwrap().push(format!("file {name}"));
+            }
+            fn on_hook_start(&self, hook: HookKind, _script: &Path) {
+                self.0.lock().unwrap().push(format!("start {hook}"));
+            }
+            fn on_hook_end(
+                &self,
+                hook: HookKind,
+                succeeded: bool,
+                _: std::time::Duration,
+            ) {
+                self.0.lock().unwrap().push(format!("end {hook} {succeeded}"));
+            }
+            fn on_finish(&self, result: Result<&GenerationSummary, &Error>) {
+                let summary = result.unwrap();
+                self.0.lock().unwrap().push(format!("finish {}", summary.files));
+            }
         }
+
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n  token:\n    type: str\n    secret: {}\n",
+        )
+        .unwrap();
+        fs::write(template.path().join("README.md.baker.j2"), "# {{ name }}").unwrap();
+        fs::create_dir(template.path().join("hooks")).unwrap();
+        let hook = template.path().join("hooks").join("post");
+        fs::write(&hook, "#!/bin/sh\ncat > /dev/null\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        let output = TempDir::new().unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().to_path_buf();
+        args.answers = Some(r#"{"name": "demo", "token": "hunter2"}"#.to_string());
+        args.non_interactive = true;
+        args.force = true;
+        args.skip_confirms = vec![SkipConfirm::All];
+        let events = Arc::new(Mutex::new(Vec::new()));
+        Runner::new(args).with_observer(Recorder(events.clone())).run().unwrap();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                r#"answer name="demo""#,
+                r#"answer token="********""#,
+                "file README.md",
+                "start post-hook",
+                "end post-hook true",
+                "finish 1",
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn dumps_the_context_for_post_hooks() {
+        use std::os::unix::fs::PermissionsExt;
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\ncontext_alias: cookiecutter\nquestions:\n  name:\n    type: str\n  token:\n    type: str\n    secret: {}\n",
+        )
+        .unwrap();
+        fs::create_dir(template.path().join("hooks")).unwrap();
+        let hook = template.path().join("hooks").join("post");
+        fs::write(
+            &hook,
+            "#!/bin/sh\ncp \"$BAKER_CONTEXT_FILE\" \"$BAKER_OUTPUT_ROOT\"\n",
+        )
+        .unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        let output = TempDir::new().unwrap();
+        let dump = output.path().join("dump.json");
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().join("project");
+        args.answers = Some(r#"{"name": "demo", "token": "hunter2"}"#.to_string());
+        args.dump_context = Some(dump.clone());
+        args.non_interactive = true;
+        args.skip_confirms = vec![SkipConfirm::All];
+        Runner::new(args).run().unwrap();
+
+        let answers = json!({"name": "demo", "token": "********"});
+        let dumped: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&dump).unwrap()).unwrap();
+        assert_eq!(dumped["cookiecutter"], answers);
+        assert_eq!(dumped["token"], "********");
+        let copied = fs::read_to_string(output.path().join("project/dump.json"));
+        assert_eq!(copied.unwrap(), fs::read_to_string(&dump).unwrap());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn hardlink_copy_mode_links_assets() {
+        use std::os::unix::fs::MetadataExt;
+        let template = TempDir::new().unwrap();
+        fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions: {}\n",
+        )
+        .unwrap();
+        fs::write(template.path().join("logo.png"), [0u8; 16]).unwrap();
+        let output = TempDir::new().unwrap();
+
+        let mut args = base_args();
+        args.template = template.path().display().to_string();
+        args.output_dir = output.path().to_path_buf();
+        args.force = true;
+        args.non_interactive = true;
+        args.copy_mode = CopyMode::Hardlink;
+        Runner::new(args).run().unwrap();
+
+        let copied = fs::metadata(output.path().join("logo.png")).unwrap();
+        let original = fs::metadata(template.path().join("logo.png")).unwrap();
+        assert_eq!(copied.ino(), original.ino());
+        assert_eq!(copied.nlink(), 2);
     }
 
     #[test]
@@ -550,13 +1384,14 @@ mod tests {
         args.skip_confirms = vec![SkipConfirm::Overwrite];
         let runner = Runner::new(args);
         assert!(runner.should_skip_overwrite_prompts());
-        assert!(!runner.should_skip_hook_prompts());
+        assert!(!runner.should_skip_hook_prompt(HookKind::Pre));
 
         let mut args = base_args();
         args.skip_confirms = vec![SkipConfirm::Hooks];
         let runner = Runner::new(args);
         assert!(!runner.should_skip_overwrite_prompts());
-        assert!(runner.should_skip_hook_prompts());
+        assert!(runner.should_skip_hook_prompt(HookKind::Pre));
+        assert!(runner.should_skip_hook_prompt(HookKind::Post));
     }
 
     #[test]
@@ -580,6 +1415,20 @@ mod tests {
         assert_eq!(result, temp_dir.path());
     }
 
+    #[test]
+    fn aborted_output_is_removed_only_when_created_and_asked() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("out");
+        std::fs::create_dir_all(&output).unwrap();
+
+        clean_aborted_output(&output, true, false);
+        assert!(output.exists());
+        clean_aborted_output(&output, false, true);
+        assert!(output.exists());
+        clean_aborted_output(&output, true, true);
+        assert!(!output.exists());
+    }
+
     #[test]
     fn build_templates_import_globset_matches_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -609,20 +1458,18 @@ mod tests {
         let hooks_dir = temp_dir.path().join("hooks");
         std::fs::create_dir_all(&hooks_dir).unwrap();
         std::fs::write(hooks_dir.join("pre"), "echo pre").unwrap();
-        let runner = Runner::new(base_args());
-        let execute_hooks =
-            runner.confirm_hook_execution(temp_dir.path(), true, "pre", "post").unwrap();
-        assert!(execute_hooks);
-    }
-
-    #[test]
-    fn get_path_if_exists_returns_display_string() {
-        let temp_dir = TempDir::new().unwrap();
-        let file_path = temp_dir.path().join("file.txt");
-        std::fs::write(&file_path, "content").unwrap();
-        let runner = Runner::new(base_args());
-        assert!(runner.get_path_if_exists(&file_path).contains("file.txt"));
-        assert!(runner.get_path_if_exists(temp_dir.path().join("missing")).is_empty());
+        let mut args = base_args();
+        args.skip_confirms = vec![SkipConfirm::Hook(HookKind::Pre)];
+        let runner = Runner::new(args);
+        let approval = runner
+            .confirm_hook_execution(
+                temp_dir.path(),
+                &TemplateSourceInfo::Tar { hash: "abc".to_string() },
+                "pre",
+                "post",
+            )
+            .unwrap();
+        assert_eq!(approval, HookApproval { pre: true, post: false });
     }
 
     #[test]
@@ -0,0 +1,361 @@
+//! `baker self-update` — replaces the running executable with the latest release,
+//! and the notice of a newer release shown in `--verbose` runs.
+//!
+//! Releases are looked up on GitHub. The archive for this platform is downloaded
+//! with its `.sha256` and `.minisig` files and only installed when the checksum
+//! matches and the minisign signature verifies against the release key built into
+//! Baker; the archive is unpacked with the system `tar`.
+
+use crate::{
+    cli::SelfUpdateArgs,
+    error::{Error, Result},
+};
+
+/// Main entry point for `baker self-update`.
+#[cfg(not(feature = "self-update"))]
+pub fn run_self_update(_args: SelfUpdateArgs) -> Result<()> {
+    Err(Error::Other(anyhow::anyhow!(
+        "This Baker was built without the `self-update` feature; update it the way it was installed"
+    )))
+}
+
+/// Logs a notice when a newer release is out. Only available with `self-update`.
+#[cfg(not(feature = "self-update"))]
+pub fn notify_if_outdated() {}
+
+#[cfg(feature = "self-update")]
+pub use imp::{notify_if_outdated, run_self_update};
+
+#[cfg(feature = "self-update")]
+mod imp {
+    use super::*;
+    use crate::{config::user::cache_dir, prompt::confirm, temp::TempDir};
+    use serde::{Deserialize, Serialize};
+    use sha2::{Digest, Sha256};
+    use std::{
+        path::{Path, PathBuf},
+        process::Command,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+    use walkdir::WalkDir;
+
+    /// How long the version seen by [`notify_if_outdated`] is trusted.
+    const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+    /// How long a failed lookup is remembered, so offline runs do not wait for the
+    /// timeout every time.
+    const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+    /// The minisign public key release archives are signed with, set when the
+    /// release is built.
+    const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("BAKER_RELEASE_PUBLIC_KEY");
+
+    /// A GitHub release.
+    #[derive(Debug, Deserialize)]
+    struct Release {
+        tag_name: String,
+        assets: Vec<Asset>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Asset {
+        name: String,
+        browser_download_url: String,
+    }
+
+    impl Release {
+        fn version(&self) -> Result<semver::Version> {
+            semver::Version::parse(self.tag_name.trim_start_matches('v')).map_err(|e| {
+                Error::Other(anyhow::anyhow!(
+                    "Invalid release version '{}': {e}",
+                    self.tag_name
+                ))
+            })
+        }
+
+        fn asset(&self, name: &str) -> Result<&Asset> {
+            self.assets.iter().find(|asset| asset.name == name).ok_or_else(|| {
+                Error::Other(anyhow::anyhow!(
+                    "Release {} has no '{name}' for this platform",
+                    self.tag_name
+                ))
+            })
+        }
+    }
+
+    /// The latest version seen, cached between `--verbose` runs.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct LatestVersion {
+        version: String,
+        checked_at: u64,
+        /// Whether the lookup at `checked_at` failed.
+        #[serde(default)]
+        failed: bool,
+    }
+
+    pub fn run_self_update(args: SelfUpdateArgs) -> Result<()> {
+        let current = current_version();
+        let release = latest_release(Duration::from_secs(30))?;
+        let latest = release.version()?;
+        if latest <= current {
+            println!("Baker {current} is the latest version.");
+            return Ok(());
+        }
+        if args.check {
+            println!("Baker {latest} is available (installed: {current}).");
+            return Ok(());
+        }
+        if !confirm(args.yes, format!("Update Baker {current} to {latest}?"))? {
+            println!("Update cancelled.");
+            return Ok(());
+        }
+
+        let public_key = RELEASE_PUBLIC_KEY.ok_or_else(|| {
+            Error::Other(anyhow::anyhow!(
+                "This Baker was built without a release signing key; update it the way it was installed"
+            ))
+        })?;
+        let name = archive_name()?;
+        let archive = download(&release.asset(&name)?.browser_download_url)?;
+        let checksum =
+            download(&release.asset(&format!("{name}.sha256"))?.browser_download_url)?;
+        verify_checksum(&archive, &String::from_utf8_lossy(&checksum))?;
+        let signature =
+            download(&release.asset(&format!("{name}.minisig"))?.browser_download_url)?;
+        verify_signature(&archive, &String::from_utf8_lossy(&signature), public_key)?;
+
+        let staging = TempDir::new("self-update")?;
+        let archive_path = staging.path().join(&name);
+        std::fs::write(&archive_path, archive)?;
+        let binary = unpack(&archive_path, staging.path())?;
+        replace_executable(&binary, &std::env::current_exe()?)?;
+        println!("Updated Baker {current} to {latest}.");
+        Ok(())
+    }
+
+    pub fn notify_if_outdated() {
+        match latest_known_version() {
+            Ok(latest) if latest > current_version() => log::info!(
+                "Baker {latest} is available (installed: {}); run `baker self-update`",
+                current_version()
+            ),
+            Ok(_) => {}
+            Err(e) => log::debug!("Cannot check for a newer Baker: {e}"),
+        }
+    }
+
+    fn current_version() -> semver::Version {
+        semver::Version::parse(env!("CARGO_PKG_VERSION")).expect("valid package version")
+    }
+
+    /// The latest version, from the cache when it was checked within a day. A
+    /// failed lookup is not retried within an hour.
+    fn latest_known_version() -> Result<semver::Version> {
+        let path = cache_dir().map(|dir| dir.join("baker").join("latest-version.json"));
+        let now =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let cached = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<LatestVersion>(&content).ok())
+            .filter(|cached| {
+                let interval =
+                    if cached.failed { RETRY_INTERVAL } else { CHECK_INTERVAL };
+                now.saturating_sub(cached.checked_at) < interval.as_secs()
+            });
+        match cached {
+            Some(cached) if cached.failed => {
+                return Err(Error::Other(anyhow::anyhow!(
+                    "the last lookup failed, not retrying yet"
+                )))
+            }
+            Some(cached) => {
+                if let Ok(version) = semver::Version::parse(&cached.version) {
+                    return Ok(version);
+                }
+            }
+            None => {}
+        }
+        // A short timeout, so an offline machine does not slow the run down.
+        let version = latest_release(Duration::from_secs(2)).and_then(|r| r.version());
+        if let Some(path) = path {
+            let latest = LatestVersion {
+                version: version.as_ref().map(ToString::to_string).unwrap_or_default(),
+                checked_at: now,
+                failed: version.is_err(),
+            };
+            let written = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|()| std::fs::write(&path, serde_json::to_vec(&latest)?));
+            if let Err(e) = written {
+                log::debug!(
+                    "Cannot cache the latest version in '{}': {e}",
+                    path.display()
+                );
+            }
+        }
+        version
+    }
+
+    fn client(timeout: Duration) -> Result<reqwest::blocking::Client> {
+        reqwest::blocking::Client::builder()
+            .user_agent(concat!("baker/", env!("CARGO_PKG_VERSION")))
+            .timeout(timeout)
+            .build()
+            .map_err(|e| Error::Other(e.into()))
+    }
+
+    fn latest_release(timeout: Duration) -> Result<Release> {
+        let repository =
+            env!("CARGO_PKG_REPOSITORY").trim_start_matches("https://github.com/");
+        let url = format!("https://api.github.com/repos/{repository}/releases/latest");
+        let mut request =
+            client(timeout)?.get(&url).header("Accept", "application/vnd.github+json");
+        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|e| {
+                Error::Network(format!("Cannot look up the latest release: {e}"))
+            })
+    }
+
+    fn download(url: &str) -> Result<Vec<u8>> {
+        log::debug!("Downloading '{url}'");
+        client(Duration::from_secs(300))?
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| Error::Network(format!("Cannot download '{url}': {e}")))
+    }
+
+    /// Name of the release archive for the platform Baker was built for.
+    fn archive_name() -> Result<String> {
+        let arch = std::env::consts::ARCH;
+        let target = match std::env::consts::OS {
+            "linux" if cfg!(target_env = "musl") => format!("{arch}-unknown-linux-musl"),
+            "linux" => format!("{arch}-unknown-linux-gnu"),
+            "macos" => format!("{arch}-apple-darwin"),
+            "windows" => format!("{arch}-pc-windows-msvc"),
+            os => {
+                return Err(Error::Other(anyhow::anyhow!(
+                    "No Baker releases are published for {os}"
+                )))
+            }
+        };
+        let extension = if cfg!(windows) { "zip" } else { "tar.xz" };
+        Ok(format!("baker-{target}.{extension}"))
+    }
+
+    /// Checks `content` against a `sha256sum`-style line: the hex digest, optionally
+    /// followed by the file name.
+    fn verify_checksum(content: &[u8], checksum: &str) -> Result<()> {
+        let expected = checksum.split_whitespace().next().unwrap_or_default();
+        let actual = hex::encode(Sha256::digest(content));
+        if !expected.eq_ignore_ascii_case(&actual) {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Checksum mismatch for the downloaded release: expected {expected}, got {actual}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the minisign `signature` of `content` against `public_key`, the
+    /// base64 key of a `minisign.pub` file. Only prehashed signatures, the
+    /// default of minisign, are accepted.
+    fn verify_signature(content: &[u8], signature: &str, public_key: &str) -> Result<()> {
+        let invalid = |e: minisign_verify::Error| {
+            Error::Other(anyhow::anyhow!(
+                "Signature check failed for the downloaded release: {e}"
+            ))
+        };
+        let public_key = minisign_verify::PublicKey::from_base64(public_key.trim())
+            .map_err(invalid)?;
+        let signature = minisign_verify::Signature::decode(signature).map_err(invalid)?;
+        public_key.verify(content, &signature, false).map_err(invalid)
+    }
+
+    /// Unpacks `archive` into `dir` and returns the path of the `baker` binary in it.
+    fn unpack(archive: &Path, dir: &Path) -> Result<PathBuf> {
+        let status = Command::new("tar")
+            .arg("-xf")
+            .arg(archive)
+            .arg("-C")
+            .arg(dir)
+            .status()
+            .map_err(|e| Error::Other(anyhow::anyhow!("Cannot run tar: {e}")))?;
+        if !status.success() {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Cannot unpack '{}': tar exited with {status}",
+                archive.display()
+            )));
+        }
+        let binary = if cfg!(windows) { "baker.exe" } else { "baker" };
+        WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.file_type().is_file() && entry.file_name() == binary)
+            .map(|entry| entry.into_path())
+            .ok_or_else(|| {
+                Error::Other(anyhow::anyhow!("The release archive has no '{binary}'"))
+            })
+    }
+
+    /// Moves `binary` over `current`. The new binary is copied next to the current
+    /// one first, so the final rename stays on one filesystem. Windows cannot
+    /// replace a running executable, but it can rename it out of the way.
+    fn replace_executable(binary: &Path, current: &Path) -> Result<()> {
+        let staged = current.with_extension("new");
+        std::fs::copy(binary, &staged)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+        }
+        if cfg!(windows) {
+            let old = current.with_extension("old");
+            let _ = std::fs::remove_file(&old);
+            std::fs::rename(current, &old)?;
+        }
+        std::fs::rename(&staged, current)?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn verifies_checksums_and_replaces_the_executable() {
+            let digest = hex::encode(Sha256::digest(b"binary"));
+            assert!(
+                verify_checksum(b"binary", &format!("{digest}  baker.tar.xz\n")).is_ok()
+            );
+            assert!(verify_checksum(b"tampered", &digest).is_err());
+            assert!(verify_checksum(b"binary", "").is_err());
+
+            let public_key = "RWSoUnP2LwOyrOHMXAZz6ptQa9PYt/idf0kluZD6l2pOZjB3hAQnLdts";
+            let signature = "untrusted comment: signature from minisign secret key\n\
+                RUSoUnP2LwOyrOVcNd6EKnoQAZjKobiMumDCVWSbFiz+jaIVcKi3WofvZYU9ZeVFp7gvzAX3vw3DsxvVXr3jh41bpwR5/fugEgc=\n\
+                trusted comment: timestamp:1700000000\tfile:baker.tar.xz\n\
+                mDRhJSaQaRxCCcKnPYsDumKhFdPt3yay9YWVUe2z72BbqPlNYJ6Wuhrnacz1BTaGTozSkbELEOP9P8NHbTCuCg==\n";
+            assert!(verify_signature(b"binary", signature, public_key).is_ok());
+            assert!(verify_signature(b"tampered", signature, public_key).is_err());
+            assert!(verify_signature(b"binary", "", public_key).is_err());
+
+            let dir = tempfile::tempdir().unwrap();
+            let current = dir.path().join("baker");
+            let binary = dir.path().join("download");
+            std::fs::write(&current, "old").unwrap();
+            std::fs::write(&binary, "new").unwrap();
+            replace_executable(&binary, &current).unwrap();
+            assert_eq!(std::fs::read_to_string(&current).unwrap(), "new");
+            assert!(archive_name().unwrap().starts_with("baker-"));
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! `baker shell` — an interactive session on a template's context.
+//!
+//! The template is loaded and its questions answered as in a non-interactive
+//! generation. Each line typed is then rendered against the answers: a line with
+//! `{{` or `{%` as a template, anything else as an expression. Commands starting
+//! with `:` inspect the context and render single files.
+
+use crate::{
+    cli::{
+        answer_source::parse_answer_value,
+        answers::AnswerCollector,
+        audit::fetch_template_root,
+        context::GenerationContext,
+        processor::FileProcessor,
+        update::{add_templates_in_renderer, load_and_validate_config},
+        ShellArgs,
+    },
+    error::{Error, Result},
+    ignore::parse_bakerignore_file,
+    renderer::TemplateRenderer,
+    template::{get_template_engine, processor::TemplateProcessor},
+};
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+const HELP: &str = "\
+{{ template }} / {% ... %}  render a template string
+<expression>              evaluate an expression, e.g. entities | length
+:vars                     list the answers
+:filters                  list Baker's filters and functions
+:files                    list the files the template produces
+:render <file>            render one of the files
+:set <key>=<value>        change an answer (JSON, or a string)
+:help                     show this help
+:quit                     leave the shell
+";
+
+/// Main entry point for `baker shell`.
+pub fn run_shell(args: ShellArgs) -> Result<()> {
+    let (template_root, _tmp) = fetch_template_root(&args.template)?;
+    let mut engine = get_template_engine();
+    let mut shell = Shell::load(&template_root, &args, &mut engine)?;
+    println!("Template '{}' loaded; :help lists the commands.", args.template);
+    shell.run(std::io::stdin().lock(), &mut std::io::stdout())
+}
+
+/// A template, its answers and the engine rendering them.
+struct Shell<'a> {
+    engine: &'a dyn TemplateRenderer,
+    context: GenerationContext,
+}
+
+impl<'a> Shell<'a> {
+    fn load(
+        template_root: &Path,
+        args: &ShellArgs,
+        engine: &'a mut dyn TemplateRenderer,
+    ) -> Result<Self> {
+        let template_root = template_root.to_path_buf();
+        let config = load_and_validate_config(&template_root, args.profile.as_deref())?;
+        add_templates_in_renderer(&template_root, &config, engine);
+        let engine: &'a dyn TemplateRenderer = engine;
+
+        let answers = AnswerCollector::new(engine, true, &template_root)
+            .with_set_answers(args.set.clone())
+            .collect_answers(
+                &config,
+                None,
+                args.answers.clone(),
+                args.answers_file.clone(),
+            )?;
+        // Files are only rendered, so the output root is a placeholder that
+        // output paths are shown relative to.
+        let mut context = GenerationContext::new(
+            template_root,
+            PathBuf::from("output"),
+            config,
+            Vec::new(),
+            true,
+            false,
+            None,
+        );
+        context.set_answers(answers);
+        Ok(Self { engine, context })
+    }
+
+    /// Reads lines from `input` until it ends or `:quit`, writing each result to
+    /// `out`. Errors of a line are printed and the session goes on.
+    fn run(&mut self, mut input: impl BufRead, out: &mut impl Write) -> Result<()> {
+        loop {
+            write!(out, "baker> ")?;
+            out.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(out)?;
+                return Ok(());
+            }
+            let line = line.trim();
+            if matches!(line, ":quit" | ":q" | ":exit") {
+                return Ok(());
+            }
+            if line.is_empty() {
+                continue;
+            }
+            match self.eval(line) {
+                Ok(result) if result.ends_with('\n') => write!(out, "{result}")?,
+                Ok(result) => writeln!(out, "{result}")?,
+                Err(e) => writeln!(out, "error: {e}")?,
+            }
+        }
+    }
+
+    fn eval(&mut self, line: &str) -> Result<String> {
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        match command {
+            ":help" => Ok(HELP.to_string()),
+            ":vars" => Ok(self.vars()),
+            ":filters" => Ok(self.engine.registry().describe(None)),
+            ":files" => Ok(self.files()?.join("\n")),
+            ":render" => self.render_file(argument),
+            ":set" => self.set(argument),
+            _ if command.starts_with(':') => Err(Error::Other(anyhow::anyhow!(
+                "unknown command '{command}', see :help"
+            ))),
+            _ if line.contains("{{") || line.contains("{%") => {
+                self.engine.render(line, self.context.template_answers(), Some("shell"))
+            }
+            _ => self.engine.render(
+                &format!("{{{{ {line} }}}}"),
+                self.context.template_answers(),
+                Some("shell"),
+            ),
+        }
+    }
+
+    fn vars(&self) -> String {
+        let Some(answers) = self.context.answers().as_object() else {
+            return String::new();
+        };
+        answers.iter().map(|(key, value)| format!("{key} = {value}\n")).collect()
+    }
+
+    fn set(&mut self, assignment: &str) -> Result<String> {
+        let Some((key, value)) = assignment.split_once('=') else {
+            return Err(Error::Other(anyhow::anyhow!("expected :set <key>=<value>")));
+        };
+        let mut answers = self.context.answers().clone();
+        answers[key.trim()] = parse_answer_value(value.trim());
+        self.context.set_answers(answers);
+        Ok(format!("{} = {}", key.trim(), self.context.answers()[key.trim()]))
+    }
+
+    /// Renders the template with `render`, which gets the file processor.
+    fn with_files<T>(&self, render: impl FnOnce(&FileProcessor) -> T) -> Result<T> {
+        let bakerignore = parse_bakerignore_file(self.context.template_root())?;
+        let processor = TemplateProcessor::new(self.engine, &self.context, &bakerignore);
+        let files = FileProcessor::new(processor, &self.context);
+        Ok(render(&files))
+    }
+
+    fn files(&self) -> Result<Vec<String>> {
+        self.with_files(|files| files.plan().into_keys().collect())
+    }
+
+    /// Renders the file at `output`, a path listed by `:files`, or the output of the
+    /// template entry at `output`.
+    fn render_file(&self, output: &str) -> Result<String> {
+        if output.is_empty() {
+            return Err(Error::Other(anyhow::anyhow!("expected :render <file>")));
+        }
+        self.with_files(|files| {
+            let plan = files.plan();
+            let (output, provenance) = plan
+                .iter()
+                .find(|(path, _)| path.as_str() == output)
+                .or_else(|| plan.iter().find(|(_, file)| file.template == output))
+                .ok_or_else(|| {
+                    Error::Other(anyhow::anyhow!(
+                        "'{output}' is not produced by the template, see :files"
+                    ))
+                })?;
+            files.render_file(&provenance.template, Path::new(output))?.ok_or_else(|| {
+                Error::Other(anyhow::anyhow!("'{output}' is copied, not rendered"))
+            })
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> ShellArgs {
+        ShellArgs {
+            template: String::new(),
+            answers: Some(r#"{"entities": ["user", "order"]}"#.to_string()),
+            answers_file: None,
+            set: Vec::new(),
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn evaluates_expressions_and_renders_files() {
+        let template = tempfile::tempdir().unwrap();
+        std::fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    default: demo\n  entities:\n    type: json\n",
+        )
+        .unwrap();
+        std::fs::write(
+            template.path().join("README.md.baker.j2"),
+            "# {{ name | pascal_case }}\n",
+        )
+        .unwrap();
+
+        let mut engine = get_template_engine();
+        let mut shell = Shell::load(template.path(), &args(), &mut engine).unwrap();
+        let script = "entities | length\n{{ name }}!\n:set name=my app\n:files\n\
+                      :render README.md\n:vars\nmissing(\n:quit\nnot evaluated\n";
+        let mut out = Vec::new();
+        shell.run(script.as_bytes(), &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> =
+            out.split("baker> ").map(str::trim_end).filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines[0], "2");
+        assert_eq!(lines[1], "demo!");
+        assert_eq!(lines[2], "name = \"my app\"");
+        assert_eq!(lines[3], "README.md");
+        assert_eq!(lines[4], "# MyApp");
+        assert_eq!(lines[5], "entities = [\"user\",\"order\"]\nname = \"my app\"");
+        assert!(lines[6].starts_with("error: "));
+        assert_eq!(lines.len(), 7);
+    }
+}
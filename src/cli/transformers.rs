@@ -0,0 +1,156 @@
+//! Answer transformers: rewrites of the collected answers before they are frozen
+//! into the [`GenerationContext`](crate::cli::context::GenerationContext).
+//!
+//! Templates enable the built-ins with `answer_transformers` in their config.
+//! Embedding applications register their own with [`Runner::with_transformer`],
+//! which run after the built-ins:
+//!
+//! ```ignore
+//! use baker::cli::runner::Runner;
+//!
+//! Runner::new(args)
+//!     .with_transformer(|answers: &mut serde_json::Value| {
+//!         answers["org"] = "acme".into();
+//!         Ok(())
+//!     })
+//!     .run()?;
+//! ```
+//!
+//! [`Runner::with_transformer`]: crate::cli::runner::Runner::with_transformer
+
+use crate::{config::AnswerTransform, error::Result};
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// `${VAR}` in an answer.
+static ENV_VARIABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid env regex")
+});
+
+/// Rewrites the answers in place.
+pub trait AnswerTransformer {
+    fn transform(&self, answers: &mut Value) -> Result<()>;
+}
+
+impl<F> AnswerTransformer for F
+where
+    F: Fn(&mut Value) -> Result<()>,
+{
+    fn transform(&self, answers: &mut Value) -> Result<()> {
+        self(answers)
+    }
+}
+
+impl AnswerTransformer for AnswerTransform {
+    fn transform(&self, answers: &mut Value) -> Result<()> {
+        match self {
+            AnswerTransform::TrimStrings => {
+                map_strings(answers, &|s| s.trim().to_string())
+            }
+            AnswerTransform::LowercaseKeys => lowercase_keys(answers),
+            AnswerTransform::ExpandEnv => map_strings(answers, &expand_env),
+        }
+        Ok(())
+    }
+}
+
+/// Applies the config's built-in transformers, then `custom`, in order.
+pub fn apply_transformers(
+    builtins: &[AnswerTransform],
+    custom: &[Box<dyn AnswerTransformer>],
+    answers: &mut Value,
+) -> Result<()> {
+    for transformer in builtins {
+        transformer.transform(answers)?;
+    }
+    for transformer in custom {
+        transformer.transform(answers)?;
+    }
+    Ok(())
+}
+
+fn map_strings(value: &mut Value, f: &dyn Fn(&str) -> String) {
+    match value {
+        Value::String(s) => *s = f(s),
+        Value::Array(items) => items.iter_mut().for_each(|item| map_strings(item, f)),
+        Value::Object(map) => map.values_mut().for_each(|item| map_strings(item, f)),
+        _ => {}
+    }
+}
+
+fn lowercase_keys(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(lowercase_keys),
+        Value::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(key, mut item)| {
+                    lowercase_keys(&mut item);
+                    (key.to_lowercase(), item)
+                })
+                .collect();
+        }
+        _ => {}
+    }
+}
+
+/// Replaces `${VAR}` with the variable's value; unset variables expand to nothing,
+/// as in a shell.
+fn expand_env(s: &str) -> String {
+    ENV_VARIABLE
+        .replace_all(s, |caps: &Captures| std::env::var(&caps[1]).unwrap_or_default())
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builtins_rewrite_nested_answers() {
+        std::env::set_var("BAKER_TRANSFORMER_TEST_ORG", "acme");
+        let mut answers = json!({
+            "Name": "  service  ",
+            "Owners": [" ${BAKER_TRANSFORMER_TEST_ORG}/team ", "${BAKER_UNSET_VARIABLE}x"],
+            "Db": {"Port": 5432, "Host": " localhost"},
+        });
+
+        apply_transformers(
+            &[
+                AnswerTransform::ExpandEnv,
+                AnswerTransform::TrimStrings,
+                AnswerTransform::LowercaseKeys,
+            ],
+            &[],
+            &mut answers,
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers,
+            json!({
+                "name": "service",
+                "owners": ["acme/team", "x"],
+                "db": {"port": 5432, "host": "localhost"},
+            })
+        );
+    }
+
+    #[test]
+    fn custom_transformers_run_after_builtins() {
+        let custom: Vec<Box<dyn AnswerTransformer>> =
+            vec![Box::new(|answers: &mut Value| {
+                answers["name"] =
+                    json!(format!("{}!", answers["name"].as_str().unwrap()));
+                Ok(())
+            })];
+        let mut answers = json!({"name": " baker "});
+
+        apply_transformers(&[AnswerTransform::TrimStrings], &custom, &mut answers)
+            .unwrap();
+
+        assert_eq!(answers, json!({"name": "baker!"}));
+    }
+}
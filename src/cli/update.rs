@@ -3,22 +3,40 @@
 
 use crate::{
     cli::{
-        answers::AnswerCollector, context::GenerationContext, hooks::run_hook,
-        processor::FileProcessor, UpdateArgs,
+        answer_source::answer_source_for,
+        answers::{pre_hook_answers, AnswerCollector},
+        changelog,
+        context::GenerationContext,
+        encrypted,
+        hooks::{run_hook, HookOptions},
+        observer::HookKind,
+        processor::FileProcessor,
+        transformers::apply_transformers,
+        trust::{self, HookApproval},
+        SkipConfirm, UpdateArgs,
     },
-    config::{Config, ConfigV1},
+    config::{policy, Config, ConfigV1, LoadOptions, UserConfig},
     conflict::ConflictStyle,
-    error::Result,
-    generated::{self, BakerGenerated},
+    copier,
+    error::{Error, Result},
+    generated::{self, BakerGenerated, FileProvenance},
     ignore::parse_bakerignore_file,
+    interrupt,
+    keyring::Keyring,
     loader::{get_template, TemplateSourceInfo},
+    lock,
     renderer::TemplateRenderer,
-    template::{get_template_engine, processor::TemplateProcessor},
+    secrets,
+    temp::TempDir,
+    template::{
+        get_template_engine,
+        processor::{unsafe_path_reason, TemplateProcessor},
+    },
 };
 use globset::{Glob, GlobSetBuilder};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use tempfile::TempDir;
 use walkdir::WalkDir;
 
 // Public entry point
@@ -62,22 +80,41 @@ impl UpdateRunner {
             .unwrap_or(crate::constants::DEFAULT_GENERATED_FILE_NAME);
 
         let cwd = self.working_dir.clone();
-        let meta: BakerGenerated = generated::read(&cwd, file_name)?;
+        let mut meta: BakerGenerated = match generated::read(&cwd, file_name) {
+            Err(Error::GeneratedFileNotFound { path }) => match copier::read(&cwd)? {
+                Some(meta) => {
+                    log::info!("Using answers from '{}'", copier::COPIER_ANSWERS_FILE);
+                    meta
+                }
+                None => return Err(Error::GeneratedFileNotFound { path }),
+            },
+            result => result?,
+        };
 
         log::info!("Found generated metadata (generated_at={})", meta.generated_at);
 
         let skip_overwrite = self.should_skip_overwrite_prompts();
         let (loaded, _tmp_guard) =
             self.fetch_updated_template(&meta.template, skip_overwrite)?;
+        if let Ok(repo) = git2::Repository::open(&loaded.root) {
+            copier::resolve_commit(&repo, &mut meta.template)?;
+        }
 
         if self.sources_are_identical(&meta.template, &loaded.source) {
             println!("Template has not changed since last generation — nothing to do.");
             return Ok(());
         }
 
+        if !self.review_changes(&loaded.root, &meta.template, &loaded.source)? {
+            println!("Update cancelled.");
+            return Ok(());
+        }
+
+        encrypted::decrypt_answers(&mut meta, &cwd.join(file_name))?;
         let merged_answers = self.merge_answers(meta.answers.clone())?;
 
-        let config = load_and_validate_config(&loaded.root)?;
+        let config = load_and_validate_config(&loaded.root, meta.profile.as_deref())?;
+        crate::prompt::theme::set_template_theme(&config.prompt_theme)?;
 
         let conflict_style: Option<ConflictStyle> =
             self.args.conflict_style.or(config.conflict_marker_style);
@@ -95,41 +132,58 @@ impl UpdateRunner {
 
         let mut engine = get_template_engine();
         add_templates_in_renderer(&loaded.root, context.config(), &mut engine);
+        engine.set_output_root(&cwd);
 
-        let execute_hooks = self.confirm_hooks(&context, &engine)?;
+        let execute_hooks = self.confirm_hooks(&context, &loaded.source, &engine)?;
 
         let pre_hook_output =
-            self.maybe_run_pre_hook(&context, &engine, execute_hooks)?;
+            self.maybe_run_pre_hook(&context, &engine, execute_hooks.pre)?;
 
         if let Some(ref hook_json) = pre_hook_output {
-            if let Ok(hook_val) = serde_json::from_str::<serde_json::Value>(hook_json) {
-                if let (Some(base), Some(extra)) =
-                    (merged_answers.as_object(), hook_val.as_object())
-                {
-                    let mut merged = base.clone();
-                    merged.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
-                    context.set_answers(serde_json::Value::Object(merged));
-                }
+            let extra = pre_hook_answers(context.config(), hook_json)?;
+            if let Some(base) = merged_answers.as_object() {
+                let mut merged = base.clone();
+                merged.extend(extra);
+                context.set_answers(serde_json::Value::Object(merged));
             }
         }
 
         let merged_json_str = serde_json::to_string(context.answers())?;
-        let collector =
+        let mut collector =
             AnswerCollector::new(&engine, self.args.non_interactive, &loaded.root);
-        let final_answers = collector.collect_answers(
+        if self.args.keyring {
+            collector = collector.with_keyring(Keyring::for_template(&loaded.source)?);
+        }
+        let mut final_answers = collector.collect_answers(
             context.config(),
             pre_hook_output,
             Some(merged_json_str),
             None,
         )?;
+        context.set_recorded_answers(final_answers.clone());
+        apply_transformers(
+            &context.config().answer_transformers,
+            &[],
+            &mut final_answers,
+        )?;
         context.set_answers(final_answers);
+        context.set_secret_references(collector.secret_references().clone());
 
         let bakerignore = parse_bakerignore_file(context.template_root())?;
         let processor = TemplateProcessor::new(&engine, &context, &bakerignore);
-        let file_processor = FileProcessor::new(processor, &context);
+        let file_processor = FileProcessor::new(processor, &context)
+            .with_cancellation(interrupt::flag())
+            .with_copy_buffer_size(UserConfig::load()?.copy.buffer_size);
+        let planned = file_processor.plan();
+        let moves = detect_moves(&meta.files, &planned);
+        let moved = apply_moves(&cwd, &moves, context.dry_run())?;
         file_processor.process_all_files()?;
 
-        self.maybe_run_post_hook(&context, &engine, execute_hooks)?;
+        let orphans = find_orphans(&meta.files, &planned, &moves);
+        let pruned =
+            self.prune_orphans(&cwd, &meta.files, &orphans, context.dry_run())?;
+
+        self.maybe_run_post_hook(&context, &engine, execute_hooks.post)?;
 
         if context.dry_run() {
             log::info!(
@@ -137,10 +191,38 @@ impl UpdateRunner {
                 cwd.join(file_name).display()
             );
         } else {
+            let answers = generated::strip_secret_answers(
+                context.recorded_answers(),
+                context.config(),
+            );
             let answers =
-                generated::strip_secret_answers(context.answers(), context.config());
-            let new_meta = BakerGenerated::new(loaded.source, answers);
-            generated::write(&cwd, file_name, &new_meta)?;
+                secrets::restore_references(&answers, context.secret_references());
+            let files = file_processor.provenance();
+            if lock::read(&cwd)?.is_some() {
+                let lock = lock::BakerLock::new(loaded.source.clone(), &answers, &files);
+                lock::write(&cwd, &lock)?;
+            }
+            let new_meta = BakerGenerated::new(loaded.source, answers)
+                .with_files(files)
+                .with_profile(meta.profile.clone())
+                .with_encrypted_answers(
+                    meta.encrypted_answers || self.args.encrypt_answers,
+                );
+            encrypted::write_generated(&cwd, file_name, &new_meta)?;
+            if context.config().copier_answers
+                || cwd.join(copier::COPIER_ANSWERS_FILE).exists()
+            {
+                copier::write(&cwd, &new_meta)?;
+            }
+        }
+
+        for (from, to) in &moved {
+            let verb = if context.dry_run() { "Would move" } else { "Moved" };
+            println!("{verb} '{from}' -> '{to}'");
+        }
+        for path in &pruned {
+            let verb = if context.dry_run() { "Would delete" } else { "Deleted" };
+            println!("{verb} '{path}'");
         }
 
         println!(
@@ -159,7 +241,7 @@ impl UpdateRunner {
     /// Re-fetches the template from its original source.
     ///
     /// For git sources, clones into a temp directory and returns both the loaded
-    /// template and the `TempDir` guard (RAII cleanup on drop). For filesystem
+    /// template and the [`TempDir`] guard (RAII cleanup on drop). For filesystem
     /// sources, loads directly.
     fn fetch_updated_template(
         &self,
@@ -168,7 +250,7 @@ impl UpdateRunner {
     ) -> Result<(crate::loader::LoadedTemplate, Option<TempDir>)> {
         match stored {
             TemplateSourceInfo::Git { url, .. } => {
-                let tmp = TempDir::new()?;
+                let tmp = TempDir::new("clone")?;
                 let tmp_path = tmp.path().to_path_buf();
                 let loaded = clone_git_into_tmp(url, &tmp_path)?;
                 Ok((loaded, Some(tmp)))
@@ -177,7 +259,90 @@ impl UpdateRunner {
                 let loaded = get_template(path.as_str(), skip_overwrite)?;
                 Ok((loaded, None))
             }
+            TemplateSourceInfo::Plugin { url, .. } => {
+                let loaded = get_template(url.as_str(), skip_overwrite)?;
+                Ok((loaded, None))
+            }
+            TemplateSourceInfo::Tar { .. } => Err(Error::Other(anyhow::anyhow!(
+                "The template was read from a tar stream and cannot be loaded again"
+            ))),
+        }
+    }
+
+    /// Offers the orphaned files the user has not modified for deletion: deleted
+    /// outright with `--prune`, after confirmation otherwise. Modified orphans are
+    /// kept. Returns the deleted files (or, in a dry run, those that would be).
+    fn prune_orphans(
+        &self,
+        root: &Path,
+        recorded: &BTreeMap<String, FileProvenance>,
+        orphans: &[String],
+        dry_run: bool,
+    ) -> Result<Vec<String>> {
+        for path in orphans {
+            check_recorded_path(path)?;
+        }
+        let mut pruned = Vec::new();
+        for path in orphans {
+            let file = root.join(path);
+            let Ok(content) = std::fs::read(&file) else {
+                continue;
+            };
+            if recorded.get(path).map(|p| p.hash.as_str())
+                != Some(generated::content_hash(&content).as_str())
+            {
+                log::warn!("Keeping '{path}': no longer generated, but modified since");
+                continue;
+            }
+
+            // A dry run reports what the real run would do, without asking.
+            let delete = if self.args.prune {
+                true
+            } else if self.args.non_interactive {
+                println!("'{path}' is no longer generated; use --prune to delete it");
+                false
+            } else if dry_run {
+                println!("Would ask whether to delete '{path}', no longer generated");
+                false
+            } else {
+                crate::prompt::confirm(
+                    false,
+                    format!(
+                        "'{path}' is no longer generated by the template. Delete it?"
+                    ),
+                )?
+            };
+            if !delete {
+                continue;
+            }
+            if !dry_run {
+                std::fs::remove_file(&file)?;
+                remove_empty_parents(root, &file);
+            }
+            pruned.push(path.clone());
+        }
+        Ok(pruned)
+    }
+
+    /// Shows what changed in the template since the last generation and, when
+    /// interactive, asks whether to go on. Returns `false` if the user declines.
+    fn review_changes(
+        &self,
+        template_root: &Path,
+        stored: &TemplateSourceInfo,
+        fresh: &TemplateSourceInfo,
+    ) -> Result<bool> {
+        let Some(changes) = changelog::describe_changes(template_root, stored, fresh)
+        else {
+            return Ok(true);
+        };
+        println!("Changes since last generation:\n{changes}\n");
+
+        let skip = self.args.skip_confirms.contains(&crate::cli::SkipConfirm::All);
+        if skip || self.args.non_interactive || self.args.dry_run {
+            return Ok(true);
         }
+        crate::prompt::confirm(false, "Continue with the update?".to_string())
     }
 
     fn sources_are_identical(
@@ -194,6 +359,10 @@ impl UpdateRunner {
                 TemplateSourceInfo::Filesystem { hash: old_hash, .. },
                 TemplateSourceInfo::Filesystem { hash: new_hash, .. },
             ) => old_hash == new_hash,
+            (
+                TemplateSourceInfo::Plugin { hash: old_hash, .. },
+                TemplateSourceInfo::Plugin { hash: new_hash, .. },
+            ) => old_hash == new_hash,
             _ => false,
         }
     }
@@ -233,15 +402,11 @@ impl UpdateRunner {
     }
 
     fn should_skip_overwrite_prompts(&self) -> bool {
-        use crate::cli::SkipConfirm;
-        self.args.skip_confirms.contains(&SkipConfirm::All)
-            || self.args.skip_confirms.contains(&SkipConfirm::Overwrite)
+        self.args.skip_confirms.iter().any(SkipConfirm::skips_all_overwrites)
     }
 
-    fn should_skip_hook_prompts(&self) -> bool {
-        use crate::cli::SkipConfirm;
-        self.args.skip_confirms.contains(&SkipConfirm::All)
-            || self.args.skip_confirms.contains(&SkipConfirm::Hooks)
+    fn should_skip_hook_prompt(&self, kind: HookKind) -> bool {
+        self.args.skip_confirms.iter().any(|skip| skip.skips_hook(kind))
     }
 
     fn maybe_run_pre_hook(
@@ -277,13 +442,21 @@ impl UpdateRunner {
                 &config.pre_hook_runner,
                 context.answers_opt(),
             )?;
+            let prompts = answer_source_for(self.args.non_interactive);
             run_hook(
                 context.template_root(),
                 context.output_root(),
                 &pre_hook_file,
                 None,
                 &runner,
-                false,
+                HookOptions {
+                    working_dir: config.pre_hook_working_dir,
+                    inherit_stdout: false,
+                    prompts: config.hook_prompts.then_some(prompts.as_ref()),
+                    image: config.pre_hook_image.as_deref(),
+                    context_file: None,
+                    stream: (!self.args.quiet_hooks).then_some(HookKind::Pre),
+                },
             )
         } else {
             Ok(None)
@@ -323,13 +496,21 @@ impl UpdateRunner {
                 &config.post_hook_runner,
                 context.answers_opt(),
             )?;
+            let prompts = answer_source_for(self.args.non_interactive);
             run_hook(
                 context.template_root(),
                 context.output_root(),
                 &post_hook_file,
                 Some(context.answers()),
                 &runner,
-                config.post_hook_print_stdout,
+                HookOptions {
+                    working_dir: config.post_hook_working_dir,
+                    inherit_stdout: config.post_hook_print_stdout,
+                    prompts: config.hook_prompts.then_some(prompts.as_ref()),
+                    image: config.post_hook_image.as_deref(),
+                    context_file: None,
+                    stream: (!self.args.quiet_hooks).then_some(HookKind::Post),
+                },
             )?;
         }
         Ok(())
@@ -339,8 +520,9 @@ impl UpdateRunner {
     fn confirm_hooks(
         &self,
         context: &GenerationContext,
+        source: &TemplateSourceInfo,
         engine: &dyn TemplateRenderer,
-    ) -> Result<bool> {
+    ) -> Result<HookApproval> {
         let config = context.config();
         let pre_hook_filename = engine
             .render(
@@ -362,38 +544,29 @@ impl UpdateRunner {
         let post_hook_file =
             context.template_root().join("hooks").join(&post_hook_filename);
 
-        if !pre_hook_file.exists() && !post_hook_file.exists() {
-            return Ok(false);
-        }
-
         if context.dry_run() {
-            return Ok(false);
-        }
-
-        let mut hook_list = String::new();
-        if pre_hook_file.exists() {
-            hook_list.push_str(&format!("{}\n", pre_hook_file.display()));
-        }
-        if post_hook_file.exists() {
-            hook_list.push_str(&format!("{}\n", post_hook_file.display()));
+            return Ok(HookApproval::default());
         }
 
-        crate::prompt::confirm(
-            self.should_skip_hook_prompts(),
-            format!(
-                "WARNING: This template contains the following hooks that will execute commands on your system:\n{hook_list}Do you want to run these hooks?",
-            ),
+        trust::approve_hooks(
+            source,
+            context.template_root(),
+            &pre_hook_file,
+            &post_hook_file,
+            |kind| self.should_skip_hook_prompt(kind),
         )
     }
 }
 
 // Standalone helpers
 
-fn load_and_validate_config(template_root: &PathBuf) -> Result<ConfigV1> {
-    let config = Config::load_config(template_root)?;
-    let Config::V1(config) = config;
-    config.validate()?;
-    Ok(config)
+pub(crate) fn load_and_validate_config(
+    template_root: &PathBuf,
+    profile: Option<&str>,
+) -> Result<ConfigV1> {
+    let options =
+        LoadOptions { profile: profile.map(str::to_string), ..Default::default() };
+    Config::load_validated(template_root, &options)
 }
 
 fn render_hook_runner(
@@ -410,11 +583,13 @@ fn render_hook_runner(
 }
 
 /// Add import templates from `template_root` to the engine (mirrors Runner::add_templates_in_renderer).
-fn add_templates_in_renderer(
+pub(crate) fn add_templates_in_renderer(
     template_root: &Path,
     config: &ConfigV1,
     engine: &mut dyn TemplateRenderer,
 ) {
+    engine.set_template_root(template_root);
+    engine.set_undefined_behavior(config.undefined_behavior);
     let import_root = if let Some(ref s) = config.import_root {
         let p = Path::new(s);
         if p.is_absolute() {
@@ -459,12 +634,134 @@ fn add_templates_in_renderer(
         });
 }
 
+/// Pairs files of the previous generation that the template no longer produces with
+/// the new files that replace them: the output of the same template entry, or else
+/// a file with the same generated content. Ambiguous matches are not moves.
+fn detect_moves(
+    old: &BTreeMap<String, FileProvenance>,
+    new: &BTreeMap<String, FileProvenance>,
+) -> Vec<(String, String)> {
+    let removed: Vec<_> =
+        old.iter().filter(|(path, _)| !new.contains_key(*path)).collect();
+    let added: Vec<_> = new.iter().filter(|(path, _)| !old.contains_key(*path)).collect();
+
+    let unique = |matches: Vec<&String>| match matches.as_slice() {
+        [only] => Some((*only).clone()),
+        _ => None,
+    };
+
+    let mut moves: Vec<(String, String)> = Vec::new();
+    for (from, before) in &removed {
+        let free = |to: &&String| !moves.iter().any(|(_, taken)| taken == *to);
+        let by_template = unique(
+            added
+                .iter()
+                .filter(|(_, after)| after.template == before.template)
+                .map(|(to, _)| *to)
+                .filter(free)
+                .collect(),
+        );
+        let by_content = || {
+            let same_hash = removed.iter().filter(|(_, other)| other.hash == before.hash);
+            if same_hash.count() > 1 {
+                return None;
+            }
+            unique(
+                added
+                    .iter()
+                    .filter(|(_, after)| after.hash == before.hash)
+                    .map(|(to, _)| *to)
+                    .filter(free)
+                    .collect(),
+            )
+        };
+        if let Some(to) = by_template.or_else(by_content) {
+            moves.push(((*from).clone(), to));
+        }
+    }
+    moves
+}
+
+/// Files of the previous generation that the template no longer produces and
+/// that did not move elsewhere.
+fn find_orphans(
+    old: &BTreeMap<String, FileProvenance>,
+    planned: &BTreeMap<String, FileProvenance>,
+    moves: &[(String, String)],
+) -> Vec<String> {
+    old.keys()
+        .filter(|path| !planned.contains_key(*path))
+        .filter(|path| !moves.iter().any(|(from, _)| from == *path))
+        .cloned()
+        .collect()
+}
+
+/// Moves the user's copies of moved files to their new location, so that the
+/// update merges the new content into them. Returns the moves performed (or, in a
+/// dry run, the ones that would be).
+fn apply_moves(
+    root: &Path,
+    moves: &[(String, String)],
+    dry_run: bool,
+) -> Result<Vec<(String, String)>> {
+    for (from, to) in moves {
+        check_recorded_path(from)?;
+        check_recorded_path(to)?;
+    }
+    let mut performed = Vec::new();
+    for (from, to) in moves {
+        let source = root.join(from);
+        let target = root.join(to);
+        if !source.is_file() || target.exists() {
+            log::debug!("Not moving '{from}' to '{to}': source missing or target exists");
+            continue;
+        }
+        if !dry_run {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(&source, &target)?;
+            remove_empty_parents(root, &source);
+        }
+        performed.push((from.clone(), to.clone()));
+    }
+    Ok(performed)
+}
+
+/// Fails when `path`, as recorded in the generated metadata, leads out of the
+/// project, as only an edited metadata file can.
+fn check_recorded_path(path: &str) -> Result<()> {
+    match unsafe_path_reason(Path::new(path)) {
+        Some(reason) => Err(Error::Other(anyhow::anyhow!(
+            "'{path}' in the generated metadata is outside the project: {reason}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Removes the directories left empty between `path` and `root`.
+fn remove_empty_parents(root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir.filter(|d| d.starts_with(root) && *d != root) {
+        if std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
 /// Clone a git repository into a sub-directory of `parent` and return its `LoadedTemplate`.
-fn clone_git_into_tmp(url: &str, parent: &Path) -> Result<crate::loader::LoadedTemplate> {
+pub(crate) fn clone_git_into_tmp(
+    url: &str,
+    parent: &Path,
+) -> Result<crate::loader::LoadedTemplate> {
     use crate::loader::git::GitLoader;
 
+    policy::check_source(&crate::loader::TemplateSource::Git(url.to_string()))?;
     std::fs::create_dir_all(parent)?;
-    GitLoader::new(url.to_string(), true).load_into_parent(parent)
+    GitLoader::new(url.to_string(), true)
+        .with_settings(UserConfig::load()?.git)
+        .load_into_parent(parent)
 }
 
 #[cfg(test)]
@@ -478,6 +775,113 @@ mod tests {
     use std::{fs, path::Path};
     use tempfile::tempdir;
 
+    fn provenance(entries: &[(&str, &str, &str)]) -> BTreeMap<String, FileProvenance> {
+        entries
+            .iter()
+            .map(|(path, template, hash)| {
+                (
+                    path.to_string(),
+                    FileProvenance {
+                        template: template.to_string(),
+                        hash: hash.to_string(),
+                        binary: false,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detect_moves_matches_by_template_then_unique_content() {
+        let old = provenance(&[
+            ("app/__init__.py", "{{slug}}/__init__.py", "empty"),
+            ("src/main.rs", "src/main.rs.baker.j2", "main"),
+            ("docs/a.md", "docs/a.md", "same"),
+            ("docs/b.md", "docs/b.md", "same"),
+            ("kept.txt", "kept.txt", "kept"),
+        ]);
+        let new = provenance(&[
+            ("service/__init__.py", "{{slug}}/__init__.py", "empty"),
+            ("src/bin/app.rs", "src/bin/app.rs.baker.j2", "main"),
+            ("guide/a.md", "guide/a.md", "same"),
+            ("kept.txt", "kept.txt", "changed"),
+        ]);
+
+        assert_eq!(
+            detect_moves(&old, &new),
+            vec![
+                ("app/__init__.py".to_string(), "service/__init__.py".to_string()),
+                ("src/main.rs".to_string(), "src/bin/app.rs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_moves_refuses_paths_outside_the_project() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(dir.path().join("outside.txt"), "x").unwrap();
+
+        for (from, to) in [("../outside.txt", "inside.txt"), ("a.txt", "/tmp/a.txt")] {
+            let moves = [(from.to_string(), to.to_string())];
+            let err = apply_moves(&project, &moves, false).unwrap_err();
+            assert!(err.to_string().contains("outside the project"), "{err}");
+        }
+        assert!(dir.path().join("outside.txt").exists());
+        assert!(!project.join("inside.txt").exists());
+    }
+
+    #[test]
+    fn prune_orphans_refuses_paths_outside_the_project() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        let outside = dir.path().join("outside.txt");
+        fs::write(&outside, "x").unwrap();
+        let recorded = provenance(&[(
+            "../outside.txt",
+            "outside.txt",
+            &generated::content_hash(b"x"),
+        )]);
+
+        let args = UpdateArgs { prune: true, ..default_update_args() };
+        let runner = UpdateRunner::with_working_dir(args, project.clone());
+        let orphans = ["../outside.txt".to_string()];
+        let err = runner.prune_orphans(&project, &recorded, &orphans, false).unwrap_err();
+        assert!(err.to_string().contains("outside the project"), "{err}");
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn prune_orphans_dry_run_reports_what_the_real_run_would_do() {
+        let project = tempdir().unwrap();
+        fs::write(project.path().join("old.txt"), "x").unwrap();
+        let recorded =
+            provenance(&[("old.txt", "old.txt", &generated::content_hash(b"x"))]);
+        let orphans = ["old.txt".to_string()];
+
+        // Without --prune a non-interactive run keeps the file.
+        let args = UpdateArgs { non_interactive: true, ..default_update_args() };
+        let runner = UpdateRunner::with_working_dir(args, project.path().to_path_buf());
+        let pruned = runner.prune_orphans(project.path(), &recorded, &orphans, true);
+        assert!(pruned.unwrap().is_empty());
+
+        // An interactive run would ask, so nothing is reported as deleted.
+        let runner = UpdateRunner::with_working_dir(
+            default_update_args(),
+            project.path().to_path_buf(),
+        );
+        let pruned = runner.prune_orphans(project.path(), &recorded, &orphans, true);
+        assert!(pruned.unwrap().is_empty());
+
+        let args = UpdateArgs { prune: true, ..default_update_args() };
+        let runner = UpdateRunner::with_working_dir(args, project.path().to_path_buf());
+        let pruned = runner.prune_orphans(project.path(), &recorded, &orphans, true);
+        assert_eq!(pruned.unwrap(), orphans);
+        assert!(project.path().join("old.txt").exists());
+    }
+
     fn default_update_args() -> UpdateArgs {
         UpdateArgs {
             generated_file: None,
@@ -487,6 +891,7 @@ mod tests {
             dry_run: false,
             skip_confirms: vec![],
             non_interactive: false,
+            ..Default::default()
         }
     }
 
@@ -607,19 +1012,25 @@ questions: {}
         args.skip_confirms = vec![SkipConfirm::Overwrite];
         let runner = UpdateRunner::new(args);
         assert!(runner.should_skip_overwrite_prompts());
-        assert!(!runner.should_skip_hook_prompts());
+        assert!(!runner.should_skip_hook_prompt(HookKind::Pre));
 
         let mut args = default_update_args();
         args.skip_confirms = vec![SkipConfirm::Hooks];
         let runner = UpdateRunner::new(args);
         assert!(!runner.should_skip_overwrite_prompts());
-        assert!(runner.should_skip_hook_prompts());
+        assert!(runner.should_skip_hook_prompt(HookKind::Pre));
 
         let mut args = default_update_args();
         args.skip_confirms = vec![SkipConfirm::All];
         let runner = UpdateRunner::new(args);
         assert!(runner.should_skip_overwrite_prompts());
-        assert!(runner.should_skip_hook_prompts());
+        assert!(runner.should_skip_hook_prompt(HookKind::Post));
+
+        let mut args = default_update_args();
+        args.skip_confirms = vec![SkipConfirm::Hook(HookKind::Post)];
+        let runner = UpdateRunner::new(args);
+        assert!(!runner.should_skip_hook_prompt(HookKind::Pre));
+        assert!(runner.should_skip_hook_prompt(HookKind::Post));
     }
 
     #[test]
@@ -629,7 +1040,7 @@ questions: {}
         fs::write(dir.path().join("baker.yaml"), "schemaVersion: v1\nquestions: {}\n")
             .expect("write config");
 
-        let valid = load_and_validate_config(&dir.path().to_path_buf())
+        let valid = load_and_validate_config(&dir.path().to_path_buf(), None)
             .expect("valid config should load");
         assert_eq!(valid.template_suffix, ".baker.j2");
 
@@ -639,7 +1050,7 @@ questions: {}
         )
         .expect("write invalid config");
 
-        let err = load_and_validate_config(&dir.path().to_path_buf())
+        let err = load_and_validate_config(&dir.path().to_path_buf(), None)
             .expect_err("invalid config should fail");
         assert!(matches!(err, crate::error::Error::ConfigValidation(_)));
     }
@@ -772,6 +1183,10 @@ questions: {}
         fs::create_dir_all(&hooks_dir).expect("create hooks dir");
 
         let engine = crate::template::get_template_engine();
+        let source = TemplateSourceInfo::Filesystem {
+            path: template_dir.path().display().to_string(),
+            hash: "abc".to_string(),
+        };
 
         // No hooks => false
         let context_no_hooks = GenerationContext::new(
@@ -784,9 +1199,12 @@ questions: {}
             None,
         );
         let runner = UpdateRunner::new(default_update_args());
-        assert!(!runner
-            .confirm_hooks(&context_no_hooks, &engine)
-            .expect("confirm hooks with no files"));
+        assert_eq!(
+            runner
+                .confirm_hooks(&context_no_hooks, &source, &engine)
+                .expect("confirm hooks with no files"),
+            HookApproval::default()
+        );
 
         // Hooks present but dry-run => false
         fs::write(hooks_dir.join("pre"), "#!/bin/sh\n").expect("write pre");
@@ -799,9 +1217,12 @@ questions: {}
             false,
             None,
         );
-        assert!(!runner
-            .confirm_hooks(&context_dry_run, &engine)
-            .expect("confirm hooks in dry run"));
+        assert_eq!(
+            runner
+                .confirm_hooks(&context_dry_run, &source, &engine)
+                .expect("confirm hooks in dry run"),
+            HookApproval::default()
+        );
 
         // Hooks present and skip flag set => true (no interactive prompt)
         fs::write(hooks_dir.join("post"), "#!/bin/sh\n").expect("write post");
@@ -817,9 +1238,12 @@ questions: {}
             false,
             None,
         );
-        assert!(runner
-            .confirm_hooks(&context, &engine)
-            .expect("confirm hooks with skip flag"));
+        assert_eq!(
+            runner
+                .confirm_hooks(&context, &source, &engine)
+                .expect("confirm hooks with skip flag"),
+            HookApproval { pre: true, post: true }
+        );
     }
 
     #[test]
@@ -0,0 +1,357 @@
+//! `baker validate` — checks a template's config without generating anything.
+//!
+//! With `--conditions`, every combination of answers to the yes/no and choice
+//! questions is tried: each is answered in declaration order, branching only on
+//! the questions its `ask_if` conditions actually ask, and the files it produces
+//! are planned. Files no combination produces and questions no combination asks
+//! are dead branches of the template.
+
+use crate::{
+    cli::{
+        answers::AnswerCollector,
+        audit::fetch_template_root,
+        context::GenerationContext,
+        processor::FileProcessor,
+        update::{add_templates_in_renderer, load_and_validate_config},
+        ValidateArgs,
+    },
+    config::{question::Question, ConfigV1, Type},
+    error::{Error, Result},
+    ignore::parse_bakerignore_file,
+    renderer::TemplateRenderer,
+    template::{get_template_engine, processor::TemplateProcessor},
+};
+use indexmap::IndexSet;
+use serde_json::{Map, Value};
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// Main entry point for `baker validate`.
+pub fn run_validate(args: ValidateArgs) -> Result<()> {
+    let (template_root, _tmp) = fetch_template_root(&args.template)?;
+    let mut engine = get_template_engine();
+    let config = load_and_validate_config(&template_root, args.profile.as_deref())?;
+    if !args.conditions {
+        println!("The config of '{}' is valid.", args.template);
+        return Ok(());
+    }
+
+    add_templates_in_renderer(&template_root, &config, &mut engine);
+    let report =
+        check_conditions(&template_root, &config, &engine, args.max_combinations)?;
+    print!("{report}");
+    if report.has_dead_branches() {
+        return Err(Error::Other(anyhow::anyhow!(
+            "The template has files or questions that no answers reach"
+        )));
+    }
+    Ok(())
+}
+
+/// The answers of one combination and what they lead to.
+#[derive(Debug)]
+pub struct Combination {
+    /// The enumerated answers, in question order.
+    pub choices: Vec<(String, Value)>,
+    /// The top-level questions asked.
+    pub questions: Vec<String>,
+    /// The files produced, relative to the output directory, or the error the
+    /// answers end in, e.g. a failed validation.
+    pub files: std::result::Result<Vec<String>, String>,
+}
+
+/// The outcome of `baker validate --conditions`.
+#[derive(Debug)]
+pub struct ConditionReport {
+    pub combinations: Vec<Combination>,
+    /// Whether more combinations exist than were tried.
+    pub truncated: bool,
+    /// Template files no combination produces.
+    pub dead_files: Vec<String>,
+    /// Questions no combination asks.
+    pub never_asked: Vec<String>,
+}
+
+impl ConditionReport {
+    pub fn has_dead_branches(&self) -> bool {
+        !self.dead_files.is_empty() || !self.never_asked.is_empty()
+    }
+}
+
+impl Display for ConditionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, combination) in self.combinations.iter().enumerate() {
+            let choices: Vec<String> = combination
+                .choices
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            let choices = match choices.is_empty() {
+                true => "defaults".to_string(),
+                false => choices.join(", "),
+            };
+            writeln!(f, "[{}] {choices}", index + 1)?;
+            writeln!(f, "    questions: {}", combination.questions.join(", "))?;
+            match &combination.files {
+                Ok(files) => writeln!(f, "    files: {}", files.join(", "))?,
+                Err(error) => writeln!(f, "    error: {error}")?,
+            }
+        }
+        if self.truncated {
+            writeln!(
+                f,
+                "\nOnly the first {} combinations were tried; dead branches are not reported.",
+                self.combinations.len()
+            )?;
+            return Ok(());
+        }
+        if !self.has_dead_branches() {
+            return writeln!(f, "\nEvery file and question is reachable.");
+        }
+        for file in &self.dead_files {
+            writeln!(f, "\nFile '{file}' is never generated.")?;
+        }
+        for key in &self.never_asked {
+            writeln!(f, "\nQuestion '{key}' is never asked; check its ask_if chain.")?;
+        }
+        Ok(())
+    }
+}
+
+/// Tries up to `max_combinations` combinations of answers to the yes/no and choice
+/// questions of `config`.
+pub fn check_conditions(
+    template_root: &Path,
+    config: &ConfigV1,
+    engine: &dyn TemplateRenderer,
+    max_combinations: usize,
+) -> Result<ConditionReport> {
+    let questions: Vec<(&String, &Question)> = config.questions.iter().collect();
+    let mut branches = Vec::new();
+    let truncated = !enumerate(
+        &questions,
+        engine,
+        Branch::default(),
+        max_combinations,
+        &mut branches,
+    )?;
+
+    let mut combinations = Vec::new();
+    let mut produced = BTreeSet::new();
+    let mut asked = BTreeSet::new();
+    for branch in branches {
+        let answers = [&branch.choices[..], &branch.placeholders[..]].concat();
+        let files = plan(template_root, config, engine, &answers);
+        if let Ok(files) = &files {
+            produced.extend(files.iter().map(|(_, template)| template.clone()));
+            // Questions only count as asked when their answers are accepted.
+            asked.extend(branch.asked.iter().cloned());
+        }
+        combinations.push(Combination {
+            choices: branch.choices,
+            questions: branch.asked.into_iter().collect(),
+            files: files
+                .map(|files| files.into_iter().map(|(output, _)| output).collect()),
+        });
+    }
+
+    let (dead_files, never_asked) = match truncated {
+        true => (Vec::new(), Vec::new()),
+        false => (
+            template_files(template_root)?
+                .into_iter()
+                .filter(|file| !produced.contains(file))
+                .collect(),
+            config
+                .questions
+                .keys()
+                .filter(|key| !asked.contains(*key))
+                .cloned()
+                .collect(),
+        ),
+    };
+    Ok(ConditionReport { combinations, truncated, dead_files, never_asked })
+}
+
+/// The answers of a combination so far.
+#[derive(Debug, Clone, Default)]
+struct Branch {
+    /// Every answer, for the conditions of the questions that follow.
+    answers: Map<String, Value>,
+    choices: Vec<(String, Value)>,
+    /// Placeholder answers to text questions without a default, so that file
+    /// names built from them are not empty.
+    placeholders: Vec<(String, Value)>,
+    asked: IndexSet<String>,
+}
+
+/// Answers `questions` in order, pushing a branch per combination into `branches`.
+/// Returns `false` when it stopped at `max` branches.
+fn enumerate(
+    questions: &[(&String, &Question)],
+    engine: &dyn TemplateRenderer,
+    mut branch: Branch,
+    max: usize,
+    branches: &mut Vec<Branch>,
+) -> Result<bool> {
+    let Some(((key, question), rest)) = questions.split_first() else {
+        if branches.len() == max {
+            return Ok(false);
+        }
+        branches.push(branch);
+        return Ok(true);
+    };
+    let rendered =
+        question.render(key, &Value::Object(branch.answers.clone()), engine)?;
+    let values = match rendered.ask_if {
+        true => {
+            branch.asked.insert(key.to_string());
+            answer_values(question)
+        }
+        false => Vec::new(),
+    };
+    if values.is_empty() {
+        let blank = rendered.default.is_null() || rendered.default == "";
+        if rendered.ask_if && blank && matches!(question.r#type, Type::Str) {
+            let placeholder = Value::String(key.to_string());
+            branch.answers.insert(key.to_string(), placeholder.clone());
+            branch.placeholders.push((key.to_string(), placeholder));
+        } else if !rendered.default.is_null() {
+            branch.answers.insert(key.to_string(), rendered.default);
+        }
+        return enumerate(rest, engine, branch, max, branches);
+    }
+    for value in values {
+        let mut next = branch.clone();
+        next.answers.insert(key.to_string(), value.clone());
+        next.choices.push((key.to_string(), value));
+        if !enumerate(rest, engine, next, max, branches)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// The answers tried for `question`: both for yes/no questions, each choice, and
+/// none or all of the choices of a multiselect. Other questions take their default,
+/// or a placeholder.
+fn answer_values(question: &Question) -> Vec<Value> {
+    let choices = question.choices.iter().map(|choice| Value::String(choice.clone()));
+    match question.r#type {
+        Type::Bool => vec![Value::Bool(false), Value::Bool(true)],
+        Type::Str if question.choices.is_empty() => Vec::new(),
+        Type::Str if question.multiselect => {
+            vec![Value::Array(Vec::new()), Value::Array(choices.collect())]
+        }
+        Type::Str => choices.collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The files the template produces for `answers`, each with its template entry.
+fn plan(
+    template_root: &Path,
+    config: &ConfigV1,
+    engine: &dyn TemplateRenderer,
+    answers: &[(String, Value)],
+) -> std::result::Result<Vec<(String, String)>, String> {
+    let set_answers =
+        answers.iter().map(|(key, value)| format!("{key}={value}")).collect();
+    let answers = AnswerCollector::new(engine, true, template_root)
+        .with_set_answers(set_answers)
+        .collect_answers(config, None, None, None)
+        .map_err(|e| e.to_string())?;
+    let mut context = GenerationContext::new(
+        template_root.to_path_buf(),
+        PathBuf::from("output"),
+        config.clone(),
+        Vec::new(),
+        true,
+        false,
+        None,
+    );
+    context.set_answers(answers);
+    let bakerignore = parse_bakerignore_file(template_root).map_err(|e| e.to_string())?;
+    let processor = TemplateProcessor::new(engine, &context, &bakerignore);
+    let files = FileProcessor::new(processor, &context).plan();
+    Ok(files
+        .into_iter()
+        .map(|(output, provenance)| (output, provenance.template))
+        .collect())
+}
+
+/// Every file of the template that is not ignored, relative to its root.
+fn template_files(template_root: &Path) -> Result<Vec<String>> {
+    let ignored = parse_bakerignore_file(template_root)?;
+    let mut files = Vec::new();
+    let entries = WalkDir::new(template_root).min_depth(1).sort_by_file_name();
+    for entry in entries.into_iter().filter_entry(|entry| !ignored.is_match(entry.path()))
+    {
+        let entry = entry?;
+        if !entry.file_type().is_dir() {
+            let path = entry.path().strip_prefix(template_root).unwrap_or(entry.path());
+            files.push(path.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, LoadOptions};
+    use std::fs;
+
+    #[test]
+    fn reports_combinations_and_dead_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("baker.yaml"),
+            r#"schemaVersion: v1
+questions:
+  use_ci:
+    type: bool
+    default: false
+  ci:
+    type: str
+    choices: [github, gitlab]
+    default: github
+    ask_if: use_ci
+  legacy:
+    type: bool
+    default: false
+    ask_if: "use_ci and not use_ci"
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("{% if use_ci %}ci{% endif %}")).unwrap();
+        fs::write(root.join("{% if use_ci %}ci{% endif %}/{{ ci }}.yml"), "").unwrap();
+        fs::write(root.join("{% if legacy %}legacy.txt{% endif %}"), "").unwrap();
+        fs::write(root.join("README.md"), "").unwrap();
+        let config = Config::load_validated(root, &LoadOptions::default()).unwrap();
+        let engine = get_template_engine();
+
+        let report = check_conditions(root, &config, &engine, 16).unwrap();
+        let choices: Vec<_> =
+            report.combinations.iter().map(|c| c.choices.clone()).collect();
+        assert_eq!(choices.len(), 3);
+        assert_eq!(report.combinations[0].questions, ["use_ci"]);
+        assert_eq!(report.combinations[0].files, Ok(vec!["README.md".to_string()]));
+        assert_eq!(
+            report.combinations[2].files,
+            Ok(vec!["README.md".to_string(), "ci/gitlab.yml".to_string()])
+        );
+        assert_eq!(report.dead_files, ["{% if legacy %}legacy.txt{% endif %}"]);
+        assert_eq!(report.never_asked, ["legacy"]);
+        assert!(report.to_string().contains("Question 'legacy' is never asked"));
+
+        let truncated = check_conditions(root, &config, &engine, 2).unwrap();
+        assert!(truncated.truncated);
+        assert!(!truncated.has_dead_branches());
+    }
+}
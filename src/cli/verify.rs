@@ -0,0 +1,212 @@
+//! `baker verify` — re-renders a generated project's template in memory, at the
+//! version recorded in `.baker-lock.json`, and checks that it still produces
+//! exactly the locked files. With `--files` it also checks the generated files on
+//! disk against the checksums in the generated metadata.
+
+use crate::{
+    cli::{
+        answers::AnswerCollector,
+        context::GenerationContext,
+        encrypted,
+        processor::FileProcessor,
+        update::{
+            add_templates_in_renderer, clone_git_into_tmp, load_and_validate_config,
+        },
+        VerifyArgs,
+    },
+    constants::DEFAULT_GENERATED_FILE_NAME,
+    copier,
+    error::{Error, Result},
+    generated::{self, BakerGenerated, FileDrift, FileProvenance},
+    ignore::parse_bakerignore_file,
+    loader::{get_template, LoadedTemplate, TemplateSourceInfo},
+    lock::{self, answers_hash, LOCK_FILE_NAME},
+    renderer::TemplateRenderer,
+    temp::TempDir,
+    template::{get_template_engine, processor::TemplateProcessor},
+};
+use std::{collections::BTreeMap, path::Path};
+
+/// Main entry point for `baker verify`.
+pub fn run_verify(args: VerifyArgs) -> Result<()> {
+    let dir = &args.dir;
+    let lock = lock::read(dir)?;
+    if lock.is_none() && !args.files {
+        return Err(Error::Other(anyhow::anyhow!(
+            "No {LOCK_FILE_NAME} in '{}'. Generate with --lock first.",
+            dir.display()
+        )));
+    }
+    let file_name = args.generated_file.as_deref().unwrap_or(DEFAULT_GENERATED_FILE_NAME);
+    let mut meta = generated::read(dir, file_name)?;
+    if args.files {
+        verify_files_on_disk(dir, &meta.files)?;
+    }
+    let Some(lock) = lock else {
+        return Ok(());
+    };
+    encrypted::decrypt_answers(&mut meta, &dir.join(file_name))?;
+    if answers_hash(&meta.answers) != lock.answers_hash {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Answers in '{file_name}' changed since {LOCK_FILE_NAME} was written"
+        )));
+    }
+    if lock.baker_version != env!("CARGO_PKG_VERSION") {
+        println!(
+            "Note: files were locked by Baker {}, verifying with {}",
+            lock.baker_version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    let (loaded, _tmp) = fetch_locked_template(&lock.template, LOCK_FILE_NAME)?;
+    let rendered = with_recorded_answers(&loaded, dir, &meta, |files| files.plan())?;
+
+    let mismatches = lock.mismatches(&rendered);
+    if mismatches.is_empty() {
+        println!("Verified {} files against {LOCK_FILE_NAME}", lock.files.len());
+        return Ok(());
+    }
+    for mismatch in &mismatches {
+        println!("{mismatch}");
+    }
+    Err(Error::Other(anyhow::anyhow!(
+        "Rendered output does not match {LOCK_FILE_NAME}: {} file(s) differ",
+        mismatches.len()
+    )))
+}
+
+/// Renders `loaded` in memory, as a dry run into `dir`, with the answers and profile
+/// recorded in `meta`, and passes the file processor to `render`.
+pub(crate) fn with_recorded_answers<T>(
+    loaded: &LoadedTemplate,
+    dir: &Path,
+    meta: &BakerGenerated,
+    render: impl FnOnce(&FileProcessor) -> T,
+) -> Result<T> {
+    let config = load_and_validate_config(&loaded.root, meta.profile.as_deref())?;
+    let mut context = GenerationContext::new(
+        loaded.root.clone(),
+        dir.to_path_buf(),
+        config,
+        Vec::new(),
+        true, // dry_run: nothing is written
+        false,
+        None,
+    );
+
+    let mut engine = get_template_engine();
+    add_templates_in_renderer(&loaded.root, context.config(), &mut engine);
+    engine.set_output_root(dir);
+    let mut collector = AnswerCollector::new(&engine, true, &loaded.root);
+    let answers = collector.collect_answers(
+        context.config(),
+        None,
+        Some(serde_json::to_string(&meta.answers)?),
+        None,
+    )?;
+    context.set_answers(answers);
+
+    let bakerignore = parse_bakerignore_file(context.template_root())?;
+    let processor = TemplateProcessor::new(&engine, &context, &bakerignore);
+    let files = FileProcessor::new(processor, &context);
+    let rendered = render(&files);
+    Ok(rendered)
+}
+
+/// Checks the generated files on disk against their recorded checksums. Edited text
+/// files are only listed, as editing generated code is expected; changed or missing
+/// binary assets point at corruption and fail the check.
+fn verify_files_on_disk(
+    dir: &Path,
+    files: &BTreeMap<String, FileProvenance>,
+) -> Result<()> {
+    let drifted = generated::drifted_files(dir, files)?;
+    let (assets, text): (Vec<_>, Vec<_>) = drifted.iter().partition(|d| d.binary);
+    let describe = |drift: &FileDrift| {
+        let state = if drift.missing { "missing" } else { "changed" };
+        format!("{state}: {}", drift.path)
+    };
+    if !text.is_empty() {
+        println!("Modified since generation:");
+        text.iter().for_each(|drift| println!("  {}", describe(drift)));
+    }
+    if assets.is_empty() {
+        println!("Checked {} generated files on disk", files.len());
+        return Ok(());
+    }
+    println!("Binary assets that differ from the template:");
+    assets.iter().for_each(|drift| println!("  {}", describe(drift)));
+    Err(Error::Other(anyhow::anyhow!(
+        "{} binary asset(s) changed or are missing since generation",
+        assets.len()
+    )))
+}
+
+/// Loads the template exactly as recorded in `recorded_in`: git templates are
+/// checked out at the recorded commit. Other sources can only be loaded as they are
+/// now, so a changed content hash is reported.
+pub(crate) fn fetch_locked_template(
+    locked: &TemplateSourceInfo,
+    recorded_in: &str,
+) -> Result<(LoadedTemplate, Option<TempDir>)> {
+    let (loaded, tmp) = match locked {
+        TemplateSourceInfo::Git { url, .. } => {
+            let tmp = TempDir::new("clone")?;
+            let mut loaded = clone_git_into_tmp(url, tmp.path())?;
+            let repo = git2::Repository::open(&loaded.root)?;
+            let mut source = locked.clone();
+            copier::resolve_commit(&repo, &mut source)?;
+            if let TemplateSourceInfo::Git { commit, .. } = &source {
+                repo.set_head_detached(git2::Oid::from_str(commit)?)?;
+            }
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+            loaded.source = source;
+            (loaded, Some(tmp))
+        }
+        TemplateSourceInfo::Filesystem { path, .. } => (get_template(path, true)?, None),
+        TemplateSourceInfo::Plugin { url, .. } => (get_template(url, true)?, None),
+        TemplateSourceInfo::Tar { .. } => {
+            return Err(Error::Other(anyhow::anyhow!(
+                "The template was read from a tar stream and cannot be loaded again"
+            )))
+        }
+    };
+    if &loaded.source != locked {
+        println!("Note: the template changed since {recorded_in} was written");
+    }
+    Ok((loaded, tmp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_files_without_decrypting_the_answers() {
+        let dir = tempfile::tempdir().unwrap();
+        let armored = "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n";
+        let files = BTreeMap::from([(
+            "README.md".to_string(),
+            FileProvenance {
+                template: "README.md".to_string(),
+                hash: generated::content_hash(b"# demo"),
+                binary: false,
+            },
+        )]);
+        let source = TemplateSourceInfo::Tar { hash: "abc".to_string() };
+        let meta = BakerGenerated::new(source, serde_json::json!(armored))
+            .with_files(files)
+            .with_encrypted_answers(true);
+        encrypted::write_generated(dir.path(), DEFAULT_GENERATED_FILE_NAME, &meta)
+            .unwrap();
+        std::fs::write(dir.path().join("README.md"), "# demo").unwrap();
+
+        let args = VerifyArgs {
+            dir: dir.path().to_path_buf(),
+            generated_file: None,
+            files: true,
+        };
+        run_verify(args).unwrap();
+    }
+}
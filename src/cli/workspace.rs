@@ -0,0 +1,430 @@
+//! `baker workspace` — a registry of generated projects, so that a template change
+//! can be rolled out to all of them with one `baker workspace update --all`.
+//!
+//! The registry is a YAML file listing the project directories. It is read from
+//! `$BAKER_WORKSPACE` if set, otherwise from `baker/workspace.yaml` in the user's
+//! config directory; `--registry` points at another one, such as a file checked into
+//! a platform team's repository.
+
+use crate::{
+    cli::{update::run_update_in_dir, UpdateArgs, WorkspaceArgs, WorkspaceCommand},
+    config::user::config_dir,
+    constants::DEFAULT_GENERATED_FILE_NAME,
+    copier,
+    error::{Error, Result},
+    generated::{self, BakerGenerated},
+    loader::TemplateSourceInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable overriding the workspace registry location.
+pub const WORKSPACE_ENV: &str = "BAKER_WORKSPACE";
+
+/// A generated project in the registry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceProject {
+    /// Absolute path of the project directory.
+    pub path: PathBuf,
+    /// Name of the project's generated-metadata file, when not the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_file: Option<String>,
+}
+
+impl WorkspaceProject {
+    fn generated_file(&self) -> &str {
+        self.generated_file.as_deref().unwrap_or(DEFAULT_GENERATED_FILE_NAME)
+    }
+
+    /// Reads the project's metadata, falling back to copier's answers file.
+    fn metadata(&self) -> Result<BakerGenerated> {
+        match generated::read(&self.path, self.generated_file()) {
+            Err(Error::GeneratedFileNotFound { path }) => {
+                copier::read(&self.path)?.ok_or(Error::GeneratedFileNotFound { path })
+            }
+            result => result,
+        }
+    }
+}
+
+/// The registered projects, in the order they were added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default)]
+    pub projects: Vec<WorkspaceProject>,
+}
+
+impl Workspace {
+    /// Loads the registry from `path`; a missing file is an empty registry.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content).map_err(|e| {
+            Error::Other(anyhow::anyhow!(
+                "Invalid workspace registry '{}': {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Writes the registry to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Registers the generated project in `dir`. Returns `false` when it already was.
+    /// Fails when the directory has no Baker (or copier) metadata.
+    pub fn add(&mut self, dir: &Path, generated_file: Option<String>) -> Result<bool> {
+        let path = fs::canonicalize(dir).map_err(|e| {
+            Error::Other(anyhow::anyhow!("Cannot register '{}': {e}", dir.display()))
+        })?;
+        if self.find(&path).is_some() {
+            return Ok(false);
+        }
+        let project = WorkspaceProject { path, generated_file };
+        project.metadata()?;
+        self.projects.push(project);
+        Ok(true)
+    }
+
+    /// Unregisters the project in `dir`. Returns `false` when it was not registered.
+    pub fn remove(&mut self, dir: &Path) -> bool {
+        let path = absolute(dir);
+        let before = self.projects.len();
+        self.projects.retain(|project| project.path != path);
+        self.projects.len() != before
+    }
+
+    fn find(&self, path: &Path) -> Option<&WorkspaceProject> {
+        self.projects.iter().find(|project| project.path == path)
+    }
+
+    /// The registered projects in `dirs`, failing on any that is not registered.
+    fn select(&self, dirs: &[PathBuf]) -> Result<Vec<WorkspaceProject>> {
+        dirs.iter()
+            .map(|dir| {
+                self.find(&absolute(dir)).cloned().ok_or_else(|| {
+                    Error::Other(anyhow::anyhow!(
+                        "'{}' is not registered in the workspace",
+                        dir.display()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// One line per project: its template, the template version it was generated
+    /// from, and how many generated files were changed locally since.
+    pub fn status(&self) -> WorkspaceTable {
+        let mut table = WorkspaceTable::new(["PROJECT", "TEMPLATE", "VERSION", "STATUS"]);
+        for project in &self.projects {
+            let path = project.path.display().to_string();
+            if !project.path.is_dir() {
+                table.push([path, "-".into(), "-".into(), "missing".into()]);
+                continue;
+            }
+            let meta = match project.metadata() {
+                Ok(meta) => meta,
+                Err(e) => {
+                    table.push([path, "-".into(), "-".into(), format!("error: {e}")]);
+                    continue;
+                }
+            };
+            let (template, version) = describe_source(&meta.template);
+            let status = match generated::drifted_files(&project.path, &meta.files) {
+                Ok(drifted) if drifted.is_empty() => "clean".to_string(),
+                Ok(drifted) => format!("{} changed", drifted.len()),
+                Err(e) => format!("error: {e}"),
+            };
+            table.push([path, template, version, status]);
+        }
+        table
+    }
+}
+
+/// Location of the workspace registry.
+pub fn workspace_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(WORKSPACE_ENV).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    config_dir().map(|dir| dir.join("baker").join("workspace.yaml"))
+}
+
+/// Main entry point for `baker workspace`.
+pub fn run_workspace(args: WorkspaceArgs) -> Result<()> {
+    let registry = args.registry.or_else(workspace_path).ok_or_else(|| {
+        Error::Other(anyhow::anyhow!(
+            "Cannot locate the workspace registry; set {WORKSPACE_ENV} or pass --registry"
+        ))
+    })?;
+    let mut workspace = Workspace::load(&registry)?;
+
+    match args.command {
+        WorkspaceCommand::Add { dirs, generated_file } => {
+            for dir in &dirs {
+                if workspace.add(dir, generated_file.clone())? {
+                    println!("Added '{}'", dir.display());
+                } else {
+                    println!("'{}' is already registered", dir.display());
+                }
+            }
+            workspace.save(&registry)
+        }
+        WorkspaceCommand::Remove { dirs } => {
+            for dir in &dirs {
+                if !workspace.remove(dir) {
+                    return Err(Error::Other(anyhow::anyhow!(
+                        "'{}' is not registered in the workspace",
+                        dir.display()
+                    )));
+                }
+            }
+            workspace.save(&registry)
+        }
+        WorkspaceCommand::List => {
+            if workspace.projects.is_empty() {
+                println!("No projects registered in '{}'", registry.display());
+            } else {
+                print!("{}", workspace.status());
+            }
+            Ok(())
+        }
+        WorkspaceCommand::Update(update_args) => {
+            let projects = if update_args.all {
+                workspace.projects.clone()
+            } else {
+                workspace.select(&update_args.dirs)?
+            };
+            let report = update_projects(&projects, &update_args.update);
+            print!("{report}");
+            report.into_result()
+        }
+    }
+}
+
+/// Runs `baker update` in each project with `args`, carrying on after failures.
+/// A project's registered metadata file name is used unless `args` sets one.
+pub fn update_projects(
+    projects: &[WorkspaceProject],
+    args: &UpdateArgs,
+) -> WorkspaceReport {
+    let mut report = WorkspaceReport::default();
+    for project in projects {
+        log::info!("Updating '{}'", project.path.display());
+        let mut project_args = args.clone();
+        if project_args.generated_file.is_none() {
+            project_args.generated_file = project.generated_file.clone();
+        }
+        let result = run_update_in_dir(project_args, project.path.clone());
+        if let Err(e) = &result {
+            log::error!("Failed to update '{}': {e}", project.path.display());
+        }
+        report.results.push((project.path.clone(), result.err().map(|e| e.to_string())));
+    }
+    report
+}
+
+/// Outcome of updating several projects.
+#[derive(Debug, Default)]
+pub struct WorkspaceReport {
+    results: Vec<(PathBuf, Option<String>)>,
+}
+
+impl WorkspaceReport {
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|(_, err)| err.is_some()).count()
+    }
+
+    /// Fails when any project failed.
+    pub fn into_result(self) -> Result<()> {
+        match self.failures() {
+            0 => Ok(()),
+            failed => Err(Error::Other(anyhow::anyhow!(
+                "{failed} of {} projects failed to update",
+                self.results.len()
+            ))),
+        }
+    }
+}
+
+impl Display for WorkspaceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut table = WorkspaceTable::new(["PROJECT", "RESULT"]);
+        for (path, err) in &self.results {
+            let result = match err {
+                None => "ok".to_string(),
+                Some(err) => format!("failed: {err}"),
+            };
+            table.push([path.display().to_string(), result]);
+        }
+        write!(f, "{table}")?;
+        writeln!(
+            f,
+            "\n{} updated, {} failed",
+            self.results.len() - self.failures(),
+            self.failures()
+        )
+    }
+}
+
+/// A plain-text table with left-aligned columns.
+#[derive(Debug)]
+pub struct WorkspaceTable {
+    rows: Vec<Vec<String>>,
+}
+
+impl WorkspaceTable {
+    fn new<const N: usize>(headers: [&str; N]) -> Self {
+        Self { rows: vec![headers.iter().map(|h| h.to_string()).collect()] }
+    }
+
+    fn push<const N: usize>(&mut self, row: [String; N]) {
+        self.rows.push(row.to_vec());
+    }
+}
+
+impl Display for WorkspaceTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let columns = self.rows[0].len();
+        let widths: Vec<usize> = (0..columns)
+            .map(|i| {
+                self.rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0)
+            })
+            .collect();
+        for row in &self.rows {
+            let mut line = String::new();
+            for (i, cell) in row.iter().enumerate() {
+                if i + 1 == columns {
+                    line.push_str(cell);
+                } else {
+                    line.push_str(&format!("{cell:<width$}  ", width = widths[i]));
+                }
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The template location and a short version: the git tag or abbreviated commit,
+/// or the abbreviated content hash.
+fn describe_source(source: &TemplateSourceInfo) -> (String, String) {
+    let short = |s: &str| s.chars().take(12).collect::<String>();
+    match source {
+        TemplateSourceInfo::Git { url, commit, tag } => {
+            (url.clone(), tag.clone().unwrap_or_else(|| short(commit)))
+        }
+        TemplateSourceInfo::Filesystem { path, hash } => (path.clone(), short(hash)),
+        TemplateSourceInfo::Plugin { url, hash } => (url.clone(), short(hash)),
+        TemplateSourceInfo::Tar { hash } => ("(stdin)".to_string(), short(hash)),
+    }
+}
+
+/// `dir` made absolute without requiring it to exist, so removed projects can
+/// still be unregistered.
+fn absolute(dir: &Path) -> PathBuf {
+    fs::canonicalize(dir).unwrap_or_else(|_| {
+        std::env::current_dir().map(|cwd| cwd.join(dir)).unwrap_or_else(|_| dir.into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn generated_project(root: &Path, name: &str) -> PathBuf {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("README.md"), "hello").unwrap();
+        let files = [(
+            "README.md".to_string(),
+            generated::FileProvenance {
+                template: "README.md".to_string(),
+                hash: generated::content_hash(b"hello"),
+                binary: false,
+            },
+        )]
+        .into();
+        let meta = BakerGenerated::new(
+            TemplateSourceInfo::Git {
+                url: "https://github.com/acme/service.git".to_string(),
+                commit: "0123456789abcdef".to_string(),
+                tag: None,
+            },
+            json!({"name": name}),
+        )
+        .with_files(files);
+        generated::write(&dir, DEFAULT_GENERATED_FILE_NAME, &meta).unwrap();
+        dir
+    }
+
+    #[test]
+    fn registers_projects_and_round_trips_the_registry() {
+        let root = tempfile::tempdir().unwrap();
+        let users = generated_project(root.path(), "users");
+        let orders = generated_project(root.path(), "orders");
+        let registry = root.path().join("config/workspace.yaml");
+
+        let mut workspace = Workspace::load(&registry).unwrap();
+        assert!(workspace.add(&users, None).unwrap());
+        assert!(workspace.add(&orders, Some("meta.yaml".to_string())).is_err());
+        assert!(workspace.add(&orders, None).unwrap());
+        assert!(!workspace.add(&users, None).unwrap());
+        workspace.save(&registry).unwrap();
+
+        let mut loaded = Workspace::load(&registry).unwrap();
+        assert_eq!(loaded.projects, workspace.projects);
+        assert!(loaded.remove(&users));
+        assert!(!loaded.remove(&users));
+        assert_eq!(loaded.projects.len(), 1);
+    }
+
+    #[test]
+    fn status_shows_version_and_local_changes() {
+        let root = tempfile::tempdir().unwrap();
+        let users = generated_project(root.path(), "users");
+        let orders = generated_project(root.path(), "orders");
+        let mut workspace = Workspace::default();
+        workspace.add(&users, None).unwrap();
+        workspace.add(&orders, None).unwrap();
+        fs::write(orders.join("README.md"), "edited").unwrap();
+        fs::remove_dir_all(&users).unwrap();
+
+        let status = workspace.status().to_string();
+        let lines: Vec<&str> = status.lines().collect();
+
+        assert!(lines[0].starts_with("PROJECT"));
+        assert!(lines[1].ends_with(" missing"));
+        assert!(lines[2].contains("https://github.com/acme/service.git  0123456789ab"));
+        assert!(lines[2].ends_with("1 changed"));
+    }
+
+    #[test]
+    fn update_report_lists_each_project() {
+        let report = WorkspaceReport {
+            results: vec![
+                ("/srv/users".into(), None),
+                ("/srv/orders".into(), Some("template not found".to_string())),
+            ],
+        };
+        let summary = report.to_string();
+        assert!(summary.contains("/srv/users   ok\n"));
+        assert!(summary.contains("/srv/orders  failed: template not found\n"));
+        assert!(summary.ends_with("1 updated, 1 failed\n"));
+        assert!(report.into_result().is_err());
+    }
+}
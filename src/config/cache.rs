@@ -0,0 +1,119 @@
+//! Cache of validated template configurations.
+//!
+//! A configuration is stored as JSON, with its `x-types` expanded, under the hash
+//! of the config file's content and the Baker version. Loading a cached config
+//! skips the YAML parsing and the validation, including the compilation of its
+//! JSON schemas, which adds up for large configs generated over and over in CI.
+
+use crate::{
+    config::{
+        loader::{config_value, read_config_file},
+        user::cache_dir,
+        Config, ConfigV1, LoadOptions,
+    },
+    error::Result,
+};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Validated configurations, one file per config content.
+#[derive(Debug, Clone)]
+pub struct ConfigCache {
+    dir: PathBuf,
+}
+
+impl ConfigCache {
+    /// Cache stored under `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache stored under `baker/config` in the user's cache directory.
+    pub fn default_location() -> Option<Self> {
+        cache_dir().map(|dir| Self::new(dir.join("baker").join("config")))
+    }
+
+    /// Loads and validates the configuration of `template_root`, from the cache
+    /// when its config file, and the overlay of the profile, did not change since it
+    /// was cached. `options` are passed on to [`Config::load_validated`].
+    pub fn load(&self, template_root: &Path, options: &LoadOptions) -> Result<ConfigV1> {
+        let Some((file_name, content)) =
+            read_config_file(template_root, options.profile.as_deref())?
+        else {
+            // Cookiecutter templates and missing configs are not cached.
+            return Config::load_validated(template_root, options);
+        };
+        let path =
+            self.dir.join(format!("{}.json", key(&file_name, &content, options.strict)));
+        if path.exists() {
+            let cached = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|cached| {
+                    serde_json::from_str::<Config>(&cached).map_err(|e| e.to_string())
+                });
+            match cached {
+                Ok(Config::V1(config)) => {
+                    log::debug!("Using cached config '{}'", path.display());
+                    return Ok(config);
+                }
+                Err(e) => log::warn!("Ignoring cached config '{}': {e}", path.display()),
+            }
+        }
+
+        let config = Config::load_validated(template_root, options)?;
+        let stored = config_value(&file_name, &content).and_then(|value| {
+            std::fs::create_dir_all(&self.dir)?;
+            std::fs::write(&path, serde_json::to_string(&value)?)?;
+            Ok(())
+        });
+        if let Err(e) = stored {
+            log::warn!("Failed to cache config '{}': {e}", path.display());
+        }
+        Ok(config)
+    }
+}
+
+fn key(file_name: &str, content: &str, strict: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(b"\0");
+    // A config validated without strict mode may still have unknown keys.
+    hasher.update([u8::from(strict)]);
+    hasher.update(file_name.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(content.as_bytes());
+    hex::encode(hasher.finalize())[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_validated_configs_by_content() {
+        let template = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = ConfigCache::new(cache_dir.path());
+        std::fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\nx-types:\n  name:\n    type: str\n    help: Name\nquestions:\n  project:\n    uses: name\n",
+        )
+        .unwrap();
+
+        let config = cache.load(template.path(), &LoadOptions::default()).unwrap();
+        assert_eq!(config.questions["project"].help, "Name");
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+
+        let cached = cache.load(template.path(), &LoadOptions::default()).unwrap();
+        assert_eq!(cached.questions["project"].help, "Name");
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+
+        std::fs::write(
+            template.path().join("baker.yaml"),
+            "schemaVersion: v1\ntemplate_suffix: ''\n",
+        )
+        .unwrap();
+        assert!(cache.load(template.path(), &LoadOptions::default()).is_err());
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+//! Compatibility with cookiecutter templates.
+//!
+//! A template without a Baker config but with a `cookiecutter.json` is loaded as
+//! if it had a `baker.yaml` asking the same questions:
+//!
+//! - strings, numbers and booleans become `str` and `bool` questions with the value
+//!   as default; lists become choices defaulting to their first entry; objects become
+//!   `json` questions
+//! - `__name` variables are rendered from their default without being asked
+//! - other `_name` variables (cookiecutter's private settings) are left out
+//!
+//! The answers are also available under `cookiecutter`, so `{{ cookiecutter.name }}`
+//! works in file names and contents, and every file is rendered, not only those with
+//! the template suffix. The `{{cookiecutter.project_slug}}/` root directory then
+//! renders into the project directory inside the output directory.
+
+use crate::{
+    config::ConfigV1,
+    error::{Error, Result},
+};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+/// Name of the cookiecutter config file.
+pub const COOKIECUTTER_CONFIG_FILENAME: &str = "cookiecutter.json";
+
+/// Name under which cookiecutter templates find their answers.
+pub const COOKIECUTTER_CONTEXT_ALIAS: &str = "cookiecutter";
+
+/// Builds a Baker config from the content of a `cookiecutter.json`.
+pub fn config_from_cookiecutter(content: &str) -> Result<ConfigV1> {
+    // An IndexMap keeps the file's order, which later defaults depend on.
+    let variables: IndexMap<String, Value> =
+        serde_json::from_str(content).map_err(|e| {
+            Error::ConfigValidation(format!(
+                "Invalid {COOKIECUTTER_CONFIG_FILENAME}: {e}"
+            ))
+        })?;
+
+    let mut questions = IndexMap::new();
+    for (name, value) in variables {
+        let computed = name.starts_with("__");
+        if name.starts_with('_') && !computed {
+            log::debug!("Ignoring cookiecutter private variable '{name}'");
+            continue;
+        }
+        let mut question = question_for(&name, value);
+        if computed {
+            question["ask_if"] = json!("false");
+        }
+        questions.insert(name, serde_json::from_value(question)?);
+    }
+
+    let mut config: ConfigV1 = serde_json::from_value(json!({
+        "context_alias": COOKIECUTTER_CONTEXT_ALIAS,
+        "render_all": true,
+    }))?;
+    config.questions = questions;
+    Ok(config)
+}
+
+fn question_for(name: &str, value: Value) -> Value {
+    let help = name.replace('_', " ");
+    match value {
+        Value::Bool(default) => json!({"type": "bool", "help": help, "default": default}),
+        Value::Array(choices) => {
+            let choices: Vec<String> = choices.iter().map(scalar_to_string).collect();
+            json!({
+                "type": "str",
+                "help": help,
+                "choices": choices,
+                "default": choices.first(),
+            })
+        }
+        Value::Object(_) => json!({"type": "json", "help": help, "default": value}),
+        scalar => {
+            json!({"type": "str", "help": help, "default": scalar_to_string(&scalar)})
+        }
+    }
+}
+
+fn scalar_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Type;
+
+    #[test]
+    fn maps_cookiecutter_variables_to_questions() {
+        let config = config_from_cookiecutter(
+            r#"{
+                "project_name": "My Project",
+                "project_slug": "{{ cookiecutter.project_name | lower | replace(' ', '_') }}",
+                "license": ["MIT", "BSD-3"],
+                "use_docker": false,
+                "version": 1,
+                "settings": {"debug": true},
+                "__prefix": "{{ cookiecutter.project_slug }}_",
+                "_copy_without_render": ["*.png"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.context_alias.as_deref(), Some("cookiecutter"));
+        assert!(config.render_all);
+        let names: Vec<&str> = config.questions.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            [
+                "project_name",
+                "project_slug",
+                "license",
+                "use_docker",
+                "version",
+                "settings",
+                "__prefix"
+            ]
+        );
+        assert_eq!(config.questions["license"].choices, ["MIT", "BSD-3"]);
+        assert_eq!(config.questions["license"].default, json!("MIT"));
+        assert!(matches!(config.questions["use_docker"].r#type, Type::Bool));
+        assert_eq!(config.questions["version"].default, json!("1"));
+        assert!(matches!(config.questions["settings"].r#type, Type::Json));
+        assert_eq!(config.questions["__prefix"].ask_if, "false");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_non_object_files() {
+        let err = config_from_cookiecutter("[1, 2]").unwrap_err();
+        assert!(err.to_string().contains("Invalid cookiecutter.json"));
+    }
+}
@@ -0,0 +1,119 @@
+//! The side-effect-free subset of expressions allowed with `safe_expressions`.
+//!
+//! Filters and tests may be called, and the functions that neither read the
+//! filesystem nor depend on when they run. Method calls, which reach into
+//! whatever object an answer happens to hold, and calls of computed values are
+//! rejected.
+
+/// Functions an expression may call in the safe subset.
+const SAFE_FUNCTIONS: &[&str] = &["range", "dict", "is_windows"];
+
+/// A token of an expression, as far as calls are concerned.
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    Punct(char),
+    Literal,
+}
+
+/// Describes the first call of `source` outside the safe subset, if any.
+pub(crate) fn unsafe_call(source: &str) -> Option<String> {
+    let tokens = tokenize(source);
+    for (index, token) in tokens.iter().enumerate() {
+        if *token != Token::Punct('(') {
+            continue;
+        }
+        let before = |back: usize| index.checked_sub(back).and_then(|i| tokens.get(i));
+        match (before(1), before(2), before(3)) {
+            (Some(Token::Word(name)), Some(Token::Punct('.')), _) => {
+                return Some(format!("calls method '{name}'"));
+            }
+            (Some(Token::Word(_)), Some(Token::Punct('|')), _)
+            | (Some(Token::Word(_)), Some(Token::Word("is")), _)
+            | (Some(Token::Word(_)), Some(Token::Word("not")), Some(Token::Word("is"))) =>
+                {}
+            (Some(Token::Word(name)), _, _) if SAFE_FUNCTIONS.contains(name) => {}
+            (Some(Token::Word(name)), _, _) if !is_keyword(name) => {
+                return Some(format!("calls function '{name}'"));
+            }
+            (Some(Token::Punct(')' | ']')), _, _) => {
+                return Some("calls a computed value".to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Words after which `(` opens a group rather than a call.
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "and" | "or" | "not" | "in" | "if" | "else" | "is")
+}
+
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '"' || c == '\'' {
+            let mut escaped = false;
+            for (_, next) in chars.by_ref() {
+                if next == c && !escaped {
+                    break;
+                }
+                escaped = next == '\\' && !escaped;
+            }
+            tokens.push(Token::Literal);
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, next)) = chars.peek() {
+                if !(next.is_alphanumeric() || next == '_') {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Word(&source[start..end]));
+        } else if c.is_ascii_digit() {
+            while chars
+                .peek()
+                .is_some_and(|&(_, next)| next.is_alphanumeric() || next == '.')
+            {
+                chars.next();
+            }
+            tokens.push(Token::Literal);
+        } else if !c.is_whitespace() {
+            tokens.push(Token::Punct(c));
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_filters_tests_and_pure_functions_only() {
+        for safe in [
+            "use_ci and ci in ['github', 'gitlab']",
+            "name | length > 3 and name is not startingwith('_')",
+            "(a or b) and not (c) and x in range(3)",
+            "name | regex_match('^[a-z]+$') and 'x.upper()' != name",
+            "1.5 < version | float",
+        ] {
+            assert_eq!(unsafe_call(safe), None, "{safe}");
+        }
+        assert_eq!(
+            unsafe_call("name.upper() == 'A'").as_deref(),
+            Some("calls method 'upper'")
+        );
+        assert_eq!(
+            unsafe_call("git_remote_url() != ''").as_deref(),
+            Some("calls function 'git_remote_url'")
+        );
+        assert_eq!(
+            unsafe_call("callbacks[0]()").as_deref(),
+            Some("calls a computed value")
+        );
+    }
+}
@@ -1,6 +1,16 @@
 //! Configuration loading and management
 
+use crate::config::cookiecutter::{
+    config_from_cookiecutter, COOKIECUTTER_CONFIG_FILENAME,
+};
+use crate::config::expression::unsafe_call;
+use crate::config::position;
 use crate::config::question::Question;
+use crate::config::strict;
+use crate::config::types::{
+    AnswerLayer, AnswerTransform, AnswersValidation, Durability, HookWorkingDir,
+    Introspector, NonAsciiNames, SuffixBehavior, Type, UndefinedBehavior, WindowsPaths,
+};
 use crate::conflict::ConflictStyle;
 use crate::constants::{
     CONFIG_FILENAMES, DEFAULT_LOOP_CONTENT_SEPARATOR, DEFAULT_LOOP_SEPARATOR,
@@ -8,15 +18,22 @@ use crate::constants::{
 };
 use crate::error::{Error, Result};
 use crate::ext::PathExt;
+use crate::prompt::theme::TemplatePromptTheme;
+use globset::GlobBuilder;
 use indexmap::IndexMap;
 use serde::Deserialize;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
 
 /// Main configuration structure holding all questions
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ConfigV1 {
     #[serde(default = "get_default_template_suffix")]
     pub template_suffix: String,
+    /// Further suffixes with their own behavior, e.g. `.baker.raw: raw`. The suffix
+    /// is stripped from the output file name.
+    #[serde(default)]
+    pub suffixes: IndexMap<String, SuffixBehavior>,
     #[serde(default = "get_default_loop_separator")]
     pub loop_separator: String,
     #[serde(default = "get_default_loop_content_separator")]
@@ -27,6 +44,11 @@ pub struct ConfigV1 {
     pub import_root: Option<String>,
     #[serde(default)]
     pub questions: IndexMap<String, Question>,
+    /// Glob patterns, relative to the template root, of files holding more
+    /// questions. Their questions follow those of `questions`, in the order of the
+    /// patterns and, for each pattern, of the file paths.
+    #[serde(default)]
+    pub questions_files: Vec<String>,
     #[serde(default = "get_default_post_hook_filename")]
     pub post_hook_filename: String,
     #[serde(default = "get_default_pre_hook_filename")]
@@ -37,8 +59,36 @@ pub struct ConfigV1 {
     pub pre_hook_runner: Vec<String>,
     #[serde(default = "get_default_post_hook_print_stdout")]
     pub post_hook_print_stdout: bool,
+    /// Let hooks ask questions through the `baker:ask` stdout protocol. Stdin then
+    /// stays open after the context line, so hooks must read it line by line.
+    #[serde(default)]
+    pub hook_prompts: bool,
+    /// JSON Schema the pre-hook's JSON output must match. Keys outside its
+    /// `properties` are rejected unless it sets `additionalProperties`.
+    #[serde(default)]
+    pub hook_answers_schema: Option<serde_json::Value>,
+    /// Expose the pre-hook's output as `hooks.pre.<key>` instead of merging it
+    /// into the answers, so it cannot collide with questions.
+    #[serde(default)]
+    pub namespace_hook_answers: bool,
+    /// Directory the pre-hook runs in. Defaults to the directory Baker was started from.
+    #[serde(default)]
+    pub pre_hook_working_dir: Option<HookWorkingDir>,
+    /// Directory the post-hook runs in. Defaults to the directory Baker was started from.
+    #[serde(default)]
+    pub post_hook_working_dir: Option<HookWorkingDir>,
+    /// Container image the pre-hook runs in, e.g. `python:3.12`.
+    #[serde(default)]
+    pub pre_hook_image: Option<String>,
+    /// Container image the post-hook runs in.
+    #[serde(default)]
+    pub post_hook_image: Option<String>,
     #[serde(default = "get_default_follow_symlinks")]
     pub follow_symlinks: bool,
+    /// Template for a directory inside the output directory that the project is
+    /// generated into, rendered once the answers are known.
+    #[serde(default)]
+    pub output_subdir: Option<String>,
     /// Name of the generated-metadata file written to the output directory.
     /// Defaults to `.baker-generated.yaml`.
     #[serde(default)]
@@ -47,20 +97,415 @@ pub struct ConfigV1 {
     /// Defaults to `git` style.
     #[serde(default)]
     pub conflict_marker_style: Option<ConflictStyle>,
+    /// Order in which predefined answer sources are merged, lowest precedence first.
+    /// Sources left out are ignored. Defaults to `AnswerLayer::DEFAULT_PRECEDENCE`.
+    #[serde(default)]
+    pub answer_precedence: Option<Vec<AnswerLayer>>,
+    /// Built-in transformers applied in order to the collected answers before
+    /// rendering.
+    #[serde(default)]
+    pub answer_transformers: Vec<AnswerTransform>,
+    /// Name under which the answers are also available to file names and contents,
+    /// e.g. `cookiecutter` for `{{ cookiecutter.name }}`.
+    #[serde(default)]
+    pub context_alias: Option<String>,
+    /// Render every text file, not only those ending in a template suffix. File
+    /// names are kept as they are.
+    #[serde(default)]
+    pub render_all: bool,
+    /// Also write copier's `.copier-answers.yml`, for projects moving from copier.
+    #[serde(default)]
+    pub copier_answers: bool,
+    /// Rules over the complete answers, checked after the last question and before
+    /// anything is generated.
+    #[serde(default)]
+    pub validations: Vec<AnswersValidation>,
+    /// Reject keys Baker does not know instead of ignoring them, so that a typo
+    /// like `qustions` does not silently drop the questions.
+    #[serde(default)]
+    pub strict: bool,
+    /// Restrict `ask_if` and validation conditions to filters, tests and pure
+    /// functions, rejecting method calls and functions that read the filesystem.
+    #[serde(default)]
+    pub safe_expressions: bool,
+    /// Apply the `.editorconfig` at the template root to rendered text files.
+    #[serde(default)]
+    pub editorconfig: bool,
+    /// How generated files are written: atomically by default, optionally
+    /// flushed to disk.
+    #[serde(default)]
+    pub durability: Durability,
+    /// On Windows, whether rendered names Windows cannot create are rejected or
+    /// escaped.
+    #[serde(default)]
+    pub windows_paths: WindowsPaths,
+    /// Whether rendered names that are not ASCII are kept, transliterated or
+    /// rejected.
+    #[serde(default)]
+    pub non_ascii_names: NonAsciiNames,
+    /// Whether templates fail on variables that are not defined. Failures name
+    /// the defined variables closest to the undefined one.
+    #[serde(default)]
+    pub undefined_behavior: UndefinedBehavior,
+    /// Values read from the project in the output directory before the questions
+    /// are asked, keyed by the context name they are available under.
+    #[serde(default)]
+    pub introspect: IndexMap<String, Introspector>,
+    /// Prompt prefix, accent color and yes/no words, used where the user config
+    /// does not set them.
+    #[serde(default)]
+    pub prompt_theme: TemplatePromptTheme,
+}
+
+/// A validation failure and the path of the config key it is about, e.g.
+/// `["questions", "name", "default"]`.
+#[derive(Debug)]
+struct Problem {
+    path: Vec<String>,
+    message: String,
+}
+
+impl Problem {
+    fn new(path: &[&str], message: impl Into<String>) -> Self {
+        Self {
+            path: path.iter().map(|s| s.to_string()).collect(),
+            message: message.into(),
+        }
+    }
+
+    fn at(path: Vec<String>, message: impl Into<String>) -> Self {
+        Self { path, message: message.into() }
+    }
 }
 
 impl ConfigV1 {
     pub fn validate(&self) -> Result<(), Error> {
+        self.check().map_err(|problem| Error::ConfigValidation(problem.message))
+    }
+
+    fn check(&self) -> std::result::Result<(), Problem> {
         if self.template_suffix.is_empty() {
-            return Err(Error::ConfigValidation(
-                "template_suffix must not be empty".into(),
+            return Err(Problem::new(
+                &["template_suffix"],
+                "template_suffix must not be empty",
             ));
         }
         if !self.template_suffix.starts_with('.') || self.template_suffix.len() < 2 {
-            return Err(Error::ConfigValidation("template_suffix must start with '.' and have at least 1 character after it".into()));
+            return Err(Problem::new(&["template_suffix"], "template_suffix must start with '.' and have at least 1 character after it"));
+        }
+        for suffix in self.suffixes.keys() {
+            if !suffix.starts_with('.') || suffix.len() < 2 {
+                return Err(Problem::new(&["suffixes", suffix], format!(
+                    "suffix '{suffix}' must start with '.' and have at least 1 character after it"
+                )));
+            }
+            if *suffix == self.template_suffix {
+                return Err(Problem::new(
+                    &["suffixes", suffix],
+                    format!("suffix '{suffix}' is already the template_suffix"),
+                ));
+            }
+        }
+        for (index, pattern) in self.template_globs.iter().enumerate() {
+            if let Err(e) = globset::Glob::new(pattern) {
+                return Err(Problem::new(
+                    &["template_globs", &index.to_string()],
+                    format!("invalid glob '{pattern}' in template_globs: {}", e.kind()),
+                ));
+            }
+        }
+        let precedence = self.answer_precedence();
+        if let Some(duplicate) =
+            precedence.iter().enumerate().find(|(i, l)| precedence[..*i].contains(l))
+        {
+            return Err(Problem::new(
+                &["answer_precedence"],
+                format!("answer_precedence lists '{}' more than once", duplicate.1),
+            ));
+        }
+        let safe = self.safe_expressions;
+        check_questions(&self.questions, &["questions".to_string()], "", safe)?;
+        for (index, validation) in self.validations.iter().enumerate() {
+            let path = ["validations".to_string(), index.to_string()];
+            check_expression(&path, "condition", &validation.condition, safe)?;
+            check_template(&path, "error_message", &validation.error_message)?;
+            if let Some(key) =
+                validation.keys.iter().find(|key| !self.questions.contains_key(*key))
+            {
+                return Err(Problem::at(
+                    [&path[..], &["keys".to_string()]].concat(),
+                    format!(
+                        "validation '{}' refers to unknown question '{key}'",
+                        validation.condition
+                    ),
+                ));
+            }
+        }
+        if let Some(alias) = &self.context_alias {
+            if self.questions.contains_key(alias) {
+                return Err(Problem::new(
+                    &["context_alias"],
+                    format!("question '{alias}' collides with the context_alias"),
+                ));
+            }
+        }
+        if let Some(key) =
+            self.introspect.keys().find(|key| self.questions.contains_key(*key))
+        {
+            return Err(Problem::new(
+                &["introspect", key],
+                format!("question '{key}' collides with the introspected context"),
+            ));
+        }
+        if self.namespace_hook_answers && self.questions.contains_key("hooks") {
+            return Err(Problem::new(
+                &["questions", "hooks"],
+                "question 'hooks' collides with namespaced hook answers",
+            ));
+        }
+        if let Some(schema) = &self.hook_answers_schema {
+            jsonschema::validator_for(schema).map_err(|e| {
+                Problem::new(
+                    &["hook_answers_schema"],
+                    format!("Invalid hook_answers_schema: {e}"),
+                )
+            })?;
         }
         Ok(())
     }
+
+    /// The answers as file names and contents see them: with the answers also
+    /// under `context_alias`, when one is set.
+    pub fn template_context(&self, answers: &serde_json::Value) -> serde_json::Value {
+        let (Some(alias), Some(map)) = (&self.context_alias, answers.as_object()) else {
+            return answers.clone();
+        };
+        let mut context = map.clone();
+        context.insert(alias.clone(), answers.clone());
+        serde_json::Value::Object(context)
+    }
+
+    /// The effective answer precedence, lowest first.
+    pub fn answer_precedence(&self) -> &[AnswerLayer] {
+        self.answer_precedence.as_deref().unwrap_or(AnswerLayer::DEFAULT_PRECEDENCE)
+    }
+}
+
+/// Checks the questions under `path` in the config; `prefix` names them in
+/// messages. With `safe`, their expressions must be in the safe subset.
+fn check_questions(
+    questions: &IndexMap<String, Question>,
+    path: &[String],
+    prefix: &str,
+    safe: bool,
+) -> std::result::Result<(), Problem> {
+    for (key, question) in questions {
+        check_question(
+            question,
+            &[path, std::slice::from_ref(key)].concat(),
+            &format!("{prefix}{key}"),
+            safe,
+        )?;
+    }
+    Ok(())
+}
+
+/// Checks that `object` questions, and only those, have `properties`, that
+/// `list` questions, and only those, have an `item`, and that the templates and
+/// expressions of the question parse.
+fn check_question(
+    question: &Question,
+    path: &[String],
+    name: &str,
+    safe: bool,
+) -> std::result::Result<(), Problem> {
+    let child = |key: &str| [path, &[key.to_string()]].concat();
+    let is_object = matches!(question.r#type, Type::Object);
+    if is_object && question.properties.is_empty() {
+        return Err(Problem::at(
+            path.to_vec(),
+            format!("object question '{name}' has no properties"),
+        ));
+    }
+    if !is_object && !question.properties.is_empty() {
+        return Err(Problem::at(
+            child("properties"),
+            format!("question '{name}' has properties but is not of type object"),
+        ));
+    }
+    let is_list = matches!(question.r#type, Type::List);
+    if is_list != question.item.is_some() {
+        return Err(if is_list {
+            Problem::at(path.to_vec(), format!("list question '{name}' has no item"))
+        } else {
+            Problem::at(
+                child("item"),
+                format!("question '{name}' has an item but is not of type list"),
+            )
+        });
+    }
+    check_template(path, "help", &question.help)?;
+    check_template(path, "description", &question.description)?;
+    if let Some(default) = question.default.as_str() {
+        check_template(path, "default", default)?;
+    }
+    check_expression(path, "ask_if", &question.ask_if, safe)?;
+    let validation = child("validation");
+    check_expression(&validation, "condition", &question.validation.condition, safe)?;
+    check_template(&validation, "error_message", &question.validation.error_message)?;
+    for (index, set) in question.sets.iter().enumerate() {
+        let set_path = [path, &["sets".to_string(), index.to_string()]].concat();
+        // Answers are set among the top-level questions only.
+        if path.len() > 2 {
+            return Err(Problem::at(
+                set_path,
+                format!("question '{name}' is nested and cannot set answers"),
+            ));
+        }
+        check_expression(&set_path, "when", &set.when, safe)?;
+        for (target, value) in &set.values {
+            let value_path = [&set_path[..], &["values".to_string()]].concat();
+            if target == name {
+                return Err(Problem::at(
+                    [&value_path[..], std::slice::from_ref(target)].concat(),
+                    format!("question '{name}' sets its own answer"),
+                ));
+            }
+            if let Some(template) = value.as_str() {
+                check_template(&value_path, target, template)?;
+            }
+        }
+    }
+    check_questions(
+        &question.properties,
+        &child("properties"),
+        &format!("{name}."),
+        safe,
+    )?;
+    if let Some(item) = &question.item {
+        check_question(item, &child("item"), &format!("{name}[]"), safe)?;
+    }
+    Ok(())
+}
+
+/// Checks that the Jinja template `source` at `path.field` parses.
+fn check_template(
+    path: &[String],
+    field: &str,
+    source: &str,
+) -> std::result::Result<(), Problem> {
+    if !source.contains("{{") && !source.contains("{%") {
+        return Ok(());
+    }
+    minijinja::Environment::new().template_from_str(source).map(|_| ()).map_err(|e| {
+        Problem::at(
+            [path, &[field.to_string()]].concat(),
+            format!("invalid template in {}.{field}: {e}", path.join(".")),
+        )
+    })
+}
+
+/// Checks that the Jinja expression `source` at `path.field` parses and, with
+/// `safe`, that it only makes calls of the safe subset.
+fn check_expression(
+    path: &[String],
+    field: &str,
+    source: &str,
+    safe: bool,
+) -> std::result::Result<(), Problem> {
+    if source.trim().is_empty() {
+        return Ok(());
+    }
+    let problem =
+        |message: String| Problem::at([path, &[field.to_string()]].concat(), message);
+    minijinja::Environment::new().compile_expression(source).map_err(|e| {
+        problem(format!("invalid expression in {}.{field}: {e}", path.join(".")))
+    })?;
+    match unsafe_call(source).filter(|_| safe) {
+        Some(call) => Err(problem(format!(
+            "unsafe expression in {}.{field}: '{source}' {call}, which safe_expressions forbids",
+            path.join(".")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Top-level key of the reusable question definitions.
+pub(crate) const QUESTION_TYPES_KEY: &str = "x-types";
+
+/// Replaces `uses: <name>` in every question, including nested `properties` and
+/// list `item`s, with the definition `<name>` from `x-types`. Keys set on the
+/// question itself take precedence. Definitions may use other definitions.
+fn expand_question_types(config: &mut serde_json::Value) -> Result<()> {
+    let types = match config.as_object_mut().and_then(|c| c.remove(QUESTION_TYPES_KEY)) {
+        Some(serde_json::Value::Object(types)) => types,
+        _ => {
+            return Err(Error::ConfigValidation(format!(
+                "{QUESTION_TYPES_KEY} must be a mapping of names to question definitions"
+            )))
+        }
+    };
+    if let Some(serde_json::Value::Object(questions)) = config.get_mut("questions") {
+        for (key, question) in questions.iter_mut() {
+            expand_question(key, question, &types, &mut Vec::new())?;
+        }
+    }
+    Ok(())
+}
+
+fn expand_question(
+    key: &str,
+    question: &mut serde_json::Value,
+    types: &serde_json::Map<String, serde_json::Value>,
+    using: &mut Vec<String>,
+) -> Result<()> {
+    let Some(fields) = question.as_object_mut() else {
+        return Ok(());
+    };
+    if let Some(uses) = fields.remove("uses") {
+        let name = uses.as_str().unwrap_or_default().to_string();
+        if using.contains(&name) {
+            return Err(Error::ConfigValidation(format!(
+                "{QUESTION_TYPES_KEY} '{name}' uses itself through '{}'",
+                using.join("' -> '")
+            )));
+        }
+        let mut base = types.get(&name).cloned().ok_or_else(|| {
+            Error::ConfigValidation(format!(
+                "question '{key}' uses unknown type '{name}'; define it under {QUESTION_TYPES_KEY}"
+            ))
+        })?;
+        using.push(name);
+        expand_question(key, &mut base, types, using)?;
+        using.pop();
+        if let serde_json::Value::Object(mut merged) = base {
+            merged.extend(std::mem::take(fields));
+            *fields = merged;
+        }
+    }
+
+    if let Some(serde_json::Value::Object(properties)) = fields.get_mut("properties") {
+        for (name, property) in properties.iter_mut() {
+            expand_question(&format!("{key}.{name}"), property, types, &mut Vec::new())?;
+        }
+    }
+    if let Some(item) = fields.get_mut("item") {
+        expand_question(&format!("{key}[]"), item, types, &mut Vec::new())?;
+    }
+    Ok(())
+}
+
+/// Parses the content of the config file `file_name` into JSON, with the
+/// `x-types` definitions expanded.
+pub(crate) fn config_value(file_name: &str, content: &str) -> Result<serde_json::Value> {
+    let mut value: serde_json::Value = match file_name {
+        "baker.json" => serde_json::from_str(content)?,
+        _ => serde_yaml::from_str(content)?,
+    };
+    if value.get(QUESTION_TYPES_KEY).is_some() {
+        expand_question_types(&mut value)?;
+    }
+    Ok(value)
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,24 +515,71 @@ pub enum Config {
     V1(ConfigV1),
 }
 
+/// How [`Config::load_validated`] loads a configuration.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Reject unknown keys even when the config does not set `strict: true`.
+    pub strict: bool,
+    /// Merge the overlay `baker.<profile>.yaml` over the base config.
+    pub profile: Option<String>,
+}
+
 impl Config {
     pub fn load_config<P: AsRef<Path>>(template_root: P) -> Result<Self> {
-        let template_root = template_root.as_ref().to_path_buf();
+        Self::load_profile(template_root, None)
+    }
+
+    /// Loads the configuration of `template_root` with the overlay of `profile`, if
+    /// given, merged over it.
+    pub fn load_profile<P: AsRef<Path>>(
+        template_root: P,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        let template_root = template_root.as_ref();
         let template_dir = template_root.to_str_checked()?.to_string();
 
-        for config_file_name in CONFIG_FILENAMES.iter() {
-            let config_file_path = template_root.join(config_file_name);
+        if let Some((file_name, content)) = read_config_file(template_root, profile)? {
+            let mut value: serde_json::Value = match file_name.as_str() {
+                "baker.json" => serde_json::from_str(&content)?,
+                _ => serde_yaml::from_str(&content)?,
+            };
+            if value.get(QUESTION_TYPES_KEY).is_some() {
+                expand_question_types(&mut value)?;
+                return Ok(serde_json::from_value(value)?);
+            }
 
-            if config_file_path.exists() {
-                let content = std::fs::read_to_string(config_file_path)?;
-                let config: Config = match *config_file_name {
-                    "baker.json" => serde_json::from_str(&content)?,
-                    "baker.yaml" | "baker.yml" => serde_yaml::from_str(&content)?,
-                    _ => unreachable!(),
-                };
+            // Parse the text itself so that errors point at a line and column.
+            // The schemaVersion tag hides them, so a failing v1 config is
+            // parsed again without it.
+            let is_v1 = value.get("schemaVersion").and_then(|v| v.as_str()) == Some("v1");
+            return match file_name.as_str() {
+                "baker.json" => serde_json::from_str(&content).map_err(|e| {
+                    match serde_json::from_str::<ConfigV1>(&content) {
+                        Err(positioned) if is_v1 => positioned,
+                        _ => e,
+                    }
+                    .into()
+                }),
+                _ => serde_yaml::from_str(&content).map_err(|e| {
+                    match serde_yaml::from_str::<ConfigV1>(&content) {
+                        Err(positioned) if is_v1 => positioned,
+                        _ => e,
+                    }
+                    .into()
+                }),
+            };
+        }
 
-                return Ok(config);
+        let cookiecutter_path = template_root.join(COOKIECUTTER_CONFIG_FILENAME);
+        if cookiecutter_path.exists() {
+            if let Some(profile) = profile {
+                return Err(Error::ConfigValidation(format!(
+                    "profile '{profile}' needs a Baker config, not {COOKIECUTTER_CONFIG_FILENAME}"
+                )));
             }
+            log::info!("No Baker config found, loading {COOKIECUTTER_CONFIG_FILENAME}");
+            let content = std::fs::read_to_string(cookiecutter_path)?;
+            return Ok(Config::V1(config_from_cookiecutter(&content)?));
         }
 
         Err(Error::ConfigNotFound {
@@ -95,6 +587,278 @@ impl Config {
             config_files: CONFIG_FILENAMES.join(", "),
         })
     }
+
+    /// Loads and validates the configuration of `template_root`. Validation errors
+    /// name the offending key and, for YAML configs, its line and column.
+    ///
+    /// Unknown keys are rejected when the config sets `strict: true` or
+    /// `options.strict` is set, e.g. by `--strict-config`.
+    pub fn load_validated<P: AsRef<Path>>(
+        template_root: P,
+        options: &LoadOptions,
+    ) -> Result<ConfigV1> {
+        let profile = options.profile.as_deref();
+        let Config::V1(config) = Self::load_profile(&template_root, profile)?;
+        let config_file = read_config_file(template_root.as_ref(), profile)?;
+        if options.strict || config.strict {
+            if let Some((file_name, content)) = &config_file {
+                check_strict(file_name, content)?;
+            }
+        }
+        let Err(problem) = config.check() else {
+            return Ok(config);
+        };
+        Err(Error::ConfigValidation(format!(
+            "{} (at {})",
+            problem.message,
+            locate(&problem.path, config_file.as_ref())
+        )))
+    }
+}
+
+/// `path` and where it is written, e.g. `questions.name, baker.yaml:3:5`. Merged
+/// profiles are not a file of their own, so they get no line and column.
+fn locate(path: &[String], config_file: Option<&(String, String)>) -> String {
+    let key = path.join(".");
+    let location = match config_file {
+        Some((file_name, content))
+            if matches!(file_name.as_str(), "baker.yaml" | "baker.yml") =>
+        {
+            match position::locate(content, path) {
+                Some((line, column)) => format!("{file_name}:{line}:{column}"),
+                None => file_name.to_string(),
+            }
+        }
+        Some((file_name, _)) => file_name.to_string(),
+        None => COOKIECUTTER_CONFIG_FILENAME.to_string(),
+    };
+    format!("{key}, {location}")
+}
+
+/// Fails with every key of the config file that Baker does not know.
+fn check_strict(file_name: &str, content: &str) -> Result<()> {
+    let value: serde_json::Value = match file_name {
+        "baker.json" => serde_json::from_str(content)?,
+        _ => serde_yaml::from_str(content)?,
+    };
+    let config_file = Some((file_name.to_string(), content.to_string()));
+    let errors: Vec<String> = strict::unknown_keys(&value)
+        .into_iter()
+        .map(|unknown| {
+            let suggestion = unknown
+                .suggestion
+                .map(|known| format!(" (did you mean '{known}'?)"))
+                .unwrap_or_default();
+            let key = unknown.path.last().cloned().unwrap_or_default();
+            format!(
+                "unknown key '{key}'{suggestion} (at {})",
+                locate(&unknown.path, config_file.as_ref())
+            )
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(Error::ConfigValidation(errors.join("; ")))
+}
+
+/// The name and content of the Baker config file of `template_root`, if it has one.
+///
+/// The questions of its `questions_files` are merged into the content, as YAML,
+/// and the name names the patterns. With a `profile`, the content is the base
+/// config with the overlay
+/// `baker.<profile>.yaml` (or `.yml`, `.json`) merged over it, as YAML, and the
+/// name names both files.
+pub(crate) fn read_config_file(
+    template_root: &Path,
+    profile: Option<&str>,
+) -> Result<Option<(String, String)>> {
+    let mut base = None;
+    for file_name in CONFIG_FILENAMES {
+        let path = template_root.join(file_name);
+        if path.exists() {
+            base = Some((file_name, std::fs::read_to_string(path)?));
+            break;
+        }
+    }
+    let Some((file_name, content)) = base else {
+        return Ok(None);
+    };
+    let (file_name, content) = merge_questions_files(template_root, file_name, content)?;
+    let Some(profile) = profile else {
+        return Ok(Some((file_name, content)));
+    };
+
+    if profile.is_empty()
+        || !profile.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::ConfigValidation(format!(
+            "invalid profile '{profile}': use letters, digits, '-' and '_'"
+        )));
+    }
+    let overlay_names: Vec<String> = ["yaml", "yml", "json"]
+        .iter()
+        .map(|ext| format!("baker.{profile}.{ext}"))
+        .collect();
+    let Some(overlay_name) =
+        overlay_names.iter().find(|name| template_root.join(name).exists())
+    else {
+        return Err(Error::ConfigNotFound {
+            template_dir: template_root.to_str_checked()?.to_string(),
+            config_files: overlay_names.join(", "),
+        });
+    };
+
+    // YAML is a superset of JSON, and unlike JSON values its mappings keep the
+    // order of the questions.
+    let mut merged: serde_yaml::Value = serde_yaml::from_str(&content)?;
+    let overlay: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(
+        template_root.join(overlay_name),
+    )?)?;
+    match overlay {
+        serde_yaml::Value::Mapping(_) => merge_overlay(&mut merged, overlay),
+        serde_yaml::Value::Null => {}
+        _ => {
+            return Err(Error::ConfigValidation(format!(
+                "{overlay_name} must be a mapping of config keys"
+            )))
+        }
+    }
+    Ok(Some((
+        format!("{file_name} with {overlay_name}"),
+        serde_yaml::to_string(&merged)?,
+    )))
+}
+
+/// `content` of the config file `file_name` with the questions of the files
+/// matching its `questions_files` added after its own. A question defined twice is
+/// an error.
+fn merge_questions_files(
+    template_root: &Path,
+    file_name: &str,
+    content: String,
+) -> Result<(String, String)> {
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&content)?;
+    let patterns: Vec<String> = match config.get("questions_files") {
+        None | Some(serde_yaml::Value::Null) => Vec::new(),
+        Some(patterns) => serde_yaml::from_value(patterns.clone()).map_err(|e| {
+            Error::ConfigValidation(format!(
+                "questions_files must be a list of glob patterns: {e}"
+            ))
+        })?,
+    };
+    if patterns.is_empty() {
+        return Ok((file_name.to_string(), content));
+    }
+
+    let serde_yaml::Value::Mapping(fields) = &mut config else {
+        return Ok((file_name.to_string(), content));
+    };
+    let questions = fields.entry("questions".into()).or_insert(serde_yaml::Value::Null);
+    if questions.is_null() {
+        *questions = serde_yaml::Mapping::new().into();
+    }
+    let serde_yaml::Value::Mapping(questions) = questions else {
+        return Ok((file_name.to_string(), content));
+    };
+    let mut merged: Vec<PathBuf> = Vec::new();
+    for pattern in &patterns {
+        for path in questions_files(template_root, pattern)? {
+            if merged.contains(&path) {
+                continue;
+            }
+            let name = path.to_string_lossy().replace('\\', "/");
+            let file = std::fs::read_to_string(template_root.join(&path))?;
+            let file_questions = match serde_yaml::from_str(&file) {
+                Ok(serde_yaml::Value::Mapping(file_questions)) => file_questions,
+                Ok(serde_yaml::Value::Null) => serde_yaml::Mapping::new(),
+                Ok(_) => {
+                    return Err(Error::ConfigValidation(format!(
+                        "{name} must be a mapping of question keys to questions"
+                    )))
+                }
+                Err(e) => {
+                    return Err(Error::ConfigValidation(format!("Invalid {name}: {e}")))
+                }
+            };
+            for (key, question) in file_questions {
+                if questions.contains_key(&key) {
+                    return Err(Error::ConfigValidation(format!(
+                        "question '{}' of {name} is already defined",
+                        key.as_str().unwrap_or_default()
+                    )));
+                }
+                questions.insert(key, question);
+            }
+            merged.push(path);
+        }
+    }
+    Ok((
+        format!("{file_name} with {}", patterns.join(", ")),
+        serde_yaml::to_string(&config)?,
+    ))
+}
+
+/// The files under `template_root` matching the glob `pattern`, relative to
+/// `template_root` and sorted.
+fn questions_files(template_root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let relative = Path::new(pattern);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(Error::ConfigValidation(format!(
+            "questions_files pattern '{pattern}' must stay inside the template"
+        )));
+    }
+    let matcher = GlobBuilder::new(pattern.trim_start_matches("./"))
+        .literal_separator(true)
+        .build()
+        .map_err(|e| {
+            Error::ConfigValidation(format!("Invalid questions_files pattern: {e}"))
+        })?
+        .compile_matcher();
+    // Walk from the longest leading part of the pattern without glob characters.
+    let base: PathBuf = relative
+        .components()
+        .take_while(|c| !c.as_os_str().to_string_lossy().contains(['*', '?', '[', '{']))
+        .collect();
+    let mut files: Vec<PathBuf> = WalkDir::new(template_root.join(&base))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            entry.path().strip_prefix(template_root).ok().map(Path::to_path_buf)
+        })
+        .filter(|path| matcher.is_match(path.to_string_lossy().replace('\\', "/")))
+        .collect();
+    if files.is_empty() {
+        return Err(Error::ConfigValidation(format!(
+            "questions_files pattern '{pattern}' matches no files"
+        )));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Merges a profile `overlay` into `base`: mappings are merged key by key, with
+/// new keys added after the existing ones, a `null` removes the key, and any other
+/// value, lists included, replaces the one in `base`.
+fn merge_overlay(base: &mut serde_yaml::Value, overlay: serde_yaml::Value) {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(base), serde_yaml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                if value.is_null() {
+                    base.shift_remove(&key);
+                } else if let Some(existing) = base.get_mut(&key) {
+                    merge_overlay(existing, value);
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
 }
 
 fn get_default_template_suffix() -> String {
@@ -244,4 +1008,342 @@ questions: {}"#;
         let Config::V1(cfg) = config;
         assert_eq!(cfg.import_root, Some("/usr/local/templates".to_string()));
     }
+
+    #[test]
+    fn answer_precedence_defaults_and_parses() {
+        let raw = r#"schemaVersion: v1
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert_eq!(cfg.answer_precedence(), AnswerLayer::DEFAULT_PRECEDENCE);
+
+        let raw = r#"schemaVersion: v1
+answer_precedence: [answers, pre_hook]
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert_eq!(
+            cfg.answer_precedence(),
+            &[AnswerLayer::Answers, AnswerLayer::PreHook]
+        );
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn answer_precedence_rejects_duplicates() {
+        let raw = r#"schemaVersion: v1
+answer_precedence: [env, answers, env]
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("'env' more than once"));
+    }
+
+    #[test]
+    fn suffixes_are_parsed_and_validated() {
+        let raw = r#"schemaVersion: v1
+suffixes:
+  .baker.raw: raw
+  .baker.append: append
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert_eq!(cfg.suffixes[".baker.append"], SuffixBehavior::Append);
+        assert!(cfg.validate().is_ok());
+
+        let raw = r#"schemaVersion: v1
+suffixes:
+  .baker.j2: raw
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("already the template_suffix"));
+
+        let raw = r#"schemaVersion: v1
+suffixes:
+  raw: raw
+questions: {}"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg.validate().unwrap_err().to_string().contains("must start with '.'"));
+    }
+
+    #[test]
+    fn hook_answers_options_are_validated() {
+        let raw = r#"schemaVersion: v1
+namespace_hook_answers: true
+questions:
+  hooks:
+    type: str"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg.validate().unwrap_err().to_string().contains("'hooks' collides"));
+
+        let raw = r#"schemaVersion: v1
+hook_answers_schema: { type: 12 }"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg.validate().unwrap_err().to_string().contains("hook_answers_schema"));
+    }
+
+    #[test]
+    fn validations_must_refer_to_questions() {
+        let raw = r#"schemaVersion: v1
+questions:
+  tls_enabled:
+    type: bool
+validations:
+  - condition: "not tls_enabled or tls_cert"
+    keys: [tls_cert]"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("refers to unknown question 'tls_cert'"));
+    }
+
+    #[test]
+    fn load_validated_points_at_the_offending_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let load = |config: &str| {
+            std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+            Config::load_validated(dir.path(), &LoadOptions::default())
+                .unwrap_err()
+                .to_string()
+        };
+
+        let err = load(
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    default: \"{{ oops\"\n",
+        );
+        assert!(err.contains("invalid template in questions.name.default"), "{err}");
+        assert!(err.ends_with("(at questions.name.default, baker.yaml:5:5)"), "{err}");
+
+        let err = load("schemaVersion: v1\ntemplate_globs:\n  - '*.j2'\n  - '[bad'\n");
+        assert!(err.contains("invalid glob '[bad'"), "{err}");
+        assert!(err.ends_with("(at template_globs.1, baker.yaml:4:3)"), "{err}");
+
+        let err = load(
+            "schemaVersion: v1\nquestions:\n  admin:\n    type: bool\nvalidations:\n  - condition: \"admin and\"\n",
+        );
+        assert!(err.ends_with("(at validations.0.condition, baker.yaml:6:5)"), "{err}");
+
+        let config = "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    ask_if: \"name.startswith('a')\"\n";
+        std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+        assert!(Config::load_validated(dir.path(), &LoadOptions::default()).is_ok());
+        let err = load(&format!("{config}safe_expressions: true\n"));
+        assert!(err.contains("calls method 'startswith'"), "{err}");
+        assert!(err.ends_with("(at questions.name.ask_if, baker.yaml:5:5)"), "{err}");
+
+        let err = load("schemaVersion: v1\nquestions:\n  name:\n    type: strr\n");
+        assert!(err.contains("questions.name.type: unknown variant `strr`"), "{err}");
+        assert!(err.ends_with("at line 4 column 11"), "{err}");
+    }
+
+    #[test]
+    fn profiles_are_merged_over_the_base_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("baker.yaml"),
+            r#"schemaVersion: v1
+post_hook_runner: [sh]
+template_globs: ["*.j2"]
+questions:
+  name:
+    type: str
+    help: Name
+    default: demo
+  replicas:
+    type: str
+  debug:
+    type: bool
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("baker.prod.yaml"),
+            r#"template_globs: ["deploy/*.j2"]
+questions:
+  replicas:
+    default: "3"
+  debug: null
+  region:
+    type: str
+"#,
+        )
+        .unwrap();
+
+        let Config::V1(base) = Config::load_profile(dir.path(), None).unwrap();
+        assert_eq!(
+            base.questions.keys().collect::<Vec<_>>(),
+            ["name", "replicas", "debug"]
+        );
+
+        let options = LoadOptions { profile: Some("prod".into()), ..Default::default() };
+        let config = Config::load_validated(dir.path(), &options).unwrap();
+        assert_eq!(
+            config.questions.keys().collect::<Vec<_>>(),
+            ["name", "replicas", "region"]
+        );
+        assert_eq!(config.questions["name"].help, "Name");
+        assert_eq!(config.questions["replicas"].default, serde_json::json!("3"));
+        assert!(matches!(config.questions["replicas"].r#type, Type::Str));
+        assert_eq!(config.template_globs, ["deploy/*.j2"]);
+        assert_eq!(config.post_hook_runner, ["sh"]);
+
+        let err = Config::load_profile(dir.path(), Some("dev")).unwrap_err().to_string();
+        assert!(err.contains("baker.dev.yaml, baker.dev.yml, baker.dev.json"), "{err}");
+        assert!(Config::load_profile(dir.path(), Some("../prod")).is_err());
+
+        std::fs::write(dir.path().join("baker.prod.yaml"), "template_suffix: ''\n")
+            .unwrap();
+        let err = Config::load_validated(dir.path(), &options).unwrap_err().to_string();
+        assert!(
+            err.ends_with("(at template_suffix, baker.yaml with baker.prod.yaml)"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn questions_files_are_merged_in_file_name_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "baker.yaml",
+            "schemaVersion: v1\nstrict: true\nquestions_files: [\"questions/*.yaml\", extra.json]\nquestions:\n  name:\n    type: str\n",
+        );
+        write("questions/b.yaml", "replicas:\n  type: str\nregion:\n  type: str\n");
+        write("questions/a.yaml", "database:\n  type: bool\n");
+        write("questions/nested/c.yaml", "ignored:\n  type: str\n");
+        write("extra.json", r#"{"zone": {"type": "str"}}"#);
+
+        let config = Config::load_validated(dir.path(), &LoadOptions::default()).unwrap();
+        assert_eq!(
+            config.questions.keys().collect::<Vec<_>>(),
+            ["name", "database", "replicas", "region", "zone"]
+        );
+
+        write("questions/d.yaml", "name:\n  type: str\n");
+        let err = Config::load_profile(dir.path(), None).unwrap_err().to_string();
+        assert!(err.contains("question 'name' of questions/d.yaml is already defined"));
+
+        write("baker.yaml", "schemaVersion: v1\nquestions_files: [\"missing/*.yaml\"]\n");
+        let err = Config::load_profile(dir.path(), None).unwrap_err().to_string();
+        assert!(err.contains("'missing/*.yaml' matches no files"), "{err}");
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = "schemaVersion: v1\nqustions:\n  name:\n    type: str\n";
+        std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+        assert!(Config::load_validated(dir.path(), &LoadOptions::default())
+            .unwrap()
+            .questions
+            .is_empty());
+
+        let err = Config::load_validated(
+            dir.path(),
+            &LoadOptions { strict: true, ..Default::default() },
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains("unknown key 'qustions' (did you mean 'questions'?) (at qustions, baker.yaml:2:1)"),
+            "{err}"
+        );
+
+        std::fs::write(dir.path().join("baker.yaml"), format!("strict: true\n{config}"))
+            .unwrap();
+        assert!(Config::load_validated(dir.path(), &LoadOptions::default()).is_err());
+    }
+
+    #[test]
+    fn load_config_expands_question_types() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("baker.yaml"),
+            r#"schemaVersion: v1
+x-types:
+  port:
+    type: json
+    schema: '{"type": "integer", "minimum": 1, "maximum": 65535}'
+    default: "8080"
+  admin_port:
+    uses: port
+    help: Admin port
+questions:
+  http_port:
+    uses: port
+    help: HTTP port
+  admin:
+    uses: admin_port
+    default: "9000"
+  services:
+    type: list
+    item:
+      type: object
+      properties:
+        port:
+          uses: port
+"#,
+        )
+        .unwrap();
+
+        let Config::V1(cfg) = Config::load_config(dir.path()).unwrap();
+        assert!(cfg.validate().is_ok());
+        let http = &cfg.questions["http_port"];
+        assert!(matches!(http.r#type, Type::Json));
+        assert_eq!(http.help, "HTTP port");
+        assert_eq!(http.default, serde_json::json!("8080"));
+        assert!(http.schema.as_deref().unwrap().contains("65535"));
+        let admin = &cfg.questions["admin"];
+        assert_eq!(admin.help, "Admin port");
+        assert_eq!(admin.default, serde_json::json!("9000"));
+        let item = cfg.questions["services"].item.as_deref().unwrap();
+        assert!(item.properties["port"].schema.is_some());
+    }
+
+    #[test]
+    fn load_config_rejects_unknown_and_cyclic_question_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let load = |config: &str| {
+            std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+            Config::load_config(dir.path()).unwrap_err().to_string()
+        };
+
+        let err =
+            load("schemaVersion: v1\nx-types: {}\nquestions:\n  a:\n    uses: nope\n");
+        assert!(err.contains("question 'a' uses unknown type 'nope'"), "{err}");
+
+        let err = load(
+            "schemaVersion: v1\nx-types:\n  a: {uses: b}\n  b: {uses: a}\nquestions:\n  q:\n    uses: a\n",
+        );
+        assert!(err.contains("uses itself"), "{err}");
+    }
+
+    #[test]
+    fn object_questions_require_properties() {
+        let raw = r#"schemaVersion: v1
+questions:
+  database:
+    type: object
+    properties:
+      tls:
+        type: object"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("'database.tls' has no properties"), "{err}");
+
+        let raw = r#"schemaVersion: v1
+questions:
+  name:
+    type: str
+    properties:
+      first:
+        type: str"#;
+        let Config::V1(cfg) = serde_yaml::from_str(raw).expect("valid config");
+        assert!(cfg.validate().unwrap_err().to_string().contains("not of type object"));
+    }
 }
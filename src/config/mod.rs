@@ -4,15 +4,35 @@
 //! - `types`: Basic types and enums used throughout the config system
 //! - `question`: Question definition and rendering logic
 //! - `loader`: Configuration file loading and parsing
+//! - `user`: Per-user settings shared by all templates
+//! - `policy`: Organization policy on allowed template sources
+//! - `cookiecutter`: Loading cookiecutter templates
+//! - `cache`: Cache of validated configurations
+//! - `strict`: Rejecting unknown keys in strict mode
+//! - `expression`: The safe subset of condition expressions
 
+pub mod cache;
+pub mod cookiecutter;
+mod expression;
 pub mod loader;
+pub mod policy;
+mod position;
 pub mod question;
+mod strict;
 pub mod types;
+pub mod user;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export commonly used types for convenience
-pub use loader::{Config, ConfigV1};
+pub use cache::ConfigCache;
+pub use loader::{Config, ConfigV1, LoadOptions};
 pub use question::{IntoQuestionType, Question, QuestionRendered};
-pub use types::{QuestionType, Secret, Type, Validation};
+pub(crate) use strict::close_matches;
+pub use types::{
+    AnswerLayer, AnswerTransform, AnswersValidation, Durability, HookWorkingDir,
+    Introspector, ManifestKind, NonAsciiNames, QuestionType, Secret, SetAnswers,
+    SuffixBehavior, Type, UndefinedBehavior, Validation, WindowsPaths,
+};
+pub use user::{GitSettings, UserConfig};
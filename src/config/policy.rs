@@ -0,0 +1,198 @@
+//! Organization policy restricting which template sources may be used
+//!
+//! The system-wide policy is read from `/etc/baker/policy.yaml`
+//! (`%ProgramData%\baker\policy.yaml` on Windows). `$BAKER_POLICY` names a further
+//! policy that applies on top of it, so it can tighten the system policy but not
+//! loosen it. Missing files allow every source.
+
+use crate::error::{Error, Result};
+use crate::loader::TemplateSource;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Environment variable naming a policy applied in addition to the system one.
+pub const POLICY_ENV: &str = "BAKER_POLICY";
+
+/// Which template sources a policy allows.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Git origins templates may be cloned from, as a host (`git.acme.corp`) or a
+    /// host and path prefix (`github.com/acme`). Any origin when unset.
+    #[serde(default)]
+    pub allowed_git_origins: Option<Vec<String>>,
+    /// Whether templates may be loaded from local paths, or as a tar stream on
+    /// stdin.
+    #[serde(default = "get_default_allow")]
+    pub allow_local_paths: bool,
+    /// Whether templates may be loaded by `baker-loader-<scheme>` plugins.
+    #[serde(default = "get_default_allow")]
+    pub allow_plugins: bool,
+    /// File the policy was read from, named in denials.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl Policy {
+    /// Loads the policy from `path`, or `None` if there is no file.
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut policy: Policy = if content.trim().is_empty() {
+            serde_yaml::from_str("{}")?
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| {
+                Error::ConfigValidation(format!(
+                    "Invalid policy '{}': {e}",
+                    path.display()
+                ))
+            })?
+        };
+        policy.path = path.to_path_buf();
+        Ok(Some(policy))
+    }
+
+    /// Fails with [`Error::PolicyDenied`] unless the policy allows `source`.
+    pub fn check(&self, source: &TemplateSource) -> Result<()> {
+        let reason = match source {
+            TemplateSource::FileSystem(_) | TemplateSource::TarStream
+                if !self.allow_local_paths =>
+            {
+                "local templates are not allowed".to_string()
+            }
+            TemplateSource::Plugin(_) if !self.allow_plugins => {
+                "templates loaded by plugins are not allowed".to_string()
+            }
+            TemplateSource::Git(url) => match &self.allowed_git_origins {
+                Some(allowed) if !allowed.iter().any(|o| origin_matches(url, o)) => {
+                    format!("its origin is not one of: {}", allowed.join(", "))
+                }
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        Err(Error::PolicyDenied {
+            template: source.to_string(),
+            policy: self.path.clone(),
+            reason,
+        })
+    }
+}
+
+/// Checks `source` against the system policy and the one named by `$BAKER_POLICY`.
+pub fn check_source(source: &TemplateSource) -> Result<()> {
+    let env_path =
+        std::env::var_os(POLICY_ENV).filter(|v| !v.is_empty()).map(PathBuf::from);
+    for path in system_policy_path().into_iter().chain(env_path) {
+        if let Some(policy) = Policy::load_from(&path)? {
+            policy.check(source)?;
+        }
+    }
+    Ok(())
+}
+
+/// Location of the system-wide policy.
+pub fn system_policy_path() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("ProgramData")
+            .filter(|v| !v.is_empty())
+            .map(|dir| PathBuf::from(dir).join("baker").join("policy.yaml"))
+    } else {
+        Some(PathBuf::from("/etc/baker/policy.yaml"))
+    }
+}
+
+/// The host and path of a git URL, lowercased and without user or `.git`, e.g.
+/// `github.com/acme/template` for `git@github.com:acme/template.git`. Empty, `.`
+/// and `..` segments are resolved, so `acme/../evil` is `evil`.
+fn git_origin(url: &str) -> String {
+    let origin = match Url::parse(url) {
+        Ok(parsed) => {
+            format!("{}{}", parsed.host_str().unwrap_or_default(), parsed.path())
+        }
+        // scp-like `user@host:path`
+        Err(_) => {
+            let rest = url.split_once('@').map_or(url, |(_, rest)| rest);
+            rest.replacen(':', "/", 1)
+        }
+    };
+    let mut segments = origin.split('/');
+    let mut normalized = vec![segments.next().unwrap_or_default()];
+    for segment in segments {
+        match segment {
+            "" | "." => {}
+            // Leaving the host never matches an allowed origin.
+            ".." if normalized.len() == 1 => return String::new(),
+            ".." => {
+                normalized.pop();
+            }
+            segment => normalized.push(segment),
+        }
+    }
+    let origin = normalized.join("/");
+    origin.strip_suffix(".git").unwrap_or(&origin).to_lowercase()
+}
+
+/// Whether `url` is `allowed`, or below it at a path segment.
+fn origin_matches(url: &str, allowed: &str) -> bool {
+    let origin = git_origin(url);
+    if origin.is_empty() {
+        return false;
+    }
+    let allowed = allowed.trim_end_matches('/').to_lowercase();
+    origin == allowed
+        || origin.strip_prefix(&allowed).is_some_and(|rest| rest.starts_with('/'))
+}
+
+fn get_default_allow() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(yaml: &str) -> Policy {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        Policy::load_from(&path).unwrap().unwrap()
+    }
+
+    #[test]
+    fn allows_only_listed_git_origins() {
+        let policy = policy(
+            "allowed_git_origins: [github.com/acme, git.acme.corp]\nallow_local_paths: false\n",
+        );
+        let git = |url: &str| policy.check(&TemplateSource::Git(url.to_string()));
+        assert!(git("https://github.com/acme/api-template.git").is_ok());
+        assert!(git("git@github.com:Acme/api-template.git").is_ok());
+        assert!(git("ssh://git@git.acme.corp/platform/tpl").is_ok());
+        assert!(git("https://github.com/acme-evil/tpl").is_err());
+        assert!(git("git@github.com:acme/../evil/tpl").is_err());
+        assert!(git("ssh://git@github.com/acme/%2e%2e/evil/tpl").is_err());
+        assert!(git("git@github.com:acme/./api//tpl.git").is_ok());
+        assert!(git("git@git.acme.corp:../../github.com/evil").is_err());
+        assert!(git("https://gitlab.com/acme/tpl").is_err());
+
+        let err = policy
+            .check(&TemplateSource::FileSystem(PathBuf::from("./tpl")))
+            .unwrap_err();
+        assert!(err.to_string().contains("local templates are not allowed"));
+        assert!(policy.check(&TemplateSource::Plugin("s3://b/t".to_string())).is_ok());
+    }
+
+    #[test]
+    fn missing_or_empty_policy_allows_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Policy::load_from(&dir.path().join("policy.yaml")).unwrap().is_none());
+
+        let policy = policy("");
+        assert!(policy.check(&TemplateSource::FileSystem(PathBuf::from("."))).is_ok());
+        assert!(policy.check(&TemplateSource::Git("https://x.org/a/b".into())).is_ok());
+    }
+}
@@ -0,0 +1,134 @@
+//! Finds where a key of a YAML config is written, so that validation errors can
+//! point at a line and column.
+//!
+//! Only block style is understood: keys of flow mappings and sequences (`{...}`,
+//! `[...]`) resolve to the position of their parent.
+
+/// A non-empty, non-comment line.
+struct Line<'a> {
+    number: usize,
+    /// Indentation of the line, the `- ` of a sequence item included.
+    indent: usize,
+    /// Indentation and text of the mapping key on the line, after any `- `.
+    key_indent: usize,
+    key_text: &'a str,
+    item: bool,
+}
+
+fn lines(content: &str) -> Vec<Line<'_>> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let text = line.trim_start();
+            if text.is_empty() || text.starts_with('#') || text == "---" {
+                return None;
+            }
+            let indent = line.len() - text.len();
+            let (key_indent, key_text, item) = match text.strip_prefix("- ") {
+                Some(rest) => {
+                    let rest_trimmed = rest.trim_start();
+                    (indent + 2 + rest.len() - rest_trimmed.len(), rest_trimmed, true)
+                }
+                None => (indent, text, false),
+            };
+            Some(Line { number: index + 1, indent, key_indent, key_text, item })
+        })
+        .collect()
+}
+
+fn is_key(text: &str, key: &str) -> bool {
+    [key.to_string(), format!("\"{key}\""), format!("'{key}'")].iter().any(|quoted| {
+        text.strip_prefix(quoted.as_str())
+            .is_some_and(|rest| rest.trim_start().starts_with(':'))
+    })
+}
+
+/// The 1-based line and column of the entry at `path` in `content`, e.g.
+/// `["questions", "name", "default"]` or `["validations", "0", "condition"]`, or of
+/// the deepest part of the path that could be found.
+pub(crate) fn locate(content: &str, path: &[String]) -> Option<(usize, usize)> {
+    let lines = lines(content);
+    let mut scope = &lines[..];
+    let mut found = None;
+    for segment in path {
+        let first = scope.first()?;
+        let (position, end) =
+            if let (Ok(index), true) = (segment.parse::<usize>(), first.item) {
+                let items: Vec<usize> = scope
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| line.item && line.indent == first.indent)
+                    .map(|(at, _)| at)
+                    .collect();
+                let Some(&at) = items.get(index) else { break };
+                let end = items.get(index + 1).copied().unwrap_or(scope.len());
+                let line = &scope[at];
+                ((line.number, line.indent + 1), (at, end))
+            } else {
+                let indent = first.key_indent;
+                let Some(at) = scope.iter().position(|line| {
+                    line.key_indent == indent && is_key(line.key_text, segment)
+                }) else {
+                    break;
+                };
+                let end = scope[at + 1..]
+                    .iter()
+                    .position(|line| line.key_indent <= indent)
+                    .map_or(scope.len(), |offset| at + 1 + offset);
+                let line = &scope[at];
+                ((line.number, line.key_indent + 1), (at + 1, end))
+            };
+        found = Some(position);
+        scope = &scope[end.0..end.1];
+        if scope.is_empty() {
+            break;
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn locates_keys_and_sequence_items() {
+        let content = r#"schemaVersion: v1
+# comment
+questions:
+  name:
+    type: str
+    default: "{{ oops"
+  "port":
+    type: json
+template_globs:
+  - "*.j2"
+  - "[bad"
+validations:
+  - condition: "name"
+    keys: [name]
+  - keys:
+      - port
+    condition: "port >"
+"#;
+        assert_eq!(locate(content, &path("questions.name.default")), Some((6, 5)));
+        assert_eq!(locate(content, &path("questions.port.type")), Some((8, 5)));
+        assert_eq!(locate(content, &path("template_globs.1")), Some((11, 3)));
+        assert_eq!(locate(content, &path("validations.0.condition")), Some((13, 5)));
+        assert_eq!(locate(content, &path("validations.1.condition")), Some((17, 5)));
+        assert_eq!(locate(content, &path("validations.1.keys.0")), Some((16, 7)));
+    }
+
+    #[test]
+    fn falls_back_to_the_deepest_key_found() {
+        let content = "questions:\n  name: {type: str, default: x}\n";
+        assert_eq!(locate(content, &path("questions.name.default")), Some((2, 3)));
+        assert_eq!(locate(content, &path("questions.missing")), Some((1, 1)));
+        assert_eq!(locate(content, &path("missing")), None);
+    }
+}
@@ -1,17 +1,22 @@
 //! Question configuration and rendering logic
 
 use crate::config::types::{
-    get_default_validation, QuestionType, Secret, Type, Validation,
+    get_default_validation, QuestionType, Secret, SetAnswers, Type, Validation,
 };
+use crate::error::{Error, Result};
 use crate::renderer::TemplateRenderer;
+use indexmap::IndexMap;
 use serde::Deserialize;
 
 /// Represents a single question in the configuration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Question {
     /// Help text/prompt to display to the user
     #[serde(default)]
     pub help: String,
+    /// Long-form markdown help, shown on demand while the question is asked
+    #[serde(default)]
+    pub description: String,
     /// Type of the question (string or boolean)
     #[serde(rename = "type")]
     pub r#type: Type,
@@ -37,6 +42,18 @@ pub struct Question {
     pub schema_file: Option<String>,
     #[serde(default = "get_default_validation")]
     pub validation: Validation,
+    /// Sub-questions of an `object` question, answered into a nested object
+    #[serde(default)]
+    pub properties: IndexMap<String, Question>,
+    /// Definition of each entry of a `list` question
+    #[serde(default)]
+    pub item: Option<Box<Question>>,
+    /// Seconds after which an unanswered prompt accepts the default
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Answers set for other keys once this question is answered
+    #[serde(default)]
+    pub sets: Vec<SetAnswers>,
 }
 
 #[derive(Debug)]
@@ -44,6 +61,7 @@ pub struct QuestionRendered {
     pub ask_if: bool,
     pub default: serde_json::Value,
     pub help: String,
+    pub description: String,
     pub r#type: QuestionType,
 }
 
@@ -66,6 +84,8 @@ impl IntoQuestionType for Question {
             (Type::Bool, _) => QuestionType::Boolean,
             (Type::Json, _) => QuestionType::Json,
             (Type::Yaml, _) => QuestionType::Yaml,
+            (Type::Object, _) => QuestionType::Object,
+            (Type::List, _) => QuestionType::List,
         }
     }
 }
@@ -89,13 +109,15 @@ impl Question {
         answers: &serde_json::Value,
         engine: &dyn TemplateRenderer,
         question_type: &QuestionType,
-    ) -> serde_json::Value {
+    ) -> Result<serde_json::Value> {
         if let Some(answer) = self.prefilled_answer(question_key, answers) {
-            return answer;
+            return Ok(answer);
         }
 
-        let default = self.default.clone();
-        match question_type {
+        let default = self
+            .conditional_default(question_key, answers, engine)?
+            .unwrap_or_else(|| self.default.clone());
+        Ok(match question_type {
             QuestionType::MultipleChoice => default,
             QuestionType::Boolean => {
                 serde_json::Value::Bool(default.as_bool().unwrap_or(false))
@@ -106,7 +128,52 @@ impl Question {
             QuestionType::Json | QuestionType::Yaml => {
                 self.render_structured_default(default, answers, engine, question_type)
             }
+            QuestionType::Object if default.is_object() => default,
+            QuestionType::Object => serde_json::json!({}),
+            QuestionType::List if default.is_array() => default,
+            QuestionType::List => serde_json::json!([]),
+        })
+    }
+
+    /// Resolves a default written as `{when, value}` branches: the `value` of the
+    /// first branch whose `when` expression is true, a branch without `when`
+    /// always matching. `None` when the default is not conditional; an error when
+    /// a `when` expression cannot be evaluated.
+    fn conditional_default(
+        &self,
+        question_key: &str,
+        answers: &serde_json::Value,
+        engine: &dyn TemplateRenderer,
+    ) -> Result<Option<serde_json::Value>> {
+        let Some(branches) = self.default.as_array() else {
+            return Ok(None);
+        };
+        let is_branch = |branch: &serde_json::Value| {
+            branch.as_object().is_some_and(|b| {
+                b.contains_key("value") && b.keys().all(|k| k == "when" || k == "value")
+            })
+        };
+        if branches.is_empty() || !branches.iter().all(is_branch) {
+            return Ok(None);
+        }
+
+        for branch in branches {
+            let matches = match branch.get("when") {
+                Some(serde_json::Value::String(when)) => {
+                    engine.execute_expression(when, answers).map_err(|e| {
+                        Error::Other(anyhow::anyhow!(
+                            "Cannot evaluate `when: {when}` in the default of question '{question_key}': {e}"
+                        ))
+                    })?
+                }
+                Some(serde_json::Value::Bool(when)) => *when,
+                _ => true,
+            };
+            if matches {
+                return Ok(Some(branch["value"].clone()));
+            }
         }
+        Ok(Some(serde_json::Value::Null))
     }
 
     fn render_textual_default(
@@ -158,6 +225,19 @@ impl Question {
         engine.render(&self.help, answers, Some("help")).unwrap_or(self.help.clone())
     }
 
+    fn render_description(
+        &self,
+        answers: &serde_json::Value,
+        engine: &dyn TemplateRenderer,
+    ) -> String {
+        if self.description.is_empty() {
+            return String::new();
+        }
+        engine
+            .render(&self.description, answers, Some("description"))
+            .unwrap_or(self.description.clone())
+    }
+
     fn evaluate_condition(
         &self,
         answers: &serde_json::Value,
@@ -171,14 +251,15 @@ impl Question {
         question_key: &str,
         answers: &serde_json::Value,
         engine: &dyn TemplateRenderer,
-    ) -> QuestionRendered {
+    ) -> Result<QuestionRendered> {
         let question_type = self.question_type();
         let default =
-            self.render_default_value(question_key, answers, engine, &question_type);
+            self.render_default_value(question_key, answers, engine, &question_type)?;
         let help = self.render_help_text(answers, engine);
+        let description = self.render_description(answers, engine);
         let ask_if = self.evaluate_condition(answers, engine);
 
-        QuestionRendered { default, ask_if, help, r#type: question_type }
+        Ok(QuestionRendered { default, ask_if, help, description, r#type: question_type })
     }
 }
 
@@ -204,6 +285,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -213,7 +299,7 @@ mod tests {
         let answers = json!({ "name": "prefilled" });
         let renderer = build_renderer();
 
-        let rendered = question.render("name", &answers, &renderer);
+        let rendered = question.render("name", &answers, &renderer).unwrap();
 
         assert_eq!(rendered.default, json!("prefilled"));
         assert!(rendered.ask_if);
@@ -226,7 +312,7 @@ mod tests {
         let answers = json!({ "project": "Demo" });
         let renderer = build_renderer();
 
-        let rendered = question.render("name", &answers, &renderer);
+        let rendered = question.render("name", &answers, &renderer).unwrap();
 
         assert_eq!(rendered.default, json!("Demo"));
         assert_eq!(rendered.help, "Demo help");
@@ -238,7 +324,7 @@ mod tests {
         let answers = json!({});
         let renderer = build_renderer();
 
-        let rendered = question.render("settings", &answers, &renderer);
+        let rendered = question.render("settings", &answers, &renderer).unwrap();
 
         assert_eq!(rendered.default, json!({ "enabled": true }));
     }
@@ -249,7 +335,7 @@ mod tests {
         let answers = json!({});
         let renderer = build_renderer();
 
-        let rendered = question.render("settings", &answers, &renderer);
+        let rendered = question.render("settings", &answers, &renderer).unwrap();
 
         assert_eq!(rendered.default, json!({}));
     }
@@ -260,18 +346,56 @@ mod tests {
         let answers = json!({});
         let renderer = build_renderer();
 
-        let rendered = question.render("settings", &answers, &renderer);
+        let rendered = question.render("settings", &answers, &renderer).unwrap();
 
         assert_eq!(rendered.default, json!({ "enabled": true }));
     }
 
+    #[test]
+    fn conditional_default_uses_first_matching_branch() {
+        let mut question = base_question(
+            Type::Str,
+            json!([
+                {"when": "engine == 'postgres'", "value": "5432"},
+                {"when": "engine == 'mysql'", "value": "3306"},
+                {"value": "{{ engine }}-port"},
+            ]),
+        );
+        let renderer = build_renderer();
+
+        let render = |q: &Question, engine: &str| {
+            q.render("port", &json!({ "engine": engine }), &renderer).unwrap().default
+        };
+        assert_eq!(render(&question, "mysql"), json!("3306"));
+        assert_eq!(render(&question, "postgres"), json!("5432"));
+        assert_eq!(render(&question, "sqlite"), json!("sqlite-port"));
+
+        question.default = json!([{"when": "engine == 'mysql'", "value": "3306"}]);
+        assert_eq!(render(&question, "sqlite"), json!(""));
+
+        question.multiselect = true;
+        question.choices = vec!["a".to_string(), "b".to_string()];
+        question.default = json!(["a", "b"]);
+        assert_eq!(render(&question, "sqlite"), json!(["a", "b"]));
+    }
+
+    #[test]
+    fn conditional_default_reports_when_errors_with_the_question() {
+        let question = base_question(
+            Type::Str,
+            json!([{"when": "engine ==", "value": "5432"}, {"value": "0"}]),
+        );
+        let err = question.render("port", &json!({}), &build_renderer()).unwrap_err();
+        assert!(err.to_string().contains("question 'port'"), "{err}");
+    }
+
     #[test]
     fn boolean_defaults_are_rendered_from_value() {
         let question = base_question(Type::Bool, json!(true));
         let answers = json!({});
         let renderer = build_renderer();
 
-        let rendered = question.render("confirm", &answers, &renderer);
+        let rendered = question.render("confirm", &answers, &renderer).unwrap();
 
         assert!(rendered.default.as_bool().unwrap());
     }
@@ -0,0 +1,222 @@
+//! Strict mode for the config file: keys Baker does not know are rejected, with
+//! the closest known key as a suggestion, instead of being silently ignored.
+
+use crate::config::loader::{ConfigV1, QUESTION_TYPES_KEY};
+use crate::config::question::Question;
+use crate::config::types::{AnswersValidation, Secret, SetAnswers, Validation};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+/// A key of the config file that no struct declares.
+#[derive(Debug)]
+pub(crate) struct UnknownKey {
+    pub path: Vec<String>,
+    pub suggestion: Option<&'static str>,
+}
+
+/// Deserializer that only records the field names of the struct asked for.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("field names recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+        enum identifier ignored_any
+    }
+}
+
+/// The keys `T` reads from a mapping, as declared by its `Deserialize` derive.
+fn fields_of<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Every key of `config` (the raw config, before `x-types` are expanded) that
+/// Baker does not know.
+pub(crate) fn unknown_keys(config: &serde_json::Value) -> Vec<UnknownKey> {
+    let mut unknown = Vec::new();
+    let mut top_level = fields_of::<ConfigV1>().to_vec();
+    top_level.extend(["schemaVersion", QUESTION_TYPES_KEY]);
+    check_keys(config, &top_level, &[], &mut unknown);
+
+    for section in ["questions", QUESTION_TYPES_KEY] {
+        if let Some(questions) = config.get(section).and_then(|v| v.as_object()) {
+            for (name, question) in questions {
+                check_question(
+                    question,
+                    &mut vec![section.into(), name.clone()],
+                    &mut unknown,
+                );
+            }
+        }
+    }
+    if let Some(validations) = config.get("validations").and_then(|v| v.as_array()) {
+        for (index, validation) in validations.iter().enumerate() {
+            let path = vec!["validations".into(), index.to_string()];
+            check_keys(validation, fields_of::<AnswersValidation>(), &path, &mut unknown);
+        }
+    }
+    unknown
+}
+
+fn check_question(
+    question: &serde_json::Value,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<UnknownKey>,
+) {
+    let mut known = fields_of::<Question>().to_vec();
+    // Replaced by the x-types definition before the question is parsed.
+    known.push("uses");
+    check_keys(question, &known, path, unknown);
+
+    for (key, fields) in
+        [("validation", fields_of::<Validation>()), ("secret", fields_of::<Secret>())]
+    {
+        if let Some(value) = question.get(key) {
+            path.push(key.into());
+            check_keys(value, fields, path, unknown);
+            path.pop();
+        }
+    }
+    if let Some(sets) = question.get("sets").and_then(|v| v.as_array()) {
+        for (index, set) in sets.iter().enumerate() {
+            path.extend(["sets".into(), index.to_string()]);
+            check_keys(set, fields_of::<SetAnswers>(), path, unknown);
+            path.truncate(path.len() - 2);
+        }
+    }
+    if let Some(properties) = question.get("properties").and_then(|v| v.as_object()) {
+        for (name, property) in properties {
+            path.extend(["properties".into(), name.clone()]);
+            check_question(property, path, unknown);
+            path.truncate(path.len() - 2);
+        }
+    }
+    if let Some(item) = question.get("item") {
+        path.push("item".into());
+        check_question(item, path, unknown);
+        path.pop();
+    }
+}
+
+fn check_keys(
+    value: &serde_json::Value,
+    known: &[&'static str],
+    path: &[String],
+    unknown: &mut Vec<UnknownKey>,
+) {
+    let Some(fields) = value.as_object() else {
+        return;
+    };
+    for key in fields.keys().filter(|key| !known.contains(&key.as_str())) {
+        let mut key_path = path.to_vec();
+        key_path.push(key.clone());
+        unknown.push(UnknownKey { path: key_path, suggestion: suggest(key, known) });
+    }
+}
+
+/// The known key closest to `key`, if it is close enough to be a typo.
+fn suggest(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    close_matches(key, known.iter().copied()).into_iter().next()
+}
+
+/// The `candidates` close enough to `key` to be a typo of it, closest first.
+pub(crate) fn close_matches<'a>(
+    key: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<&'a str> {
+    let length = key.chars().count();
+    let max_distance = (length / 3).clamp(2, 3);
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance && *distance < length)
+        .collect();
+    matches.sort_by_key(|(distance, _)| *distance);
+    matches.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let config: serde_json::Value = serde_yaml::from_str(
+            r#"schemaVersion: v1
+qustions: {}
+questions:
+  name:
+    type: str
+    hepl: Name
+    uses: base
+    validation:
+      conditon: "true"
+  tags:
+    type: list
+    item:
+      type: str
+      frobnicate: true
+validations:
+  - condition: "true"
+    key: [name]
+"#,
+        )
+        .unwrap();
+        let found: Vec<(String, Option<&str>)> = unknown_keys(&config)
+            .into_iter()
+            .map(|key| (key.path.join("."), key.suggestion))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("qustions".to_string(), Some("questions")),
+                ("questions.name.hepl".to_string(), Some("help")),
+                ("questions.name.validation.conditon".to_string(), Some("condition")),
+                ("questions.tags.item.frobnicate".to_string(), None),
+                ("validations.0.key".to_string(), Some("keys")),
+            ]
+        );
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("qustions", "questions"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -22,6 +22,11 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
@@ -29,8 +34,8 @@ mod config_tests {
             "prev_answer": "World"
         });
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
-        let QuestionRendered { ask_if, help, default, r#type } = result;
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
+        let QuestionRendered { ask_if, help, default, r#type, .. } = result;
         assert!(!ask_if);
         assert_eq!(help, "Hello, World".to_string());
         assert_eq!(default, serde_json::Value::Bool(false));
@@ -56,6 +61,11 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
@@ -63,8 +73,8 @@ mod config_tests {
             "question": "Please select your stack"
         });
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
-        let QuestionRendered { ask_if, help, default, r#type } = result;
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
+        let QuestionRendered { ask_if, help, default, r#type, .. } = result;
         assert!(ask_if);
         assert_eq!(help, "Please select your stack".to_string());
         assert_eq!(default, json!(vec!["Python".to_string(), "Django".to_string()]));
@@ -84,12 +94,17 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
         let answers = json!({});
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
         let QuestionRendered { ask_if, r#type, .. } = result;
         assert!(ask_if);
         assert_eq!(r#type, QuestionType::Text);
@@ -108,12 +123,17 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
         let answers = json!({"answer": "Here is an answer"});
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
         let QuestionRendered { ask_if, r#type, .. } = result;
         assert!(!ask_if);
         assert_eq!(r#type, QuestionType::Text);
@@ -132,12 +152,17 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
         let answers = json!({"question1": "This is a default value for the question1"});
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
         let QuestionRendered { ask_if, r#type, default, .. } = result;
         assert!(!ask_if);
         assert_eq!(r#type, QuestionType::Text);
@@ -157,12 +182,17 @@ mod config_tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let engine = get_template_engine();
 
         let answers = json!({});
 
-        let result = question.render("question1".as_ref(), &answers, &engine);
+        let result = question.render("question1".as_ref(), &answers, &engine).unwrap();
         let QuestionRendered { ask_if, r#type, default, .. } = result;
         assert!(ask_if);
         assert_eq!(r#type, QuestionType::Text);
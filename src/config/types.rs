@@ -1,10 +1,11 @@
 //! Basic types and enums for configuration
 
 use crate::constants::validation;
+use indexmap::IndexMap;
 use serde::Deserialize;
 
 /// Type of question to be presented to the user
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Type {
     /// String input question type
@@ -15,9 +16,13 @@ pub enum Type {
     Json,
     /// YAML structured input type
     Yaml,
+    /// Nested object asked as a sub-form of its `properties`
+    Object,
+    /// List of `item` answers, added one at a time
+    List,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Secret {
     /// Whether the secret should have confirmation
     #[serde(default)]
@@ -26,7 +31,7 @@ pub struct Secret {
     pub mistmatch_err: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Validation {
     #[serde(default = "get_default_condition")]
     pub condition: String,
@@ -34,6 +39,181 @@ pub struct Validation {
     pub error_message: String,
 }
 
+/// A rule over the complete answers, checked once every question is answered.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnswersValidation {
+    /// Expression over all answers that must be true.
+    pub condition: String,
+    /// Message shown when the condition is false; can use the answers.
+    #[serde(default = "get_default_error_message")]
+    pub error_message: String,
+    /// Questions asked again when the rule fails in an interactive run.
+    #[serde(default)]
+    pub keys: Vec<String>,
+}
+
+/// Answers a question sets for other keys once it is answered, e.g. the port of
+/// the chosen database. Keys that already have an answer keep it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetAnswers {
+    /// Expression over the answers so far; the values are set when it is true.
+    /// Always true when empty.
+    #[serde(default)]
+    pub when: String,
+    /// Values by key. Strings are rendered as templates.
+    pub values: IndexMap<String, serde_json::Value>,
+}
+
+/// A source of predefined answers. Sources are merged in the order given by
+/// `answer_precedence`, so later sources override earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerLayer {
+    /// JSON printed by the pre-hook.
+    PreHook,
+    /// `BAKER_ANSWER_<KEY>` environment variables.
+    Env,
+    /// The `--answers-file` file.
+    AnswersFile,
+    /// The `--answers` argument.
+    Answers,
+    /// `--set key=value` arguments.
+    Set,
+}
+
+impl AnswerLayer {
+    /// Default merge order, lowest precedence first.
+    pub const DEFAULT_PRECEDENCE: &'static [AnswerLayer] = &[
+        AnswerLayer::PreHook,
+        AnswerLayer::Env,
+        AnswerLayer::AnswersFile,
+        AnswerLayer::Answers,
+        AnswerLayer::Set,
+    ];
+}
+
+impl std::fmt::Display for AnswerLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AnswerLayer::PreHook => "pre-hook",
+            AnswerLayer::Env => "env",
+            AnswerLayer::AnswersFile => "--answers-file",
+            AnswerLayer::Answers => "--answers",
+            AnswerLayer::Set => "--set",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// What the processor does with a file ending in one of the `suffixes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuffixBehavior {
+    /// Render the file, like files ending in `template_suffix`.
+    Render,
+    /// Copy the file verbatim, without rendering it.
+    Raw,
+    /// Render the file and append it to the existing output file.
+    Append,
+}
+
+/// A built-in rewrite of the answers, applied after they are collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerTransform {
+    /// Strip leading and trailing whitespace from string answers.
+    TrimStrings,
+    /// Lowercase the keys of answer objects.
+    LowercaseKeys,
+    /// Replace `${VAR}` in string answers with the environment variable's value.
+    ExpandEnv,
+}
+
+/// Reads values of the project a template is applied to, such as its name and
+/// version, into the context of the questions.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Introspector {
+    /// A built-in reader of a well-known manifest.
+    Manifest(ManifestKind),
+    /// A JSON, YAML or TOML file, relative to the output directory, read whole.
+    File { file: String },
+}
+
+/// Manifests read by a built-in [`Introspector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestKind {
+    /// `Cargo.toml`, from its `[package]` table.
+    Cargo,
+    /// `package.json`.
+    Npm,
+    /// `pyproject.toml`, from its `[project]` table, or `[tool.poetry]`.
+    Pyproject,
+}
+
+/// How generated files are written to the output directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Durability {
+    /// Write a temporary file next to the target and rename it into place, so an
+    /// interrupted run never leaves a truncated file.
+    #[default]
+    Atomic,
+    /// Like `atomic`, and flush the file and its directory to disk.
+    Fsync,
+    /// Write the target in place.
+    Direct,
+}
+
+/// What happens on Windows to rendered paths Windows cannot create, such as
+/// `nul.txt` or `a:b`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowsPaths {
+    /// Fail with the offending name.
+    #[default]
+    Reject,
+    /// Replace invalid characters and suffix reserved names with `_`.
+    Escape,
+}
+
+/// What happens to rendered file names that are not ASCII.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonAsciiNames {
+    /// Keep them, normalized to NFC.
+    #[default]
+    Allow,
+    /// Replace them with an ASCII spelling, e.g. `café` with `cafe`.
+    Transliterate,
+    /// Fail with the offending name.
+    Reject,
+}
+
+/// How templates treat variables that are not defined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UndefinedBehavior {
+    /// Render them as nothing; only attributes of them are errors.
+    #[default]
+    Lenient,
+    /// Fail when they are printed or iterated, but let `{% if %}` test them.
+    SemiStrict,
+    /// Fail on any use but the `defined` and `undefined` tests.
+    Strict,
+}
+
+/// Directory a hook process is started in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookWorkingDir {
+    /// The template root.
+    Template,
+    /// The output directory, created first if it does not exist yet.
+    Output,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum QuestionType {
     MultipleChoice,
@@ -42,6 +222,8 @@ pub enum QuestionType {
     Boolean,
     Json,
     Yaml,
+    Object,
+    List,
 }
 
 fn get_default_error_message() -> String {
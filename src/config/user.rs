@@ -0,0 +1,318 @@
+//! Per-user settings that apply to every template
+//!
+//! Read from `$BAKER_CONFIG` if set, otherwise from `baker/config.yaml` in the
+//! user's config directory (`$XDG_CONFIG_HOME`, `~/.config`, or `%APPDATA%` on
+//! Windows). A missing file means defaults.
+
+use crate::constants::DEFAULT_COPY_BUFFER_SIZE;
+use crate::error::{Error, Result};
+use crate::loader::fetch::{FetchBudget, FetchCoordinator};
+use crate::prompt::theme::PromptTheme;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable overriding the user config file location.
+pub const USER_CONFIG_ENV: &str = "BAKER_CONFIG";
+
+/// Settings from the user's Baker config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// How git templates are fetched.
+    #[serde(default)]
+    pub git: GitSettings,
+    /// Short names for template sources, accepted wherever a template is.
+    #[serde(default)]
+    pub aliases: IndexMap<String, String>,
+    /// Look of the terminal prompts.
+    #[serde(default)]
+    pub prompt_theme: PromptTheme,
+    /// How files that are not rendered are copied.
+    #[serde(default)]
+    pub copy: CopySettings,
+}
+
+/// Settings for copying the files of a template that are not rendered.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CopySettings {
+    /// Size in bytes of the buffer copied files are read with, when they are
+    /// hashed and where the platform has no faster way to copy them.
+    #[serde(default = "get_default_copy_buffer_size")]
+    pub buffer_size: usize,
+}
+
+impl Default for CopySettings {
+    fn default() -> Self {
+        Self { buffer_size: get_default_copy_buffer_size() }
+    }
+}
+
+/// Retry and mirror settings for git template fetching.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitSettings {
+    /// How many times a clone failing with a network error is retried.
+    #[serde(default = "get_default_retries")]
+    pub retries: u32,
+    /// Delay before the first retry in milliseconds; doubled on every further retry.
+    #[serde(default = "get_default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// Mirror URLs tried in order when a clone fails, keyed by URL prefix. The
+    /// matching prefix of the template URL is replaced by each mirror.
+    #[serde(default)]
+    pub mirrors: IndexMap<String, Vec<String>>,
+    /// How many submodules are fetched at the same time.
+    #[serde(default = "get_default_parallelism")]
+    pub parallelism: usize,
+    /// Seconds to wait for all submodules to be fetched; no limit when unset.
+    #[serde(default)]
+    pub fetch_timeout_secs: Option<u64>,
+    /// Bytes all submodules may download together; no limit when unset.
+    #[serde(default)]
+    pub fetch_max_bytes: Option<u64>,
+}
+
+impl Default for GitSettings {
+    fn default() -> Self {
+        Self {
+            retries: get_default_retries(),
+            retry_delay_ms: get_default_retry_delay_ms(),
+            mirrors: IndexMap::new(),
+            parallelism: get_default_parallelism(),
+            fetch_timeout_secs: None,
+            fetch_max_bytes: None,
+        }
+    }
+}
+
+impl GitSettings {
+    /// URLs to try for `url`: the URL itself, then its mirrors. The longest
+    /// matching prefix wins.
+    pub fn candidates(&self, url: &str) -> Vec<String> {
+        let mut candidates = vec![url.to_string()];
+        let best = self
+            .mirrors
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len());
+        if let Some((prefix, mirrors)) = best {
+            let rest = &url[prefix.len()..];
+            candidates.extend(mirrors.iter().map(|mirror| format!("{mirror}{rest}")));
+        }
+        candidates
+    }
+
+    /// Coordinator for fetching submodules with these limits. The time limit
+    /// starts when this is called.
+    pub fn fetch_coordinator(&self) -> FetchCoordinator {
+        FetchCoordinator::new(
+            self.parallelism,
+            FetchBudget::new(
+                self.fetch_timeout_secs.map(Duration::from_secs),
+                self.fetch_max_bytes,
+            ),
+        )
+    }
+
+    /// Delay before retry number `attempt` (starting at 1).
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(self.retry_delay_ms.saturating_mul(factor))
+    }
+}
+
+impl UserConfig {
+    /// The source `template` stands for: the alias target when `template` is the
+    /// name of an alias, otherwise `template` itself. Targets are not resolved again.
+    pub fn resolve_alias<'a>(&'a self, template: &'a str) -> &'a str {
+        match self.aliases.get(template) {
+            Some(target) => {
+                log::debug!("Template alias '{template}' resolves to '{target}'");
+                target
+            }
+            None => template,
+        }
+    }
+
+    /// Loads the user config from its default location.
+    pub fn load() -> Result<Self> {
+        match user_config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the user config from `path`, returning defaults if it does not exist.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_yaml::from_str(&content).map_err(|e| {
+            Error::ConfigValidation(format!(
+                "Invalid user config '{}': {e}",
+                path.display()
+            ))
+        })
+    }
+}
+
+/// The platform config directory: `$XDG_CONFIG_HOME`, `~/.config`, or `%APPDATA%`.
+pub fn config_dir() -> Option<PathBuf> {
+    let non_empty = |var: &str| std::env::var_os(var).filter(|v| !v.is_empty());
+    non_empty("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| {
+        if cfg!(windows) {
+            non_empty("APPDATA").map(PathBuf::from)
+        } else {
+            non_empty("HOME").map(|home| PathBuf::from(home).join(".config"))
+        }
+    })
+}
+
+/// The platform data directory: `$XDG_DATA_HOME`, `~/.local/share`, or
+/// `%LOCALAPPDATA%`.
+pub fn data_dir() -> Option<PathBuf> {
+    let non_empty = |var: &str| std::env::var_os(var).filter(|v| !v.is_empty());
+    non_empty("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+        if cfg!(windows) {
+            non_empty("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            non_empty("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        }
+    })
+}
+
+/// The platform cache directory: `$XDG_CACHE_HOME`, `~/.cache`, or
+/// `%LOCALAPPDATA%`.
+pub fn cache_dir() -> Option<PathBuf> {
+    let non_empty = |var: &str| std::env::var_os(var).filter(|v| !v.is_empty());
+    non_empty("XDG_CACHE_HOME").map(PathBuf::from).or_else(|| {
+        if cfg!(windows) {
+            non_empty("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            non_empty("HOME").map(|home| PathBuf::from(home).join(".cache"))
+        }
+    })
+}
+
+/// Location of the user config file.
+pub fn user_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(USER_CONFIG_ENV).filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    config_dir().map(|dir| dir.join("baker").join("config.yaml"))
+}
+
+fn get_default_retries() -> u32 {
+    2
+}
+
+fn get_default_retry_delay_ms() -> u64 {
+    500
+}
+
+fn get_default_parallelism() -> usize {
+    4
+}
+
+fn get_default_copy_buffer_size() -> usize {
+    DEFAULT_COPY_BUFFER_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = UserConfig::load_from(&dir.path().join("config.yaml")).unwrap();
+        assert_eq!(config.git.retries, 2);
+        assert!(config.git.mirrors.is_empty());
+        assert_eq!(config.git.parallelism, 4);
+        assert_eq!(config.git.fetch_timeout_secs, None);
+        assert_eq!(config.git.fetch_max_bytes, None);
+        assert_eq!(config.copy.buffer_size, DEFAULT_COPY_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn parses_copy_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "copy:\n  buffer_size: 1048576\n").unwrap();
+        assert_eq!(UserConfig::load_from(&path).unwrap().copy.buffer_size, 1024 * 1024);
+    }
+
+    #[test]
+    fn parses_git_settings_and_resolves_mirrors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            r#"git:
+  retries: 4
+  retry_delay_ms: 100
+  mirrors:
+    "https://github.com/": ["https://mirror.example.com/github/"]
+    "https://github.com/acme/": ["https://git.acme.corp/", "ssh://backup.acme.corp/"]
+"#,
+        )
+        .unwrap();
+
+        let git = UserConfig::load_from(&path).unwrap().git;
+        assert_eq!(git.retries, 4);
+        assert_eq!(
+            git.candidates("https://github.com/acme/tpl.git"),
+            [
+                "https://github.com/acme/tpl.git",
+                "https://git.acme.corp/tpl.git",
+                "ssh://backup.acme.corp/tpl.git",
+            ]
+        );
+        assert_eq!(
+            git.candidates("https://github.com/other/tpl"),
+            [
+                "https://github.com/other/tpl",
+                "https://mirror.example.com/github/other/tpl"
+            ]
+        );
+        assert_eq!(git.candidates("https://gitlab.com/x/y"), ["https://gitlab.com/x/y"]);
+
+        assert_eq!(git.retry_delay(1), Duration::from_millis(100));
+        assert_eq!(git.retry_delay(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn resolves_aliases_without_chaining() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "aliases:\n  api: https://github.com/acme/api-template.git\n  svc: api\n",
+        )
+        .unwrap();
+
+        let config = UserConfig::load_from(&path).unwrap();
+        assert_eq!(
+            config.resolve_alias("api"),
+            "https://github.com/acme/api-template.git"
+        );
+        assert_eq!(config.resolve_alias("svc"), "api");
+        assert_eq!(config.resolve_alias("./api"), "./api");
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "git:\n  retry: 3\n").unwrap();
+        let err = UserConfig::load_from(&path).unwrap_err();
+        assert!(err.to_string().contains("Invalid user config"));
+    }
+}
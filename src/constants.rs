@@ -18,6 +18,9 @@ pub const DEFAULT_PRE_HOOK: &str = "pre";
 /// Default post-hook filename
 pub const DEFAULT_POST_HOOK: &str = "post";
 
+/// Default size in bytes of the buffer copied files are read with
+pub const DEFAULT_COPY_BUFFER_SIZE: usize = 256 * 1024;
+
 /// Ignore file name
 pub const IGNORE_FILE: &str = ".bakerignore";
 
@@ -27,6 +30,18 @@ pub const DEFAULT_GENERATED_FILE_NAME: &str = ".baker-generated.yaml";
 /// STDIN indicator for CLI arguments
 pub const STDIN_INDICATOR: &str = "-";
 
+/// Prefix of environment variables providing answers, e.g. `BAKER_ANSWER_PROJECT_NAME`
+pub const ENV_ANSWER_PREFIX: &str = "BAKER_ANSWER_";
+
+/// Prefix marking an answer as a secret reference, e.g. `!secret env:MY_TOKEN`
+pub const SECRET_REFERENCE_PREFIX: &str = "!secret ";
+
+/// Executable name prefix used to resolve secrets for non built-in providers
+pub const SECRET_EXEC_PREFIX: &str = "baker-secret-";
+
+/// First line of an ASCII-armored age file
+pub const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
 /// JSON Schema validation messages
 pub mod validation {
     pub const INVALID_ANSWER: &str = "Invalid answer";
@@ -34,10 +49,26 @@ pub mod validation {
     pub const DEFAULT_CONDITION: &str = "true";
 }
 
-/// Exit codes
+/// Exit codes. They are part of the command line contract, so scripts can branch on
+/// the kind of failure; a code never changes meaning.
 pub mod exit_codes {
     pub const SUCCESS: i32 = 0;
+    /// Any failure without a more specific code
     pub const FAILURE: i32 = 1;
+    /// Invalid command line arguments
+    pub const USAGE: i32 = 2;
+    /// Invalid or missing template configuration
+    pub const CONFIG: i32 = 3;
+    /// A template file failed to render or write
+    pub const RENDER: i32 = 4;
+    /// A hook failed
+    pub const HOOK: i32 = 5;
+    /// An answer failed its validation
+    pub const VALIDATION: i32 = 6;
+    /// A network operation failed
+    pub const NETWORK: i32 = 7;
+    /// The user aborted, e.g. with Ctrl-C (128 + SIGINT)
+    pub const ABORTED: i32 = 130;
 }
 
 /// Verbosity levels
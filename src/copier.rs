@@ -0,0 +1,209 @@
+//! Copier-compatible answers file, `.copier-answers.yml`.
+//!
+//! Templates opt in with `copier_answers: true`; Baker then writes the file next to
+//! its own metadata, so copier can keep updating the project. `baker update` reads
+//! the file when a project has no Baker metadata yet, so projects generated by
+//! copier can be updated by Baker. Copier records a tag, or `git describe` output,
+//! as `_commit`; it is kept as the tag and resolved to a commit id once the
+//! template is cloned, see [`resolve_commit`].
+
+use crate::{
+    error::{Error, Result},
+    generated::BakerGenerated,
+    loader::{git::GitLoader, TemplateSourceInfo},
+};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Name of copier's answers file.
+pub const COPIER_ANSWERS_FILE: &str = ".copier-answers.yml";
+
+/// Header copier writes at the top of the answers file.
+const HEADER: &str =
+    "# Changes here will be overwritten by Copier; NEVER EDIT MANUALLY\n";
+
+/// Writes the source and answers of `data` to `<dir>/.copier-answers.yml`.
+pub fn write(dir: &Path, data: &BakerGenerated) -> Result<()> {
+    let (src_path, commit) = match &data.template {
+        TemplateSourceInfo::Git { url, commit, tag } => {
+            (url.clone(), Some(tag.clone().unwrap_or_else(|| commit.clone())))
+        }
+        TemplateSourceInfo::Filesystem { path, .. } => (path.clone(), None),
+        TemplateSourceInfo::Plugin { url, .. } => (url.clone(), None),
+        TemplateSourceInfo::Tar { .. } => ("-".to_string(), None),
+    };
+    let mut answers = Map::new();
+    if let Some(commit) = commit {
+        answers.insert("_commit".to_string(), Value::String(commit));
+    }
+    answers.insert("_src_path".to_string(), Value::String(src_path));
+    if let Some(user_answers) = data.answers.as_object() {
+        answers.extend(user_answers.clone());
+    }
+    let yaml = serde_yaml::to_string(&Value::Object(answers))?;
+    std::fs::write(dir.join(COPIER_ANSWERS_FILE), format!("{HEADER}{yaml}"))?;
+    Ok(())
+}
+
+/// Reads `<dir>/.copier-answers.yml` as Baker metadata, or `None` when it is absent.
+/// Copier's `_`-prefixed settings are dropped from the answers.
+pub fn read(dir: &Path) -> Result<Option<BakerGenerated>> {
+    let path = dir.join(COPIER_ANSWERS_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    let Value::Object(mut answers) = serde_yaml::from_str(&content)? else {
+        return Err(invalid(&path, "expected a mapping"));
+    };
+    let src_path = match answers.remove("_src_path") {
+        Some(Value::String(src_path)) => expand_shortcut(&src_path),
+        _ => return Err(invalid(&path, "missing _src_path")),
+    };
+    let commit = answers.remove("_commit").and_then(|c| c.as_str().map(str::to_string));
+    answers.retain(|key, _| !key.starts_with('_'));
+
+    let template = if GitLoader::<&str>::is_git_url(&src_path) {
+        let (commit, tag) = match commit {
+            Some(commit) if is_commit_id(&commit) => (commit, None),
+            tag => (String::new(), tag),
+        };
+        TemplateSourceInfo::Git { url: src_path, commit, tag }
+    } else {
+        TemplateSourceInfo::Filesystem { path: src_path, hash: String::new() }
+    };
+    Ok(Some(BakerGenerated::new(template, Value::Object(answers))))
+}
+
+/// Fills in the commit id of a git `source` read by [`read`], which only has the
+/// tag copier recorded, by resolving the tag in the cloned template `repo`.
+/// Other sources are left as they are.
+pub fn resolve_commit(
+    repo: &git2::Repository,
+    source: &mut TemplateSourceInfo,
+) -> Result<()> {
+    let TemplateSourceInfo::Git { commit, tag: Some(tag), .. } = source else {
+        return Ok(());
+    };
+    if commit.is_empty() {
+        let resolved =
+            repo.revparse_single(tag).and_then(|object| object.peel_to_commit());
+        *commit = resolved
+            .map_err(|e| {
+                Error::Other(anyhow::anyhow!(
+                    "Cannot find '{tag}', the _commit of {COPIER_ANSWERS_FILE}, in the template: {}",
+                    e.message()
+                ))
+            })?
+            .id()
+            .to_string();
+    }
+    Ok(())
+}
+
+/// Whether `commit` is a full commit id rather than a tag.
+fn is_commit_id(commit: &str) -> bool {
+    commit.len() == 40 && commit.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Expands copier's `gh:` and `gl:` source shortcuts.
+fn expand_shortcut(src_path: &str) -> String {
+    if let Some(repo) = src_path.strip_prefix("gh:") {
+        format!("https://github.com/{repo}.git")
+    } else if let Some(repo) = src_path.strip_prefix("gl:") {
+        format!("https://gitlab.com/{repo}.git")
+    } else {
+        src_path.to_string()
+    }
+}
+
+fn invalid(path: &Path, reason: &str) -> Error {
+    Error::Other(anyhow::anyhow!(
+        "Invalid copier answers file '{}': {reason}",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_source_and_answers() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = BakerGenerated::new(
+            TemplateSourceInfo::Git {
+                url: "https://github.com/acme/template.git".to_string(),
+                commit: "abc123".to_string(),
+                tag: Some("v1.2.0".to_string()),
+            },
+            json!({"project_name": "demo", "use_docker": true}),
+        );
+
+        write(dir.path(), &data).unwrap();
+        let content =
+            std::fs::read_to_string(dir.path().join(COPIER_ANSWERS_FILE)).unwrap();
+        assert!(content.starts_with(HEADER));
+        assert!(content.contains(
+            "_commit: v1.2.0\n_src_path: https://github.com/acme/template.git\n"
+        ));
+
+        let read_back = read(dir.path()).unwrap().unwrap();
+        assert_eq!(
+            read_back.template,
+            TemplateSourceInfo::Git {
+                url: "https://github.com/acme/template.git".to_string(),
+                commit: String::new(),
+                tag: Some("v1.2.0".to_string()),
+            }
+        );
+        assert_eq!(read_back.answers, data.answers);
+    }
+
+    #[test]
+    fn reads_copier_shortcuts_and_drops_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(COPIER_ANSWERS_FILE),
+            "_src_path: gh:acme/template\n_commit: v2\n_skip_if_exists: []\nname: demo\n",
+        )
+        .unwrap();
+
+        let data = read(dir.path()).unwrap().unwrap();
+
+        assert_eq!(
+            data.template,
+            TemplateSourceInfo::Git {
+                url: "https://github.com/acme/template.git".to_string(),
+                commit: String::new(),
+                tag: Some("v2".to_string()),
+            }
+        );
+        assert_eq!(data.answers, json!({"name": "demo"}));
+        assert!(read(&dir.path().join("missing")).unwrap().is_none());
+    }
+
+    #[test]
+    fn resolves_the_recorded_tag_to_a_commit_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let id = repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[]).unwrap();
+        let object = repo.find_object(id, None).unwrap();
+        repo.tag("v2", &object, &sig, "release", false).unwrap();
+        let source = |commit: &str, tag: &str| TemplateSourceInfo::Git {
+            url: "https://github.com/acme/template.git".to_string(),
+            commit: commit.to_string(),
+            tag: Some(tag.to_string()),
+        };
+
+        let mut tagged = source("", "v2");
+        resolve_commit(&repo, &mut tagged).unwrap();
+        assert_eq!(tagged, source(&id.to_string(), "v2"));
+
+        let err = resolve_commit(&repo, &mut source("", "v3")).unwrap_err();
+        assert!(err.to_string().starts_with("Cannot find 'v3'"), "{err}");
+    }
+}
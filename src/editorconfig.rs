@@ -0,0 +1,436 @@
+//! Applies the `.editorconfig` at the template root to rendered text files, so the
+//! generated project follows the indentation, line endings and charset the
+//! template declares.
+//!
+//! Sections match output paths relative to the output directory. Later sections
+//! override earlier ones, as in editors. Numeric ranges like `{1..3}` are not
+//! supported. Files where leading whitespace carries meaning, see
+//! [`keeps_indentation`], are never re-indented.
+
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use std::{path::Path, sync::LazyLock};
+
+/// Name of the file read from the template root.
+pub const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Space,
+    Tab,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    Utf8,
+    Utf8Bom,
+    Latin1,
+    Utf16Be,
+    Utf16Le,
+}
+
+/// The properties Baker applies, merged from every section matching a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Properties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<usize>,
+    pub tab_width: Option<usize>,
+    pub end_of_line: Option<EndOfLine>,
+    pub charset: Option<Charset>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+#[derive(Debug)]
+struct Section {
+    matcher: GlobMatcher,
+    properties: Properties,
+}
+
+/// The sections of an `.editorconfig` file.
+#[derive(Debug)]
+pub struct EditorConfig {
+    sections: Vec<Section>,
+}
+
+impl EditorConfig {
+    /// Reads `.editorconfig` from `template_root`, if there is one.
+    pub fn load(template_root: &Path) -> Option<Self> {
+        let path = template_root.join(EDITORCONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Some(Self::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::warn!("Ignoring '{}': {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Parses the content of an `.editorconfig` file. Unknown properties and
+    /// values are ignored; sections with an invalid glob are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut sections = Vec::new();
+        let mut current: Option<Section> = None;
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(pattern) =
+                line.strip_prefix('[').and_then(|l| l.strip_suffix(']'))
+            {
+                sections.extend(current.take());
+                match matcher(pattern) {
+                    Ok(matcher) => {
+                        current =
+                            Some(Section { matcher, properties: Properties::default() })
+                    }
+                    Err(e) => log::warn!(
+                        "Skipping {EDITORCONFIG_FILE} section [{pattern}]: {e}"
+                    ),
+                }
+                continue;
+            }
+            let (Some(section), Some((key, value))) =
+                (current.as_mut(), line.split_once('='))
+            else {
+                // Preamble such as `root = true`, or a section that was skipped.
+                continue;
+            };
+            section
+                .properties
+                .set(&key.trim().to_lowercase(), &value.trim().to_lowercase());
+        }
+        sections.extend(current);
+        Self { sections }
+    }
+
+    /// The properties for the output file at `path`, relative to the output root.
+    pub fn properties(&self, path: &Path) -> Properties {
+        let mut properties = Properties::default();
+        for section in self.sections.iter().filter(|s| s.matcher.is_match(path)) {
+            properties.merge(&section.properties);
+        }
+        properties
+    }
+}
+
+/// A shell or Ruby here-document, like `<<EOF`, `<<-'EOF'` or `<<~SQL`.
+static HEREDOC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<<[-~]?\s*(["']?)[A-Z_][A-Z0-9_]*["']?"#).expect("valid heredoc regex")
+});
+
+/// Whether re-indenting the file at `path` with `content` could change its
+/// meaning: makefiles need tabs before recipes, and here-documents are taken
+/// literally (or have their tabs stripped with `<<-`).
+pub fn keeps_indentation(path: &Path, content: &str) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let makefile = matches!(name, "Makefile" | "makefile" | "GNUmakefile")
+        || name.ends_with(".mk")
+        || name.ends_with(".mak");
+    makefile || HEREDOC.is_match(content)
+}
+
+/// Sections without a `/` match files in any directory; the others are relative to
+/// the root, like `.editorconfig` globs.
+fn matcher(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.contains('/') => pattern.to_string(),
+        None => format!("**/{pattern}"),
+    };
+    Ok(GlobBuilder::new(&pattern).literal_separator(true).build()?.compile_matcher())
+}
+
+impl Properties {
+    fn set(&mut self, key: &str, value: &str) {
+        let size = value.parse::<usize>().ok().filter(|size| *size > 0);
+        let flag = match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        match key {
+            "indent_style" => {
+                self.indent_style = match value {
+                    "space" => Some(IndentStyle::Space),
+                    "tab" => Some(IndentStyle::Tab),
+                    _ => None,
+                }
+            }
+            // `indent_size = tab` means the tab width.
+            "indent_size" => self.indent_size = size,
+            "tab_width" => self.tab_width = size,
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(EndOfLine::Lf),
+                    "crlf" => Some(EndOfLine::Crlf),
+                    "cr" => Some(EndOfLine::Cr),
+                    _ => None,
+                }
+            }
+            "charset" => {
+                self.charset = match value {
+                    "utf-8" => Some(Charset::Utf8),
+                    "utf-8-bom" => Some(Charset::Utf8Bom),
+                    "latin1" => Some(Charset::Latin1),
+                    "utf-16be" => Some(Charset::Utf16Be),
+                    "utf-16le" => Some(Charset::Utf16Le),
+                    _ => None,
+                }
+            }
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = flag,
+            "insert_final_newline" => self.insert_final_newline = flag,
+            _ => {}
+        }
+    }
+
+    fn merge(&mut self, other: &Properties) {
+        self.indent_style = other.indent_style.or(self.indent_style);
+        self.indent_size = other.indent_size.or(self.indent_size);
+        self.tab_width = other.tab_width.or(self.tab_width);
+        self.end_of_line = other.end_of_line.or(self.end_of_line);
+        self.charset = other.charset.or(self.charset);
+        self.trim_trailing_whitespace =
+            other.trim_trailing_whitespace.or(self.trim_trailing_whitespace);
+        self.insert_final_newline =
+            other.insert_final_newline.or(self.insert_final_newline);
+    }
+
+    /// Columns of one indentation level.
+    fn indent_width(&self) -> usize {
+        self.indent_size.or(self.tab_width).unwrap_or(4)
+    }
+
+    /// Columns of a tab.
+    fn tab_width(&self) -> usize {
+        self.tab_width.or(self.indent_size).unwrap_or(4)
+    }
+
+    /// Applies the indentation, trailing whitespace and final newline properties to
+    /// `content`, which uses `\n` line endings.
+    pub fn format(&self, content: &str) -> String {
+        if self.indent_style.is_none()
+            && self.trim_trailing_whitespace != Some(true)
+            && self.insert_final_newline.is_none()
+        {
+            return content.to_string();
+        }
+        let mut lines: Vec<String> = content
+            .split('\n')
+            .map(|line| {
+                let line = match self.trim_trailing_whitespace {
+                    Some(true) => line.trim_end(),
+                    _ => line,
+                };
+                match self.indent_style {
+                    Some(style) => self.reindent(line, style),
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+        // `split` yields an empty last line when content ends with a newline.
+        let ends_with_newline =
+            lines.len() > 1 && lines.last().is_some_and(String::is_empty);
+        match self.insert_final_newline {
+            Some(true) if !ends_with_newline && !content.is_empty() => {
+                lines.push(String::new())
+            }
+            Some(false) => {
+                while lines.len() > 1 && lines.last().is_some_and(String::is_empty) {
+                    lines.pop();
+                }
+            }
+            _ => {}
+        }
+        lines.join("\n")
+    }
+
+    /// Rewrites the leading whitespace of `line` in `style`.
+    fn reindent(&self, line: &str, style: IndentStyle) -> String {
+        let text = line.trim_start_matches([' ', '\t']);
+        let leading = &line[..line.len() - text.len()];
+        let columns: usize =
+            leading.chars().map(|c| if c == '\t' { self.tab_width() } else { 1 }).sum();
+        let indent = match style {
+            IndentStyle::Space => " ".repeat(columns),
+            IndentStyle::Tab => {
+                let levels = columns / self.indent_width();
+                let rest = columns % self.indent_width();
+                format!("{}{}", "\t".repeat(levels), " ".repeat(rest))
+            }
+        };
+        format!("{indent}{text}")
+    }
+
+    /// Decodes the `bytes` of a file written with the charset of the properties,
+    /// without its byte order mark, or `None` when they are not valid in that
+    /// charset. Without a charset, the bytes are read as UTF-8 as they are.
+    pub fn decode(&self, bytes: &[u8]) -> Option<String> {
+        match self.charset {
+            None => String::from_utf8(bytes.to_vec()).ok(),
+            Some(Charset::Utf8) | Some(Charset::Utf8Bom) => {
+                let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF][..]).unwrap_or(bytes);
+                String::from_utf8(bytes.to_vec()).ok()
+            }
+            Some(Charset::Latin1) => {
+                Some(bytes.iter().map(|&byte| char::from(byte)).collect())
+            }
+            Some(Charset::Utf16Be) => decode_utf16(bytes, u16::from_be_bytes),
+            Some(Charset::Utf16Le) => decode_utf16(bytes, u16::from_le_bytes),
+        }
+    }
+
+    /// Encodes `content`, which uses `\n` line endings, with the line endings and
+    /// charset of the properties. Line endings default to `native`. With a
+    /// charset, a byte order mark in `content` is dropped; the charset decides
+    /// whether the file gets one.
+    pub fn encode(&self, content: &str, native: EndOfLine) -> Vec<u8> {
+        let content = match self.charset {
+            Some(_) => content.strip_prefix('\u{feff}').unwrap_or(content),
+            None => content,
+        };
+        let content = content.replace("\r\n", "\n");
+        let content = match self.end_of_line.unwrap_or(native) {
+            EndOfLine::Lf => content,
+            EndOfLine::Crlf => content.replace('\n', "\r\n"),
+            EndOfLine::Cr => content.replace('\n', "\r"),
+        };
+        match self.charset {
+            None | Some(Charset::Utf8) => content.into_bytes(),
+            Some(Charset::Utf8Bom) => {
+                [&[0xEF, 0xBB, 0xBF][..], content.as_bytes()].concat()
+            }
+            Some(Charset::Latin1) => {
+                match content.chars().map(|c| u8::try_from(c).ok()).collect() {
+                    Some(bytes) => bytes,
+                    None => {
+                        log::warn!(
+                            "Content is not representable in latin1, writing UTF-8"
+                        );
+                        content.into_bytes()
+                    }
+                }
+            }
+            Some(Charset::Utf16Be) => std::iter::once(0xFEFF)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_be_bytes)
+                .collect(),
+            Some(Charset::Utf16Le) => std::iter::once(0xFEFF)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units: Vec<u16> =
+        bytes.chunks_exact(2).map(|pair| from_bytes([pair[0], pair[1]])).collect();
+    let units = units.strip_prefix(&[0xFEFF]).unwrap_or(&units);
+    String::from_utf16(units).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDITORCONFIG: &str = r#"root = true
+
+[*]
+end_of_line = lf
+insert_final_newline = true
+trim_trailing_whitespace = true
+
+[*.py]
+indent_style = space
+indent_size = 4
+
+[Makefile]
+indent_style = tab
+
+[docs/**/*.md]
+trim_trailing_whitespace = false
+charset = utf-8-bom
+"#;
+
+    #[test]
+    fn later_matching_sections_override_earlier_ones() {
+        let config = EditorConfig::parse(EDITORCONFIG);
+        let py = config.properties(Path::new("src/app/main.py"));
+        assert_eq!(py.indent_style, Some(IndentStyle::Space));
+        assert_eq!(py.indent_size, Some(4));
+        assert_eq!(py.end_of_line, Some(EndOfLine::Lf));
+
+        let make = config.properties(Path::new("Makefile"));
+        assert_eq!(make.indent_style, Some(IndentStyle::Tab));
+
+        let docs = config.properties(Path::new("docs/guide/intro.md"));
+        assert_eq!(docs.trim_trailing_whitespace, Some(false));
+        assert_eq!(docs.charset, Some(Charset::Utf8Bom));
+        assert_eq!(config.properties(Path::new("README.md")).charset, None);
+    }
+
+    #[test]
+    fn formats_and_encodes_content() {
+        let config = EditorConfig::parse(EDITORCONFIG);
+        let py = config.properties(Path::new("main.py"));
+        assert_eq!(py.format("def f():\n\treturn 1   \n"), "def f():\n    return 1\n");
+        assert_eq!(py.format("x = 1"), "x = 1\n");
+
+        let make = config.properties(Path::new("Makefile"));
+        assert_eq!(make.format("all:\n        cc -o a a.c\n"), "all:\n\t\tcc -o a a.c\n");
+
+        let crlf =
+            Properties { end_of_line: Some(EndOfLine::Crlf), ..Default::default() };
+        assert_eq!(crlf.encode("a\nb\n", EndOfLine::Lf), b"a\r\nb\r\n");
+        let latin1 = Properties { charset: Some(Charset::Latin1), ..Default::default() };
+        assert_eq!(latin1.encode("café", EndOfLine::Lf), b"caf\xe9");
+        let no_newline =
+            Properties { insert_final_newline: Some(false), ..Default::default() };
+        assert_eq!(no_newline.format("a\n\n"), "a");
+    }
+
+    #[test]
+    fn decodes_what_it_encodes_without_doubling_the_bom() {
+        for charset in [
+            Charset::Utf8,
+            Charset::Utf8Bom,
+            Charset::Latin1,
+            Charset::Utf16Be,
+            Charset::Utf16Le,
+        ] {
+            let properties = Properties { charset: Some(charset), ..Default::default() };
+            let encoded = properties.encode("café\n", EndOfLine::Lf);
+            let decoded = properties.decode(&encoded).unwrap();
+            assert_eq!(decoded, "café\n", "{charset:?}");
+            assert_eq!(
+                properties.encode(&decoded, EndOfLine::Lf),
+                encoded,
+                "{charset:?}"
+            );
+        }
+        let bom = Properties { charset: Some(Charset::Utf8Bom), ..Default::default() };
+        assert_eq!(bom.encode("\u{feff}a", EndOfLine::Lf), b"\xEF\xBB\xBFa");
+        assert_eq!(Properties::default().decode(b"\xff"), None);
+    }
+
+    #[test]
+    fn keeps_the_indentation_of_makefiles_and_heredocs() {
+        assert!(keeps_indentation(Path::new("Makefile"), "all:\n\tcc a.c\n"));
+        assert!(keeps_indentation(Path::new("rules.mk"), ""));
+        assert!(keeps_indentation(Path::new("setup.sh"), "cat <<-'EOF'\n\tx\nEOF\n"));
+        assert!(!keeps_indentation(Path::new("main.cpp"), "std::cout << x;\n"));
+        assert!(!keeps_indentation(Path::new("main.py"), "def f():\n    pass\n"));
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "terminal-ui")]
 use dialoguer::Error as DialoguerError;
 use std::process::ExitStatus;
 use thiserror::Error;
@@ -15,6 +16,7 @@ pub enum Error {
     )]
     ConfigNotFound { template_dir: String, config_files: String },
 
+    #[cfg(feature = "terminal-ui")]
     #[error("Dialoguer error: {0}")]
     DialoguerError(#[from] DialoguerError),
 
@@ -39,6 +41,9 @@ pub enum Error {
     #[error("Template rendering failed: {0}")]
     MinijinjaError(#[from] minijinja::Error),
 
+    #[error("Template rendering failed: {error}; {hint}")]
+    UndefinedValue { error: minijinja::Error, hint: String },
+
     #[error("Hook script '{script}' failed with exit code: {status}")]
     HookExecutionError { script: String, status: ExitStatus },
 
@@ -53,6 +58,21 @@ pub enum Error {
     #[error("Cannot process path '{source_path}': {e}")]
     ProcessError { source_path: String, e: String },
 
+    #[error(
+        "Template entry '{template_entry}' renders to '{rendered}', which would be written outside the output directory: {reason}"
+    )]
+    UnsafeTargetPath { template_entry: String, rendered: String, reason: String },
+
+    #[error(
+        "Template entry '{template_entry}' renders to '{rendered}', which Windows cannot create: {reason}. Change the answer, or set `windows_paths: escape` in baker.yaml to replace such names"
+    )]
+    InvalidWindowsPath { template_entry: String, rendered: String, reason: String },
+
+    #[error(
+        "Template entry '{template_entry}' renders to '{rendered}', whose name '{name}' is not ASCII. Change the answer, or set `non_ascii_names: transliterate` in baker.yaml"
+    )]
+    NonAsciiName { template_entry: String, rendered: String, name: String },
+
     #[error(
         "Generated metadata file not found at '{path}'. Run 'baker generate' first."
     )]
@@ -61,20 +81,92 @@ pub enum Error {
     #[error("Unsupported generated metadata version '{found}'. Expected '1'.")]
     UnsupportedGeneratedVersion { found: String },
 
+    #[error("Failed to resolve secret '{reference}': {reason}")]
+    SecretResolutionError { reference: String, reason: String },
+
     #[error("Answers JSON is not an object")]
     AnswersNotObject,
 
+    #[error("Validation error: {0}")]
+    AnswerValidation(String),
+
+    #[error("JSON Schema validation error: {0}")]
+    AnswerSchemaValidation(String),
+
+    #[error("{} template entries failed:\n{}", .0.len(), .0.join("\n"))]
+    TemplateEntriesFailed(Vec<String>),
+
+    #[error(
+        "{} outputs are rendered by more than one template entry, so one would overwrite the other:\n{}",
+        .0.len(),
+        .0.join("\n")
+    )]
+    OutputCollisions(Vec<String>),
+
+    #[error("{0}")]
+    Network(String),
+
+    #[error("Cannot use the template {template}: {reason} (policy '{}')", .policy.display())]
+    PolicyDenied { template: String, policy: std::path::PathBuf, reason: String },
+
+    #[error("{0}")]
+    Aborted(String),
+
     #[error("{0}")]
     Other(#[from] anyhow::Error),
 }
 
+impl Error {
+    /// The process exit code reporting this error, one of [`exit_codes`].
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::ConfigValidation(_)
+            | Error::ConfigNotFound { .. }
+            | Error::YAMLParseError(_)
+            | Error::TemplateDoesNotExistsError { .. } => exit_codes::CONFIG,
+            Error::MinijinjaError(_)
+            | Error::UndefinedValue { .. }
+            | Error::ProcessError { .. }
+            | Error::UnsafeTargetPath { .. }
+            | Error::InvalidWindowsPath { .. }
+            | Error::NonAsciiName { .. }
+            | Error::TemplateEntriesFailed(_)
+            | Error::OutputCollisions(_) => exit_codes::RENDER,
+            Error::HookExecutionError { .. } => exit_codes::HOOK,
+            Error::AnswerValidation(_) | Error::AnswerSchemaValidation(_) => {
+                exit_codes::VALIDATION
+            }
+            Error::Network(_) => exit_codes::NETWORK,
+            Error::Git2Error(e)
+                if matches!(
+                    e.class(),
+                    git2::ErrorClass::Net
+                        | git2::ErrorClass::Http
+                        | git2::ErrorClass::Ssh
+                ) =>
+            {
+                exit_codes::NETWORK
+            }
+            Error::Aborted(_) => exit_codes::ABORTED,
+            #[cfg(feature = "terminal-ui")]
+            Error::DialoguerError(DialoguerError::IO(e))
+                if e.kind() == std::io::ErrorKind::Interrupted =>
+            {
+                exit_codes::ABORTED
+            }
+            _ => exit_codes::FAILURE,
+        }
+    }
+}
+
 /// Standard Result type for Baker operations
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
-/// Default error handler that prints the error message and exits with code 1
+/// Default error handler that prints the error message and exits with its
+/// [`Error::exit_code`]
 pub fn default_error_handler(err: Error) {
     log::error!("{err}");
-    std::process::exit(exit_codes::FAILURE);
+    std::process::exit(err.exit_code());
 }
 
 #[cfg(test)]
@@ -98,4 +190,37 @@ mod tests {
         let err = Error::AnswersNotObject;
         assert_eq!(err.to_string(), "Answers JSON is not an object");
     }
+
+    #[test]
+    fn exit_codes_follow_error_kind() {
+        let net = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "reset",
+        );
+        let repo = git2::Error::new(
+            git2::ErrorCode::NotFound,
+            git2::ErrorClass::Repository,
+            "gone",
+        );
+        let cases = [
+            (Error::ConfigValidation("bad".to_string()), exit_codes::CONFIG),
+            (
+                Error::ProcessError { source_path: "a".to_string(), e: "b".to_string() },
+                exit_codes::RENDER,
+            ),
+            (
+                Error::TemplateEntriesFailed(vec!["  - a: b".to_string()]),
+                exit_codes::RENDER,
+            ),
+            (Error::AnswerValidation("bad".to_string()), exit_codes::VALIDATION),
+            (Error::Git2Error(net), exit_codes::NETWORK),
+            (Error::Git2Error(repo), exit_codes::FAILURE),
+            (Error::Aborted("stop".to_string()), exit_codes::ABORTED),
+            (Error::AnswersNotObject, exit_codes::FAILURE),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.exit_code(), code, "{err}");
+        }
+    }
 }
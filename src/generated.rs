@@ -1,11 +1,17 @@
 //! Generated metadata file — written to the output directory after every generate run.
 
 use crate::{
-    config::ConfigV1, constants::DEFAULT_GENERATED_FILE_NAME, error::Result,
+    config::ConfigV1,
+    constants::{AGE_ARMOR_HEADER, DEFAULT_GENERATED_FILE_NAME},
+    error::Result,
     loader::TemplateSourceInfo,
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 
 /// The top-level structure serialised to `.baker-generated.yaml`.
@@ -19,6 +25,40 @@ pub struct BakerGenerated {
     pub template: TemplateSourceInfo,
     /// The answers collected during generation, serialised as a JSON value.
     pub answers: serde_json::Value,
+    /// Every generated file, keyed by its path relative to the output directory.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, FileProvenance>,
+    /// The config profile generated with, reused by `baker update`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Whether the answers are stored age-encrypted. [`read`] leaves them
+    /// encrypted, as armored text in `answers`, for the callers that need them to
+    /// decrypt with [`crate::cli::encrypted::decrypt_answers`].
+    #[serde(skip)]
+    pub encrypted_answers: bool,
+}
+
+/// Where a generated file came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileProvenance {
+    /// Path of the template entry that produced the file, relative to the template root.
+    pub template: String,
+    /// [`content_hash`] of the content the template produced.
+    pub hash: String,
+    /// Whether the content is binary, such as an image or a font.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+}
+
+/// A generated file whose content on disk no longer has its recorded hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDrift {
+    /// Path relative to the output directory.
+    pub path: String,
+    /// Whether the recorded content is binary.
+    pub binary: bool,
+    /// Whether the file no longer exists.
+    pub missing: bool,
 }
 
 impl BakerGenerated {
@@ -29,20 +69,145 @@ impl BakerGenerated {
             generated_at: Utc::now().to_rfc3339(),
             template,
             answers,
+            files: BTreeMap::new(),
+            profile: None,
+            encrypted_answers: false,
         }
     }
+
+    /// Records the provenance of the generated files.
+    pub fn with_files(mut self, files: BTreeMap<String, FileProvenance>) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Records the config profile generated with.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Stores the answers age-encrypted when `encrypted` is set.
+    pub fn with_encrypted_answers(mut self, encrypted: bool) -> Self {
+        self.encrypted_answers = encrypted;
+        self
+    }
+
+    /// The armored age ciphertext of the answers, while they are still encrypted.
+    pub fn answers_ciphertext(&self) -> Option<&str> {
+        encrypted_answers(&self.answers)
+    }
+}
+
+/// SHA-256 hex digest of file content. Text is hashed with `\n` line endings, so
+/// that a file written with native line endings matches the rendered content.
+pub fn content_hash(content: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    match std::str::from_utf8(content) {
+        Ok(text) => hasher.update(text.replace("\r\n", "\n").as_bytes()),
+        Err(_) => hasher.update(content),
+    }
+    hex::encode(hasher.finalize())
 }
 
-/// Write a `BakerGenerated` record to `<output_dir>/<file_name>`.
+/// [`content_hash`] and [`is_binary`] of the file at `path`, read `buffer_size`
+/// bytes at a time instead of whole, so large copied files are not held in memory.
+pub fn file_hash(path: &Path, buffer_size: usize) -> std::io::Result<(String, bool)> {
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; buffer_size.max(1)];
+    let mut raw = Sha256::new();
+    let mut text = Sha256::new();
+    // Bytes held back from `text`: an incomplete UTF-8 sequence, or a `\r` that
+    // the next chunk may complete to `\r\n`. `None` once the file is not UTF-8.
+    let mut pending = Some(Vec::new());
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        raw.update(&buffer[..read]);
+        let Some(held) = &mut pending else {
+            continue;
+        };
+        held.extend_from_slice(&buffer[..read]);
+        let mut complete = match std::str::from_utf8(held) {
+            Ok(_) => held.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                pending = None;
+                continue;
+            }
+        };
+        if held[..complete].ends_with(b"\r") {
+            complete -= 1;
+        }
+        update_without_crlf(&mut text, &held[..complete]);
+        held.drain(..complete);
+    }
+    match pending {
+        Some(held) if std::str::from_utf8(&held).is_ok() => {
+            update_without_crlf(&mut text, &held);
+            Ok((hex::encode(text.finalize()), false))
+        }
+        _ => Ok((hex::encode(raw.finalize()), true)),
+    }
+}
+
+/// Hashes `text` with `\r\n` replaced by `\n`, like [`content_hash`].
+fn update_without_crlf(hasher: &mut Sha256, text: &[u8]) {
+    let mut start = 0;
+    for (index, pair) in text.windows(2).enumerate() {
+        if pair == b"\r\n" {
+            hasher.update(&text[start..index]);
+            start = index + 1;
+        }
+    }
+    hasher.update(&text[start..]);
+}
+
+/// Whether `content` is binary rather than UTF-8 text.
+pub fn is_binary(content: &[u8]) -> bool {
+    std::str::from_utf8(content).is_err()
+}
+
+/// The recorded `files` under `dir` whose content changed or that were removed.
+pub fn drifted_files(
+    dir: &Path,
+    files: &BTreeMap<String, FileProvenance>,
+) -> Result<Vec<FileDrift>> {
+    let mut drifted = Vec::new();
+    for (path, provenance) in files {
+        let drift = |missing| FileDrift {
+            path: path.clone(),
+            binary: provenance.binary,
+            missing,
+        };
+        match std::fs::read(dir.join(path)) {
+            Ok(content) if content_hash(&content) != provenance.hash => {
+                drifted.push(drift(false))
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                drifted.push(drift(true))
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(drifted)
+}
+
+/// Write a `BakerGenerated` record to `<output_dir>/<file_name>`, with the answers
+/// as they are; [`crate::cli::encrypted::write_generated`] encrypts them first
+/// when `encrypted_answers` is set.
 pub fn write(output_dir: &Path, file_name: &str, data: &BakerGenerated) -> Result<()> {
     let path = output_dir.join(file_name);
-    let yaml = serde_yaml::to_string(data)?;
-    std::fs::write(&path, yaml)?;
+    std::fs::write(&path, serde_yaml::to_string(data)?)?;
     log::debug!("Wrote generated metadata to '{}'", path.display());
     Ok(())
 }
 
-/// Read a `BakerGenerated` record from `<dir>/<file_name>`.
+/// Read a `BakerGenerated` record from `<dir>/<file_name>`. Encrypted answers are
+/// left encrypted.
 ///
 /// Returns `Err(GeneratedFileNotFound)` when the file is absent.
 pub fn read(dir: &Path, file_name: &str) -> Result<BakerGenerated> {
@@ -51,15 +216,21 @@ pub fn read(dir: &Path, file_name: &str) -> Result<BakerGenerated> {
         return Err(crate::error::Error::GeneratedFileNotFound { path });
     }
     let content = std::fs::read_to_string(&path)?;
-    let data: BakerGenerated = serde_yaml::from_str(&content)?;
+    let mut data: BakerGenerated = serde_yaml::from_str(&content)?;
     if data.version != "1" {
         return Err(crate::error::Error::UnsupportedGeneratedVersion {
             found: data.version,
         });
     }
+    data.encrypted_answers = encrypted_answers(&data.answers).is_some();
     Ok(data)
 }
 
+/// The armored age ciphertext of answers stored encrypted.
+fn encrypted_answers(answers: &serde_json::Value) -> Option<&str> {
+    answers.as_str().filter(|text| text.starts_with(AGE_ARMOR_HEADER))
+}
+
 /// Resolve the effective generated-file name from (in priority order):
 /// 1. CLI flag (`cli_override`)
 /// 2. Config field (`config_value`)
@@ -90,6 +261,25 @@ pub fn strip_secret_answers(
     filtered
 }
 
+/// `answers` with the values of secret questions, and of answers resolved from
+/// the `!secret` `references`, masked, for output meant for debugging.
+pub fn redact_secret_answers(
+    answers: &serde_json::Value,
+    config: &ConfigV1,
+    references: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Value {
+    let mut redacted = answers.clone();
+    if let Some(obj) = redacted.as_object_mut() {
+        for (key, value) in obj.iter_mut() {
+            let secret = config.questions.get(key).is_some_and(|q| q.secret.is_some());
+            if secret || references.contains_key(key) {
+                *value = serde_json::json!("********");
+            }
+        }
+    }
+    redacted
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,6 +328,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn only_armored_age_answers_are_encrypted() {
+        let armored = "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n";
+        assert_eq!(encrypted_answers(&serde_json::json!(armored)), Some(armored));
+        assert_eq!(encrypted_answers(&serde_json::json!("plain")), None);
+        assert_eq!(encrypted_answers(&serde_json::json!({"name": armored})), None);
+
+        let tmp = TempDir::new().unwrap();
+        let data =
+            BakerGenerated::new(make_filesystem_source(), serde_json::json!("plain"));
+        write(tmp.path(), ".baker-generated.yaml", &data).unwrap();
+        let loaded = read(tmp.path(), ".baker-generated.yaml").unwrap();
+        assert!(!loaded.encrypted_answers);
+        assert_eq!(loaded.answers, "plain");
+    }
+
+    #[test]
+    fn encrypted_answers_are_read_without_decrypting() {
+        let armored = "-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n-----END AGE ENCRYPTED FILE-----\n";
+        let tmp = TempDir::new().unwrap();
+        let files = BTreeMap::from([(
+            "README.md".to_string(),
+            FileProvenance {
+                template: "README.md".to_string(),
+                hash: content_hash(b"# demo"),
+                binary: false,
+            },
+        )]);
+        let data =
+            BakerGenerated::new(make_filesystem_source(), serde_json::json!(armored))
+                .with_files(files);
+        write(tmp.path(), ".baker-generated.yaml", &data).unwrap();
+        std::fs::write(tmp.path().join("README.md"), "# demo").unwrap();
+
+        let loaded = read(tmp.path(), ".baker-generated.yaml").unwrap();
+        assert!(loaded.encrypted_answers);
+        assert_eq!(loaded.answers_ciphertext(), Some(armored));
+        assert!(drifted_files(tmp.path(), &loaded.files).unwrap().is_empty());
+    }
+
     #[test]
     fn read_missing_file_returns_error() {
         let tmp = TempDir::new().unwrap();
@@ -221,4 +451,50 @@ questions:
         let stripped = strip_secret_answers(&answers, &config);
         assert_eq!(stripped, answers);
     }
+
+    #[test]
+    fn file_hash_matches_content_hash_for_any_buffer_size() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("file");
+        for content in [
+            &b""[..],
+            b"line\r\nline\r\n\r\r\nend\r",
+            "caf\u{e9} \u{1F600}\r\n".as_bytes(),
+            &[0xff, 0xfe, b'\r', b'\n', 0x00],
+            b"text then \xe9 invalid",
+        ] {
+            std::fs::write(&path, content).unwrap();
+            let expected = (content_hash(content), is_binary(content));
+            for buffer_size in [1, 2, 3, 7, 64 * 1024] {
+                assert_eq!(file_hash(&path, buffer_size).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn drifted_files_reports_changed_and_missing_files() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("same.txt"), "a\r\n").unwrap();
+        std::fs::write(tmp.path().join("logo.png"), [0xffu8, 0]).unwrap();
+        let provenance = |content: &[u8]| FileProvenance {
+            template: String::new(),
+            hash: content_hash(content),
+            binary: is_binary(content),
+        };
+        let files = BTreeMap::from([
+            ("same.txt".to_string(), provenance(b"a\n")),
+            ("logo.png".to_string(), provenance(&[0xff, 1])),
+            ("gone.txt".to_string(), provenance(b"b")),
+        ]);
+
+        let drifted = drifted_files(tmp.path(), &files).unwrap();
+
+        assert_eq!(
+            drifted,
+            vec![
+                FileDrift { path: "gone.txt".to_string(), binary: false, missing: true },
+                FileDrift { path: "logo.png".to_string(), binary: true, missing: false },
+            ]
+        );
+    }
 }
@@ -18,6 +18,7 @@ const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     "baker.yaml",
     "baker.yml",
     "baker.json",
+    "cookiecutter.json",
 ];
 
 /// Baker's ignore file name
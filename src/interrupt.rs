@@ -0,0 +1,59 @@
+//! Ctrl-C handling. Once [`install`] is called, the first Ctrl-C only raises a
+//! flag: clones, hooks and file processing check it and stop with
+//! [`Error::Aborted`], so temporary directories are dropped and partial output
+//! can be cleaned up. A second Ctrl-C exits at once.
+
+use crate::{
+    constants::exit_codes,
+    error::{Error, Result},
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, LazyLock,
+};
+
+static INTERRUPTED: LazyLock<Arc<AtomicBool>> =
+    LazyLock::new(|| Arc::new(AtomicBool::new(false)));
+
+extern "C" fn handle_interrupt(_signal: libc::c_int) {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        // SAFETY: `_exit` is async-signal-safe.
+        unsafe { libc::_exit(exit_codes::ABORTED) }
+    }
+}
+
+/// Catches Ctrl-C for the rest of the process.
+pub fn install() {
+    LazyLock::force(&INTERRUPTED);
+    let handler = handle_interrupt as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only touches an already initialized atomic and calls
+    // `_exit`, both async-signal-safe.
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Whether Ctrl-C was pressed.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// The flag raised by Ctrl-C, for [`FileProcessor::with_cancellation`].
+///
+/// [`FileProcessor::with_cancellation`]: crate::cli::processor::FileProcessor::with_cancellation
+pub fn flag() -> Arc<AtomicBool> {
+    Arc::clone(&INTERRUPTED)
+}
+
+/// The error reporting a Ctrl-C.
+pub fn aborted() -> Error {
+    Error::Aborted("Aborted by user".to_string())
+}
+
+/// Fails with [`aborted`] once Ctrl-C was pressed.
+pub fn check() -> Result<()> {
+    if interrupted() {
+        return Err(aborted());
+    }
+    Ok(())
+}
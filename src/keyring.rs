@@ -0,0 +1,192 @@
+//! Secret answers kept in the OS keyring.
+//!
+//! With `--keyring`, the answers to secret questions are stored in the keyring of
+//! the user's session instead of being asked for on every generation. Each secret
+//! is stored under the service `baker:<template>` and the question key as the
+//! account, so every version of a template shares its secrets. The keyring is
+//! reached through `secret-tool` (libsecret) on Linux and `security` on macOS;
+//! other platforms are not supported. Baker's own entries, such as the age identity
+//! of encrypted answers, are stored under the service `baker`. Behind the `keyring`
+//! cargo feature.
+
+use crate::{
+    error::{Error, Result},
+    loader::TemplateSourceInfo,
+};
+#[cfg(feature = "keyring")]
+use std::process::{Command, Stdio};
+
+/// Where secret answers are kept between generations.
+pub trait SecretStore {
+    /// The secret stored for `account`, if any.
+    fn get(&self, account: &str) -> Result<Option<String>>;
+
+    /// Stores `secret` for `account`, replacing the previous one.
+    fn set(&self, account: &str, secret: &str) -> Result<()>;
+}
+
+/// The OS keyring entries of one template.
+#[derive(Debug, Clone)]
+pub struct Keyring {
+    service: String,
+}
+
+impl Keyring {
+    /// The entries of `template`. Fails when Baker was built without the `keyring`
+    /// feature.
+    pub fn for_template(template: &TemplateSourceInfo) -> Result<Self> {
+        let identity = crate::cli::memory::identity(template);
+        Self::with_service(format!("baker:{identity}"))
+    }
+
+    /// Baker's own entries, under the service `baker`, such as the age identity.
+    /// Fails when Baker was built without the `keyring` feature.
+    pub fn baker() -> Result<Self> {
+        Self::with_service("baker".to_string())
+    }
+
+    fn with_service(service: String) -> Result<Self> {
+        if !cfg!(feature = "keyring") {
+            return Err(Error::Other(anyhow::anyhow!(
+                "This Baker was built without the `keyring` feature"
+            )));
+        }
+        Ok(Self { service })
+    }
+
+    /// The keyring service the entries are stored under.
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    fn fail(&self, account: &str, reason: impl std::fmt::Display) -> Error {
+        Error::Other(anyhow::anyhow!(
+            "Keyring entry '{account}' of '{}': {reason}",
+            self.service
+        ))
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl SecretStore for Keyring {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("security");
+            command.args([
+                "find-generic-password",
+                "-w",
+                "-s",
+                &self.service,
+                "-a",
+                account,
+            ]);
+            command
+        } else if cfg!(unix) {
+            let mut command = Command::new("secret-tool");
+            command.args(["lookup", "service", &self.service, "account", account]);
+            command
+        } else {
+            return Err(
+                self.fail(account, "the keyring is not supported on this platform")
+            );
+        };
+        let output = command.stdin(Stdio::null()).output().map_err(|e| {
+            self.fail(account, format!("cannot run the keyring tool: {e}"))
+        })?;
+        let secret = String::from_utf8_lossy(&output.stdout);
+        let secret = secret.trim_end_matches(['\r', '\n']);
+        // Both tools exit with an error and print nothing for a missing entry.
+        if !output.status.success() || secret.is_empty() {
+            log::debug!(
+                "No keyring entry '{account}' of '{}': {}",
+                self.service,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(None);
+        }
+        Ok(Some(secret.to_string()))
+    }
+
+    fn set(&self, account: &str, secret: &str) -> Result<()> {
+        if secret.chars().any(char::is_control) {
+            return Err(self.fail(account, "the secret contains control characters"));
+        }
+        // The secret goes on stdin, never on the command line where other users
+        // could see it in the process list.
+        let (mut command, input) = if cfg!(target_os = "macos") {
+            if [account, &self.service].iter().any(|s| s.chars().any(char::is_control)) {
+                return Err(self.fail(account, "the name contains control characters"));
+            }
+            let mut command = Command::new("security");
+            command.arg("-i");
+            let input = format!(
+                "add-generic-password -U -s {} -a {} -w {}\n",
+                quote(&self.service),
+                quote(account),
+                quote(secret)
+            );
+            (command, input)
+        } else if cfg!(unix) {
+            let label = format!("Baker: {account} of {}", self.service);
+            let mut command = Command::new("secret-tool");
+            command
+                .args(["store", "--label", &label, "service", &self.service])
+                .args(["account", account]);
+            (command, secret.to_string())
+        } else {
+            return Err(
+                self.fail(account, "the keyring is not supported on this platform")
+            );
+        };
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                self.fail(account, format!("cannot run the keyring tool: {e}"))
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            std::io::Write::write_all(&mut stdin, input.as_bytes())?;
+        }
+        let output = child.wait_with_output().map_err(|e| {
+            self.fail(account, format!("cannot run the keyring tool: {e}"))
+        })?;
+        if !output.status.success() {
+            return Err(
+                self.fail(account, String::from_utf8_lossy(&output.stderr).trim())
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `text` as one double-quoted argument of a `security -i` command.
+#[cfg(feature = "keyring")]
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(not(feature = "keyring"))]
+impl SecretStore for Keyring {
+    fn get(&self, account: &str) -> Result<Option<String>> {
+        Err(self.fail(account, "Baker was built without the `keyring` feature"))
+    }
+
+    fn set(&self, account: &str, _secret: &str) -> Result<()> {
+        Err(self.fail(account, "Baker was built without the `keyring` feature"))
+    }
+}
+
+#[cfg(all(test, feature = "keyring"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_arguments_of_security_commands() {
+        assert_eq!(quote(r#"pa"ss\word"#), r#""pa\"ss\\word""#);
+        let keyring = Keyring { service: "baker:demo".to_string() };
+        let err = keyring.set("token", "line\nadd-generic-password").unwrap_err();
+        assert!(err.to_string().contains("control characters"), "{err}");
+    }
+}
@@ -35,9 +35,42 @@ pub mod error;
 /// Generated metadata file support.
 pub mod generated;
 
+/// Lockfile for verifying that a generation is reproducible.
+pub mod lock;
+
+/// Compliance manifest of the generated files.
+pub mod manifest;
+
+/// Copier-compatible answers file.
+pub mod copier;
+
+/// Resolution of `!secret` answer references.
+pub mod secrets;
+
+/// Secret answers kept in the OS keyring.
+pub mod keyring;
+
+/// Ctrl-C handling.
+pub mod interrupt;
+
+/// Registered temporary directories.
+pub mod temp;
+
+/// Atomic file writes.
+pub mod atomic;
+
+/// Long paths, reserved names and invalid characters on Windows.
+pub mod winpath;
+
+/// Unicode normalization of rendered file names.
+pub mod names;
+
 /// Processes .bakerignore files to exclude specific paths.
 pub mod ignore;
 
+/// Applies the template's .editorconfig to rendered files.
+pub mod editorconfig;
+
 /// Template parsing and rendering functionality.
 pub mod renderer;
 
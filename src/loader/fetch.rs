@@ -0,0 +1,287 @@
+//! Runs independent network fetches concurrently.
+//!
+//! A template can pull several remote resources while loading (git submodules,
+//! for now). [`FetchCoordinator`] runs them on worker threads, at most
+//! `parallelism` at a time, and waits for all of them. A [`FetchBudget`] bounds
+//! the time and the bytes spent by the whole fetch, nested fetches included;
+//! jobs check it and stop once it is used up. Every failure is collected, so a
+//! broken submodule does not hide the others.
+
+use crate::error::{Error, Result};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+type JobFn = Box<dyn FnOnce(&FetchBudget) -> Result<()> + Send>;
+
+/// A named unit of work for the coordinator.
+pub struct FetchJob {
+    /// Name shown when the job fails, e.g. the submodule path.
+    pub name: String,
+    run: JobFn,
+}
+
+impl FetchJob {
+    /// The job receives the budget of the fetch, to report the bytes it downloads
+    /// and to stop once the budget is used up.
+    pub fn new(
+        name: impl Into<String>,
+        run: impl FnOnce(&FetchBudget) -> Result<()> + Send + 'static,
+    ) -> Self {
+        Self { name: name.into(), run: Box::new(run) }
+    }
+}
+
+/// A job that did not complete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchFailure {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Time and download limits shared by a fetch and all the fetches it starts.
+/// Clones share the bytes counted so far.
+#[derive(Debug, Clone)]
+pub struct FetchBudget {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    max_bytes: Option<u64>,
+    received: Arc<AtomicU64>,
+}
+
+impl FetchBudget {
+    /// Starts the clock: the deadline is `timeout` from now.
+    pub fn new(timeout: Option<Duration>, max_bytes: Option<u64>) -> Self {
+        Self {
+            timeout,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            max_bytes,
+            received: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Counts `bytes` more downloaded bytes.
+    pub fn add_bytes(&self, bytes: u64) {
+        self.received.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Why the fetch must stop, once the deadline has passed or more bytes than
+    /// allowed were downloaded.
+    pub fn exceeded(&self) -> Option<String> {
+        if let (Some(timeout), Some(deadline)) = (self.timeout, self.deadline) {
+            if Instant::now() >= deadline {
+                return Some(format!("did not finish within {}s", timeout.as_secs_f64()));
+            }
+        }
+        match self.max_bytes {
+            Some(max) if self.received.load(Ordering::Relaxed) > max => {
+                Some(format!("exceeded the download limit of {max} bytes"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Runs [`FetchJob`]s with bounded parallelism within a [`FetchBudget`].
+#[derive(Debug, Clone)]
+pub struct FetchCoordinator {
+    parallelism: usize,
+    budget: FetchBudget,
+}
+
+impl FetchCoordinator {
+    /// Creates a coordinator running up to `parallelism` jobs at once (at least one).
+    /// Clones share the budget, so nested fetches should use a clone.
+    pub fn new(parallelism: usize, budget: FetchBudget) -> Self {
+        Self { parallelism: parallelism.max(1), budget }
+    }
+
+    /// The budget shared by the jobs of this coordinator.
+    pub fn budget(&self) -> &FetchBudget {
+        &self.budget
+    }
+
+    /// Runs all jobs and returns the ones that failed, in the order the jobs were
+    /// given. Jobs not started when the budget is used up are not run, and the
+    /// call returns only once every started job has stopped.
+    pub fn run(&self, jobs: Vec<FetchJob>) -> Vec<FetchFailure> {
+        let total = jobs.len();
+        if total == 0 {
+            return Vec::new();
+        }
+        let names: Vec<String> = jobs.iter().map(|job| job.name.clone()).collect();
+        let queue = Mutex::new(jobs.into_iter().enumerate().collect::<VecDeque<_>>());
+        let (sender, receiver) = mpsc::channel();
+
+        let mut results: Vec<Option<std::result::Result<(), String>>> =
+            (0..total).map(|_| None).collect();
+        std::thread::scope(|scope| {
+            for _ in 0..self.parallelism.min(total) {
+                let queue = &queue;
+                let sender = sender.clone();
+                let budget = &self.budget;
+                scope.spawn(move || loop {
+                    let next = queue.lock().map(|mut queue| queue.pop_front());
+                    let Ok(Some((index, job))) = next else { break };
+                    let result = match budget.exceeded() {
+                        Some(reason) => Err(reason),
+                        // A job failing once the budget is used up was most likely
+                        // stopped because of it.
+                        None => (job.run)(budget).map_err(|e| {
+                            budget.exceeded().unwrap_or_else(|| e.to_string())
+                        }),
+                    };
+                    if sender.send((index, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+            for (index, result) in receiver {
+                results[index] = Some(result);
+            }
+        });
+
+        names
+            .into_iter()
+            .zip(results)
+            .filter_map(|(name, result)| {
+                let reason = match result {
+                    Some(Ok(())) => return None,
+                    Some(Err(reason)) => reason,
+                    None => "worker stopped unexpectedly".to_string(),
+                };
+                Some(FetchFailure { name, reason })
+            })
+            .collect()
+    }
+
+    /// Like [`FetchCoordinator::run`], but turns failures into a single error listing
+    /// each of them. `what` names the kind of resource, e.g. "submodules".
+    pub fn run_all(&self, what: &str, jobs: Vec<FetchJob>) -> Result<()> {
+        let total = jobs.len();
+        let failures = self.run(jobs);
+        if failures.is_empty() {
+            return Ok(());
+        }
+        let details: Vec<String> = failures
+            .iter()
+            .map(|failure| format!("  - {}: {}", failure.name, failure.reason))
+            .collect();
+        Err(Error::Network(format!(
+            "Failed to fetch {} of {total} {what}:\n{}",
+            failures.len(),
+            details.join("\n")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn runs_every_job_and_collects_all_failures() {
+        let failing = |name: &'static str| {
+            FetchJob::new(name, move |_| {
+                Err(Error::Other(anyhow::anyhow!("{name} is down")))
+            })
+        };
+        let jobs =
+            vec![failing("first"), FetchJob::new("ok", |_| Ok(())), failing("third")];
+
+        let err = FetchCoordinator::new(2, FetchBudget::new(None, None))
+            .run_all("submodules", jobs)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to fetch 2 of 3 submodules:\n  - first: first is down\n  - third: third is down"
+        );
+    }
+
+    #[test]
+    fn never_runs_more_jobs_than_the_parallelism_limit() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let jobs = (0..8)
+            .map(|i| {
+                let running = Arc::clone(&running);
+                let peak = Arc::clone(&peak);
+                FetchJob::new(format!("job{i}"), move |_| {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    Ok(())
+                })
+            })
+            .collect();
+
+        assert!(FetchCoordinator::new(3, FetchBudget::new(None, None))
+            .run(jobs)
+            .is_empty());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn stops_jobs_still_running_at_the_timeout() {
+        let jobs = vec![
+            FetchJob::new("fast", |_| Ok(())),
+            FetchJob::new("slow", |budget| {
+                while budget.exceeded().is_none() {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                Err(Error::Other(anyhow::anyhow!("cancelled")))
+            }),
+            FetchJob::new("queued", |_| Ok(())),
+        ];
+
+        let budget = FetchBudget::new(Some(Duration::from_millis(100)), None);
+        let failures = FetchCoordinator::new(2, budget).run(jobs);
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].name, "slow");
+        assert_eq!(failures[0].reason, "did not finish within 0.1s");
+    }
+
+    #[test]
+    fn nested_fetches_share_one_byte_budget() {
+        let coordinator = FetchCoordinator::new(1, FetchBudget::new(None, Some(100)));
+        let nested = coordinator.clone();
+        let jobs = vec![
+            FetchJob::new("parent", move |budget| {
+                budget.add_bytes(60);
+                nested.run_all(
+                    "submodules",
+                    vec![FetchJob::new("child", |budget| {
+                        budget.add_bytes(60);
+                        match budget.exceeded() {
+                            Some(_) => Err(Error::Other(anyhow::anyhow!("cancelled"))),
+                            None => Ok(()),
+                        }
+                    })],
+                )
+            }),
+            FetchJob::new("sibling", |_| Ok(())),
+        ];
+
+        let failures = coordinator.run(jobs);
+
+        assert_eq!(
+            failures,
+            ["parent", "sibling"]
+                .map(|name| FetchFailure {
+                    name: name.to_string(),
+                    reason: "exceeded the download limit of 100 bytes".to_string(),
+                })
+                .to_vec()
+        );
+    }
+}
@@ -1,9 +1,11 @@
+use crate::constants::CONFIG_FILENAMES;
 use crate::error::{Error, Result};
 use crate::ignore::parse_bakerignore_file;
 use crate::loader::interface::TemplateLoader;
 use crate::loader::{LoadedTemplate, TemplateSourceInfo};
+use globset::GlobBuilder;
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Loader for templates from the local filesystem.
@@ -22,26 +24,120 @@ impl<P: AsRef<std::path::Path>> TemplateLoader for LocalLoader<P> {
     /// # Returns
     /// * `Result<LoadedTemplate>` - Loaded template with path and content hash
     fn load(&self) -> Result<LoadedTemplate> {
-        let path = self.path.as_ref();
-        if !path.exists() {
-            return Err(Error::TemplateDoesNotExistsError {
-                template_dir: path.display().to_string(),
-            });
-        }
-
-        let root = path.to_path_buf();
+        let root = resolve_template_path(self.path.as_ref())?;
         let hash = compute_directory_hash(&root)?;
 
         Ok(LoadedTemplate {
-            root,
             source: TemplateSourceInfo::Filesystem {
-                path: path.to_string_lossy().to_string(),
+                path: root.to_string_lossy().to_string(),
                 hash,
             },
+            root,
         })
     }
 }
 
+/// Resolves a template argument to its root directory.
+///
+/// A path to a config file such as `baker.yaml` resolves to the directory holding
+/// it. A path that does not exist but contains glob characters is matched against
+/// the filesystem and must match exactly one template directory.
+pub fn resolve_template_path(path: &Path) -> Result<PathBuf> {
+    if path.exists() {
+        return Ok(config_file_parent(path).unwrap_or_else(|| path.to_path_buf()));
+    }
+
+    let pattern = path.to_string_lossy();
+    if !is_glob(&pattern) {
+        return Err(Error::TemplateDoesNotExistsError {
+            template_dir: pattern.into_owned(),
+        });
+    }
+
+    let mut candidates = glob_candidates(path)?;
+    match candidates.len() {
+        0 => {
+            Err(Error::TemplateDoesNotExistsError { template_dir: pattern.into_owned() })
+        }
+        1 => Ok(candidates.remove(0)),
+        _ => Err(Error::Other(anyhow::anyhow!(
+            "Template pattern '{pattern}' matches several templates:\n{}",
+            candidates
+                .iter()
+                .map(|c| format!("  {}", c.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))),
+    }
+}
+
+/// The directory holding `path` when it names a Baker config file.
+fn config_file_parent(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    if !path.is_file() || !CONFIG_FILENAMES.contains(&name) {
+        return None;
+    }
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => Some(parent.to_path_buf()),
+        _ => Some(PathBuf::from(".")),
+    }
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '[', '{'])
+}
+
+/// Template directories (or config files, resolved to their directory) matching
+/// the glob `path`, sorted.
+fn glob_candidates(path: &Path) -> Result<Vec<PathBuf>> {
+    // Walk from the longest leading part of the path without glob characters.
+    let mut base = PathBuf::new();
+    let mut rest = Vec::new();
+    for component in path.components() {
+        let text = component.as_os_str().to_string_lossy();
+        if rest.is_empty() && !is_glob(&text) {
+            base.push(component);
+        } else if !matches!(component, Component::CurDir) {
+            rest.push(text.into_owned());
+        }
+    }
+    if base.as_os_str().is_empty() {
+        base = PathBuf::from(".");
+    }
+
+    let matcher = GlobBuilder::new(&rest.join("/"))
+        .literal_separator(true)
+        .build()
+        .map_err(|e| Error::Other(anyhow::anyhow!("Invalid template pattern: {e}")))?
+        .compile_matcher();
+
+    let mut candidates: Vec<PathBuf> = WalkDir::new(&base)
+        .min_depth(1)
+        .max_depth(rest.len())
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.path().strip_prefix(&base).is_ok_and(|rel| {
+                matcher.is_match(rel.to_string_lossy().replace('\\', "/"))
+            })
+        })
+        .filter_map(|e| {
+            if e.file_type().is_dir() {
+                let dir = e.into_path();
+                CONFIG_FILENAMES
+                    .iter()
+                    .any(|name| dir.join(name).is_file())
+                    .then_some(dir)
+            } else {
+                config_file_parent(e.path())
+            }
+        })
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    Ok(candidates)
+}
+
 /// Compute a deterministic SHA-256 hash of all template files, excluding .bakerignore patterns.
 ///
 /// File paths are collected and sorted for determinism. Both relative path and
@@ -118,4 +214,43 @@ mod tests {
         let hash2 = compute_directory_hash(root).unwrap();
         assert_ne!(hash1, hash2);
     }
+
+    fn make_template(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("baker.yaml"), "schemaVersion: v1\n").unwrap();
+    }
+
+    #[test]
+    fn config_file_path_resolves_to_its_directory() {
+        let dir = tempdir().unwrap();
+        make_template(dir.path());
+
+        let loaded = LocalLoader::new(dir.path().join("baker.yaml")).load().unwrap();
+        assert_eq!(loaded.root, dir.path());
+    }
+
+    #[test]
+    fn glob_resolves_unique_template_and_lists_ambiguous_matches() {
+        let dir = tempdir().unwrap();
+        let templates = dir.path().join("templates");
+        make_template(&templates.join("rest-api"));
+        make_template(&templates.join("grpc-api"));
+        make_template(&templates.join("cli"));
+        fs::create_dir_all(templates.join("api-docs")).unwrap();
+
+        let resolved = resolve_template_path(&templates.join("*rest*")).unwrap();
+        assert_eq!(resolved, templates.join("rest-api"));
+
+        let resolved = resolve_template_path(&templates.join("c*/baker.yaml")).unwrap();
+        assert_eq!(resolved, templates.join("cli"));
+
+        let err =
+            resolve_template_path(&templates.join("*api*")).unwrap_err().to_string();
+        assert!(err.contains("matches several templates"), "{err}");
+        assert!(err.contains("grpc-api") && err.contains("rest-api"), "{err}");
+        assert!(!err.contains("api-docs"), "{err}");
+
+        let err = resolve_template_path(&templates.join("*web*")).unwrap_err();
+        assert!(matches!(err, Error::TemplateDoesNotExistsError { .. }));
+    }
 }
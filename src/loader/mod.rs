@@ -1,12 +1,16 @@
-use crate::error::Result;
+use crate::config::{policy, UserConfig};
+use crate::error::{Error, Result};
 use crate::loader::interface::TemplateLoader;
-use crate::loader::{git::GitLoader, local::LocalLoader};
+use crate::loader::{git::GitLoader, local::LocalLoader, plugin::PluginLoader};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+pub mod fetch;
 pub mod git;
 pub mod interface;
 pub mod local;
+pub mod plugin;
+pub mod tar;
 
 #[derive(Debug)]
 pub enum TemplateSource {
@@ -14,6 +18,10 @@ pub enum TemplateSource {
     FileSystem(PathBuf),
     /// Git repository URL (HTTPS or SSH)
     Git(String),
+    /// URL with a scheme handled by a `baker-loader-<scheme>` plugin
+    Plugin(String),
+    /// Tar archive streamed on stdin
+    TarStream,
 }
 
 impl std::fmt::Display for TemplateSource {
@@ -23,12 +31,14 @@ impl std::fmt::Display for TemplateSource {
                 write!(f, "local path: '{}'", path.display())
             }
             TemplateSource::Git(repo) => write!(f, "git repository: '{repo}'"),
+            TemplateSource::Plugin(url) => write!(f, "plugin source: '{url}'"),
+            TemplateSource::TarStream => write!(f, "tar stream on stdin"),
         }
     }
 }
 
 /// Metadata about the template source captured at load time.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum TemplateSourceInfo {
     /// A local filesystem template with a content hash.
@@ -48,6 +58,18 @@ pub enum TemplateSourceInfo {
         #[serde(skip_serializing_if = "Option::is_none")]
         tag: Option<String>,
     },
+    /// A template materialized by a loader plugin.
+    Plugin {
+        /// The URL passed to the plugin.
+        url: String,
+        /// SHA-256 hex digest of the materialized template files.
+        hash: String,
+    },
+    /// A template read as a tar archive from stdin.
+    Tar {
+        /// SHA-256 hex digest of the unpacked template files.
+        hash: String,
+    },
 }
 
 /// The result of loading a template: the on-disk path plus source metadata.
@@ -62,23 +84,34 @@ pub struct LoadedTemplate {
 /// Creates a TemplateFactory from a string path or URL and loads the template.
 ///
 /// # Arguments
-/// * `s` - String containing path or git URL
+/// * `s` - String containing path, git URL, or an alias from the user config
 /// * `skip_overwrite_check` - Whether to skip confirmation for overwriting existing directories
 ///
 /// # Returns
 /// * `Result<LoadedTemplate>` - Loaded template with path and source metadata
 pub fn get_template(s: &str, skip_overwrite_check: bool) -> Result<LoadedTemplate> {
+    let user_config = UserConfig::load()?;
+    let s = user_config.resolve_alias(s);
     let source = if GitLoader::<&str>::is_git_url(s) {
         TemplateSource::Git(s.to_string())
+    } else if PluginLoader::plugin_scheme(s).is_some() {
+        TemplateSource::Plugin(s.to_string())
     } else {
         TemplateSource::FileSystem(PathBuf::from(s))
     };
+    policy::check_source(&source)?;
 
     match source {
-        TemplateSource::Git(repo) => {
-            GitLoader::new(repo.clone(), skip_overwrite_check).load()
-        }
+        TemplateSource::Git(repo) => GitLoader::new(repo.clone(), skip_overwrite_check)
+            .with_settings(user_config.git)
+            .load(),
         TemplateSource::FileSystem(path) => LocalLoader::new(path.clone()).load(),
+        TemplateSource::Plugin(url) => {
+            PluginLoader::for_url(&url).expect("plugin scheme checked above").load()
+        }
+        TemplateSource::TarStream => Err(Error::Other(anyhow::anyhow!(
+            "A tar stream is not named by a string; read it with `--stdin-tar`"
+        ))),
     }
 }
 
@@ -0,0 +1,160 @@
+use crate::error::{Error, Result};
+use crate::loader::interface::TemplateLoader;
+use crate::loader::local::compute_directory_hash;
+use crate::loader::{LoadedTemplate, TemplateSourceInfo};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use url::Url;
+
+/// Prefix of the executables that load templates for other URL schemes.
+pub const PLUGIN_PREFIX: &str = "baker-loader-";
+
+/// Schemes Baker handles itself.
+const BUILTIN_SCHEMES: &[&str] = &["http", "https", "git", "ssh", "file"];
+
+/// Loader delegating to an external `baker-loader-<scheme>` executable.
+///
+/// The executable is called with the template URL as its only argument. It
+/// materializes the template into a directory of its choosing and prints that
+/// directory's path as the last line of its stdout. Its stderr is passed through.
+pub struct PluginLoader {
+    url: String,
+    program: PathBuf,
+}
+
+impl PluginLoader {
+    /// Creates a loader for `url`, or `None` when its scheme is handled by Baker.
+    pub fn for_url(url: &str) -> Option<Self> {
+        let scheme = Self::plugin_scheme(url)?;
+        Some(Self {
+            url: url.to_string(),
+            program: PathBuf::from(format!("{PLUGIN_PREFIX}{scheme}")),
+        })
+    }
+
+    /// The scheme of `url` when it needs a loader plugin. Single-letter schemes
+    /// are Windows drive letters, not URLs.
+    pub fn plugin_scheme(url: &str) -> Option<String> {
+        let parsed = Url::parse(url).ok()?;
+        let scheme = parsed.scheme();
+        (scheme.len() > 1 && !BUILTIN_SCHEMES.contains(&scheme))
+            .then(|| scheme.to_string())
+    }
+}
+
+impl TemplateLoader for PluginLoader {
+    /// Loads a template by running its loader plugin.
+    ///
+    /// # Returns
+    /// * `Result<LoadedTemplate>` - Loaded template with path and content hash
+    fn load(&self) -> Result<LoadedTemplate> {
+        let program = self.program.display();
+        log::debug!("Loading '{}' with '{program}'", self.url);
+
+        let output = Command::new(&self.program)
+            .arg(&self.url)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    Error::Other(anyhow::anyhow!(
+                        "No loader for '{}': '{program}' was not found on PATH",
+                        self.url
+                    ))
+                } else {
+                    Error::Other(anyhow::anyhow!("Failed to run '{program}': {e}"))
+                }
+            })?;
+
+        if !output.status.success() {
+            return Err(Error::Other(anyhow::anyhow!(
+                "'{program}' failed to load '{}' ({})",
+                self.url,
+                output.status
+            )));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let root = stdout
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| {
+                Error::Other(anyhow::anyhow!(
+                    "'{program}' did not print a template directory"
+                ))
+            })?;
+        if !root.is_dir() {
+            return Err(Error::TemplateDoesNotExistsError {
+                template_dir: root.display().to_string(),
+            });
+        }
+
+        let hash = compute_directory_hash(&root)?;
+        Ok(LoadedTemplate {
+            root,
+            source: TemplateSourceInfo::Plugin { url: self.url.clone(), hash },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_unknown_schemes_need_plugins() {
+        assert_eq!(
+            PluginLoader::plugin_scheme("s3://bucket/templates/api"),
+            Some("s3".to_string())
+        );
+        assert_eq!(
+            PluginLoader::plugin_scheme("oci://registry/tpl:1.0"),
+            Some("oci".to_string())
+        );
+        assert_eq!(PluginLoader::plugin_scheme("https://github.com/a/b"), None);
+        assert_eq!(PluginLoader::plugin_scheme("file:///tmp/tpl"), None);
+        assert_eq!(PluginLoader::plugin_scheme("C:\\templates\\api"), None);
+        assert_eq!(PluginLoader::plugin_scheme("./templates/api"), None);
+    }
+
+    #[test]
+    fn reports_missing_plugin() {
+        let loader = PluginLoader::for_url("nosuchscheme-xyz://bucket/tpl").unwrap();
+        let err = loader.load().unwrap_err().to_string();
+        assert!(err.contains("baker-loader-nosuchscheme-xyz"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn loads_directory_printed_by_plugin() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("materialized");
+        std::fs::create_dir(&template).unwrap();
+        std::fs::write(template.join("baker.yaml"), "schemaVersion: v1\n").unwrap();
+
+        let program = dir.path().join("baker-loader-s3");
+        std::fs::write(
+            &program,
+            format!("#!/bin/sh\necho \"fetching $1\"\necho '{}'\n", template.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        let loader = PluginLoader { url: "s3://bucket/tpl".to_string(), program };
+        let loaded = loader.load().unwrap();
+        assert_eq!(loaded.root, template);
+        match loaded.source {
+            TemplateSourceInfo::Plugin { url, hash } => {
+                assert_eq!(url, "s3://bucket/tpl");
+                assert!(!hash.is_empty());
+            }
+            _ => panic!("expected plugin source info"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::error::{Error, Result};
+use crate::loader::interface::TemplateLoader;
+use crate::loader::local::compute_directory_hash;
+use crate::loader::{LoadedTemplate, TemplateSourceInfo};
+use crate::temp::TempDir;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Loader for a template streamed as a tar archive, e.g. `git archive HEAD | baker
+/// generate --stdin-tar -o out`.
+///
+/// The archive is unpacked by the system `tar` into a temporary directory, which
+/// lives as long as the loader. An archive holding a single top-level directory,
+/// as `git archive --prefix` creates, has its template in that directory.
+pub struct TarStreamLoader {
+    dir: TempDir,
+}
+
+impl TarStreamLoader {
+    /// Unpacks the tar archive read from `input`.
+    pub fn extract(mut input: impl Read) -> Result<Self> {
+        let dir = TempDir::new("tar")?;
+        let mut child = Command::new("tar")
+            .arg("-xf")
+            .arg("-")
+            .arg("-C")
+            .arg(dir.path())
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Other(anyhow::anyhow!("Cannot run tar: {e}")))?;
+        let mut stdin = child.stdin.take().expect("tar stdin is piped");
+        let copied = std::io::copy(&mut input, &mut stdin);
+        drop(stdin);
+        let status = child.wait()?;
+        if matches!(copied, Ok(0)) {
+            return Err(Error::Other(anyhow::anyhow!("The template stream is empty")));
+        }
+        if !status.success() {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Cannot unpack the template stream: tar exited with {status}"
+            )));
+        }
+        match copied {
+            // tar stops reading at the end-of-archive marker, before the padding
+            // that follows it.
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e.into()),
+            _ => Ok(Self { dir }),
+        }
+    }
+
+    /// The template directory: the only top-level directory of the archive, or
+    /// else the directory it was unpacked into.
+    fn root(&self) -> Result<PathBuf> {
+        let mut entries = std::fs::read_dir(self.dir.path())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        match entries.pop() {
+            Some(only) if entries.is_empty() && only.is_dir() => Ok(only),
+            _ => Ok(self.dir.path().to_path_buf()),
+        }
+    }
+}
+
+impl TemplateLoader for TarStreamLoader {
+    /// Loads the unpacked template.
+    ///
+    /// # Returns
+    /// * `Result<LoadedTemplate>` - Loaded template with path and content hash
+    fn load(&self) -> Result<LoadedTemplate> {
+        let root = self.root()?;
+        let hash = compute_directory_hash(&root)?;
+        Ok(LoadedTemplate { source: TemplateSourceInfo::Tar { hash }, root })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_a_template_from_a_tar_stream() {
+        let template = tempfile::tempdir().unwrap();
+        let project = template.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::write(project.join("baker.yaml"), "schemaVersion: v1\n").unwrap();
+        let archive = Command::new("tar")
+            .arg("-cf")
+            .arg("-")
+            .arg("-C")
+            .arg(template.path())
+            .arg("project")
+            .output()
+            .unwrap();
+        assert!(archive.status.success());
+
+        let loader = TarStreamLoader::extract(archive.stdout.as_slice()).unwrap();
+        let loaded = loader.load().unwrap();
+
+        assert!(loaded.root.join("baker.yaml").is_file());
+        assert!(
+            matches!(loaded.source, TemplateSourceInfo::Tar { hash } if !hash.is_empty())
+        );
+        assert!(TarStreamLoader::extract(&b""[..]).is_err());
+    }
+}
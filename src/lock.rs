@@ -0,0 +1,150 @@
+//! Generation lockfile — `.baker-lock.json`, written on request so that `baker verify`
+//! can check that the template still renders to exactly the same files.
+//!
+//! Unlike the generated metadata, the lock holds no answers and no timestamp: two
+//! generations with the same inputs produce identical locks.
+
+use crate::{
+    error::{Error, Result},
+    generated::{content_hash, FileProvenance},
+    loader::TemplateSourceInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Name of the lockfile in the output directory.
+pub const LOCK_FILE_NAME: &str = ".baker-lock.json";
+
+/// The structure serialised to `.baker-lock.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BakerLock {
+    /// Schema version — always `"1"` for now.
+    pub version: String,
+    /// Version of Baker that rendered the files.
+    pub baker_version: String,
+    /// The exact template the files were rendered from.
+    pub template: TemplateSourceInfo,
+    /// [`answers_hash`] of the answers stored in the generated metadata.
+    pub answers_hash: String,
+    /// [`content_hash`] of every generated file, keyed by its path relative to the
+    /// output directory.
+    pub files: BTreeMap<String, String>,
+}
+
+impl BakerLock {
+    /// Creates a lock for files rendered by this version of Baker.
+    pub fn new(
+        template: TemplateSourceInfo,
+        answers: &serde_json::Value,
+        files: &BTreeMap<String, FileProvenance>,
+    ) -> Self {
+        Self {
+            version: "1".to_string(),
+            baker_version: env!("CARGO_PKG_VERSION").to_string(),
+            template,
+            answers_hash: answers_hash(answers),
+            files: files.iter().map(|(path, p)| (path.clone(), p.hash.clone())).collect(),
+        }
+    }
+
+    /// Differences between the locked files and `rendered`, one line per file.
+    pub fn mismatches(&self, rendered: &BTreeMap<String, FileProvenance>) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        for (path, hash) in &self.files {
+            match rendered.get(path) {
+                None => mismatches.push(format!("missing: {path}")),
+                Some(file) if &file.hash != hash => {
+                    mismatches.push(format!("changed: {path}"))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in rendered.keys().filter(|path| !self.files.contains_key(*path)) {
+            mismatches.push(format!("unexpected: {path}"));
+        }
+        mismatches
+    }
+}
+
+/// SHA-256 hex digest of the answers' JSON serialisation.
+pub fn answers_hash(answers: &serde_json::Value) -> String {
+    content_hash(answers.to_string().as_bytes())
+}
+
+/// Write `lock` to `<output_dir>/.baker-lock.json`.
+pub fn write(output_dir: &Path, lock: &BakerLock) -> Result<()> {
+    let path = output_dir.join(LOCK_FILE_NAME);
+    let mut json = serde_json::to_string_pretty(lock)?;
+    json.push('\n');
+    std::fs::write(&path, json)?;
+    log::debug!("Wrote lockfile to '{}'", path.display());
+    Ok(())
+}
+
+/// Read the lock in `dir`, or `None` when there is none.
+pub fn read(dir: &Path) -> Result<Option<BakerLock>> {
+    let path = dir.join(LOCK_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let lock: BakerLock = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    if lock.version != "1" {
+        return Err(Error::Other(anyhow::anyhow!(
+            "Unsupported lockfile version '{}' in '{}'. Expected '1'.",
+            lock.version,
+            path.display()
+        )));
+    }
+    Ok(Some(lock))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn files(entries: &[(&str, &str)]) -> BTreeMap<String, FileProvenance> {
+        entries
+            .iter()
+            .map(|(path, hash)| {
+                let provenance = FileProvenance {
+                    template: path.to_string(),
+                    hash: hash.to_string(),
+                    binary: false,
+                };
+                (path.to_string(), provenance)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_and_mismatches() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let source = TemplateSourceInfo::Git {
+            url: "https://github.com/example/tpl".to_string(),
+            commit: "deadbeef".to_string(),
+            tag: None,
+        };
+        let lock = BakerLock::new(
+            source,
+            &json!({"name": "demo"}),
+            &files(&[("a.txt", "1"), ("b.txt", "2")]),
+        );
+        write(tmp.path(), &lock).unwrap();
+        assert_eq!(read(tmp.path()).unwrap(), Some(lock.clone()));
+        assert_eq!(lock.answers_hash, answers_hash(&json!({"name": "demo"})));
+
+        assert!(lock.mismatches(&files(&[("a.txt", "1"), ("b.txt", "2")])).is_empty());
+        assert_eq!(
+            lock.mismatches(&files(&[("a.txt", "9"), ("c.txt", "3")])),
+            ["changed: a.txt", "missing: b.txt", "unexpected: c.txt"]
+        );
+    }
+
+    #[test]
+    fn read_without_lock_returns_none() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert_eq!(read(tmp.path()).unwrap(), None);
+    }
+}
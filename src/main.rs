@@ -1,18 +1,52 @@
 use baker::{
-    cli::{get_args, get_log_level_from_verbose, run, run_update, Commands},
+    cli::{
+        get_args, get_log_level_from_verbose, notify_if_outdated, run, run_audit,
+        run_docs, run_filters, run_golden, run_grep, run_info, run_merge_driver,
+        run_render, run_self_update, run_shell, run_trust, run_update, run_validate,
+        run_verify, run_workspace, Commands,
+    },
     error::default_error_handler,
+    interrupt, prompt, temp,
 };
 
 fn main() {
     let args = get_args();
     let log_level = get_log_level_from_verbose(args.verbose);
     env_logger::Builder::new().filter_level(log_level).init();
+    temp::keep_temp(args.keep_temp);
+    prompt::plain_prompts(args.plain_prompts);
+
+    // Checked before the command runs, so its output is not interleaved with ours.
+    if args.verbose > 0 && !matches!(args.command, Commands::SelfUpdate(_)) {
+        notify_if_outdated();
+    }
 
     let result = match args.command {
-        Commands::Generate(generate_args) => run(generate_args),
-        Commands::Update(update_args) => run_update(update_args),
+        Commands::Generate(generate_args) => {
+            interrupt::install();
+            run(*generate_args)
+        }
+        Commands::Update(update_args) => {
+            interrupt::install();
+            run_update(update_args)
+        }
+        Commands::Verify(verify_args) => run_verify(verify_args),
+        Commands::Test(test_args) => run_golden(test_args),
+        Commands::Audit(audit_args) => run_audit(audit_args),
+        Commands::Info(info_args) => run_info(info_args),
+        Commands::Docs(docs_args) => run_docs(docs_args),
+        Commands::Validate(validate_args) => run_validate(validate_args),
+        Commands::Render(render_args) => run_render(render_args),
+        Commands::Workspace(workspace_args) => run_workspace(workspace_args),
+        Commands::Trust(trust_args) => run_trust(trust_args),
+        Commands::MergeDriver(merge_args) => run_merge_driver(merge_args),
+        Commands::Shell(shell_args) => run_shell(shell_args),
+        Commands::Filters(filters_args) => run_filters(filters_args),
+        Commands::Grep(grep_args) => run_grep(grep_args),
+        Commands::SelfUpdate(self_update_args) => run_self_update(self_update_args),
     };
 
+    temp::cleanup();
     if let Err(err) = result {
         default_error_handler(err);
     }
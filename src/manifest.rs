@@ -0,0 +1,276 @@
+//! Compliance manifest — written on request to document where a generated project
+//! came from: the template, the Baker version, the answers and a SHA-256 hash of
+//! every generated file.
+//!
+//! The manifest is written as Baker's own JSON or as a minimal SPDX 2.3 document.
+//! File hashes are of the bytes on disk, so they can be checked with `sha256sum`.
+
+use crate::{error::Result, generated::FileProvenance, loader::TemplateSourceInfo};
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Format of the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ManifestFormat {
+    /// Baker's manifest, `.baker-manifest.json`.
+    Json,
+    /// An SPDX 2.3 JSON document, `.baker-manifest.spdx.json`.
+    Spdx,
+}
+
+impl ManifestFormat {
+    /// Name of the manifest in the output directory.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ManifestFormat::Json => ".baker-manifest.json",
+            ManifestFormat::Spdx => ".baker-manifest.spdx.json",
+        }
+    }
+}
+
+/// The structure serialised to `.baker-manifest.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BakerManifest {
+    /// Schema version — always `"1"` for now.
+    pub version: String,
+    /// RFC3339 timestamp of when the generation ran.
+    pub generated_at: String,
+    /// Version of Baker that generated the files.
+    pub baker_version: String,
+    /// The template the files were generated from.
+    pub template: TemplateSourceInfo,
+    /// Keys of the answers given, secret ones included.
+    pub answer_keys: Vec<String>,
+    /// The answers, without secrets, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answers: Option<Value>,
+    /// Every generated file, sorted by path.
+    pub files: Vec<ManifestFile>,
+}
+
+/// A generated file listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// Path relative to the output directory, with `/` separators.
+    pub path: String,
+    /// Path of the template entry that produced the file.
+    pub template: String,
+    /// SHA-256 hex digest of the file as written.
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl BakerManifest {
+    /// Builds the manifest of the `files` written to `output_dir`. `answers` are
+    /// recorded only when `answer_values` is given. Files that no longer exist,
+    /// removed by a post-generation hook, are left out with a warning.
+    pub fn new(
+        output_dir: &Path,
+        template: TemplateSourceInfo,
+        answer_keys: Vec<String>,
+        answer_values: Option<Value>,
+        files: &BTreeMap<String, FileProvenance>,
+    ) -> Result<Self> {
+        let mut entries = Vec::with_capacity(files.len());
+        for (path, provenance) in files {
+            let content = match std::fs::read(output_dir.join(path)) {
+                Ok(content) => content,
+                // A post-generation hook may have removed the file.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    log::warn!(
+                        "Leaving '{path}' out of the manifest: it no longer exists"
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            entries.push(ManifestFile {
+                path: path.clone(),
+                template: provenance.template.clone(),
+                sha256: hex::encode(Sha256::digest(&content)),
+                size: content.len() as u64,
+            });
+        }
+        Ok(Self {
+            version: "1".to_string(),
+            generated_at: Utc::now().to_rfc3339(),
+            baker_version: env!("CARGO_PKG_VERSION").to_string(),
+            template,
+            answer_keys,
+            answers: answer_values,
+            files: entries,
+        })
+    }
+
+    /// The manifest as an SPDX 2.3 document: the generated project is a package
+    /// downloaded from the template, containing the files. Answers are recorded in
+    /// the document comment.
+    pub fn to_spdx(&self, name: &str) -> Value {
+        let (location, version) = match &self.template {
+            TemplateSourceInfo::Filesystem { path, hash } => (path.clone(), hash.clone()),
+            TemplateSourceInfo::Git { url, commit, tag } => {
+                (format!("git+{url}@{commit}"), tag.clone().unwrap_or(commit.clone()))
+            }
+            TemplateSourceInfo::Plugin { url, hash } => (url.clone(), hash.clone()),
+            TemplateSourceInfo::Tar { hash } => ("NOASSERTION".to_string(), hash.clone()),
+        };
+        let mut comment = format!(
+            "Generated by Baker from {location}. Answer keys: {}.",
+            self.answer_keys.join(", ")
+        );
+        if let Some(answers) = &self.answers {
+            comment.push_str(&format!(" Answers: {answers}"));
+        }
+        let ids: Vec<String> =
+            (1..=self.files.len()).map(|i| format!("SPDXRef-File-{i}")).collect();
+        let files: Vec<Value> = self
+            .files
+            .iter()
+            .zip(&ids)
+            .map(|(file, id)| {
+                json!({
+                    "SPDXID": id,
+                    "fileName": format!("./{}", file.path),
+                    "checksums": [{"algorithm": "SHA256", "checksumValue": file.sha256}],
+                    "comment": format!("Rendered from template entry {}", file.template),
+                })
+            })
+            .collect();
+        let relationships: Vec<Value> = std::iter::once(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": "SPDXRef-Package",
+        }))
+        .chain(ids.iter().map(|id| {
+            json!({
+                "spdxElementId": "SPDXRef-Package",
+                "relationshipType": "CONTAINS",
+                "relatedSpdxElement": id,
+            })
+        }))
+        .collect();
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": name,
+            "documentNamespace": format!(
+                "https://spdx.org/spdxdocs/baker-{name}-{}",
+                self.generated_at.replace([':', '+'], "-")
+            ),
+            "creationInfo": {
+                "created": self.generated_at,
+                "creators": [format!("Tool: baker-{}", self.baker_version)],
+            },
+            "comment": comment,
+            "packages": [{
+                "SPDXID": "SPDXRef-Package",
+                "name": name,
+                "versionInfo": version,
+                "downloadLocation": location,
+                "filesAnalyzed": true,
+                "hasFiles": ids,
+            }],
+            "files": files,
+            "relationships": relationships,
+        })
+    }
+}
+
+/// Write `manifest` to `<output_dir>` in `format`.
+pub fn write(
+    output_dir: &Path,
+    manifest: &BakerManifest,
+    format: ManifestFormat,
+) -> Result<()> {
+    let path = output_dir.join(format.file_name());
+    let value = match format {
+        ManifestFormat::Json => serde_json::to_value(manifest)?,
+        ManifestFormat::Spdx => {
+            let name = output_dir
+                .canonicalize()
+                .ok()
+                .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "project".to_string());
+            manifest.to_spdx(&name)
+        }
+    };
+    let mut json = serde_json::to_string_pretty(&value)?;
+    json.push('\n');
+    std::fs::write(&path, json)?;
+    log::debug!("Wrote manifest to '{}'", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_files_with_hashes_of_their_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("README.md"), "hello\r\n").unwrap();
+        let files = BTreeMap::from([(
+            "README.md".to_string(),
+            FileProvenance {
+                template: "README.md.j2".to_string(),
+                hash: String::new(),
+                binary: false,
+            },
+        )]);
+        let template = TemplateSourceInfo::Git {
+            url: "https://github.com/acme/tpl".to_string(),
+            commit: "abc123".to_string(),
+            tag: Some("v1.0.0".to_string()),
+        };
+        let manifest = BakerManifest::new(
+            dir.path(),
+            template,
+            vec!["name".to_string()],
+            None,
+            &files,
+        )
+        .unwrap();
+        assert_eq!(manifest.files[0].sha256, hex::encode(Sha256::digest(b"hello\r\n")));
+        assert_eq!(manifest.files[0].size, 7);
+
+        write(dir.path(), &manifest, ManifestFormat::Json).unwrap();
+        let json =
+            std::fs::read_to_string(dir.path().join(".baker-manifest.json")).unwrap();
+        assert!(!json.contains("\"answers\""));
+
+        let spdx = manifest.to_spdx("demo");
+        assert_eq!(spdx["packages"][0]["versionInfo"], "v1.0.0");
+        assert_eq!(
+            spdx["packages"][0]["downloadLocation"],
+            "git+https://github.com/acme/tpl@abc123"
+        );
+        assert_eq!(spdx["files"][0]["fileName"], "./README.md");
+        assert_eq!(spdx["relationships"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn leaves_out_files_removed_after_generation() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("kept.txt"), "kept").unwrap();
+        let provenance = FileProvenance {
+            template: "file.j2".to_string(),
+            hash: String::new(),
+            binary: false,
+        };
+        let files = BTreeMap::from([
+            ("kept.txt".to_string(), provenance.clone()),
+            ("removed.txt".to_string(), provenance),
+        ]);
+        let template = TemplateSourceInfo::Tar { hash: "abc".to_string() };
+        let manifest =
+            BakerManifest::new(dir.path(), template, Vec::new(), None, &files).unwrap();
+        assert_eq!(manifest.files.len(), 1);
+        assert_eq!(manifest.files[0].path, "kept.txt");
+    }
+}
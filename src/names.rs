@@ -0,0 +1,86 @@
+//! Normalization of rendered file names.
+//!
+//! Every rendered name is put in Unicode NFC, the form most filesystems and
+//! tools expect, and stripped of control characters. macOS may store names
+//! decomposed, so an answer typed as `é` and a template file named `é` compare
+//! equal only after normalization. Names that are not ASCII can additionally be
+//! transliterated or rejected with `non_ascii_names`.
+
+use crate::config::NonAsciiNames;
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+
+/// `name` in NFC, without control characters.
+pub fn normalize(name: &str) -> String {
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    ComposingNormalizerBorrowed::new_nfc().normalize(&name).into_owned()
+}
+
+/// `name`, normalized, with `policy` applied. Fails with the offending name when
+/// the policy rejects it.
+pub fn apply(name: &str, policy: NonAsciiNames) -> Result<String, String> {
+    let name = normalize(name);
+    match policy {
+        _ if name.is_ascii() => Ok(name),
+        NonAsciiNames::Allow => Ok(name),
+        NonAsciiNames::Transliterate => Ok(transliterate(&name)),
+        NonAsciiNames::Reject => Err(name),
+    }
+}
+
+/// `name` in ASCII: accents are dropped, a few letters are spelled out, e.g. `ß`
+/// as `ss`, and characters without an ASCII form become `_`.
+pub fn transliterate(name: &str) -> String {
+    let decomposed = DecomposingNormalizerBorrowed::new_nfd().normalize(name);
+    let mut ascii = String::with_capacity(name.len());
+    for c in decomposed.chars().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            c if c.is_ascii() => ascii.push(c),
+            'ß' => ascii.push_str("ss"),
+            'æ' => ascii.push_str("ae"),
+            'Æ' => ascii.push_str("AE"),
+            'œ' => ascii.push_str("oe"),
+            'Œ' => ascii.push_str("OE"),
+            'þ' => ascii.push_str("th"),
+            'Þ' => ascii.push_str("TH"),
+            'ø' => ascii.push('o'),
+            'Ø' => ascii.push('O'),
+            'ł' => ascii.push('l'),
+            'Ł' => ascii.push('L'),
+            'đ' | 'ð' => ascii.push('d'),
+            'Đ' | 'Ð' => ascii.push('D'),
+            'ı' => ascii.push('i'),
+            _ => ascii.push('_'),
+        }
+    }
+    ascii
+}
+
+/// Whether `c` is a combining mark, such as the accent of a decomposed `é`.
+fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1AFF}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20FF}'
+        | '\u{FE20}'..='\u{FE2F}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_and_applies_the_ascii_policy() {
+        // `e` followed by a combining acute accent, as macOS may store it.
+        assert_eq!(normalize("cafe\u{301}.md"), "caf\u{e9}.md");
+        assert_eq!(normalize("bad\u{7}name\n"), "badname");
+
+        assert_eq!(transliterate("Größe-Ærø.md"), "Grosse-AEro.md");
+        assert_eq!(transliterate("日本.txt"), "__.txt");
+
+        assert_eq!(apply("naïve", NonAsciiNames::Allow).unwrap(), "naïve");
+        assert_eq!(apply("naïve", NonAsciiNames::Transliterate).unwrap(), "naive");
+        assert_eq!(apply("naïve", NonAsciiNames::Reject).unwrap_err(), "naïve");
+        assert_eq!(apply("plain", NonAsciiNames::Reject).unwrap(), "plain");
+    }
+}
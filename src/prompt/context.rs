@@ -38,6 +38,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: crate::config::types::get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let default = serde_json::Value::String("default_value".to_string());
         let help = "This is a help message";
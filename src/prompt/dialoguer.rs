@@ -7,16 +7,29 @@ use super::interface::{
     ConfirmationConfig, MultipleChoiceConfig, SecretConfig, SingleChoiceConfig,
     StructuredDataConfig, TextPromptConfig,
 };
+use super::theme::{Color, PromptTheme, Symbols};
 use crate::{error::Result, prompt::parser::DataParser};
-use dialoguer::{Confirm, Editor, Input, MultiSelect, Password, Select};
+use dialoguer::{
+    console::{style, Style},
+    theme::{ColorfulTheme, Theme},
+    Confirm, Editor, Input, MultiSelect, Password, Select,
+};
 use serde_json::Value;
+use std::fmt;
 
 /// Default terminal-backed prompt provider implemented with `dialoguer`.
-pub struct DialoguerPrompter;
+pub struct DialoguerPrompter {
+    theme: DialoguerTheme,
+}
 
 impl DialoguerPrompter {
     pub fn new() -> Self {
-        Self
+        Self::with_theme(&PromptTheme::default())
+    }
+
+    /// A prompter drawing its prompts with `theme`.
+    pub fn with_theme(theme: &PromptTheme) -> Self {
+        Self { theme: DialoguerTheme::new(theme) }
     }
 }
 
@@ -41,7 +54,9 @@ impl super::interface::TextPrompter for DialoguerPrompter {
 
 impl super::interface::SingleChoicePrompter for DialoguerPrompter {
     fn prompt_single_choice(&self, config: &SingleChoiceConfig) -> Result<usize> {
-        let mut select = Select::new().with_prompt(&config.prompt).items(&config.choices);
+        let mut select = Select::with_theme(&self.theme)
+            .with_prompt(&config.prompt)
+            .items(&config.choices);
 
         if let Some(default_index) = config.default_index {
             select = select.default(default_index);
@@ -56,7 +71,7 @@ impl super::interface::MultipleChoicePrompter for DialoguerPrompter {
         &self,
         config: &MultipleChoiceConfig,
     ) -> Result<Vec<usize>> {
-        let indices = MultiSelect::new()
+        let indices = MultiSelect::with_theme(&self.theme)
             .with_prompt(&config.prompt)
             .items(&config.choices)
             .defaults(&config.defaults)
@@ -68,7 +83,7 @@ impl super::interface::MultipleChoicePrompter for DialoguerPrompter {
 
 impl super::interface::ConfirmationPrompter for DialoguerPrompter {
     fn prompt_confirmation(&self, config: &ConfirmationConfig) -> Result<bool> {
-        let result = Confirm::new()
+        let result = Confirm::with_theme(&self.theme)
             .with_prompt(&config.prompt)
             .default(config.default)
             .interact()?;
@@ -84,7 +99,7 @@ impl super::interface::StructuredDataPrompter for DialoguerPrompter {
 
         let options = vec!["Enter in terminal", "Open editor"];
 
-        let selection = Select::new()
+        let selection = Select::with_theme(&self.theme)
             .with_prompt(&config.prompt)
             .items(&options)
             .default(0)
@@ -102,6 +117,12 @@ impl super::interface::StructuredDataPrompter for DialoguerPrompter {
     }
 }
 
+impl super::interface::HelpPagePrompter for DialoguerPrompter {
+    fn show_help_page(&self, page: &str) -> Result<()> {
+        super::pager::show_help_page(page)
+    }
+}
+
 impl DialoguerPrompter {
     /// Handle password input with optional confirmation
     fn prompt_password(
@@ -109,7 +130,7 @@ impl DialoguerPrompter {
         prompt: &str,
         secret_config: &SecretConfig,
     ) -> Result<String> {
-        let mut password = Password::new().with_prompt(prompt);
+        let mut password = Password::with_theme(&self.theme).with_prompt(prompt);
 
         if secret_config.confirm {
             let error_message = if secret_config.mismatch_error.is_empty() {
@@ -127,7 +148,7 @@ impl DialoguerPrompter {
 
     /// Handle regular text input
     fn prompt_regular_text(&self, prompt: &str, default: &str) -> Result<String> {
-        Ok(Input::new()
+        Ok(Input::with_theme(&self.theme)
             .with_prompt(prompt)
             .default(default.to_string())
             .interact_text()?)
@@ -139,7 +160,7 @@ impl DialoguerPrompter {
         default_content: &str,
         is_yaml: bool,
     ) -> Result<Value> {
-        let content: String = Input::new()
+        let content: String = Input::with_theme(&self.theme)
             .with_prompt("Enter content")
             .default(default_content.to_string())
             .interact_text()?;
@@ -162,3 +183,194 @@ impl DialoguerPrompter {
         DataParser::parse_structured_content(&content, is_yaml)
     }
 }
+
+/// [`ColorfulTheme`] set up from a [`PromptTheme`], with its yes/no words.
+struct DialoguerTheme {
+    colorful: ColorfulTheme,
+    yes: String,
+    no: String,
+}
+
+impl DialoguerTheme {
+    fn new(theme: &PromptTheme) -> Self {
+        let ascii = theme.symbols == Symbols::Ascii;
+        let symbol = |unicode: &str, ascii_symbol: &str| {
+            if ascii { ascii_symbol } else { unicode }.to_string()
+        };
+        // Without colors every style is plain; high contrast trades the dimmed
+        // hints for bold, bright text.
+        let paint = |style: Style| {
+            if theme.colors {
+                style.for_stderr()
+            } else {
+                Style::new().for_stderr()
+            }
+        };
+        let accent = |fallback: Style| match theme.accent {
+            Some(color) => fallback.fg(console_color(color)),
+            None => fallback,
+        };
+        let dimmed = if theme.high_contrast {
+            Style::new().white().bright().bold()
+        } else {
+            Style::new().black().bright()
+        };
+        let prompt_prefix = theme.prefix.clone().unwrap_or_else(|| "?".to_string());
+        let colorful = ColorfulTheme {
+            defaults_style: paint(accent(Style::new().cyan())),
+            prompt_style: paint(Style::new().bold()),
+            prompt_prefix: paint(accent(Style::new().yellow())).apply_to(prompt_prefix),
+            prompt_suffix: paint(dimmed.clone()).apply_to(symbol("›", ">")),
+            success_prefix: paint(Style::new().green()).apply_to(symbol("✔", "+")),
+            success_suffix: paint(dimmed.clone()).apply_to(symbol("·", "-")),
+            error_prefix: paint(Style::new().red()).apply_to(symbol("✘", "x")),
+            error_style: paint(Style::new().red()),
+            hint_style: paint(dimmed),
+            values_style: paint(Style::new().green()),
+            active_item_style: paint(accent(Style::new().cyan())),
+            inactive_item_style: Style::new().for_stderr(),
+            active_item_prefix: paint(accent(Style::new().green()))
+                .apply_to(symbol("❯", ">")),
+            inactive_item_prefix: style(" ".to_string()).for_stderr(),
+            checked_item_prefix: paint(Style::new().green()).apply_to(symbol("✔", "[x]")),
+            unchecked_item_prefix: paint(Style::new().magenta())
+                .apply_to(symbol("⬚", "[ ]")),
+            picked_item_prefix: paint(accent(Style::new().green()))
+                .apply_to(symbol("❯", ">")),
+            unpicked_item_prefix: style(" ".to_string()).for_stderr(),
+            ..ColorfulTheme::default()
+        };
+        Self {
+            colorful,
+            yes: theme.answer_word(true).to_string(),
+            no: theme.answer_word(false).to_string(),
+        }
+    }
+}
+
+fn console_color(color: Color) -> dialoguer::console::Color {
+    use dialoguer::console::Color as Console;
+    match color {
+        Color::Black => Console::Black,
+        Color::Red => Console::Red,
+        Color::Green => Console::Green,
+        Color::Yellow => Console::Yellow,
+        Color::Blue => Console::Blue,
+        Color::Magenta => Console::Magenta,
+        Color::Cyan => Console::Cyan,
+        Color::White => Console::White,
+    }
+}
+
+impl Theme for DialoguerTheme {
+    fn format_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
+        self.colorful.format_prompt(f, prompt)
+    }
+
+    fn format_error(&self, f: &mut dyn fmt::Write, err: &str) -> fmt::Result {
+        self.colorful.format_error(f, err)
+    }
+
+    fn format_confirm_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: Option<bool>,
+    ) -> fmt::Result {
+        let theme = &self.colorful;
+        if !prompt.is_empty() {
+            write!(
+                f,
+                "{} {} ",
+                theme.prompt_prefix,
+                theme.prompt_style.apply_to(prompt)
+            )?;
+        }
+        write!(f, "{} {}", theme.hint_style.apply_to("(y/n)"), theme.prompt_suffix)?;
+        match default {
+            Some(default) => {
+                let word = if default { &self.yes } else { &self.no };
+                write!(f, " {}", theme.defaults_style.apply_to(word))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn format_confirm_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selection: Option<bool>,
+    ) -> fmt::Result {
+        let word = match selection {
+            Some(true) => &self.yes,
+            Some(false) => &self.no,
+            None => "",
+        };
+        self.colorful.format_input_prompt_selection(f, prompt, word)
+    }
+
+    fn format_input_prompt(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        default: Option<&str>,
+    ) -> fmt::Result {
+        self.colorful.format_input_prompt(f, prompt, default)
+    }
+
+    fn format_input_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        sel: &str,
+    ) -> fmt::Result {
+        self.colorful.format_input_prompt_selection(f, prompt, sel)
+    }
+
+    fn format_password_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+    ) -> fmt::Result {
+        self.colorful.format_password_prompt_selection(f, prompt)
+    }
+
+    fn format_multi_select_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selections: &[&str],
+    ) -> fmt::Result {
+        self.colorful.format_multi_select_prompt_selection(f, prompt, selections)
+    }
+
+    fn format_select_prompt_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        active: bool,
+    ) -> fmt::Result {
+        self.colorful.format_select_prompt_item(f, text, active)
+    }
+
+    fn format_multi_select_prompt_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        checked: bool,
+        active: bool,
+    ) -> fmt::Result {
+        self.colorful.format_multi_select_prompt_item(f, text, checked, active)
+    }
+
+    fn format_sort_prompt_item(
+        &self,
+        f: &mut dyn fmt::Write,
+        text: &str,
+        picked: bool,
+        active: bool,
+    ) -> fmt::Result {
+        self.colorful.format_sort_prompt_item(f, text, picked, active)
+    }
+}
@@ -14,6 +14,12 @@ use crate::{
 };
 use serde_json::Value;
 
+/// Added to the prompt of text questions that have a `description`.
+const HELP_HINT: &str = "? for help";
+
+/// Extra entry of choice and yes/no prompts of questions that have a `description`.
+const SHOW_HELP_CHOICE: &str = "? Show help";
+
 /// Coordinates a [`PromptProvider`] to ask a question described by a [`PromptContext`].
 ///
 /// The handler inspects the `Question` metadata, builds the appropriate configuration
@@ -36,38 +42,114 @@ impl<P: PromptProvider> PromptHandler<P> {
             QuestionType::Boolean => self.prompt_confirmation(prompt_context),
             QuestionType::Json => self.prompt_structured_data(prompt_context, false),
             QuestionType::Yaml => self.prompt_structured_data(prompt_context, true),
+            QuestionType::Object | QuestionType::List => {
+                self.prompt_structured_data(prompt_context, false)
+            }
         }
     }
 
+    /// The question's long-form help, if it has one.
+    fn help_page<'c>(&self, prompt_context: &PromptContext<'c>) -> Option<&'c str> {
+        let description = prompt_context.question.description.trim();
+        (!description.is_empty()).then_some(description)
+    }
+
+    /// Text answers of `?` show the help page and ask again. Secrets have no help
+    /// page, as `?` could be the secret.
     fn prompt_text(&self, prompt_context: &PromptContext) -> Result<Value> {
-        let config = self.create_text_config(prompt_context);
-        let result = self.provider.prompt_text(&config)?;
-        Ok(Value::String(result))
+        let mut config = self.create_text_config(prompt_context);
+        let help_page =
+            self.help_page(prompt_context).filter(|_| config.secret.is_none());
+        if help_page.is_some() {
+            config.prompt = format!("{} ({HELP_HINT})", config.prompt);
+        }
+        loop {
+            let result = self.provider.prompt_text(&config)?;
+            match help_page {
+                Some(page) if result.trim() == "?" => {
+                    self.provider.show_help_page(page)?
+                }
+                _ => return Ok(Value::String(result)),
+            }
+        }
     }
 
     fn prompt_single_choice(&self, prompt_context: &PromptContext) -> Result<Value> {
-        let config = self.create_single_choice_config(prompt_context);
-        let selection_index = self.provider.prompt_single_choice(&config)?;
-        let selected_choice = &prompt_context.question.choices[selection_index];
-        Ok(Value::String(selected_choice.clone()))
+        let mut config = self.create_single_choice_config(prompt_context);
+        let help_page = self.help_page(prompt_context);
+        if help_page.is_some() {
+            config.choices.push(SHOW_HELP_CHOICE.to_string());
+        }
+        loop {
+            let selection_index = self.provider.prompt_single_choice(&config)?;
+            match (help_page, prompt_context.question.choices.get(selection_index)) {
+                (_, Some(selected_choice)) => {
+                    return Ok(Value::String(selected_choice.clone()))
+                }
+                (Some(page), None) => self.provider.show_help_page(page)?,
+                (None, None) => return Err(invalid_selection(selection_index)),
+            }
+        }
     }
 
+    /// Selecting the help entry shows the help page and asks again, keeping the
+    /// other selections.
     fn prompt_multiple_choice(&self, prompt_context: &PromptContext) -> Result<Value> {
-        let config = self.create_multiple_choice_config(prompt_context);
-        let indices = self.provider.prompt_multiple_choice(&config)?;
-
-        let selected: Vec<Value> = indices
-            .iter()
-            .map(|&i| Value::String(prompt_context.question.choices[i].clone()))
-            .collect();
+        let choices = &prompt_context.question.choices;
+        let mut config = self.create_multiple_choice_config(prompt_context);
+        let help_page = self.help_page(prompt_context);
+        if help_page.is_some() {
+            config.choices.push(SHOW_HELP_CHOICE.to_string());
+            config.defaults.push(false);
+        }
+        loop {
+            let indices = self.provider.prompt_multiple_choice(&config)?;
+            if let Some(page) = help_page.filter(|_| indices.contains(&choices.len())) {
+                self.provider.show_help_page(page)?;
+                config.defaults = (0..config.choices.len())
+                    .map(|i| i != choices.len() && indices.contains(&i))
+                    .collect();
+                continue;
+            }
 
-        Ok(Value::Array(selected))
+            let selected = indices
+                .iter()
+                .map(|&i| {
+                    choices
+                        .get(i)
+                        .map(|choice| Value::String(choice.clone()))
+                        .ok_or_else(|| invalid_selection(i))
+                })
+                .collect::<Result<Vec<Value>>>()?;
+            return Ok(Value::Array(selected));
+        }
     }
 
+    /// Yes/no questions with a help page are asked as a choice between yes, no and
+    /// the help entry.
     fn prompt_confirmation(&self, prompt_context: &PromptContext) -> Result<Value> {
         let config = self.create_confirmation_config(prompt_context);
-        let result = self.provider.prompt_confirmation(&config)?;
-        Ok(Value::Bool(result))
+        let Some(page) = self.help_page(prompt_context) else {
+            let result = self.provider.prompt_confirmation(&config)?;
+            return Ok(Value::Bool(result));
+        };
+
+        let choices = SingleChoiceConfig {
+            prompt: config.prompt,
+            choices: vec![
+                "Yes".to_string(),
+                "No".to_string(),
+                SHOW_HELP_CHOICE.to_string(),
+            ],
+            default_index: Some(if config.default { 0 } else { 1 }),
+        };
+        loop {
+            match self.provider.prompt_single_choice(&choices)? {
+                0 => return Ok(Value::Bool(true)),
+                1 => return Ok(Value::Bool(false)),
+                _ => self.provider.show_help_page(page)?,
+            }
+        }
     }
 
     fn prompt_structured_data(
@@ -199,13 +281,17 @@ impl<P: PromptProvider> PromptHandler<P> {
     }
 }
 
+fn invalid_selection(index: usize) -> crate::error::Error {
+    crate::error::Error::Other(anyhow::anyhow!("Invalid selection {index}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{Question, Secret, Type, Validation};
     use crate::prompt::interface::{
-        ConfirmationPrompter, MultipleChoicePrompter, SingleChoicePrompter,
-        StructuredDataPrompter, TextPrompter,
+        ConfirmationPrompter, HelpPagePrompter, MultipleChoicePrompter,
+        SingleChoicePrompter, StructuredDataPrompter, TextPrompter,
     };
     use serde_json::json;
     use std::cell::RefCell;
@@ -225,6 +311,7 @@ mod tests {
         multiple_choice_calls: RefCell<Vec<MultipleChoiceConfig>>,
         confirmation_calls: RefCell<Vec<ConfirmationConfig>>,
         structured_data_calls: RefCell<Vec<StructuredDataConfig>>,
+        help_pages: RefCell<Vec<String>>,
     }
 
     impl MockProvider {
@@ -316,6 +403,13 @@ mod tests {
         }
     }
 
+    impl HelpPagePrompter for MockProvider {
+        fn show_help_page(&self, page: &str) -> Result<()> {
+            self.help_pages.borrow_mut().push(page.to_string());
+            Ok(())
+        }
+    }
+
     fn create_test_validation() -> Validation {
         Validation {
             condition: "true".to_string(),
@@ -335,6 +429,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -353,6 +452,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -368,6 +472,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -388,6 +497,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -403,6 +517,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -418,6 +537,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -433,6 +557,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: create_test_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         }
     }
 
@@ -576,6 +705,69 @@ mod tests {
         assert_eq!(calls[0].defaults, vec![true, true, false, false]);
     }
 
+    #[test]
+    fn test_question_mark_shows_description_and_asks_again() {
+        let mock = MockProvider::new()
+            .with_text_response("?".to_string())
+            .with_text_response("Jane".to_string());
+        let prompt_handler = PromptHandler::new(mock);
+        let mut question = create_text_question();
+        question.description = "# Name\nUsed in the README.".to_string();
+        let default_value = json!("John");
+        let context = PromptContext::new(&question, &default_value, "Enter your name");
+
+        let result = prompt_handler.create_prompt(&context).unwrap();
+
+        assert_eq!(result, json!("Jane"));
+        let calls = prompt_handler.provider.get_text_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].prompt, "Enter your name (? for help)");
+        assert_eq!(
+            *prompt_handler.provider.help_pages.borrow(),
+            ["# Name\nUsed in the README."]
+        );
+    }
+
+    #[test]
+    fn test_help_entry_of_choice_and_confirmation_prompts() {
+        let mock = MockProvider::new()
+            .with_single_choice_response(3)
+            .with_single_choice_response(2)
+            .with_multiple_choice_response(vec![1, 4])
+            .with_multiple_choice_response(vec![1])
+            .with_single_choice_response(2)
+            .with_single_choice_response(1);
+        let prompt_handler = PromptHandler::new(mock);
+
+        let mut color = create_single_choice_question();
+        color.description = "Colors".to_string();
+        let default_color = json!("blue");
+        let context = PromptContext::new(&color, &default_color, "Color");
+        assert_eq!(prompt_handler.create_prompt(&context).unwrap(), json!("green"));
+        let calls = prompt_handler.provider.get_single_choice_calls();
+        assert_eq!(calls[0].choices, ["red", "blue", "green", "? Show help"]);
+
+        let mut languages = create_multiple_choice_question();
+        languages.description = "Languages".to_string();
+        let no_languages = json!([]);
+        let context = PromptContext::new(&languages, &no_languages, "Languages");
+        assert_eq!(prompt_handler.create_prompt(&context).unwrap(), json!(["python"]));
+        let calls = prompt_handler.provider.get_multiple_choice_calls();
+        assert_eq!(calls[1].defaults, [false, true, false, false, false]);
+
+        let mut proceed = create_boolean_question();
+        proceed.description = "Proceed".to_string();
+        let yes = json!(true);
+        let context = PromptContext::new(&proceed, &yes, "Continue?");
+        assert_eq!(prompt_handler.create_prompt(&context).unwrap(), json!(false));
+        assert!(prompt_handler.provider.get_confirmation_calls().is_empty());
+
+        assert_eq!(
+            *prompt_handler.provider.help_pages.borrow(),
+            ["Colors", "Languages", "Proceed"]
+        );
+    }
+
     #[test]
     fn test_prompt_multiple_choice_empty_defaults() {
         let mock = MockProvider::new().with_multiple_choice_response(vec![2]);
@@ -0,0 +1,91 @@
+//! Prompt provider used when Baker is built without the `terminal-ui` feature.
+//!
+//! Every prompt fails with a clear error, so headless embeddings must supply all
+//! answers up front (or use a non-interactive `AnswerSource`).
+
+use super::interface::{
+    ConfirmationConfig, ConfirmationPrompter, HelpPagePrompter, MultipleChoiceConfig,
+    MultipleChoicePrompter, SingleChoiceConfig, SingleChoicePrompter,
+    StructuredDataConfig, StructuredDataPrompter, TextPromptConfig, TextPrompter,
+};
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// Prompt provider that refuses to prompt.
+#[derive(Debug, Default)]
+pub struct HeadlessPrompter;
+
+impl HeadlessPrompter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn unavailable<T>(prompt: &str) -> Result<T> {
+        Err(Error::Other(anyhow::anyhow!(
+            "Cannot prompt for '{prompt}': interactive prompts are unavailable in this build (enable the `terminal-ui` feature)"
+        )))
+    }
+}
+
+impl TextPrompter for HeadlessPrompter {
+    fn prompt_text(&self, config: &TextPromptConfig) -> Result<String> {
+        Self::unavailable(&config.prompt)
+    }
+}
+
+impl SingleChoicePrompter for HeadlessPrompter {
+    fn prompt_single_choice(&self, config: &SingleChoiceConfig) -> Result<usize> {
+        Self::unavailable(&config.prompt)
+    }
+}
+
+impl MultipleChoicePrompter for HeadlessPrompter {
+    fn prompt_multiple_choice(
+        &self,
+        config: &MultipleChoiceConfig,
+    ) -> Result<Vec<usize>> {
+        Self::unavailable(&config.prompt)
+    }
+}
+
+impl ConfirmationPrompter for HeadlessPrompter {
+    fn prompt_confirmation(&self, config: &ConfirmationConfig) -> Result<bool> {
+        Self::unavailable(&config.prompt)
+    }
+}
+
+impl StructuredDataPrompter for HeadlessPrompter {
+    fn prompt_structured_data(&self, config: &StructuredDataConfig) -> Result<Value> {
+        Self::unavailable(&config.prompt)
+    }
+}
+
+impl HelpPagePrompter for HeadlessPrompter {
+    fn show_help_page(&self, _page: &str) -> Result<()> {
+        Self::unavailable("help")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_prompt_fails() {
+        let prompter = HeadlessPrompter::new();
+        let err = prompter
+            .prompt_confirmation(&ConfirmationConfig {
+                prompt: "Continue?".to_string(),
+                default: false,
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("terminal-ui"));
+        assert!(prompter
+            .prompt_text(&TextPromptConfig {
+                prompt: "Name".to_string(),
+                default: None,
+                secret: None,
+            })
+            .is_err());
+    }
+}
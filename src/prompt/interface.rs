@@ -79,6 +79,11 @@ pub trait StructuredDataPrompter {
     fn prompt_structured_data(&self, config: &StructuredDataConfig) -> Result<Value>;
 }
 
+/// Abstract interface for showing the long-form help of a question
+pub trait HelpPagePrompter {
+    fn show_help_page(&self, page: &str) -> Result<()>;
+}
+
 /// Combined interface that provides all prompt types
 pub trait PromptProvider:
     TextPrompter
@@ -86,9 +91,50 @@ pub trait PromptProvider:
     + MultipleChoicePrompter
     + ConfirmationPrompter
     + StructuredDataPrompter
+    + HelpPagePrompter
 {
 }
 
+// Boxed providers forward to the provider inside, so one can be chosen at runtime.
+impl<T: TextPrompter + ?Sized> TextPrompter for Box<T> {
+    fn prompt_text(&self, config: &TextPromptConfig) -> Result<String> {
+        (**self).prompt_text(config)
+    }
+}
+
+impl<T: SingleChoicePrompter + ?Sized> SingleChoicePrompter for Box<T> {
+    fn prompt_single_choice(&self, config: &SingleChoiceConfig) -> Result<usize> {
+        (**self).prompt_single_choice(config)
+    }
+}
+
+impl<T: MultipleChoicePrompter + ?Sized> MultipleChoicePrompter for Box<T> {
+    fn prompt_multiple_choice(
+        &self,
+        config: &MultipleChoiceConfig,
+    ) -> Result<Vec<usize>> {
+        (**self).prompt_multiple_choice(config)
+    }
+}
+
+impl<T: ConfirmationPrompter + ?Sized> ConfirmationPrompter for Box<T> {
+    fn prompt_confirmation(&self, config: &ConfirmationConfig) -> Result<bool> {
+        (**self).prompt_confirmation(config)
+    }
+}
+
+impl<T: StructuredDataPrompter + ?Sized> StructuredDataPrompter for Box<T> {
+    fn prompt_structured_data(&self, config: &StructuredDataConfig) -> Result<Value> {
+        (**self).prompt_structured_data(config)
+    }
+}
+
+impl<T: HelpPagePrompter + ?Sized> HelpPagePrompter for Box<T> {
+    fn show_help_page(&self, page: &str) -> Result<()> {
+        (**self).show_help_page(page)
+    }
+}
+
 // Blanket implementation for any type that implements all prompt interfaces
 impl<T> PromptProvider for T where
     T: TextPrompter
@@ -96,5 +142,6 @@ impl<T> PromptProvider for T where
         + MultipleChoicePrompter
         + ConfirmationPrompter
         + StructuredDataPrompter
+        + HelpPagePrompter
 {
 }
@@ -2,18 +2,29 @@
 //!
 //! The prompt subsystem is split into layers:
 //! - [`interface`]: transport-agnostic traits and configs.
-//! - [`dialoguer`]: the default terminal implementation.
+//! - [`dialoguer`]: the default terminal implementation (`terminal-ui` feature).
+//! - [`headless`]: a provider that refuses to prompt, used without `terminal-ui`.
+//! - [`plain`]: line-based prompts for screen readers, with `--plain-prompts`.
 //! - [`handler`]: orchestration that chooses which prompt to display.
 //! - [`context`]: immutable data passed to prompt providers.
 //! - [`provider`]: convenience helpers exposed to the rest of the crate.
+//! - [`pager`]: shows a question's long-form help through `$PAGER`.
+//! - [`timeout`]: waits for an answer to start before a prompt's timeout.
+//! - [`theme`]: the look of the terminal prompts.
 
 pub mod context;
+#[cfg(feature = "terminal-ui")]
 pub mod dialoguer;
 pub mod handler;
+pub mod headless;
 pub mod interface;
+pub mod pager;
 pub mod parser;
+pub mod plain;
 pub mod provider;
+pub mod theme;
+pub mod timeout;
 
 pub use context::PromptContext;
 pub use interface::*;
-pub use provider::{ask_question, confirm, get_prompt_provider, Prompter};
+pub use provider::{ask_question, confirm, get_prompt_provider, plain_prompts, Prompter};
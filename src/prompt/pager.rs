@@ -0,0 +1,38 @@
+//! Shows the long-form `description` of a question on demand.
+
+use crate::error::Result;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Pipes `page` through `$PAGER`, or prints it when no pager is set or it cannot
+/// be started.
+pub fn show_help_page(page: &str) -> Result<()> {
+    let pager = std::env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty());
+    if let Some(pager) = pager {
+        match run_pager(&pager, page) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::debug!("Cannot run pager '{pager}': {e}"),
+        }
+    }
+    println!("\n{page}\n");
+    Ok(())
+}
+
+fn run_pager(pager: &str, page: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", pager]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", pager]);
+        command
+    };
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything.
+        let _ = stdin.write_all(page.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! Line-based prompt provider for screen readers and dumb terminals.
+//!
+//! Every prompt is a line of text followed by a line of input: choices are listed
+//! with numbers, and nothing is redrawn or moved with the cursor. Selected with
+//! `--plain-prompts`.
+
+use super::interface::{
+    ConfirmationConfig, ConfirmationPrompter, HelpPagePrompter, MultipleChoiceConfig,
+    MultipleChoicePrompter, SingleChoiceConfig, SingleChoicePrompter,
+    StructuredDataConfig, StructuredDataPrompter, TextPromptConfig, TextPrompter,
+};
+use super::{parser::DataParser, theme::current_theme};
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    io::{BufRead, Write},
+};
+
+/// Prompt provider reading answers line by line from `input`.
+pub struct PlainPrompter<R, W> {
+    input: RefCell<R>,
+    output: RefCell<W>,
+    /// Whether secrets are read with the terminal echo off.
+    hide_secrets: bool,
+}
+
+impl<R: BufRead, W: Write> PlainPrompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self {
+            input: RefCell::new(input),
+            output: RefCell::new(output),
+            hide_secrets: false,
+        }
+    }
+
+    /// Writes `text` without a line break, for the answer to follow on the same line.
+    fn write(&self, text: &str) -> Result<()> {
+        let mut output = self.output.borrow_mut();
+        output.write_all(text.as_bytes())?;
+        output.flush()?;
+        Ok(())
+    }
+
+    fn write_line(&self, text: &str) -> Result<()> {
+        self.write(&format!("{text}\n"))
+    }
+
+    /// Asks `prompt` and reads the answer, trimmed of surrounding whitespace.
+    fn ask(&self, prompt: &str) -> Result<String> {
+        self.write(prompt)?;
+        let mut line = String::new();
+        if self.input.borrow_mut().read_line(&mut line)? == 0 {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Input ended before '{}' was answered",
+                prompt.trim_end().trim_end_matches(':')
+            )));
+        }
+        Ok(line.trim().to_string())
+    }
+
+    fn ask_secret(&self, prompt: &str) -> Result<String> {
+        if !self.hide_secrets {
+            return self.ask(prompt);
+        }
+        let answer = without_echo(|| self.ask(prompt));
+        // The line break typed after the secret was not echoed either.
+        self.write_line("")?;
+        answer
+    }
+}
+
+impl PlainPrompter<std::io::StdinLock<'static>, std::io::Stderr> {
+    /// Reads answers from stdin and writes prompts to stderr.
+    pub fn stdio() -> Self {
+        Self {
+            hide_secrets: true,
+            ..Self::new(std::io::stdin().lock(), std::io::stderr())
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> TextPrompter for PlainPrompter<R, W> {
+    fn prompt_text(&self, config: &TextPromptConfig) -> Result<String> {
+        let Some(secret) = &config.secret else {
+            let answer = match config.default.as_deref().filter(|d| !d.is_empty()) {
+                Some(default) => self.ask(&format!("{} [{default}]: ", config.prompt))?,
+                None => self.ask(&format!("{}: ", config.prompt))?,
+            };
+            return Ok(match answer.is_empty() {
+                true => config.default.clone().unwrap_or_default(),
+                false => answer,
+            });
+        };
+        loop {
+            let answer = self.ask_secret(&format!("{}: ", config.prompt))?;
+            if !secret.confirm
+                || self.ask_secret(&format!("{} (confirm): ", config.prompt))? == answer
+            {
+                return Ok(answer);
+            }
+            let message = match secret.mismatch_error.as_str() {
+                "" => "Mismatch",
+                message => message,
+            };
+            self.write_line(message)?;
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> SingleChoicePrompter for PlainPrompter<R, W> {
+    fn prompt_single_choice(&self, config: &SingleChoiceConfig) -> Result<usize> {
+        self.write_line(&config.prompt)?;
+        for (index, choice) in config.choices.iter().enumerate() {
+            self.write_line(&format!("  {}) {choice}", index + 1))?;
+        }
+        let prompt = match config.default_index {
+            Some(default) => format!("Choose a number [{}]: ", default + 1),
+            None => "Choose a number: ".to_string(),
+        };
+        loop {
+            let answer = self.ask(&prompt)?;
+            if answer.is_empty() {
+                if let Some(default) = config.default_index {
+                    return Ok(default);
+                }
+            }
+            if let Some(index) = choice_index(&answer, &config.choices) {
+                return Ok(index);
+            }
+            self.write_line(&format!(
+                "Enter a number from 1 to {}.",
+                config.choices.len()
+            ))?;
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> MultipleChoicePrompter for PlainPrompter<R, W> {
+    fn prompt_multiple_choice(
+        &self,
+        config: &MultipleChoiceConfig,
+    ) -> Result<Vec<usize>> {
+        self.write_line(&config.prompt)?;
+        for (index, choice) in config.choices.iter().enumerate() {
+            self.write_line(&format!("  {}) {choice}", index + 1))?;
+        }
+        let defaults: Vec<usize> = config
+            .defaults
+            .iter()
+            .enumerate()
+            .filter_map(|(index, selected)| selected.then_some(index))
+            .collect();
+        let shown: Vec<String> = defaults.iter().map(|i| (i + 1).to_string()).collect();
+        let prompt = format!(
+            "Choose numbers separated by commas, or `none` [{}]: ",
+            match shown.is_empty() {
+                true => "none".to_string(),
+                false => shown.join(","),
+            }
+        );
+        loop {
+            let answer = self.ask(&prompt)?;
+            if answer.is_empty() {
+                return Ok(defaults);
+            }
+            if answer.eq_ignore_ascii_case("none") {
+                return Ok(Vec::new());
+            }
+            let indices: Option<Vec<usize>> = answer
+                .split(',')
+                .map(|part| choice_index(part.trim(), &config.choices))
+                .collect();
+            match indices {
+                Some(mut indices) => {
+                    indices.sort_unstable();
+                    indices.dedup();
+                    return Ok(indices);
+                }
+                None => self.write_line(&format!(
+                    "Enter numbers from 1 to {}, separated by commas.",
+                    config.choices.len()
+                ))?,
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> ConfirmationPrompter for PlainPrompter<R, W> {
+    fn prompt_confirmation(&self, config: &ConfirmationConfig) -> Result<bool> {
+        let theme = current_theme();
+        let prompt =
+            format!("{} (y/n) [{}]: ", config.prompt, theme.answer_word(config.default));
+        loop {
+            let answer = self.ask(&prompt)?.to_lowercase();
+            match answer.as_str() {
+                "" => return Ok(config.default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                word if word == theme.answer_word(true).to_lowercase() => {
+                    return Ok(true)
+                }
+                word if word == theme.answer_word(false).to_lowercase() => {
+                    return Ok(false)
+                }
+                _ => self.write_line("Answer y or n.")?,
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> StructuredDataPrompter for PlainPrompter<R, W> {
+    fn prompt_structured_data(&self, config: &StructuredDataConfig) -> Result<Value> {
+        let format = if config.is_yaml { "YAML" } else { "JSON" };
+        let default = serde_json::to_string(&config.default_value)?;
+        let prompt = format!("{} ({format} on one line) [{default}]: ", config.prompt);
+        loop {
+            let answer = self.ask(&prompt)?;
+            if answer.is_empty() {
+                return Ok(config.default_value.clone());
+            }
+            match DataParser::parse_structured_content(&answer, config.is_yaml) {
+                Ok(value) => return Ok(value),
+                Err(e) => self.write_line(&e.to_string())?,
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> HelpPagePrompter for PlainPrompter<R, W> {
+    fn show_help_page(&self, page: &str) -> Result<()> {
+        self.write_line(page.trim_end())
+    }
+}
+
+/// The index of the choice `answer` names, by number or by its text.
+fn choice_index(answer: &str, choices: &[String]) -> Option<usize> {
+    match answer.parse::<usize>() {
+        Ok(number) => (1..=choices.len()).contains(&number).then(|| number - 1),
+        Err(_) => choices.iter().position(|choice| choice == answer),
+    }
+}
+
+/// Runs `read` with the terminal echo off, so a typed secret is not shown.
+#[cfg(unix)]
+fn without_echo<T>(read: impl FnOnce() -> T) -> T {
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: `termios` is plain data that `tcgetattr` fills in; it fails without
+    // touching it when stdin is not a terminal.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return read();
+    }
+    let mut hidden = original;
+    hidden.c_lflag &= !libc::ECHO;
+    // SAFETY: both are valid settings of the terminal on `fd`.
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &hidden) };
+    let result = read();
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    result
+}
+
+#[cfg(not(unix))]
+fn without_echo<T>(read: impl FnOnce() -> T) -> T {
+    read()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompter(input: &str) -> PlainPrompter<&[u8], Vec<u8>> {
+        PlainPrompter::new(input.as_bytes(), Vec::new())
+    }
+
+    fn output(prompter: PlainPrompter<&[u8], Vec<u8>>) -> String {
+        String::from_utf8(prompter.output.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn asks_line_by_line_and_retries_invalid_answers() {
+        let choice = prompter("4\nrust\n");
+        let config = SingleChoiceConfig {
+            prompt: "Language".to_string(),
+            choices: vec!["python".to_string(), "rust".to_string()],
+            default_index: Some(0),
+        };
+        assert_eq!(choice.prompt_single_choice(&config).unwrap(), 1);
+        assert_eq!(
+            output(choice),
+            "Language\n  1) python\n  2) rust\nChoose a number [1]: \
+             Enter a number from 1 to 2.\nChoose a number [1]: "
+        );
+
+        let multiple = prompter("3, 1\n");
+        let config = MultipleChoiceConfig {
+            prompt: "Features".to_string(),
+            choices: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            defaults: vec![false, true, false],
+        };
+        assert_eq!(multiple.prompt_multiple_choice(&config).unwrap(), [0, 2]);
+        assert!(output(multiple).ends_with("or `none` [2]: "));
+
+        let text = prompter("\n");
+        let config = TextPromptConfig {
+            prompt: "Name".to_string(),
+            default: Some("demo".to_string()),
+            secret: None,
+        };
+        assert_eq!(text.prompt_text(&config).unwrap(), "demo");
+
+        let confirm = prompter("maybe\nY\n");
+        let config =
+            ConfirmationConfig { prompt: "Continue?".to_string(), default: false };
+        assert!(confirm.prompt_confirmation(&config).unwrap());
+        assert!(output(confirm).contains("Answer y or n."));
+
+        let ended = prompter("");
+        let err = ended.prompt_confirmation(&config).unwrap_err();
+        assert!(err.to_string().contains("Input ended"));
+    }
+}
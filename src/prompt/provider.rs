@@ -3,20 +3,35 @@ use crate::{
     error::Result,
 };
 use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use super::{
-    context::PromptContext, dialoguer::DialoguerPrompter, handler::PromptHandler,
-    interface::PromptProvider,
-};
+use super::{context::PromptContext, handler::PromptHandler, interface::PromptProvider};
 
 /// Trait implemented by prompt backends that can render a question via a [`PromptContext`].
 pub trait Prompter<'a> {
     fn prompt(&self, prompt_context: &PromptContext<'a>) -> Result<Value>;
 }
 
-/// Convenience function to construct the default terminal prompt provider.
-pub fn get_prompt_provider() -> impl PromptProvider {
-    DialoguerPrompter::new()
+static PLAIN_PROMPTS: AtomicBool = AtomicBool::new(false);
+
+/// Switches every prompt that follows to line-based questions and answers.
+pub fn plain_prompts(plain: bool) {
+    PLAIN_PROMPTS.store(plain, Ordering::Relaxed);
+}
+
+/// Convenience function to construct the prompt provider: the plain one with
+/// `--plain-prompts`, otherwise the terminal one. Without the `terminal-ui` feature
+/// the terminal provider is headless, and every prompt fails.
+pub fn get_prompt_provider() -> Box<dyn PromptProvider> {
+    if PLAIN_PROMPTS.load(Ordering::Relaxed) {
+        return Box::new(super::plain::PlainPrompter::stdio());
+    }
+    #[cfg(feature = "terminal-ui")]
+    return Box::new(super::dialoguer::DialoguerPrompter::with_theme(
+        &super::theme::current_theme(),
+    ));
+    #[cfg(not(feature = "terminal-ui"))]
+    return Box::new(super::headless::HeadlessPrompter::new());
 }
 
 /// High-level helper that collects an answer for a single configuration question.
@@ -37,6 +52,11 @@ pub fn get_prompt_provider() -> impl PromptProvider {
 /// #     schema: None,
 /// #     schema_file: None,
 /// #     validation: baker::config::types::get_default_validation(),
+/// #     properties: Default::default(),
+/// #     item: None,
+/// #     description: String::new(),
+/// #     timeout: None,
+/// #     sets: Vec::new(),
 /// # };
 /// # let default = json!("demo");
 /// let answer = ask_question(&question, &default, "Project name".to_string())?;
@@ -47,9 +67,12 @@ pub fn ask_question(question: &Question, default: &Value, help: String) -> Resul
     ask_question_with_provider(question, default, &help, get_prompt_provider())
 }
 
-/// Confirmation helper used for compatibility with legacy call sites.
+/// Confirmation helper used for compatibility with legacy call sites. Asked in
+/// the user's theme, never a template's.
 pub fn confirm(skip: bool, prompt: String) -> Result<bool> {
-    confirm_with_provider(skip, prompt, get_prompt_provider())
+    super::theme::with_user_theme(|| {
+        confirm_with_provider(skip, prompt, get_prompt_provider())
+    })
 }
 
 fn ask_question_with_provider<'a, P: PromptProvider>(
@@ -83,6 +106,11 @@ fn confirm_with_provider<P: PromptProvider>(
         schema: None,
         schema_file: None,
         validation: get_default_validation(),
+        properties: Default::default(),
+        item: None,
+        description: String::new(),
+        timeout: None,
+        sets: Vec::new(),
     };
 
     let default_value = Value::Bool(false);
@@ -96,7 +124,7 @@ fn confirm_with_provider<P: PromptProvider>(
 #[cfg(test)]
 mod tests {
     use super::super::interface::{
-        ConfirmationConfig, ConfirmationPrompter, MultipleChoiceConfig,
+        ConfirmationConfig, ConfirmationPrompter, HelpPagePrompter, MultipleChoiceConfig,
         MultipleChoicePrompter, SingleChoiceConfig, SingleChoicePrompter,
         StructuredDataConfig, StructuredDataPrompter, TextPromptConfig, TextPrompter,
     };
@@ -140,6 +168,12 @@ mod tests {
         }
     }
 
+    impl HelpPagePrompter for TestPromptProvider {
+        fn show_help_page(&self, _page: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
     impl<'a> Prompter<'a> for TestPromptProvider {
         fn prompt(&self, context: &PromptContext<'a>) -> Result<Value> {
             match context.question.r#type {
@@ -178,6 +212,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let answer = super::ask_question_with_provider(
             &question,
@@ -221,6 +260,11 @@ mod tests {
             schema: None,
             schema_file: None,
             validation: get_default_validation(),
+            properties: Default::default(),
+            item: None,
+            description: String::new(),
+            timeout: None,
+            sets: Vec::new(),
         };
         let context = PromptContext::new(&question, &Value::Bool(false), "Help");
         let result = provider.prompt(&context);
@@ -0,0 +1,253 @@
+//! Look of the terminal prompts: prefix, colors, symbols and yes/no words.
+//!
+//! The theme comes from `prompt_theme` in the user config. A template can set its
+//! own prefix, accent color and yes/no words in its `prompt_theme`, but only where
+//! the user config leaves them unset, and never symbols the user switched off with
+//! `symbols: ascii`.
+
+use crate::config::UserConfig;
+use crate::error::Result;
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::{LazyLock, Mutex};
+
+/// Longest prompt prefix a template may set, in characters.
+pub const MAX_PREFIX_LEN: usize = 4;
+/// Longest yes/no word a template may set, in characters.
+pub const MAX_WORD_LEN: usize = 16;
+
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b(\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(\x07|\x1b\\)?|.)")
+        .expect("valid ANSI escape regex")
+});
+
+/// The symbols drawn around prompts and choices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Symbols {
+    /// `✔`, `❯`, `›` and friends.
+    #[default]
+    Unicode,
+    /// Plain ASCII, for terminals without Unicode fonts.
+    Ascii,
+}
+
+/// A terminal color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+/// The prompt theme from the user config.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PromptTheme {
+    /// Shown before every question, `?` by default.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Color of the prefix, defaults and the selected choice.
+    #[serde(default)]
+    pub accent: Option<Color>,
+    /// Word shown for a `true` answer, `yes` by default.
+    #[serde(default)]
+    pub yes: Option<String>,
+    /// Word shown for a `false` answer, `no` by default.
+    #[serde(default)]
+    pub no: Option<String>,
+    #[serde(default)]
+    pub symbols: Symbols,
+    /// Whether prompts are colored at all.
+    #[serde(default = "get_default_colors")]
+    pub colors: bool,
+    /// Bold, bright colors instead of dimmed hints, for low-contrast terminals.
+    #[serde(default)]
+    pub high_contrast: bool,
+}
+
+impl Default for PromptTheme {
+    fn default() -> Self {
+        Self {
+            prefix: None,
+            accent: None,
+            yes: None,
+            no: None,
+            symbols: Symbols::default(),
+            colors: get_default_colors(),
+            high_contrast: false,
+        }
+    }
+}
+
+/// The part of the theme a template may set in its `prompt_theme`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplatePromptTheme {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub accent: Option<Color>,
+    #[serde(default)]
+    pub yes: Option<String>,
+    #[serde(default)]
+    pub no: Option<String>,
+}
+
+impl PromptTheme {
+    /// This theme with the template's settings filling the fields it leaves unset.
+    /// Non-ASCII template text is ignored when the user asked for ASCII symbols.
+    /// Template text loses control characters and escape sequences and is cut to
+    /// [`MAX_PREFIX_LEN`] or [`MAX_WORD_LEN`] characters; yes/no words that could
+    /// be read as the opposite answer are ignored.
+    pub fn with_template(&self, template: &TemplatePromptTheme) -> Self {
+        let allowed = |text: &Option<String>, max_len: usize| {
+            text.as_deref()
+                .map(|text| sanitize(text, max_len))
+                .filter(|text| !text.is_empty())
+                .filter(|text| self.symbols == Symbols::Unicode || text.is_ascii())
+        };
+        let mut yes = allowed(&template.yes, MAX_WORD_LEN);
+        let mut no = allowed(&template.no, MAX_WORD_LEN);
+        let reads_as = |word: &Option<String>, words: [&str; 2]| {
+            word.as_deref()
+                .is_some_and(|word| words.contains(&word.to_lowercase().as_str()))
+        };
+        if reads_as(&yes, ["n", "no"]) {
+            yes = None;
+        }
+        if reads_as(&no, ["y", "yes"]) {
+            no = None;
+        }
+        if yes.is_some()
+            && yes.as_deref().map(str::to_lowercase)
+                == no.as_deref().map(str::to_lowercase)
+        {
+            (yes, no) = (None, None);
+        }
+        Self {
+            prefix: self
+                .prefix
+                .clone()
+                .or_else(|| allowed(&template.prefix, MAX_PREFIX_LEN)),
+            accent: self.accent.or(template.accent),
+            yes: self.yes.clone().or(yes),
+            no: self.no.clone().or(no),
+            ..self.clone()
+        }
+    }
+
+    /// The word for `answer`.
+    pub fn answer_word(&self, answer: bool) -> &str {
+        match answer {
+            true => self.yes.as_deref().unwrap_or("yes"),
+            false => self.no.as_deref().unwrap_or("no"),
+        }
+    }
+}
+
+static THEME: Mutex<Option<PromptTheme>> = Mutex::new(None);
+/// The theme of the user config alone, once a template's theme is set.
+static USER_THEME: Mutex<Option<PromptTheme>> = Mutex::new(None);
+
+/// Sets the theme of every prompt that follows.
+pub fn set_theme(theme: PromptTheme) {
+    *THEME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(theme);
+}
+
+/// The theme set last, or the default one.
+pub fn current_theme() -> PromptTheme {
+    THEME
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Sets the theme of the user config, completed by a template's `prompt_theme`.
+pub fn set_template_theme(template: &TemplatePromptTheme) -> Result<()> {
+    let user = UserConfig::load()?.prompt_theme;
+    set_theme(user.with_template(template));
+    *USER_THEME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(user);
+    Ok(())
+}
+
+/// Runs `f` with the theme of the user config alone, so that Baker's own
+/// confirmations, such as whether to run a template's hooks, never take the
+/// look or the yes/no words of the template asking them.
+pub fn with_user_theme<T>(f: impl FnOnce() -> T) -> T {
+    let user = USER_THEME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    let Some(user) = user else {
+        return f();
+    };
+    let previous =
+        THEME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).replace(user);
+    let result = f();
+    *THEME.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = previous;
+    result
+}
+
+/// `text` without escape sequences and control characters, cut to `max_len`
+/// characters.
+fn sanitize(text: &str, max_len: usize) -> String {
+    ANSI_ESCAPE
+        .replace_all(text, "")
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(max_len)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn get_default_colors() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_fills_only_what_the_user_left_unset() {
+        let user: PromptTheme =
+            serde_yaml::from_str("symbols: ascii\nyes: Y\naccent: yellow\n").unwrap();
+        let template: TemplatePromptTheme =
+            serde_yaml::from_str("prefix: \"🍞\"\nyes: oui\nno: non\naccent: magenta\n")
+                .unwrap();
+
+        let theme = user.with_template(&template);
+        assert_eq!(theme.prefix, None);
+        assert_eq!(theme.accent, Some(Color::Yellow));
+        assert_eq!(theme.answer_word(true), "Y");
+        assert_eq!(theme.answer_word(false), "non");
+        assert_eq!(theme.symbols, Symbols::Ascii);
+
+        let theme = PromptTheme::default().with_template(&template);
+        assert_eq!(theme.prefix.as_deref(), Some("🍞"));
+        assert!(serde_yaml::from_str::<TemplatePromptTheme>("colors: false").is_err());
+    }
+
+    #[test]
+    fn template_text_is_cleaned_and_cannot_swap_answers() {
+        let template: TemplatePromptTheme = serde_yaml::from_str(
+            "prefix: \"\\e[2J\\e[31m>>>>>>>>\"\nyes: \"no\"\nno: \"si\\u0007\\nlong enough to be cut\"\n",
+        )
+        .unwrap();
+        let theme = PromptTheme::default().with_template(&template);
+        assert_eq!(theme.prefix.as_deref(), Some(">>>>"));
+        assert_eq!(theme.answer_word(true), "yes");
+        assert_eq!(theme.answer_word(false), "silong enough to");
+
+        let template: TemplatePromptTheme =
+            serde_yaml::from_str("yes: Ja\nno: ja\n").unwrap();
+        let theme = PromptTheme::default().with_template(&template);
+        assert_eq!((theme.answer_word(true), theme.answer_word(false)), ("yes", "no"));
+    }
+}
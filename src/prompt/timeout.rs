@@ -0,0 +1,43 @@
+//! Waiting for the first key of an answer, so a prompt can accept its default when
+//! nobody answers in time.
+//!
+//! The wait only peeks at the terminal: a pressed key stays in the input buffer, so
+//! the prompt that follows reads it as the start of the answer.
+
+use std::time::Duration;
+
+/// Waits up to `timeout` for input on stdin. Returns `true` as soon as a key is
+/// pressed, and `false` when the time ran out. Stdin that is not a terminal counts
+/// as input, as do platforms where the wait is not supported.
+#[cfg(unix)]
+pub fn wait_for_key(timeout: Duration) -> bool {
+    let fd = libc::STDIN_FILENO;
+    // SAFETY: `termios` is plain data that `tcgetattr` fills in; it fails without
+    // touching it when stdin is not a terminal.
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+        return true;
+    }
+    // Without canonical mode a single key is readable at once, not a whole line.
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 1;
+    raw.c_cc[libc::VTIME] = 0;
+    let mut poll = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+    let millis = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+    // SAFETY: `raw` and `original` are valid settings of the terminal on `fd`, and
+    // `poll` points to a single initialized `pollfd`.
+    let ready = unsafe {
+        libc::tcsetattr(fd, libc::TCSANOW, &raw);
+        let ready = libc::poll(&mut poll, 1, millis);
+        libc::tcsetattr(fd, libc::TCSANOW, &original);
+        ready
+    };
+    // A signal interrupting the wait, such as a resize, is not worth the default.
+    ready != 0
+}
+
+#[cfg(not(unix))]
+pub fn wait_for_key(_timeout: Duration) -> bool {
+    true
+}
@@ -1,5 +1,14 @@
+use crate::loader::git::GitLoader;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
 use log::warn;
+use minijinja::{value::Kwargs, Error, ErrorKind, State};
 use regex::Regex;
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
+    path::{Component, Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
 
 // Re-export all the case conversion and string manipulation functions
 pub use cruet::{
@@ -23,15 +32,513 @@ pub use cruet::{
 /// # Returns
 /// * `bool` - True if the string matches the pattern, false otherwise
 pub fn regex_filter(val: &str, re: &str) -> bool {
-    match Regex::new(re) {
+    match compiled_regex(re) {
         Ok(re) => re.is_match(val),
         Err(err) => {
-            warn!("Invalid regex '{re}': {err}");
+            warn!("{err}");
             false
         }
     }
 }
 
+/// Compiles `pattern` once and reuses it for every later render.
+fn compiled_regex(pattern: &str) -> Result<Regex, Error> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+    let mut cache =
+        CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(re) = cache.get(pattern) {
+        return Ok(re.clone());
+    }
+    let re = Regex::new(pattern)
+        .map_err(|e| invalid(format!("Invalid regex '{pattern}': {e}")))?;
+    cache.insert(pattern.to_string(), re.clone());
+    Ok(re)
+}
+
+/// Replaces every match of `pattern`; the replacement may refer to groups as `$1` or `${name}`.
+///
+/// Usage: `{{ "my-app_v2" | regex_replace("[^a-z0-9]+", "_") }}` renders `my_app_v2`.
+pub fn regex_replace(
+    val: &str,
+    pattern: &str,
+    replacement: &str,
+) -> Result<String, Error> {
+    Ok(compiled_regex(pattern)?.replace_all(val, replacement).into_owned())
+}
+
+/// Returns the first match of `pattern`, or the given capture group of it, and
+/// `none` when nothing matches.
+///
+/// Usage: `{{ "v1.2.3" | regex_search("\\d+\\.\\d+") }}` renders `1.2`.
+pub fn regex_search(
+    val: &str,
+    pattern: &str,
+    group: Option<usize>,
+) -> Result<Option<String>, Error> {
+    let re = compiled_regex(pattern)?;
+    let Some(captures) = re.captures(val) else {
+        return Ok(None);
+    };
+    let group = group.unwrap_or(0);
+    if group >= captures.len() {
+        return Err(invalid(format!("Regex '{pattern}' has no group {group}")));
+    }
+    Ok(captures.get(group).map(|m| m.as_str().to_string()))
+}
+
+/// Splits a string on every match of `pattern`.
+///
+/// Usage: `{{ "a, b;c" | regex_split("[,;]\\s*") }}` renders `["a", "b", "c"]`.
+pub fn regex_split(val: &str, pattern: &str) -> Result<Vec<String>, Error> {
+    Ok(compiled_regex(pattern)?.split(val).map(str::to_string).collect())
+}
+
+/// Replaces `/` with the native path separator of the current platform.
+///
+/// Usage: `{{ "scripts/run.sh" | path_sep }}` renders `scripts\run.sh` on Windows.
+pub fn path_sep_filter(val: &str) -> String {
+    val.replace('/', std::path::MAIN_SEPARATOR_STR)
+}
+
+/// Global function returning true when Baker runs on Windows.
+///
+/// Usage: `{% if is_windows() %}...{% endif %}`
+pub fn is_windows() -> bool {
+    cfg!(windows)
+}
+
+fn invalid(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidOperation, msg)
+}
+
+fn parse_semver(val: &str) -> Result<semver::Version, Error> {
+    semver::Version::parse(val.trim_start_matches('v'))
+        .map_err(|e| invalid(format!("'{val}' is not a semantic version: {e}")))
+}
+
+/// Major component of a semantic version: `{{ "1.2.3" | semver_major }}` renders `1`.
+pub fn semver_major(val: &str) -> Result<u64, Error> {
+    Ok(parse_semver(val)?.major)
+}
+
+/// Minor component of a semantic version.
+pub fn semver_minor(val: &str) -> Result<u64, Error> {
+    Ok(parse_semver(val)?.minor)
+}
+
+/// Patch component of a semantic version.
+pub fn semver_patch(val: &str) -> Result<u64, Error> {
+    Ok(parse_semver(val)?.patch)
+}
+
+/// Bumps the major version and resets minor and patch: `1.2.3` becomes `2.0.0`.
+pub fn bump_major(val: &str) -> Result<String, Error> {
+    let v = parse_semver(val)?;
+    Ok(semver::Version::new(v.major + 1, 0, 0).to_string())
+}
+
+/// Bumps the minor version and resets patch: `1.2.3` becomes `1.3.0`.
+pub fn bump_minor(val: &str) -> Result<String, Error> {
+    let v = parse_semver(val)?;
+    Ok(semver::Version::new(v.major, v.minor + 1, 0).to_string())
+}
+
+/// Bumps the patch version: `1.2.3` becomes `1.2.4`.
+pub fn bump_patch(val: &str) -> Result<String, Error> {
+    let v = parse_semver(val)?;
+    Ok(semver::Version::new(v.major, v.minor, v.patch + 1).to_string())
+}
+
+/// Resolves a timezone argument: `utc` (default), `local` or a fixed offset like `+02:00`.
+fn parse_timezone(tz: Option<&str>) -> Result<FixedOffset, Error> {
+    match tz.unwrap_or("utc") {
+        tz if tz.eq_ignore_ascii_case("utc") || tz == "Z" => {
+            Ok(FixedOffset::east_opt(0).unwrap())
+        }
+        tz if tz.eq_ignore_ascii_case("local") => Ok(*Local::now().offset()),
+        tz => tz.parse::<FixedOffset>().map_err(|_| {
+            invalid(format!(
+                "Unknown timezone '{tz}': use utc, local or an offset like +02:00"
+            ))
+        }),
+    }
+}
+
+/// Global function returning the current time as an RFC 3339 string.
+///
+/// Usage: `{{ now() }}`, `{{ now("local") }}` or `{{ now("+02:00") }}`.
+pub fn now(tz: Option<String>) -> Result<String, Error> {
+    let offset = parse_timezone(tz.as_deref())?;
+    Ok(Utc::now().with_timezone(&offset).to_rfc3339())
+}
+
+/// Formats an RFC 3339 timestamp or `YYYY-MM-DD` date with a `strftime` format,
+/// optionally converting it to another timezone first.
+///
+/// Usage: `{{ now() | strftime("%Y") }}`, `{{ "2024-05-01T12:00:00Z" | strftime("%H:%M", "+02:00") }}`.
+pub fn strftime(val: &str, format: &str, tz: Option<String>) -> Result<String, Error> {
+    let datetime = DateTime::parse_from_rfc3339(val)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(val, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap().and_utc().fixed_offset())
+        })
+        .map_err(|_| {
+            invalid(format!("'{val}' is not an RFC 3339 timestamp or YYYY-MM-DD date"))
+        })?;
+    let datetime = match tz {
+        Some(tz) => datetime.with_timezone(&parse_timezone(Some(&tz))?),
+        None => datetime,
+    };
+    let mut out = String::new();
+    std::fmt::write(&mut out, format_args!("{}", datetime.format(format)))
+        .map_err(|_| invalid(format!("Invalid strftime format '{format}'")))?;
+    Ok(out)
+}
+
+/// Formats a byte count for humans: `{{ 1500000 | filesizeformat }}` renders `1.5 MB`.
+/// Pass `true` for binary units (`KiB`, `MiB`, ...).
+pub fn filesizeformat(bytes: f64, binary: Option<bool>) -> String {
+    let (base, units) = if binary.unwrap_or(false) {
+        (1024.0, ["Bytes", "KiB", "MiB", "GiB", "TiB", "PiB"])
+    } else {
+        (1000.0, ["Bytes", "kB", "MB", "GB", "TB", "PB"])
+    };
+    if bytes.abs() < base {
+        return format!("{bytes} {}", if bytes == 1.0 { "Byte" } else { units[0] });
+    }
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+    format!("{value:.1} {}", units[unit])
+}
+
+/// Returns the plural suffix for a count: `item{{ n | pluralize }}`, or
+/// `{{ n | pluralize("y", "ies") }}` for irregular endings.
+pub fn pluralize(count: i64, singular: Option<String>, plural: Option<String>) -> String {
+    if count == 1 {
+        singular.unwrap_or_default()
+    } else {
+        plural.unwrap_or_else(|| "s".to_string())
+    }
+}
+
+/// English ordinal of a number: `{{ 22 | ordinal }}` renders `22nd`.
+pub fn ordinal(n: i64) -> String {
+    let suffix = match (n.unsigned_abs() % 10, n.unsigned_abs() % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+fn to_json_value(value: &minijinja::Value) -> Result<serde_json::Value, Error> {
+    serde_json::to_value(value)
+        .map_err(|e| invalid(format!("Cannot serialize value: {e}")))
+}
+
+/// `value` as indented JSON, or without one every variable of the context, to
+/// look at while writing a template: `{{ debug(service) }}`.
+pub fn debug(state: &State, value: Option<minijinja::Value>) -> Result<String, Error> {
+    let value = match value {
+        Some(value) => to_json_value(&value)?,
+        None => {
+            let mut variables = serde_json::Map::new();
+            for name in state.known_variables() {
+                let Some(value) = state.lookup(&name) else { continue };
+                // Functions are plain objects.
+                if value.kind() != minijinja::value::ValueKind::Plain {
+                    variables.insert(name.into_owned(), to_json_value(&value)?);
+                }
+            }
+            serde_json::Value::Object(variables)
+        }
+    };
+    serde_json::to_string_pretty(&value).map_err(|e| invalid(e.to_string()))
+}
+
+/// Serializes a mapping as TOML: `{{ config | to_toml }}`.
+pub fn to_toml(value: minijinja::Value) -> Result<String, Error> {
+    super::formats::to_toml(&to_json_value(&value)?).map_err(invalid)
+}
+
+/// Serializes a mapping as an INI file: `{{ settings | to_ini }}`.
+pub fn to_ini(value: minijinja::Value) -> Result<String, Error> {
+    super::formats::to_ini(&to_json_value(&value)?).map_err(invalid)
+}
+
+/// Serializes a mapping as Java properties: `{{ settings | to_properties }}`.
+pub fn to_properties(value: minijinja::Value) -> Result<String, Error> {
+    super::formats::to_properties(&to_json_value(&value)?).map_err(invalid)
+}
+
+fn expect_object(
+    value: &minijinja::Value,
+    filter: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+    match to_json_value(value)? {
+        serde_json::Value::Object(map) => Ok(map),
+        other => Err(invalid(format!("{filter} expects a mapping, got {other}"))),
+    }
+}
+
+/// Accepts either a single key or a list of keys.
+fn key_list(keys: &minijinja::Value) -> Result<Vec<String>, Error> {
+    match to_json_value(keys)? {
+        serde_json::Value::String(key) => Ok(vec![key]),
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .map(|item| match item {
+                serde_json::Value::String(key) => Ok(key),
+                other => Err(invalid(format!("Expected a string key, got {other}"))),
+            })
+            .collect(),
+        other => Err(invalid(format!("Expected a key or a list of keys, got {other}"))),
+    }
+}
+
+fn deep_merge(base: &mut serde_json::Value, other: serde_json::Value) {
+    match (base, other) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, other) => *base = other,
+    }
+}
+
+/// Merges `other` on top of a mapping. Nested mappings are merged recursively
+/// unless `deep=false`, in which case top-level keys of `other` replace them.
+///
+/// Usage: `{{ preset | merge(overrides) }}`, `{{ preset | merge(overrides, deep=false) }}`
+pub fn merge(
+    value: minijinja::Value,
+    other: minijinja::Value,
+    kwargs: Kwargs,
+) -> Result<minijinja::Value, Error> {
+    let deep = kwargs.get::<Option<bool>>("deep")?.unwrap_or(true);
+    kwargs.assert_all_used()?;
+    let mut base = serde_json::Value::Object(expect_object(&value, "merge")?);
+    let other = expect_object(&other, "merge")?;
+    if deep {
+        deep_merge(&mut base, serde_json::Value::Object(other));
+    } else if let serde_json::Value::Object(map) = &mut base {
+        map.extend(other);
+    }
+    Ok(minijinja::Value::from_serialize(&base))
+}
+
+/// Sets a value at a dotted path, creating intermediate mappings as needed.
+///
+/// Usage: `{{ config | dict_set("server.port", 8080) }}`
+pub fn dict_set(
+    value: minijinja::Value,
+    path: &str,
+    new_value: minijinja::Value,
+) -> Result<minijinja::Value, Error> {
+    let mut root = serde_json::Value::Object(expect_object(&value, "dict_set")?);
+    let mut current = &mut root;
+    for segment in path.split('.') {
+        if !current.is_object() {
+            return Err(invalid(format!(
+                "dict_set cannot descend into '{segment}' of path '{path}': not a mapping"
+            )));
+        }
+        current = current
+            .as_object_mut()
+            .unwrap()
+            .entry(segment.to_string())
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+    }
+    *current = to_json_value(&new_value)?;
+    Ok(minijinja::Value::from_serialize(&root))
+}
+
+/// Keeps only the given keys: `{{ answers | pick(["name", "version"]) }}`.
+pub fn pick(
+    value: minijinja::Value,
+    keys: minijinja::Value,
+) -> Result<minijinja::Value, Error> {
+    let keys = key_list(&keys)?;
+    let mut map = expect_object(&value, "pick")?;
+    map.retain(|key, _| keys.contains(key));
+    Ok(minijinja::Value::from_serialize(&map))
+}
+
+/// Drops the given keys: `{{ answers | omit("password") }}`.
+pub fn omit(
+    value: minijinja::Value,
+    keys: minijinja::Value,
+) -> Result<minijinja::Value, Error> {
+    let keys = key_list(&keys)?;
+    let mut map = expect_object(&value, "omit")?;
+    map.retain(|key, _| !keys.contains(key));
+    Ok(minijinja::Value::from_serialize(&map))
+}
+
+/// `[key, value]` pairs sorted by key, for stable iteration:
+/// `{% for key, value in env | items_sorted %}`.
+pub fn items_sorted(value: minijinja::Value) -> Result<minijinja::Value, Error> {
+    let mut items: Vec<_> = expect_object(&value, "items_sorted")?.into_iter().collect();
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(minijinja::Value::from_serialize(&items))
+}
+
+/// Resolves `path` against the template root, refusing paths that leave it,
+/// through `..` or through a symlink pointing out of the template.
+fn template_file(template_root: &Path, path: &str) -> Result<PathBuf, Error> {
+    let outside =
+        || invalid(format!("'{path}' must be a relative path inside the template"));
+    let relative = Path::new(path);
+    let escapes = relative.components().any(|c| {
+        matches!(c, Component::ParentDir | Component::RootDir | Component::Prefix(_))
+    });
+    if escapes {
+        return Err(outside());
+    }
+    let read_error = |e: std::io::Error| {
+        invalid(format!("Cannot read '{path}' from the template: {e}"))
+    };
+    let root = template_root.canonicalize().map_err(read_error)?;
+    let file = template_root.join(relative).canonicalize().map_err(read_error)?;
+    if !file.starts_with(&root) {
+        return Err(outside());
+    }
+    Ok(file)
+}
+
+fn read_template_file(template_root: &Path, path: &str) -> Result<String, Error> {
+    let file = template_file(template_root, path)?;
+    std::fs::read_to_string(&file)
+        .map_err(|e| invalid(format!("Cannot read '{path}' from the template: {e}")))
+}
+
+/// Global function returning a file from the template root verbatim.
+///
+/// Usage: `{{ include_raw("snippets/header.txt") }}`
+pub fn include_raw(template_root: &Path, path: &str) -> Result<String, Error> {
+    read_template_file(template_root, path)
+}
+
+/// How deeply `render_file` calls may nest, so that a file rendering itself
+/// fails instead of overflowing the stack.
+const MAX_RENDER_FILE_DEPTH: usize = 32;
+
+thread_local! {
+    static RENDER_FILE_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts a nested `render_file` call for as long as it lives.
+struct RenderFileDepth;
+
+impl RenderFileDepth {
+    fn enter(path: &str) -> Result<Self, Error> {
+        let depth = RENDER_FILE_DEPTH.get();
+        if depth >= MAX_RENDER_FILE_DEPTH {
+            return Err(invalid(format!(
+                "render_file('{path}') nests more than {MAX_RENDER_FILE_DEPTH} files deep; does a file render itself?"
+            )));
+        }
+        RENDER_FILE_DEPTH.set(depth + 1);
+        Ok(Self)
+    }
+}
+
+impl Drop for RenderFileDepth {
+    fn drop(&mut self) {
+        RENDER_FILE_DEPTH.set(RENDER_FILE_DEPTH.get() - 1);
+    }
+}
+
+/// Global function rendering a file from the template root with the current
+/// context, optionally overridden by a mapping.
+///
+/// Usage: `{{ render_file("partials/service.rs.j2", {"name": item.name}) }}`
+pub fn render_file(
+    state: &State,
+    template_root: &Path,
+    path: &str,
+    ctx_override: Option<minijinja::Value>,
+) -> Result<String, Error> {
+    let _depth = RenderFileDepth::enter(path)?;
+    let source = read_template_file(template_root, path)?;
+    let mut context: BTreeMap<String, minijinja::Value> = state
+        .known_variables()
+        .into_iter()
+        .filter_map(|name| state.lookup(&name).map(|value| (name.into_owned(), value)))
+        .collect();
+    if let Some(overrides) = ctx_override {
+        for key in overrides.try_iter()? {
+            let value = overrides.get_item(&key)?;
+            context.insert(key.to_string(), value);
+        }
+    }
+    state.env().render_named_str(path, &source, context)
+}
+
+/// The git repository the output directory is in. The directory may not exist
+/// yet, so the search starts at its nearest existing ancestor.
+fn output_repository(output_root: &Path) -> Option<git2::Repository> {
+    let output_root = std::path::absolute(output_root).ok()?;
+    let existing = output_root.ancestors().find(|dir| dir.exists())?;
+    git2::Repository::discover(existing).ok()
+}
+
+/// The git config keys templates may read. Others, such as credential helpers or
+/// tokens kept in `http.extraHeader`, are not for templates.
+const GIT_CONFIG_KEYS: &[&str] = &["user.name", "user.email"];
+
+/// Global function returning `key` of the git config that applies to the output
+/// directory: its repository's, or else the user's. Only `user.name`,
+/// `user.email` and `remote.<name>.url` are readable. Undefined when unset.
+///
+/// Usage: `{{ git_config("user.name") | default("Anonymous") }}`
+pub fn git_config(output_root: &Path, key: &str) -> minijinja::Value {
+    if let Some(remote) =
+        key.strip_prefix("remote.").and_then(|key| key.strip_suffix(".url"))
+    {
+        return git_remote_url(output_root, Some(remote.to_string()));
+    }
+    if !GIT_CONFIG_KEYS.contains(&key) {
+        warn!("git_config(\"{key}\") is not readable by templates");
+        return minijinja::Value::UNDEFINED;
+    }
+    let config = match output_repository(output_root) {
+        Some(repo) => repo.config(),
+        None => git2::Config::open_default(),
+    };
+    config
+        .and_then(|config| config.get_string(key))
+        .map(minijinja::Value::from)
+        .unwrap_or(minijinja::Value::UNDEFINED)
+}
+
+/// Global function returning the URL of a remote, `origin` by default, of the
+/// repository the output directory is in, without credentials. Undefined outside
+/// a repository.
+///
+/// Usage: `{{ git_remote_url() }}`, `{{ git_remote_url("upstream") }}`
+pub fn git_remote_url(output_root: &Path, remote: Option<String>) -> minijinja::Value {
+    output_repository(output_root)
+        .and_then(|repo| {
+            let remote = repo.find_remote(remote.as_deref().unwrap_or("origin")).ok()?;
+            remote.url().map(GitLoader::<&str>::without_credentials)
+        })
+        .map(minijinja::Value::from)
+        .unwrap_or(minijinja::Value::UNDEFINED)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,4 +557,129 @@ mod tests {
     fn test_regex_filter_invalid_regex() {
         assert!(!regex_filter("anything", r"([unclosed"));
     }
+
+    #[test]
+    fn test_regex_replace_search_split() {
+        assert_eq!(regex_replace("my-app v2", "[^a-z0-9]+", "_").unwrap(), "my_app_v2");
+        assert_eq!(regex_replace("2024-05", r"(\d+)-(\d+)", "$2/$1").unwrap(), "05/2024");
+        assert_eq!(
+            regex_search("v1.2.3", r"\d+\.\d+", None).unwrap().as_deref(),
+            Some("1.2")
+        );
+        assert_eq!(
+            regex_search("v1.2.3", r"v(\d+)", Some(1)).unwrap().as_deref(),
+            Some("1")
+        );
+        assert_eq!(regex_search("none", r"\d", None).unwrap(), None);
+        assert!(regex_search("v1", r"v(\d)", Some(2)).is_err());
+        assert_eq!(regex_split("a, b;c", r"[,;]\s*").unwrap(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_invalid_regex_reports_pattern() {
+        let err = regex_replace("x", "([unclosed", "").unwrap_err();
+        assert!(err.to_string().contains("Invalid regex '([unclosed'"));
+    }
+
+    fn mj(value: serde_json::Value) -> minijinja::Value {
+        minijinja::Value::from_serialize(&value)
+    }
+
+    fn no_kwargs() -> Kwargs {
+        Kwargs::from_iter(std::iter::empty::<(&str, minijinja::Value)>())
+    }
+
+    fn js(value: minijinja::Value) -> serde_json::Value {
+        serde_json::to_value(&value).unwrap()
+    }
+
+    #[test]
+    fn test_merge_deep_and_shallow() {
+        let base = mj(serde_json::json!({"db": {"host": "a", "port": 1}, "name": "x"}));
+        let other = mj(serde_json::json!({"db": {"port": 2}}));
+        assert_eq!(
+            js(merge(base.clone(), other.clone(), no_kwargs()).unwrap()),
+            serde_json::json!({"db": {"host": "a", "port": 2}, "name": "x"})
+        );
+        assert_eq!(
+            js(merge(base, other, Kwargs::from_iter([("deep", false.into())])).unwrap()),
+            serde_json::json!({"db": {"port": 2}, "name": "x"})
+        );
+        let kwargs = no_kwargs();
+        assert!(
+            merge(mj(serde_json::json!([1])), mj(serde_json::json!({})), kwargs).is_err()
+        );
+    }
+
+    #[test]
+    fn test_dict_set_pick_omit_items_sorted() {
+        let value = mj(serde_json::json!({"b": 1, "a": {"x": 1}}));
+        assert_eq!(
+            js(dict_set(value.clone(), "a.y.z", minijinja::Value::from(true)).unwrap()),
+            serde_json::json!({"b": 1, "a": {"x": 1, "y": {"z": true}}})
+        );
+        assert!(dict_set(value.clone(), "b.c", minijinja::Value::from(1)).is_err());
+        assert_eq!(
+            js(pick(value.clone(), minijinja::Value::from("b")).unwrap()),
+            serde_json::json!({"b": 1})
+        );
+        assert_eq!(
+            js(omit(value.clone(), mj(serde_json::json!(["b"]))).unwrap()),
+            serde_json::json!({"a": {"x": 1}})
+        );
+        assert_eq!(
+            js(items_sorted(value).unwrap()),
+            serde_json::json!([["a", {"x": 1}], ["b", 1]])
+        );
+    }
+
+    #[test]
+    fn test_path_sep_filter_uses_native_separator() {
+        let expected = ["a", "b", "c.txt"].join(std::path::MAIN_SEPARATOR_STR);
+        assert_eq!(path_sep_filter("a/b/c.txt"), expected);
+    }
+
+    #[test]
+    fn test_semver_filters() {
+        assert_eq!(semver_major("v1.2.3").unwrap(), 1);
+        assert_eq!(semver_minor("1.2.3").unwrap(), 2);
+        assert_eq!(semver_patch("1.2.3").unwrap(), 3);
+        assert_eq!(bump_major("1.2.3").unwrap(), "2.0.0");
+        assert_eq!(bump_minor("1.2.3-rc.1").unwrap(), "1.3.0");
+        assert_eq!(bump_patch("1.2.3").unwrap(), "1.2.4");
+        assert!(bump_minor("one").is_err());
+    }
+
+    #[test]
+    fn test_datetime_filters() {
+        assert_eq!(
+            strftime("2024-05-01T23:30:00Z", "%Y-%m-%d", None).unwrap(),
+            "2024-05-01"
+        );
+        assert_eq!(
+            strftime("2024-05-01T23:30:00Z", "%Y-%m-%d %H:%M", Some("+02:00".into()))
+                .unwrap(),
+            "2024-05-02 01:30"
+        );
+        assert_eq!(strftime("2024-05-01", "%d/%m/%Y", None).unwrap(), "01/05/2024");
+        assert!(strftime("yesterday", "%Y", None).is_err());
+        assert!(now(Some("mars".into())).is_err());
+        assert!(DateTime::parse_from_rfc3339(&now(None).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_humanize_filters() {
+        assert_eq!(filesizeformat(1.0, None), "1 Byte");
+        assert_eq!(filesizeformat(1_500_000.0, None), "1.5 MB");
+        assert_eq!(filesizeformat(2048.0, Some(true)), "2.0 KiB");
+        assert_eq!(pluralize(1, None, None), "");
+        assert_eq!(pluralize(2, None, None), "s");
+        assert_eq!(pluralize(2, Some("y".into()), Some("ies".into())), "ies");
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(22), "22nd");
+        assert_eq!(ordinal(103), "103rd");
+        assert_eq!(ordinal(-2), "-2nd");
+        assert_eq!(ordinal(i64::MIN), "-9223372036854775808th");
+    }
 }
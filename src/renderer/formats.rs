@@ -0,0 +1,344 @@
+//! Serializers behind the `to_toml`, `to_ini` and `to_properties` filters.
+//!
+//! Keys are always written in sorted order so that re-rendering a template with
+//! the same answers produces the same file.
+
+use serde_json::{Map, Value};
+
+/// Map entries sorted by key.
+fn sorted(map: &Map<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn is_array_of_tables(value: &Value) -> bool {
+    matches!(value, Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object))
+}
+
+/// Serializes an object as a TOML document. Null values are omitted, since TOML
+/// has no null.
+pub fn to_toml(value: &Value) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err("to_toml expects a mapping at the top level".to_string());
+    };
+    let mut out = String::new();
+    write_toml_table(&mut out, &[], map)?;
+    Ok(out)
+}
+
+fn write_toml_table(
+    out: &mut String,
+    path: &[String],
+    map: &Map<String, Value>,
+) -> Result<(), String> {
+    let entries = sorted(map);
+    for (key, value) in &entries {
+        if value.is_null() || value.is_object() || is_array_of_tables(value) {
+            continue;
+        }
+        out.push_str(&format!("{} = {}\n", toml_key(key), toml_inline(value)?));
+    }
+    for (key, value) in &entries {
+        let mut child = path.to_vec();
+        child.push(toml_key(key));
+        match value {
+            Value::Object(table) => {
+                separate(out);
+                out.push_str(&format!("[{}]\n", child.join(".")));
+                write_toml_table(out, &child, table)?;
+            }
+            Value::Array(items) if is_array_of_tables(value) => {
+                for item in items {
+                    separate(out);
+                    out.push_str(&format!("[[{}]]\n", child.join(".")));
+                    if let Value::Object(table) = item {
+                        write_toml_table(out, &child, table)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Blank line between a table header and whatever precedes it.
+fn separate(out: &mut String) {
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+fn toml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn toml_inline(value: &Value) -> Result<String, String> {
+    Ok(match value {
+        Value::Null => {
+            return Err("TOML cannot represent null inside arrays or inline tables".into())
+        }
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 => format!("{f:.1}"),
+            _ => n.to_string(),
+        },
+        Value::String(s) => toml_string(s),
+        Value::Array(items) => {
+            let items: Result<Vec<_>, _> = items.iter().map(toml_inline).collect();
+            format!("[{}]", items?.join(", "))
+        }
+        Value::Object(map) => {
+            let entries: Result<Vec<_>, String> = sorted(map)
+                .into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| Ok(format!("{} = {}", toml_key(k), toml_inline(v)?)))
+                .collect();
+            format!("{{ {} }}", entries?.join(", "))
+        }
+    })
+}
+
+/// Serializes an object as an INI file. Top-level scalars come first, objects
+/// become `[section]`s and nested objects `[section.sub]`. Arrays are joined
+/// with commas and nulls are written as empty values. Keys, section names and
+/// values that contain INI syntax (`=`, `;`, `#`, brackets, quotes, backslashes,
+/// control characters) or surrounding spaces are double-quoted with backslash
+/// escapes.
+pub fn to_ini(value: &Value) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err("to_ini expects a mapping at the top level".to_string());
+    };
+    let mut out = String::new();
+    write_ini_section(&mut out, None, map);
+    Ok(out)
+}
+
+fn write_ini_section(out: &mut String, name: Option<&str>, map: &Map<String, Value>) {
+    let entries = sorted(map);
+    for (key, value) in &entries {
+        if !value.is_object() {
+            out.push_str(&format!("{} = {}\n", ini_string(key, ""), ini_value(value)));
+        }
+    }
+    for (key, value) in &entries {
+        if let Value::Object(section) = value {
+            let key = ini_string(key, ".");
+            let section_name = match name {
+                Some(parent) => format!("{parent}.{key}"),
+                None => key,
+            };
+            separate(out);
+            out.push_str(&format!("[{section_name}]\n"));
+            write_ini_section(out, Some(&section_name), section);
+        }
+    }
+}
+
+fn ini_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => ini_string(s, ""),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => ini_string(s, ","),
+                other => ini_value(other),
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// `s` as is when an INI parser reads it back unchanged, otherwise double-quoted
+/// and escaped like a TOML string. `separators` are further characters that force quoting.
+fn ini_string(s: &str, separators: &str) -> String {
+    let plain = !s.contains(|c: char| {
+        "=;#[]\"\\".contains(c) || separators.contains(c) || c.is_control()
+    }) && s.trim() == s;
+    if plain {
+        s.to_string()
+    } else {
+        toml_string(s)
+    }
+}
+
+/// Serializes a value as Java properties. Nested keys are flattened with dots and
+/// array items get an `[index]` suffix; keys and values are escaped as
+/// `java.util.Properties` expects, including `\uXXXX` for non-ASCII characters.
+pub fn to_properties(value: &Value) -> Result<String, String> {
+    let Value::Object(map) = value else {
+        return Err("to_properties expects a mapping at the top level".to_string());
+    };
+    let mut lines = Vec::new();
+    for (key, value) in sorted(map) {
+        flatten_properties(key.clone(), value, &mut lines);
+    }
+    Ok(lines.into_iter().map(|line| line + "\n").collect())
+}
+
+fn flatten_properties(key: String, value: &Value, lines: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (child, value) in sorted(map) {
+                flatten_properties(format!("{key}.{child}"), value, lines);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten_properties(format!("{key}[{i}]"), value, lines);
+            }
+        }
+        Value::Null => lines.push(format!("{}=", properties_escape(&key, true))),
+        Value::String(s) => lines.push(format!(
+            "{}={}",
+            properties_escape(&key, true),
+            properties_escape(s, false)
+        )),
+        other => lines.push(format!(
+            "{}={}",
+            properties_escape(&key, true),
+            properties_escape(&other.to_string(), false)
+        )),
+    }
+}
+
+fn properties_escape(s: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for (i, c) in s.chars().enumerate() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{c}' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' if is_key => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if is_key || i == 0 => out.push_str("\\ "),
+            c if (c as u32) < 0x20 || (c as u32) > 0x7e => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    out.push_str(&format!("\\u{unit:04X}"));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn toml_writes_sorted_tables_and_arrays_of_tables() {
+        let value = json!({
+            "name": "demo \"app\"",
+            "version": 1,
+            "ratio": 2.0,
+            "skip": null,
+            "tags": ["a", "b"],
+            "server": {"port": 8080, "tls": {"enabled": true}},
+            "users": [{"name": "ann"}, {"name": "bob"}],
+            "odd key": "x",
+        });
+        assert_eq!(
+            to_toml(&value).unwrap(),
+            r#"name = "demo \"app\""
+"odd key" = "x"
+ratio = 2.0
+tags = ["a", "b"]
+version = 1
+
+[server]
+port = 8080
+
+[server.tls]
+enabled = true
+
+[[users]]
+name = "ann"
+
+[[users]]
+name = "bob"
+"#
+        );
+        assert!(to_toml(&json!([1])).is_err());
+        assert!(to_toml(&json!({"list": [1, null]})).is_err());
+    }
+
+    #[test]
+    fn ini_writes_global_keys_then_sections() {
+        let value = json!({
+            "root": "yes",
+            "db": {"host": "localhost", "ports": [1, 2], "extra": {"a": null}},
+        });
+        assert_eq!(
+            to_ini(&value).unwrap(),
+            "root = yes\n\n[db]\nhost = localhost\nports = 1,2\n\n[db.extra]\na = \n"
+        );
+    }
+
+    #[test]
+    fn ini_quotes_keys_sections_and_values_with_ini_syntax() {
+        let value = json!({
+            "key=1": "a;b",
+            "list": ["x,y", "z"],
+            "multi": "line\n[evil]",
+            "my.section": {"path": "C:\\dir", "padded": " x "},
+        });
+        assert_eq!(
+            to_ini(&value).unwrap(),
+            r#""key=1" = "a;b"
+list = "x,y",z
+multi = "line\n[evil]"
+
+["my.section"]
+padded = " x "
+path = "C:\\dir"
+"#
+        );
+    }
+
+    #[test]
+    fn properties_flatten_and_escape() {
+        let value = json!({
+            "app": {"name": "Café", "hosts": ["a", "b"]},
+            "key with=sign": " leading",
+        });
+        assert_eq!(
+            to_properties(&value).unwrap(),
+            "app.hosts[0]=a\napp.hosts[1]=b\napp.name=Caf\\u00E9\nkey\\ with\\=sign=\\ leading\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::error::Result;
+use crate::{config::UndefinedBehavior, error::Result, renderer::registry::Registry};
 use std::path::Path;
 
 /// Trait for template rendering engines.
@@ -17,6 +17,16 @@ pub trait TemplateRenderer {
         template: &str,
     ) -> Result<(), minijinja::Error>;
 
+    /// Sets the directory that `render_file` and `include_raw` read files from.
+    fn set_template_root(&mut self, template_root: &Path);
+
+    /// Sets the directory whose git repository and config the `git_*` functions
+    /// read. Defaults to the current directory.
+    fn set_output_root(&mut self, output_root: &Path);
+
+    /// Sets whether templates fail on variables that are not defined.
+    fn set_undefined_behavior(&mut self, behavior: UndefinedBehavior);
+
     /// Renders a template string with the given context.
     ///
     /// # Arguments
@@ -48,6 +58,9 @@ pub trait TemplateRenderer {
         context: &serde_json::Value,
     ) -> Result<String>;
 
+    /// The filters, tests and functions the engine adds to the template language.
+    fn registry(&self) -> &Registry;
+
     /// Executes a template expression and returns whether it evaluates to true.
     ///
     /// # Arguments
@@ -7,10 +7,14 @@
 //! - `interface`: Core trait definitions for template rendering
 //! - `minijinja`: MiniJinja-based implementation of the template renderer
 //! - `filters`: Custom filters for template processing
+//! - `formats`: TOML, INI and properties serializers used by the `to_*` filters
+//! - `registry`: descriptions of the filters and functions, for `baker filters`
 
 pub mod filters;
+pub mod formats;
 pub mod interface;
 pub mod minijinja;
+pub mod registry;
 
 // Re-export the main types and traits for convenience
 pub use interface::TemplateRenderer;
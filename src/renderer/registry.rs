@@ -0,0 +1,150 @@
+//! Descriptions of the filters, tests and functions Baker adds to the template
+//! language. They are recorded as the engine registers them, so `baker filters`
+//! lists exactly what templates can use.
+
+use minijinja::{
+    functions::Function,
+    value::{FunctionArgs, FunctionResult},
+    Environment,
+};
+use std::fmt::Display;
+
+/// How a template calls an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntryKind {
+    /// `{{ value | name }}`
+    Filter,
+    /// `{% if value is name %}`
+    Test,
+    /// `{{ name() }}`
+    Function,
+}
+
+impl Display for EntryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryKind::Filter => write!(f, "Filters"),
+            EntryKind::Test => write!(f, "Tests"),
+            EntryKind::Function => write!(f, "Functions"),
+        }
+    }
+}
+
+/// A filter, test or function with its signature and a short description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub kind: EntryKind,
+    pub name: &'static str,
+    /// Arguments after the filtered or tested value, e.g. `(pattern, replacement)`;
+    /// empty when there are none. Optional arguments end with `?`.
+    pub arguments: &'static str,
+    pub doc: &'static str,
+}
+
+impl Entry {
+    /// The entry as a template would use it, e.g. `value | regex_split(pattern)`.
+    pub fn signature(&self) -> String {
+        match self.kind {
+            EntryKind::Filter => format!("value | {}{}", self.name, self.arguments),
+            EntryKind::Test => format!("value is {}{}", self.name, self.arguments),
+            EntryKind::Function => format!("{}{}", self.name, self.arguments),
+        }
+    }
+}
+
+/// The entries added to an environment, in registration order.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Adds `filter` to `env` as `name`, recording its description.
+    pub fn filter<F, Rv, Args>(
+        &mut self,
+        env: &mut Environment<'static>,
+        name: &'static str,
+        arguments: &'static str,
+        doc: &'static str,
+        filter: F,
+    ) where
+        F: Function<Rv, Args>,
+        Rv: FunctionResult,
+        Args: for<'a> FunctionArgs<'a>,
+    {
+        env.add_filter(name, filter);
+        self.record(Entry { kind: EntryKind::Filter, name, arguments, doc });
+    }
+
+    /// Adds `test` to `env` as `name`, recording its description.
+    pub fn test<F, Rv, Args>(
+        &mut self,
+        env: &mut Environment<'static>,
+        name: &'static str,
+        arguments: &'static str,
+        doc: &'static str,
+        test: F,
+    ) where
+        F: Function<Rv, Args>,
+        Rv: FunctionResult,
+        Args: for<'a> FunctionArgs<'a>,
+    {
+        env.add_test(name, test);
+        self.record(Entry { kind: EntryKind::Test, name, arguments, doc });
+    }
+
+    /// Adds `function` to `env` as `name`, recording its description.
+    pub fn function<F, Rv, Args>(
+        &mut self,
+        env: &mut Environment<'static>,
+        name: &'static str,
+        arguments: &'static str,
+        doc: &'static str,
+        function: F,
+    ) where
+        F: Function<Rv, Args>,
+        Rv: FunctionResult,
+        Args: for<'a> FunctionArgs<'a>,
+    {
+        env.add_function(name, function);
+        self.record(Entry { kind: EntryKind::Function, name, arguments, doc });
+    }
+
+    /// Registering a name again replaces the entry, as it replaces the callable.
+    fn record(&mut self, entry: Entry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.kind == entry.kind && e.name == entry.name)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Lists the entries by kind, each with its signature and description. Only
+    /// entries whose name contains `filter` are listed when it is given.
+    pub fn describe(&self, filter: Option<&str>) -> String {
+        let mut out = String::new();
+        for kind in [EntryKind::Filter, EntryKind::Test, EntryKind::Function] {
+            let entries: Vec<&Entry> = self
+                .entries
+                .iter()
+                .filter(|e| e.kind == kind)
+                .filter(|e| filter.is_none_or(|filter| e.name.contains(filter)))
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("{kind}:\n"));
+            for entry in entries {
+                out.push_str(&format!("  {}\n      {}\n", entry.signature(), entry.doc));
+            }
+        }
+        out
+    }
+}
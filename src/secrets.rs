@@ -0,0 +1,333 @@
+//! Secret references in answers.
+//!
+//! An answer of the form `!secret <provider>:<reference>` is replaced with the value
+//! returned by the matching [`SecretResolver`] before questions are processed. The
+//! resolved value is only kept in memory; the generated metadata file stores the
+//! original reference so that `baker update` resolves it again.
+
+use crate::{
+    constants::SECRET_REFERENCE_PREFIX,
+    error::{Error, Result},
+};
+use serde_json::{Map, Value};
+
+/// Resolves secret references for a single provider (e.g. `env`, `file`).
+pub trait SecretResolver {
+    /// Provider name matched against the part of the reference before the first `:`.
+    fn provider(&self) -> &str;
+
+    /// Returns the secret value for `reference` (the part after `<provider>:`).
+    fn resolve(&self, reference: &str) -> Result<String>;
+}
+
+/// Reads the secret from an environment variable: `!secret env:MY_TOKEN`.
+#[cfg(feature = "secrets-env")]
+pub struct EnvResolver;
+
+#[cfg(feature = "secrets-env")]
+impl SecretResolver for EnvResolver {
+    fn provider(&self) -> &str {
+        "env"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        std::env::var(reference).map_err(|e| Error::SecretResolutionError {
+            reference: format!("env:{reference}"),
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Reads the secret from a file: `!secret file:/run/secrets/token`.
+///
+/// An optional `#key` fragment selects a top-level key of a JSON or YAML document.
+#[cfg(feature = "secrets-file")]
+pub struct FileResolver;
+
+#[cfg(feature = "secrets-file")]
+impl SecretResolver for FileResolver {
+    fn provider(&self) -> &str {
+        "file"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let fail = |reason: String| Error::SecretResolutionError {
+            reference: format!("file:{reference}"),
+            reason,
+        };
+        let (path, key) = match reference.split_once('#') {
+            Some((path, key)) => (path, Some(key)),
+            None => (reference, None),
+        };
+        let content = std::fs::read_to_string(path).map_err(|e| fail(e.to_string()))?;
+
+        let Some(key) = key else {
+            return Ok(content.trim_end_matches(['\r', '\n']).to_string());
+        };
+
+        let document: serde_yaml::Value =
+            serde_yaml::from_str(&content).map_err(|e| fail(e.to_string()))?;
+        match document.get(key) {
+            Some(serde_yaml::Value::String(s)) => Ok(s.clone()),
+            Some(serde_yaml::Value::Number(n)) => Ok(n.to_string()),
+            Some(serde_yaml::Value::Bool(b)) => Ok(b.to_string()),
+            Some(_) => Err(fail(format!("key '{key}' is not a scalar value"))),
+            None => Err(fail(format!("key '{key}' not found"))),
+        }
+    }
+}
+
+/// Delegates any other provider to an executable named `baker-secret-<provider>`
+/// found on `PATH`. The reference is passed as the only argument and the secret
+/// is read from stdout, e.g. `!secret vault:kv/data/ci#token` runs
+/// `baker-secret-vault kv/data/ci#token`.
+#[cfg(feature = "secrets-exec")]
+pub struct ExecResolver {
+    provider: String,
+}
+
+#[cfg(feature = "secrets-exec")]
+impl ExecResolver {
+    pub fn new(provider: impl Into<String>) -> Self {
+        Self { provider: provider.into() }
+    }
+}
+
+#[cfg(feature = "secrets-exec")]
+impl SecretResolver for ExecResolver {
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String> {
+        let fail = |reason: String| Error::SecretResolutionError {
+            reference: format!("{}:{reference}", self.provider),
+            reason,
+        };
+        let program =
+            format!("{}{}", crate::constants::SECRET_EXEC_PREFIX, self.provider);
+        log::debug!("Resolving secret via '{program}'");
+
+        let output = std::process::Command::new(&program)
+            .arg(reference)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|e| fail(format!("failed to run '{program}': {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(fail(format!(
+                "'{program}' exited with {}: {}",
+                output.status,
+                stderr.trim()
+            )));
+        }
+
+        let stdout = String::from_utf8(output.stdout)
+            .map_err(|e| fail(format!("'{program}' returned non UTF-8 output: {e}")))?;
+        Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// The set of resolvers available for a generation run.
+pub struct SecretRegistry {
+    resolvers: Vec<Box<dyn SecretResolver>>,
+}
+
+impl Default for SecretRegistry {
+    /// Registers every built-in resolver enabled at compile time.
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::empty();
+        #[cfg(feature = "secrets-env")]
+        registry.register(Box::new(EnvResolver));
+        #[cfg(feature = "secrets-file")]
+        registry.register(Box::new(FileResolver));
+        registry
+    }
+}
+
+impl SecretRegistry {
+    /// A registry without any resolvers.
+    pub fn empty() -> Self {
+        Self { resolvers: Vec::new() }
+    }
+
+    /// Adds a resolver. Later registrations take precedence for the same provider.
+    pub fn register(&mut self, resolver: Box<dyn SecretResolver>) {
+        self.resolvers.insert(0, resolver);
+    }
+
+    /// Resolves a `<provider>:<reference>` string.
+    pub fn resolve(&self, reference: &str) -> Result<String> {
+        let Some((provider, target)) = reference.split_once(':') else {
+            return Err(Error::SecretResolutionError {
+                reference: reference.to_string(),
+                reason: "expected '<provider>:<reference>'".to_string(),
+            });
+        };
+
+        if let Some(resolver) = self.resolvers.iter().find(|r| r.provider() == provider) {
+            return resolver.resolve(target);
+        }
+
+        #[cfg(feature = "secrets-exec")]
+        {
+            ExecResolver::new(provider).resolve(target)
+        }
+
+        #[cfg(not(feature = "secrets-exec"))]
+        Err(Error::SecretResolutionError {
+            reference: reference.to_string(),
+            reason: format!("no resolver registered for provider '{provider}'"),
+        })
+    }
+
+    /// Replaces every top-level `!secret ...` string in `answers` with its resolved value.
+    ///
+    /// Returns the original references keyed by answer name so they can be written
+    /// back in place of the resolved values when answers are persisted.
+    pub fn resolve_answers(
+        &self,
+        answers: &mut Map<String, Value>,
+    ) -> Result<Map<String, Value>> {
+        let mut references = Map::new();
+        for (key, value) in answers.iter_mut() {
+            let Some(reference) = value.as_str().and_then(parse_reference) else {
+                continue;
+            };
+            log::debug!("Resolving secret reference for answer '{key}'");
+            let secret = self.resolve(reference)?;
+            references
+                .insert(key.clone(), std::mem::replace(value, Value::String(secret)));
+        }
+        Ok(references)
+    }
+}
+
+/// Returns the `<provider>:<reference>` part of a `!secret` answer, if it is one.
+pub fn parse_reference(value: &str) -> Option<&str> {
+    value.strip_prefix(SECRET_REFERENCE_PREFIX).map(str::trim).filter(|r| !r.is_empty())
+}
+
+/// Returns a copy of `answers` where resolved secrets are replaced by their original references.
+pub fn restore_references(answers: &Value, references: &Map<String, Value>) -> Value {
+    let mut restored = answers.clone();
+    if let Some(obj) = restored.as_object_mut() {
+        for (key, reference) in references {
+            if let Some(value) = obj.get_mut(key) {
+                *value = reference.clone();
+            }
+        }
+    }
+    restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct StaticResolver;
+
+    impl SecretResolver for StaticResolver {
+        fn provider(&self) -> &str {
+            "static"
+        }
+
+        fn resolve(&self, reference: &str) -> Result<String> {
+            Ok(format!("secret-{reference}"))
+        }
+    }
+
+    fn registry() -> SecretRegistry {
+        let mut registry = SecretRegistry::default();
+        registry.register(Box::new(StaticResolver));
+        registry
+    }
+
+    #[test]
+    fn parse_reference_requires_prefix() {
+        assert_eq!(parse_reference("!secret env:TOKEN"), Some("env:TOKEN"));
+        assert_eq!(
+            parse_reference("!secret   vault:kv/ci#token "),
+            Some("vault:kv/ci#token")
+        );
+        assert_eq!(parse_reference("env:TOKEN"), None);
+        assert_eq!(parse_reference("!secret "), None);
+    }
+
+    #[test]
+    fn resolve_answers_replaces_references_and_returns_originals() {
+        let mut answers = json!({
+            "name": "demo",
+            "token": "!secret static:ci",
+            "enabled": true
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+
+        let references = registry().resolve_answers(&mut answers).unwrap();
+
+        assert_eq!(answers["token"], "secret-ci");
+        assert_eq!(answers["name"], "demo");
+        assert_eq!(references.len(), 1);
+        assert_eq!(references["token"], "!secret static:ci");
+    }
+
+    #[test]
+    fn restore_references_puts_references_back() {
+        let answers = json!({"name": "demo", "token": "secret-ci"});
+        let references =
+            json!({"token": "!secret static:ci"}).as_object().unwrap().clone();
+        let restored = restore_references(&answers, &references);
+        assert_eq!(restored, json!({"name": "demo", "token": "!secret static:ci"}));
+    }
+
+    #[test]
+    fn resolve_rejects_reference_without_provider() {
+        let err = registry().resolve("TOKEN").unwrap_err();
+        assert!(err.to_string().contains("expected '<provider>:<reference>'"));
+    }
+
+    #[cfg(feature = "secrets-env")]
+    #[test]
+    fn env_resolver_reads_variable() {
+        std::env::set_var("BAKER_TEST_SECRET_TOKEN", "s3cr3t");
+        assert_eq!(registry().resolve("env:BAKER_TEST_SECRET_TOKEN").unwrap(), "s3cr3t");
+        assert!(registry().resolve("env:BAKER_TEST_SECRET_MISSING").is_err());
+    }
+
+    #[cfg(feature = "secrets-file")]
+    #[test]
+    fn file_resolver_reads_whole_file_or_key() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let plain = tmp.path().join("token");
+        std::fs::write(&plain, "plain-value\n").unwrap();
+        let doc = tmp.path().join("secrets.yaml");
+        std::fs::write(&doc, "db_password: hunter2\nport: 5432\n").unwrap();
+
+        let registry = registry();
+        assert_eq!(
+            registry.resolve(&format!("file:{}", plain.display())).unwrap(),
+            "plain-value"
+        );
+        assert_eq!(
+            registry.resolve(&format!("file:{}#db_password", doc.display())).unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            registry.resolve(&format!("file:{}#port", doc.display())).unwrap(),
+            "5432"
+        );
+        assert!(registry.resolve(&format!("file:{}#missing", doc.display())).is_err());
+    }
+
+    #[cfg(feature = "secrets-exec")]
+    #[test]
+    fn exec_resolver_reports_missing_program() {
+        let err = registry().resolve("baker-test-nonexistent:kv/ci").unwrap_err();
+        assert!(err.to_string().contains("baker-secret-baker-test-nonexistent"));
+    }
+}
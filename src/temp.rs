@@ -0,0 +1,99 @@
+//! Temporary directories for template clones and test renders.
+//!
+//! Every directory is registered, so whatever is left is removed by [`cleanup`]
+//! before Baker exits, after a success, an error or a Ctrl-C. With `--keep-temp`
+//! the directories are kept for debugging and [`cleanup`] prints their paths.
+
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+struct Registry {
+    keep: bool,
+    dirs: Vec<PathBuf>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry { keep: false, dirs: Vec::new() });
+
+fn registry() -> MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps the temporary directories instead of removing them.
+pub fn keep_temp(keep: bool) {
+    registry().keep = keep;
+}
+
+/// A registered temporary directory, removed when dropped unless `--keep-temp` is
+/// set.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Creates an empty directory named after `purpose`, e.g. `baker-clone-XXXXXX`.
+    pub fn new(purpose: &str) -> Result<Self> {
+        let path = tempfile::Builder::new()
+            .prefix(&format!("baker-{purpose}-"))
+            .tempdir()?
+            .keep();
+        log::debug!("Created temporary directory '{}'", path.display());
+        registry().dirs.push(path.clone());
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let mut registry = registry();
+        if registry.keep {
+            return;
+        }
+        registry.dirs.retain(|dir| dir != &self.path);
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            log::warn!(
+                "Failed to remove temporary directory '{}': {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Removes the registered directories that are still there, or lists them with
+/// `--keep-temp`. Called once before exiting.
+pub fn cleanup() {
+    let mut registry = registry();
+    for dir in std::mem::take(&mut registry.dirs) {
+        if !dir.exists() {
+            continue;
+        }
+        if registry.keep {
+            println!("Kept temporary directory '{}'", dir.display());
+        } else if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Failed to remove temporary directory '{}': {e}", dir.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_the_directory_when_dropped() {
+        let dir = TempDir::new("test").unwrap();
+        let path = dir.path().to_path_buf();
+        std::fs::write(path.join("file"), "content").unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with("baker-test-"));
+
+        drop(dir);
+
+        assert!(!path.exists());
+        assert!(!registry().dirs.contains(&path));
+    }
+}
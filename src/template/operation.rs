@@ -2,11 +2,32 @@ use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum TemplateOperation {
-    Copy { source: PathBuf, target: PathBuf, target_exists: bool },
-    Write { target: PathBuf, content: String, target_exists: bool },
-    CreateDirectory { target: PathBuf, target_exists: bool },
-    Ignore { source: PathBuf },
-    MultipleWrite { writes: Vec<WriteOp> },
+    Copy {
+        source: PathBuf,
+        target: PathBuf,
+        target_exists: bool,
+    },
+    Write {
+        target: PathBuf,
+        content: String,
+        target_exists: bool,
+    },
+    CreateDirectory {
+        target: PathBuf,
+        target_exists: bool,
+    },
+    /// Appends `content` to `target`, creating it when missing.
+    Append {
+        target: PathBuf,
+        content: String,
+        target_exists: bool,
+    },
+    Ignore {
+        source: PathBuf,
+    },
+    MultipleWrite {
+        writes: Vec<WriteOp>,
+    },
 }
 
 #[derive(Debug)]
@@ -14,6 +35,17 @@ pub struct WriteOp {
     pub target: PathBuf,
     pub content: String,
     pub target_exists: bool,
+    /// The loop iteration that emitted the file.
+    pub iteration: Option<LoopIteration>,
+}
+
+/// The iteration of a loop template that emitted a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopIteration {
+    /// Zero-based position in the loop.
+    pub index: usize,
+    /// The loop variable, or an array of them for `for a, b in ...`.
+    pub item: serde_json::Value,
 }
 
 impl TemplateOperation {
@@ -26,6 +58,7 @@ impl TemplateOperation {
             TemplateOperation::Copy { target, .. } => Some(target),
             TemplateOperation::Write { target, .. } => Some(target),
             TemplateOperation::CreateDirectory { target, .. } => Some(target),
+            TemplateOperation::Append { target, .. } => Some(target),
             TemplateOperation::Ignore { .. } => None,
             TemplateOperation::MultipleWrite { .. } => None,
         }
@@ -46,6 +79,9 @@ impl TemplateOperation {
             TemplateOperation::CreateDirectory { target, .. } => {
                 format!("create directory '{}'", target.display())
             }
+            TemplateOperation::Append { target, .. } => {
+                format!("append to '{}'", target.display())
+            }
             TemplateOperation::Ignore { source } => {
                 format!("ignore '{}'", source.display())
             }
@@ -128,6 +164,20 @@ impl TemplateOperation {
                 }
             }
 
+            TemplateOperation::Append { target, target_exists, .. } => {
+                if !*target_exists {
+                    format!("{}Writing to '{}'", prefix, target.display())
+                } else if user_confirmed_overwrite {
+                    format!("{}Appending to '{}'", prefix, target.display())
+                } else {
+                    format!(
+                        "{}Skipping append to '{}' (content already present)",
+                        prefix,
+                        target.display()
+                    )
+                }
+            }
+
             TemplateOperation::Ignore { source } => {
                 format!(
                     "{}Ignoring '{}' (matches ignore pattern)",
@@ -348,11 +398,13 @@ mod tests {
                 target: PathBuf::from("/tmp/file1.txt"),
                 content: "content1".to_string(),
                 target_exists: false,
+                iteration: None,
             },
             WriteOp {
                 target: PathBuf::from("/tmp/file2.txt"),
                 content: "content2".to_string(),
                 target_exists: false,
+                iteration: None,
             },
         ];
         let op = TemplateOperation::MultipleWrite { writes };
@@ -408,11 +460,13 @@ mod tests {
                 target: PathBuf::from("/output/file1.txt"),
                 content: "content1".to_string(),
                 target_exists: false,
+                iteration: None,
             },
             WriteOp {
                 target: PathBuf::from("/output/file2.txt"),
                 content: "content2".to_string(),
                 target_exists: false,
+                iteration: None,
             },
         ];
         let op = TemplateOperation::MultipleWrite { writes };
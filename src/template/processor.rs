@@ -1,15 +1,26 @@
 use crate::{
     cli::context::GenerationContext,
+    config::{NonAsciiNames, SuffixBehavior, WindowsPaths},
     error::{Error, Result},
     ext::PathExt,
+    generated::is_binary,
+    names,
     renderer::TemplateRenderer,
-    template::operation::{TemplateOperation, TemplateOperation::MultipleWrite, WriteOp},
+    template::operation::{
+        LoopIteration, TemplateOperation, TemplateOperation::MultipleWrite, WriteOp,
+    },
+    winpath,
 };
 use globset::GlobSet;
+use indexmap::IndexMap;
 use log::debug;
 use regex::Regex;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+/// Reason given when a path part renders empty. Files and directories are
+/// conditionally left out this way, so it is not treated as a failure.
+pub const INVALID_RENDERED_PATH: &str = "The rendered path is not valid";
 
 pub struct TemplateProcessor<'a, P: AsRef<Path>> {
     /// Dependencies
@@ -22,13 +33,19 @@ pub struct TemplateProcessor<'a, P: AsRef<Path>> {
     answers: &'a serde_json::Value,
     template_config: TemplateConfig<'a>,
     loop_detector: Regex,
+    loop_start_regex: Regex,
     loop_end_regex: Regex,
 }
 
 pub struct TemplateConfig<'a> {
     pub template_suffix: &'a str,
+    pub suffixes: &'a IndexMap<String, SuffixBehavior>,
     pub loop_separator: &'a str,
     pub loop_content_separator: &'a str,
+    pub render_all: bool,
+    /// What happens to names Windows cannot create; `None` on other platforms.
+    pub windows_paths: Option<WindowsPaths>,
+    pub non_ascii_names: NonAsciiNames,
 }
 
 impl<'a> TemplateProcessor<'a, PathBuf> {
@@ -40,8 +57,12 @@ impl<'a> TemplateProcessor<'a, PathBuf> {
         let config = context.config();
         let template_config = TemplateConfig {
             template_suffix: config.template_suffix.as_str(),
+            suffixes: &config.suffixes,
             loop_separator: config.loop_separator.as_str(),
             loop_content_separator: config.loop_content_separator.as_str(),
+            render_all: config.render_all,
+            windows_paths: cfg!(windows).then_some(config.windows_paths),
+            non_ascii_names: config.non_ascii_names,
         };
 
         Self {
@@ -49,10 +70,14 @@ impl<'a> TemplateProcessor<'a, PathBuf> {
             bakerignore,
             template_root: context.template_root().clone(),
             output_root: context.output_root().clone(),
-            answers: context.answers(),
+            answers: context.template_answers(),
             template_config,
             loop_detector: Regex::new(r"\{\%\s*for\s+.*in.*\%\}")
                 .expect("valid for-loop regex"),
+            loop_start_regex: Regex::new(
+                r"\{%-?\s*for\s+(\w+(?:\s*,\s*\w+)*)\s+in\s.*?-?%\}",
+            )
+            .expect("valid for-loop start regex"),
             loop_end_regex: Regex::new(r"(\{\%\s*endfor\s*\%\})")
                 .expect("valid endfor regex"),
         }
@@ -115,22 +140,31 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         true
     }
 
-    /// Checks if the provided path is a Baker template file by checking if the file's extension
-    /// is the same as `template_suffix` (defaults to .baker.j2)
+    /// Checks whether the provided path is a Baker template file, i.e. whether its file
+    /// name ends with `template_suffix` (defaults to .baker.j2) or one of `suffixes`.
+    /// The longest matching suffix wins.
     ///
     /// # Arguments
     /// * `path` - A path to the file
     ///
     /// # Returns
-    /// * `true` - if the file has the same extension as the `template_suffix`
-    /// * `false` - if the path is not a template file
+    /// * `Some((suffix, behavior))` - the matching suffix and what to do with the file
+    /// * `None` - if the path is not a template file
     ///
-    fn is_template_file<T: AsRef<Path>>(&self, path: T) -> bool {
-        let path = path.as_ref();
-
-        path.file_name().and_then(|n| n.to_str()).is_some_and(|file_name| {
-            file_name.ends_with(self.template_config.template_suffix)
-        })
+    fn suffix_behavior<T: AsRef<Path>>(
+        &self,
+        path: T,
+    ) -> Option<(&'a str, SuffixBehavior)> {
+        let file_name = path.as_ref().file_name()?.to_str()?;
+        std::iter::once((self.template_config.template_suffix, SuffixBehavior::Render))
+            .chain(
+                self.template_config
+                    .suffixes
+                    .iter()
+                    .map(|(suffix, behavior)| (suffix.as_str(), *behavior)),
+            )
+            .filter(|(suffix, _)| file_name.ends_with(suffix))
+            .max_by_key(|(suffix, _)| suffix.len())
     }
 
     /// Returns the relative path from template root for use in error messages.
@@ -157,7 +191,14 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     /// * `Result<PathBuf>` - The rendered path or an error
     ///
     fn render_template_entry(&self, template_entry: &Path) -> Result<PathBuf> {
-        let rendered_entry = self.engine.render_path(template_entry, self.answers)?;
+        // Answers may contain `/`-separated paths; use the native separator so the
+        // rendered entry is split into components consistently on every platform.
+        let with_loop_variables =
+            self.set_loop_variables(template_entry.to_str_checked()?);
+        let rendered_entry = self
+            .engine
+            .render_path(Path::new(with_loop_variables.as_ref()), self.answers)?
+            .replace('/', std::path::MAIN_SEPARATOR_STR);
 
         if !self.rendered_path_has_valid_parts(
             template_entry.to_str_checked()?,
@@ -165,7 +206,7 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         ) {
             return Err(Error::ProcessError {
                 source_path: rendered_entry.to_string(),
-                e: "The rendered path is not valid".to_string(),
+                e: INVALID_RENDERED_PATH.to_string(),
             });
         }
 
@@ -181,10 +222,12 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     /// * `Result<PathBuf>` - Path with suffix removed
     ///
     fn remove_template_suffix(&self, target_path: &Path) -> Result<PathBuf> {
+        self.remove_suffix(target_path, self.template_config.template_suffix)
+    }
+
+    fn remove_suffix(&self, target_path: &Path, suffix: &str) -> Result<PathBuf> {
         let target_path_str = target_path.to_str_checked()?;
-        let target = target_path_str
-            .strip_suffix(self.template_config.template_suffix)
-            .unwrap_or(target_path_str);
+        let target = target_path_str.strip_suffix(suffix).unwrap_or(target_path_str);
 
         Ok(PathBuf::from(target))
     }
@@ -198,6 +241,14 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     /// # Returns
     /// * `Result<PathBuf>` - The target path in the output directory
     ///
+    /// Rendered paths containing `..`, a root or a drive prefix are rejected with
+    /// `Error::UnsafeTargetPath` so a template can never write outside the output root.
+    /// Every name is normalized to NFC; names that are not ASCII are then handled
+    /// as `non_ascii_names` says. A name that ends up empty, `.` or `..` is
+    /// rejected too. On Windows, names Windows cannot create are
+    /// rejected with `Error::InvalidWindowsPath` or escaped, depending on
+    /// `windows_paths`.
+    ///
     fn get_target_path(
         &self,
         rendered_entry: &Path,
@@ -209,9 +260,84 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
                 source_path: template_entry.display().to_string(),
                 e: e.to_string(),
             })?;
+
+        if let Some(reason) = unsafe_path_reason(target_path) {
+            return Err(Error::UnsafeTargetPath {
+                template_entry: self
+                    .get_template_name(template_entry)
+                    .unwrap_or_else(|| template_entry.display().to_string()),
+                rendered: target_path.display().to_string(),
+                reason: reason.to_string(),
+            });
+        }
+
+        let target_path = self.sanitize_names(target_path, template_entry)?;
+
         Ok(self.output_root.as_ref().join(target_path))
     }
 
+    /// `target_path` with every name normalized, and with the names Windows cannot
+    /// create escaped, or an error naming the first name the config rejects.
+    fn sanitize_names(
+        &self,
+        target_path: &Path,
+        template_entry: &Path,
+    ) -> Result<PathBuf> {
+        let template_name = || {
+            self.get_template_name(template_entry)
+                .unwrap_or_else(|| template_entry.display().to_string())
+        };
+        let mut sanitized = PathBuf::new();
+        for component in target_path.components() {
+            let Component::Normal(name) = component else {
+                sanitized.push(component);
+                continue;
+            };
+            let name = names::apply(
+                &name.to_string_lossy(),
+                self.template_config.non_ascii_names,
+            )
+            .map_err(|name| Error::NonAsciiName {
+                template_entry: template_name(),
+                rendered: target_path.display().to_string(),
+                name,
+            })?;
+            let name = match self.template_config.windows_paths {
+                None => name,
+                Some(policy) => match (winpath::invalid_name_reason(&name), policy) {
+                    (None, _) => name,
+                    (Some(_), WindowsPaths::Escape) => winpath::escape_name(&name),
+                    (Some(reason), WindowsPaths::Reject) => {
+                        return Err(Error::InvalidWindowsPath {
+                            template_entry: template_name(),
+                            rendered: target_path.display().to_string(),
+                            reason,
+                        })
+                    }
+                },
+            };
+            // Normalizing drops control characters and transliterating drops
+            // accents, so `.\u{7}.` or `.\u{301}.` would become `..`.
+            let reason = match name.as_str() {
+                "" => Some("a name is empty once normalized"),
+                "." | ".." => Some("a name becomes '.' or '..' once normalized"),
+                _ => unsafe_path_reason(Path::new(&name)).or_else(|| {
+                    name.contains(['/', '\\'])
+                        .then_some("a name contains a separator once normalized")
+                }),
+            };
+            if let Some(reason) = reason {
+                return Err(Error::UnsafeTargetPath {
+                    template_entry: template_name(),
+                    rendered: target_path.display().to_string(),
+                    reason: reason.to_string(),
+                });
+            }
+            sanitized.push(name);
+        }
+        Ok(sanitized)
+    }
+
     /// Returns true if the path contains any MiniJinja for-loop block delimiters anywhere in the path (not just filename).
     pub fn is_template_with_loop<T: AsRef<Path>>(&self, template_entry: T) -> bool {
         let path_str = template_entry.as_ref().to_string_lossy();
@@ -230,16 +356,16 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         let template_entry = template_entry.as_ref().to_path_buf();
         let rendered_entry = self.render_template_entry(&template_entry)?;
         let target_path = self.get_target_path(&rendered_entry, &template_entry)?;
+        let behavior = self.suffix_behavior(&rendered_entry);
         // For template files the output path has the suffix stripped, so we must
         // compute target_exists after stripping to correctly detect pre-existing files.
-        let final_target_path = if self.is_template_file(&rendered_entry)
-            && !self.is_template_with_loop(&template_entry)
-        {
-            self.remove_template_suffix(&target_path)?
-        } else {
-            target_path.clone()
+        let final_target_path = match behavior {
+            Some((suffix, _)) if !self.is_template_with_loop(&template_entry) => {
+                self.remove_suffix(&target_path, suffix)?
+            }
+            _ => target_path.clone(),
         };
-        let target_exists = final_target_path.exists();
+        let target_exists = winpath::long_path(&final_target_path).exists();
 
         // Skip if entry is in .bakerignore
         if self.bakerignore.is_match(&template_entry) {
@@ -247,35 +373,47 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         }
 
         // Handle different types of entries
-        match (template_entry.is_file(), self.is_template_file(&rendered_entry)) {
+        match (template_entry.is_file(), behavior.map(|(_, behavior)| behavior)) {
             // Template file
-            (true, true) => {
-                let template_content = fs::read_to_string(&template_entry)?;
-                let template_name =
-                    template_entry.file_name().and_then(|name| name.to_str());
-                let relative_path = self.get_template_name(&template_entry);
+            (true, Some(SuffixBehavior::Render)) => {
                 if self.is_template_with_loop(&template_entry) {
                     debug!("Processing loop template file: {}", template_entry.display());
+                    let template_name =
+                        template_entry.file_name().and_then(|name| name.to_str());
                     return self
                         .render_loop_template_file(&template_entry, template_name);
                 }
-                let content = self
-                    .engine
-                    .render(&template_content, self.answers, template_name)
-                    .map_err(|e| Error::ProcessError {
-                        source_path: relative_path
-                            .unwrap_or_else(|| template_entry.display().to_string()),
-                        e: e.to_string(),
-                    })?;
-
                 Ok(TemplateOperation::Write {
                     target: final_target_path,
-                    content,
+                    content: self.render_file(&template_entry)?,
+                    target_exists,
+                })
+            }
+            // Raw file: copied verbatim under the name without its suffix
+            (true, Some(SuffixBehavior::Raw)) => Ok(TemplateOperation::Copy {
+                source: template_entry,
+                target: final_target_path,
+                target_exists,
+            }),
+            // Rendered and appended to the output file
+            (true, Some(SuffixBehavior::Append)) => Ok(TemplateOperation::Append {
+                content: self.render_file(&template_entry)?,
+                target: final_target_path,
+                target_exists,
+            }),
+            // Any text file, when the config renders all files
+            (true, None)
+                if self.template_config.render_all
+                    && !is_binary(&fs::read(&template_entry)?) =>
+            {
+                Ok(TemplateOperation::Write {
+                    target: target_path,
+                    content: self.render_file(&template_entry)?,
                     target_exists,
                 })
             }
             // Regular file
-            (true, false) => Ok(TemplateOperation::Copy {
+            (true, None) => Ok(TemplateOperation::Copy {
                 source: template_entry,
                 target: target_path,
                 target_exists,
@@ -288,6 +426,20 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         }
     }
 
+    /// Renders the content of a template file with the answers.
+    fn render_file(&self, template_entry: &Path) -> Result<String> {
+        let template_content = fs::read_to_string(template_entry)?;
+        let template_name = template_entry.file_name().and_then(|name| name.to_str());
+        self.engine.render(&template_content, self.answers, template_name).map_err(|e| {
+            Error::ProcessError {
+                source_path: self
+                    .get_template_name(template_entry)
+                    .unwrap_or_else(|| template_entry.display().to_string()),
+                e: e.to_string(),
+            }
+        })
+    }
+
     /// Renders the loop template file by injecting content into the loop and rendering the result.
     ///
     /// # Arguments
@@ -339,7 +491,9 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         Ok(MultipleWrite { writes: write_operations })
     }
 
-    fn split_content(&self, input: &str) -> Vec<(String, String)> {
+    /// Splits rendered loop output into the filename, content and iteration of
+    /// every emitted file.
+    fn split_content(&self, input: &str) -> Vec<(String, String, Option<LoopIteration>)> {
         input
             .split(self.template_config.loop_separator)
             .filter_map(|part| {
@@ -350,25 +504,60 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
                 let mut sections =
                     trimmed.splitn(2, self.template_config.loop_content_separator);
                 let filename = sections.next()?.trim().to_string();
-                let content = sections.next()?.trim().to_string();
-                Some((filename, content))
+                let rest = sections.next()?;
+                let (content, iteration) =
+                    match rest.rsplit_once(self.template_config.loop_content_separator) {
+                        Some((content, iteration)) => {
+                            (content, serde_json::from_str(iteration.trim()).ok())
+                        }
+                        None => (rest, None),
+                    };
+                let iteration = iteration.and_then(|value: serde_json::Value| {
+                    Some(LoopIteration {
+                        index: value.get("index")?.as_u64()? as usize,
+                        item: value.get("item")?.clone(),
+                    })
+                });
+                Some((filename, content.trim().to_string(), iteration))
             })
             .collect()
     }
 
+    /// Turns a loop template path into a template emitting, per iteration, the
+    /// filename, the content and the iteration, separated by the loop separators.
     fn inject_loop_content(
         &self,
         template_entry: &Path,
         raw_template_content: &str,
     ) -> Result<String> {
+        let separator = self.template_config.loop_content_separator;
         let injected_content = format!(
-            "{}{}{}$1",
-            self.template_config.loop_content_separator,
-            raw_template_content,
+            "{separator}{raw_template_content}{separator}\
+             {{{{ {{\"index\": loop_index, \"item\": loop_item}} | tojson }}}}{}",
             self.template_config.loop_separator
         );
-        let template_str = template_entry.to_str_checked()?;
-        Ok(self.loop_end_regex.replace_all(template_str, injected_content).into_owned())
+        let with_iteration = self.set_loop_variables(template_entry.to_str_checked()?);
+        Ok(self
+            .loop_end_regex
+            .replace_all(&with_iteration, |caps: &regex::Captures| {
+                format!("{injected_content}{}", &caps[1])
+            })
+            .into_owned())
+    }
+
+    /// Sets `loop_item` and `loop_index` at the start of every for-loop body.
+    fn set_loop_variables<'s>(&self, template: &'s str) -> std::borrow::Cow<'s, str> {
+        self.loop_start_regex.replace_all(template, |caps: &regex::Captures| {
+            let vars: Vec<&str> = caps[1].split(',').map(str::trim).collect();
+            let item = match vars.as_slice() {
+                [single] => single.to_string(),
+                many => format!("[{}]", many.join(", ")),
+            };
+            format!(
+                "{}{{% set loop_item = {item} %}}{{% set loop_index = loop.index0 %}}",
+                &caps[0]
+            )
+        })
     }
 
     fn collect_loop_write_ops(
@@ -379,29 +568,53 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     ) -> Result<Vec<WriteOp>> {
         self.split_content(rendered_content)
             .into_iter()
-            .map(|(rendered_filename, content)| {
+            .map(|(rendered_filename, content, iteration)| {
                 let mut output_file_path = PathBuf::from(&rendered_filename);
                 if !output_file_path.starts_with(rendered_parent_dir) {
                     output_file_path = rendered_parent_dir.join(&rendered_filename);
                 }
                 let final_output_path =
                     self.get_target_path(&output_file_path, template_entry)?;
-                let target_exists = final_output_path.exists();
+                let target_exists = winpath::long_path(&final_output_path).exists();
                 Ok(WriteOp {
                     target: self.remove_template_suffix(&final_output_path)?,
                     content,
                     target_exists,
+                    iteration,
                 })
             })
             .collect()
     }
 }
 
+/// Returns why a path relative to the output root would escape it, if it would.
+/// Drive letters such as `C:` are only checked on Windows, where they change the
+/// root or name an alternate data stream; elsewhere `a:b` is an ordinary name.
+pub(crate) fn unsafe_path_reason(path: &Path) -> Option<&'static str> {
+    path.components().find_map(|component| match component {
+        Component::ParentDir => Some("it contains '..'"),
+        Component::RootDir => Some("it is an absolute path"),
+        Component::Prefix(_) => Some("it contains a drive or UNC prefix"),
+        Component::Normal(part) => {
+            let part = part.as_encoded_bytes();
+            (cfg!(windows)
+                && part.len() >= 2
+                && part[0].is_ascii_alphabetic()
+                && part[1] == b':')
+                .then_some("it contains a drive letter")
+        }
+        Component::CurDir => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        cli::context::GenerationContext, config::ConfigV1, renderer::MiniJinjaRenderer,
+        cli::context::GenerationContext,
+        config::ConfigV1,
+        constants::{DEFAULT_LOOP_CONTENT_SEPARATOR, DEFAULT_LOOP_SEPARATOR},
+        renderer::MiniJinjaRenderer,
         template::operation::TemplateOperation,
     };
     use fs::File;
@@ -413,31 +626,66 @@ mod tests {
 
     fn new_test_processor(
         answers: serde_json::Value,
+    ) -> (TempDir, TempDir, TemplateProcessor<'static, PathBuf>) {
+        new_test_processor_with(answers, |_| {})
+    }
+
+    /// [`new_test_processor`] with its config changed by `configure`.
+    fn new_test_processor_with(
+        answers: serde_json::Value,
+        configure: impl FnOnce(&mut ConfigV1),
     ) -> (TempDir, TempDir, TemplateProcessor<'static, PathBuf>) {
         let template_root = TempDir::new().unwrap();
         let output_root = TempDir::new().unwrap();
         let engine = Box::new(MiniJinjaRenderer::new());
         let bakerignore = GlobSetBuilder::new().build().unwrap();
 
+        let mut config = ConfigV1 {
+            template_suffix: ".baker.j2".into(),
+            loop_separator: "".into(),
+            loop_content_separator: "".into(),
+            template_globs: Vec::new(),
+            import_root: None,
+            questions: IndexMap::new(),
+            post_hook_filename: "post".into(),
+            pre_hook_filename: "pre".into(),
+            post_hook_runner: Vec::new(),
+            pre_hook_runner: Vec::new(),
+            post_hook_print_stdout: false,
+            follow_symlinks: false,
+            generated_file_name: None,
+            conflict_marker_style: None,
+            answer_precedence: None,
+            pre_hook_working_dir: None,
+            post_hook_working_dir: None,
+            hook_prompts: false,
+            output_subdir: None,
+            hook_answers_schema: None,
+            namespace_hook_answers: false,
+            suffixes: Default::default(),
+            answer_transformers: Vec::new(),
+            context_alias: None,
+            render_all: false,
+            copier_answers: false,
+            validations: Vec::new(),
+            strict: false,
+            editorconfig: false,
+            introspect: IndexMap::new(),
+            prompt_theme: Default::default(),
+            pre_hook_image: None,
+            post_hook_image: None,
+            safe_expressions: false,
+            durability: Default::default(),
+            windows_paths: Default::default(),
+            non_ascii_names: Default::default(),
+            undefined_behavior: Default::default(),
+            questions_files: Vec::new(),
+        };
+        configure(&mut config);
         let mut context = GenerationContext::new(
             template_root.path().to_path_buf(),
             output_root.path().to_path_buf(),
-            ConfigV1 {
-                template_suffix: ".baker.j2".into(),
-                loop_separator: "".into(),
-                loop_content_separator: "".into(),
-                template_globs: Vec::new(),
-                import_root: None,
-                questions: IndexMap::new(),
-                post_hook_filename: "post".into(),
-                pre_hook_filename: "pre".into(),
-                post_hook_runner: Vec::new(),
-                pre_hook_runner: Vec::new(),
-                post_hook_print_stdout: false,
-                follow_symlinks: false,
-                generated_file_name: None,
-                conflict_marker_style: None,
-            },
+            config,
             Vec::new(),
             false,
             false,
@@ -889,6 +1137,104 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_target_path_rejects_escaping_paths() {
+        let (template_root, _output_root, processor) = new_test_processor(json!({}));
+        let template_entry = template_root.path().join("{{ name }}.txt");
+
+        let mut cases =
+            vec![("../outside.txt", "'..'"), ("nested/../../outside.txt", "'..'")];
+        if cfg!(windows) {
+            cases.push(("sub/C:evil.txt", "drive letter"));
+        } else {
+            assert_eq!(unsafe_path_reason(Path::new("sub/a:b.txt")), None);
+        }
+        for (rendered, reason) in cases {
+            let rendered_entry = template_root.path().join(rendered);
+            match processor.get_target_path(&rendered_entry, &template_entry) {
+                Err(Error::UnsafeTargetPath {
+                    template_entry,
+                    rendered: r,
+                    reason: e,
+                }) => {
+                    assert_eq!(template_entry, "{{ name }}.txt");
+                    assert_eq!(r, rendered);
+                    assert!(e.contains(reason), "unexpected reason: {e}");
+                }
+                other => {
+                    panic!("Expected UnsafeTargetPath for {rendered}, got {other:?}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_target_path_handles_names_windows_cannot_create() {
+        let (template_root, output_root, mut processor) = new_test_processor(json!({}));
+        let template_entry = template_root.path().join("{{ name }}");
+        let rendered_entry = template_root.path().join("src").join("aux.js");
+
+        processor.template_config.windows_paths = Some(WindowsPaths::Reject);
+        let err =
+            processor.get_target_path(&rendered_entry, &template_entry).unwrap_err();
+        assert!(matches!(err, Error::InvalidWindowsPath { .. }));
+        assert!(err.to_string().contains("'aux.js' is a reserved device name"));
+
+        processor.template_config.windows_paths = Some(WindowsPaths::Escape);
+        let target = processor.get_target_path(&rendered_entry, &template_entry).unwrap();
+        assert_eq!(target, output_root.path().join("src").join("aux_.js"));
+    }
+
+    #[test]
+    fn test_get_target_path_normalizes_names() {
+        let (template_root, output_root, mut processor) = new_test_processor(json!({}));
+        let template_entry = template_root.path().join("{{ name }}");
+        let rendered_entry = template_root.path().join("cafe\u{301}\u{7}.md");
+
+        let target = processor.get_target_path(&rendered_entry, &template_entry).unwrap();
+        assert_eq!(target, output_root.path().join("caf\u{e9}.md"));
+
+        processor.template_config.non_ascii_names = NonAsciiNames::Transliterate;
+        let target = processor.get_target_path(&rendered_entry, &template_entry).unwrap();
+        assert_eq!(target, output_root.path().join("cafe.md"));
+
+        processor.template_config.non_ascii_names = NonAsciiNames::Reject;
+        let err =
+            processor.get_target_path(&rendered_entry, &template_entry).unwrap_err();
+        assert!(
+            matches!(err, Error::NonAsciiName { name, .. } if name == "caf\u{e9}.md")
+        );
+    }
+
+    #[test]
+    fn test_get_target_path_rejects_names_normalized_to_dot_dot() {
+        let (template_root, _output_root, mut processor) = new_test_processor(json!({}));
+        let template_entry = template_root.path().join("{{ name }}");
+        processor.template_config.non_ascii_names = NonAsciiNames::Transliterate;
+
+        for name in [".\u{7}.", ".\u{301}.", "\u{7}"] {
+            let rendered_entry = template_root.path().join(name).join("outside.txt");
+            let err =
+                processor.get_target_path(&rendered_entry, &template_entry).unwrap_err();
+            assert!(
+                matches!(err, Error::UnsafeTargetPath { .. }),
+                "expected UnsafeTargetPath for {name:?}, got {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn process_rejects_answer_escaping_output_root() {
+        let (template_root, _output_root, processor) =
+            new_test_processor(json!({"name": "sub/../../escaped"}));
+        let template_entry = template_root.path().join("{{ name }}.txt");
+        File::create(&template_entry).unwrap();
+
+        let err = processor.process(template_entry).unwrap_err();
+        assert!(matches!(err, Error::UnsafeTargetPath { .. }));
+        assert!(err.to_string().contains("sub/../../escaped.txt"));
+    }
+
     #[test]
     fn test_process_template_file_write_operation() {
         use crate::template::operation::TemplateOperation;
@@ -942,6 +1288,46 @@ mod tests {
         assert!(!processor.is_template_with_loop(path));
     }
 
+    #[test]
+    fn loop_template_exposes_iteration_to_path_and_content() {
+        let answers = json!({"services": [{"name": "api"}, {"name": "web"}]});
+        let (template_root, output_root, processor) =
+            new_test_processor_with(answers, |config| {
+                config.loop_separator = DEFAULT_LOOP_SEPARATOR.into();
+                config.loop_content_separator = DEFAULT_LOOP_CONTENT_SEPARATOR.into();
+            });
+        let file_path = template_root.path().join(
+            "{% for service in services %}{{ loop_index }}-{{ loop_item.name }}.txt.baker.j2{% endfor %}",
+        );
+        File::create(&file_path)
+            .unwrap()
+            .write_all(b"{{ service.name }} #{{ loop_index }}")
+            .unwrap();
+
+        let MultipleWrite { writes } = processor.process(file_path).unwrap() else {
+            panic!("Expected MultipleWrite operation");
+        };
+        let emitted: Vec<_> = writes
+            .iter()
+            .map(|w| (w.target.clone(), w.content.clone(), w.iteration.clone()))
+            .collect();
+        assert_eq!(
+            emitted,
+            vec![
+                (
+                    output_root.path().join("0-api.txt"),
+                    "api #0".to_string(),
+                    Some(LoopIteration { index: 0, item: json!({"name": "api"}) })
+                ),
+                (
+                    output_root.path().join("1-web.txt"),
+                    "web #1".to_string(),
+                    Some(LoopIteration { index: 1, item: json!({"name": "web"}) })
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_is_template_with_loop_complex() {
         let (_template_root, _output_root, processor) = new_test_processor(json!({}));
@@ -0,0 +1,121 @@
+//! Paths that Windows cannot create as they are.
+//!
+//! Windows refuses file names that are reserved for devices, such as `CON` or
+//! `nul.txt`, names with characters like `:` or `?`, and names ending in a dot or
+//! a space. Such names are rejected, or escaped with `windows_paths: escape`.
+//! Paths longer than `MAX_PATH` are written through the verbatim `\\?\` prefix.
+
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+/// Paths this long or longer need the `\\?\` prefix on Windows.
+const MAX_PATH: usize = 260;
+
+/// File names Windows reserves for devices, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8",
+    "LPT9",
+];
+
+/// Characters Windows does not allow in file names.
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*', '\\'];
+
+/// Why `name`, one component of a path, cannot be created on Windows.
+pub fn invalid_name_reason(name: &str) -> Option<String> {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        return Some(format!("'{name}' is a reserved device name"));
+    }
+    if let Some(c) = name.chars().find(|c| INVALID_CHARS.contains(c) || c.is_control()) {
+        return Some(format!("'{name}' contains the character {c:?}"));
+    }
+    if name.ends_with(['.', ' ']) && name != "." && name != ".." {
+        return Some(format!("'{name}' ends with a dot or a space"));
+    }
+    None
+}
+
+/// `name` changed so that Windows can create it: invalid characters become `_`,
+/// a reserved stem gets a `_` appended, e.g. `nul.txt` becomes `nul_.txt`, and
+/// trailing dots and spaces are replaced with `_`.
+pub fn escape_name(name: &str) -> String {
+    let mut escaped: String = name
+        .chars()
+        .map(|c| match INVALID_CHARS.contains(&c) || c.is_control() {
+            true => '_',
+            false => c,
+        })
+        .collect();
+    let stem_len = escaped.find('.').unwrap_or(escaped.len());
+    let stem = escaped[..stem_len].trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+        escaped.insert(stem_len, '_');
+    }
+    let kept = escaped.trim_end_matches(['.', ' ']).len();
+    if kept < escaped.len() {
+        escaped.replace_range(kept.., &"_".repeat(escaped.len() - kept));
+    }
+    escaped
+}
+
+/// `path` in a form Windows can open even when it is longer than `MAX_PATH`: made
+/// absolute and prefixed with `\\?\`. Shorter paths, and every path on other
+/// platforms, are returned as they are.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    if !cfg!(windows) || path.as_os_str().len() < MAX_PATH {
+        return Cow::Borrowed(path);
+    }
+    let Ok(absolute) = std::path::absolute(path) else {
+        return Cow::Borrowed(path);
+    };
+    Cow::Owned(verbatim(&absolute.to_string_lossy()))
+}
+
+/// The verbatim form of an absolute Windows path, which is taken as is, without
+/// the `MAX_PATH` limit.
+fn verbatim(absolute: &str) -> PathBuf {
+    let absolute = absolute.replace('/', "\\");
+    if absolute.starts_with(r"\\?\") {
+        return PathBuf::from(absolute);
+    }
+    match absolute.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{unc}")),
+        None => PathBuf::from(format!(r"\\?\{absolute}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_and_escapes_names_windows_refuses() {
+        for name in ["nul", "CON.txt", "com1.tar.gz", "a:b", "what?", "trailing."] {
+            assert!(invalid_name_reason(name).is_some(), "{name}");
+        }
+        for name in ["console.log", "nullable.rs", ".gitignore", "LPT10", "node_modules"]
+        {
+            assert_eq!(invalid_name_reason(name), None, "{name}");
+        }
+        assert_eq!(escape_name("nul.txt"), "nul_.txt");
+        assert_eq!(escape_name("AUX"), "AUX_");
+        assert_eq!(escape_name("a<b>:c"), "a_b__c");
+        assert_eq!(escape_name("notes. "), "notes__");
+        assert_eq!(escape_name("fine.rs"), "fine.rs");
+    }
+
+    #[test]
+    fn prefixes_long_paths() {
+        assert_eq!(verbatim(r"C:\work\app"), PathBuf::from(r"\\?\C:\work\app"));
+        assert_eq!(
+            verbatim(r"\\server\share\app"),
+            PathBuf::from(r"\\?\UNC\server\share\app")
+        );
+        assert_eq!(verbatim(r"\\?\C:\app"), PathBuf::from(r"\\?\C:\app"));
+        let short = Path::new("short/path");
+        assert_eq!(long_path(short), short);
+    }
+}
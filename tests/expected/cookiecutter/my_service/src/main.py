@@ -0,0 +1 @@
+import my_service_app
\ No newline at end of file
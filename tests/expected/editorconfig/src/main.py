@@ -0,0 +1,2 @@
+def demo():
+    return "demo"
@@ -668,6 +668,7 @@ fn test_jsonschema_file_template_from_gitea() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
 
     run(args).expect("Baker run failed");
@@ -862,6 +863,7 @@ fn test_template_with_submodule_schema_file() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
 
     run(args).expect("Baker run failed - submodule schema_file should be accessible");
@@ -1051,6 +1053,7 @@ Entities count: {{ entities | length }}
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
 
     let result = run(args);
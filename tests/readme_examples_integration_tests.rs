@@ -161,6 +161,7 @@ fn test_non_interactive_mode_with_defaults() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
     run(args).unwrap();
 
@@ -193,6 +194,16 @@ fn test_nested_answer_context() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+manifest_values: false,
+answers_stream: false,
+remote: None,
+output: None,
+stdin_tar: false,
+dump_context: None,
+quiet_hooks: false,
+encrypt_answers: false,
+keyring: false,
+        ..Default::default()
     };
     run(args).unwrap();
 
@@ -295,6 +306,7 @@ fn test_answers_file() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
     run(args).unwrap();
 
@@ -334,6 +346,7 @@ fn test_answers_file_with_cli_override() {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
     run(args).unwrap();
 
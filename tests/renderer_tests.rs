@@ -87,9 +87,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_suffix_behaviors() {
+        run_and_assert(
+            "tests/templates/suffix_behaviors",
+            "tests/expected/suffix_behaviors",
+            None,
+        );
+    }
+
+    #[test]
+    fn test_editorconfig() {
+        run_and_assert(
+            "tests/templates/editorconfig",
+            "tests/expected/editorconfig",
+            None,
+        );
+    }
+
+    #[test]
+    fn test_cookiecutter_template() {
+        run_and_assert(
+            "tests/templates/cookiecutter",
+            "tests/expected/cookiecutter",
+            None,
+        );
+    }
+
     #[test]
     #[should_panic(
-        expected = "called `Result::unwrap()` on an `Err` value: ConfigValidation(\"template_suffix must start with '.' and have at least 1 character after it\")"
+        expected = "called `Result::unwrap()` on an `Err` value: ConfigValidation(\"template_suffix must start with '.' and have at least 1 character after it (at template_suffix, baker.yaml:2:1)\")"
     )]
     fn test_wrong_template_suffix() {
         run_and_assert("tests/templates/wrong_template_suffix", "", None);
@@ -97,7 +124,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "called `Result::unwrap()` on an `Err` value: ConfigValidation(\"template_suffix must not be empty\")"
+        expected = "called `Result::unwrap()` on an `Err` value: ConfigValidation(\"template_suffix must not be empty (at template_suffix, baker.yaml:2:1)\")"
     )]
     fn test_empty_template_suffix() {
         run_and_assert("tests/templates/empty_template_suffix", "", None);
@@ -0,0 +1 @@
+import {{ cookiecutter.__module }}
@@ -10,7 +10,11 @@
 
 mod utils;
 
-use baker::cli::{run, run_update_in_dir, GenerateArgs, SkipConfirm::All, UpdateArgs};
+use baker::cli::workspace::{update_projects, Workspace};
+use baker::cli::{
+    run, run_update_in_dir, run_verify, GenerateArgs, SkipConfirm::All, UpdateArgs,
+    VerifyArgs,
+};
 use baker::constants::DEFAULT_GENERATED_FILE_NAME;
 use baker::generated;
 use std::fs;
@@ -33,6 +37,7 @@ fn generate_into_tmp(template: &str, answers: Option<&str>) -> TempDir {
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
     run(args).unwrap();
     tmp
@@ -56,6 +61,7 @@ fn run_update_in(output_dir: &Path, extra_answers: Option<&str>) {
         dry_run: false,
         skip_confirms: vec![All],
         non_interactive: true,
+        ..Default::default()
     };
     run_update_in_dir(args, output_dir.to_path_buf()).unwrap();
 }
@@ -79,6 +85,7 @@ fn run_update_in_expect_err_with(
         dry_run: false,
         skip_confirms: vec![All],
         non_interactive: true,
+        ..Default::default()
     };
     format!("{}", run_update_in_dir(args, output_dir.to_path_buf()).unwrap_err())
 }
@@ -259,6 +266,7 @@ fn update_local_template_dry_run_no_changes() {
             dry_run: true,
             skip_confirms: vec![All],
             non_interactive: true,
+            ..Default::default()
         },
         output_dir.path().to_path_buf(),
     )
@@ -497,6 +505,7 @@ questions:
         dry_run: false,
         generated_file: None,
         conflict_style: None,
+        ..Default::default()
     };
     run(args).unwrap();
 
@@ -509,6 +518,105 @@ questions:
     );
 }
 
+/// Generated metadata records which template entry produced each file.
+#[test]
+fn generate_records_file_provenance_in_metadata() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+
+    let output_dir = generate_into_tmp(
+        template_dir.path().to_str().unwrap(),
+        Some(r#"{"name": "Alice"}"#),
+    );
+
+    let meta = read_meta(output_dir.path());
+    let readme = meta.files.get("README.md").expect("README.md provenance");
+    assert_eq!(readme.template, "README.md.baker.j2");
+    assert_eq!(readme.hash, generated::content_hash(b"Hello, Alice!"));
+}
+
+/// When the template moves a file, the user's copy is moved with it and the new
+/// content merged in, instead of leaving the old file behind.
+#[test]
+fn update_moves_files_the_template_moved() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    fs::create_dir_all(template_dir.path().join("src")).unwrap();
+    fs::write(
+        template_dir.path().join("src/main.rs.baker.j2"),
+        "// {{name}}\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let output_dir = generate_into_tmp(
+        template_dir.path().to_str().unwrap(),
+        Some(r#"{"name": "Alice"}"#),
+    );
+    fs::write(
+        output_dir.path().join("src/main.rs"),
+        "// Alice\nfn main() {}\n// user code\n",
+    )
+    .unwrap();
+
+    fs::remove_dir_all(template_dir.path().join("src")).unwrap();
+    fs::create_dir_all(template_dir.path().join("src/bin")).unwrap();
+    fs::write(
+        template_dir.path().join("src/bin/app.rs.baker.j2"),
+        "// {{name}}\nfn main() {}\n",
+    )
+    .unwrap();
+
+    run_update_in(output_dir.path(), None);
+
+    assert!(!output_dir.path().join("src/main.rs").exists());
+    let moved = fs::read_to_string(output_dir.path().join("src/bin/app.rs")).unwrap();
+    assert!(moved.contains("// user code"), "user edits should survive: {moved}");
+    let meta = read_meta(output_dir.path());
+    assert!(meta.files.contains_key("src/bin/app.rs"));
+    assert!(!meta.files.contains_key("src/main.rs"));
+}
+
+/// With `--prune`, files the template stopped generating are deleted unless the
+/// user modified them.
+#[test]
+fn update_prunes_unmodified_orphans() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    fs::create_dir_all(template_dir.path().join("legacy")).unwrap();
+    fs::write(template_dir.path().join("legacy/old.txt.baker.j2"), "old {{name}}")
+        .unwrap();
+    fs::write(template_dir.path().join("edited.txt.baker.j2"), "edit {{name}}").unwrap();
+
+    let output_dir = generate_into_tmp(
+        template_dir.path().to_str().unwrap(),
+        Some(r#"{"name": "Alice"}"#),
+    );
+    fs::write(output_dir.path().join("edited.txt"), "edit Alice, by hand").unwrap();
+
+    fs::remove_dir_all(template_dir.path().join("legacy")).unwrap();
+    fs::remove_file(template_dir.path().join("edited.txt.baker.j2")).unwrap();
+
+    let args = UpdateArgs {
+        generated_file: None,
+        answers: None,
+        answers_file: None,
+        conflict_style: None,
+        dry_run: false,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        prune: true,
+        ..Default::default()
+    };
+    run_update_in_dir(args, output_dir.path().to_path_buf()).unwrap();
+
+    assert!(!output_dir.path().join("legacy").exists());
+    assert_eq!(
+        fs::read_to_string(output_dir.path().join("edited.txt")).unwrap(),
+        "edit Alice, by hand"
+    );
+    assert!(output_dir.path().join("README.md").exists());
+}
+
 const DEMO_ANSWERS: &str = r#"{"project_name": "demo", "project_author": "demo", "project_slug": "demo", "use_tests": true}"#;
 
 /// Copy a directory tree from `src` (relative to the workspace root) into a
@@ -536,6 +644,150 @@ fn copy_dir_all(src: &Path, dst: &Path) {
     }
 }
 
+/// `baker verify` passes while the template renders the locked files and
+/// reports the files that differ once it no longer does.
+#[test]
+fn verify_checks_rendered_output_against_lock() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    let output_dir = TempDir::new().unwrap();
+    run(GenerateArgs {
+        template: template_dir.path().to_str().unwrap().to_string(),
+        output_dir: output_dir.path().to_path_buf(),
+        force: true,
+        answers: Some(r#"{"name": "Alice"}"#.to_string()),
+        answers_file: None,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        dry_run: false,
+        generated_file: None,
+        conflict_style: None,
+        lock: true,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let lock = baker::lock::read(output_dir.path()).unwrap().expect("lock written");
+    assert_eq!(lock.files.keys().collect::<Vec<_>>(), ["README.md"]);
+    let verify = || {
+        run_verify(VerifyArgs {
+            dir: output_dir.path().to_path_buf(),
+            generated_file: None,
+            files: false,
+        })
+    };
+    verify().unwrap();
+
+    write_template_file(template_dir.path(), "Goodbye, {{name}}!");
+    let err = verify().unwrap_err().to_string();
+    assert!(err.contains("1 file(s) differ"), "{err}");
+}
+
+#[test]
+fn verify_files_fails_only_for_changed_binary_assets() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    fs::write(template_dir.path().join("logo.png"), [0x89, b'P', b'N', b'G', 0xff])
+        .unwrap();
+    let output_dir = TempDir::new().unwrap();
+    run(GenerateArgs {
+        template: template_dir.path().to_str().unwrap().to_string(),
+        output_dir: output_dir.path().to_path_buf(),
+        force: true,
+        answers: None,
+        answers_file: None,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        dry_run: false,
+        generated_file: None,
+        conflict_style: None,
+        ..Default::default()
+    })
+    .unwrap();
+    let meta =
+        baker::generated::read(output_dir.path(), DEFAULT_GENERATED_FILE_NAME).unwrap();
+    assert!(meta.files["logo.png"].binary);
+    assert!(!meta.files["README.md"].binary);
+
+    let verify = || {
+        run_verify(VerifyArgs {
+            dir: output_dir.path().to_path_buf(),
+            generated_file: None,
+            files: true,
+        })
+    };
+    verify().unwrap();
+
+    fs::write(output_dir.path().join("README.md"), "Edited by hand").unwrap();
+    verify().unwrap();
+
+    fs::write(output_dir.path().join("logo.png"), [0x89, b'P', 0, 0, 0xff]).unwrap();
+    let err = verify().unwrap_err().to_string();
+    assert!(err.contains("1 binary asset(s) changed"), "{err}");
+}
+
+/// A project generated by copier has only `.copier-answers.yml`; update reads the
+/// source and answers from it, and keeps the file up to date for copier.
+#[test]
+fn update_reads_copier_answers_when_baker_metadata_is_missing() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    let project = TempDir::new().unwrap();
+    fs::write(
+        project.path().join(".copier-answers.yml"),
+        format!(
+            "# Changes here will be overwritten by Copier\n_src_path: {}\nname: Copier\n",
+            template_dir.path().display()
+        ),
+    )
+    .unwrap();
+
+    run_update_in(project.path(), None);
+
+    assert_eq!(
+        fs::read_to_string(project.path().join("README.md")).unwrap(),
+        "Hello, Copier!"
+    );
+    assert_eq!(read_meta(project.path()).answers["name"], "Copier");
+    let copier_answers =
+        fs::read_to_string(project.path().join(".copier-answers.yml")).unwrap();
+    assert!(copier_answers.contains("name: Copier"), "{copier_answers}");
+}
+
+/// `workspace update --all` rolls a template change out to every registered project
+/// and keeps going past a project that fails.
+#[test]
+fn workspace_updates_every_registered_project() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "Hello, {{name}}!");
+    let template = template_dir.path().to_str().unwrap();
+    let users = generate_into_tmp(template, Some(r#"{"name": "Users"}"#));
+    let orders = generate_into_tmp(template, Some(r#"{"name": "Orders"}"#));
+
+    let mut workspace = Workspace::default();
+    workspace.add(users.path(), None).unwrap();
+    workspace.add(orders.path(), None).unwrap();
+    fs::remove_file(orders.path().join(DEFAULT_GENERATED_FILE_NAME)).unwrap();
+    write_template_file(template_dir.path(), "Hi, {{name}}!");
+
+    let args = UpdateArgs {
+        generated_file: None,
+        answers: None,
+        answers_file: None,
+        conflict_style: None,
+        dry_run: false,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        ..Default::default()
+    };
+    let report = update_projects(&workspace.projects, &args);
+
+    assert_eq!(report.failures(), 1);
+    assert!(report.to_string().contains("1 updated, 1 failed"), "{report}");
+    let readme = fs::read_to_string(users.path().join("README.md")).unwrap();
+    assert!(readme.contains("Hi, Users!"), "{readme}");
+}
+
 /// Creates a minimal baker template directory:
 ///
 /// ```